@@ -0,0 +1,7 @@
+use std::sync::Arc;
+
+use ipis::core::account::AccountRef;
+
+/// Decides whether an account may issue the protected requests,
+/// such as dumping the whole address book.
+pub type Authorizer = Arc<dyn Fn(&AccountRef) -> bool + Send + Sync>;
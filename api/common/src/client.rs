@@ -0,0 +1,640 @@
+use core::time::Duration;
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+    time::Instant,
+};
+
+use ipiis_common::{
+    external_call, ByteMeter, HopInfo, Ipiis, IpiisError, RawHandlers, RequestBudget,
+    RequestHandle, RequestRegistry, RequestScheduler, ResponseCache, RetryBudget, RevocationList,
+    TimePolicy, VerificationAudit, WireCapture, DEFAULT_MAX_RESOLUTION_DEPTH, MAX_HOPS,
+};
+use ipis::{
+    core::{
+        account::{Account, AccountRef},
+        anyhow::{anyhow, bail, Error, Result},
+        value::hash::Hash,
+    },
+    futures::{
+        future::{BoxFuture, Shared},
+        FutureExt, Stream,
+    },
+    tokio::sync::Mutex,
+};
+
+use crate::{
+    auth::Authorizer,
+    config::ClientConfigSummary,
+    expiry::ExpiryTable,
+    retry::{RetryPolicy, DEFAULT_CONNECT_TIMEOUT},
+    router::{sled, BookChange, MaintenanceReport, RouteBook, RouterClient},
+};
+
+/// An address lookup being sent to the primary account, shared by the concurrent callers.
+pub type PendingAddress = Shared<BoxFuture<'static, Result<String, Arc<Error>>>>;
+
+/// The state of a client regardless of its transport,
+/// e.g. the address book, the cached records and the options.
+///
+/// The clients of the transports embed it, keeping only their connections on their own.
+#[derive(Clone)]
+pub struct ClientState {
+    pub router: RouterClient<String>,
+    /// Whether this client is embedded in a server listening as `account_me`
+    pub serving: bool,
+    /// Whether the addresses are resolved only from the seeded routes, without the upstream
+    pub static_routing: bool,
+    pub resolve_retry: RetryPolicy,
+    /// The retries shared by all the retry sites, bounding a retry storm
+    pub retry_budget: Option<RetryBudget>,
+    /// The bound of establishing a connection to the peers
+    pub connect_timeout: Duration,
+    /// The maximum number of the primaries forwarding a lookup of the kind's primary account
+    pub max_resolution_depth: u8,
+    /// The budget of the in-flight request bytes, when serving
+    pub request_budget: Option<RequestBudget>,
+    /// The limit of the concurrently handled requests, when serving
+    pub request_scheduler: Option<RequestScheduler>,
+    /// The window of the recent responses to be replayed, when serving
+    pub response_cache: Option<ResponseCache>,
+    /// The time-sensitive behaviors, e.g. the lifetime of the cached records
+    pub time_policy: TimePolicy,
+    /// The accounts whose requests are rejected, when serving
+    pub revocation_list: Option<RevocationList>,
+    /// The accounting of the transferred bytes per account, when serving
+    pub byte_meter: Option<ByteMeter>,
+    /// Whether the processing time is reported in the responses, when serving
+    pub server_timing: bool,
+    /// The audit trail of the requests failing the verification, when serving
+    pub verification_audit: Option<VerificationAudit>,
+    /// The handlers of the raw requests registered at runtime, when serving
+    pub raw_handlers: Option<RawHandlers>,
+    /// Decides who may issue the protected requests, when serving
+    pub authorizer: Option<Authorizer>,
+    /// The accounts allowed to mutate the directory besides `account_me`, when serving
+    pub admin_accounts: HashSet<AccountRef>,
+    /// The address advertised to the peers on `Hello`
+    pub advertised_address: Option<String>,
+    /// Whether any peer may register its own new address on `Hello`, when serving
+    pub hello_registration: bool,
+    /// Observes the bytes of the requests sent, for debugging
+    pub wire_capture: Option<WireCapture>,
+    /// The address lookups being sent to the primary account
+    pub pending_addresses: Arc<Mutex<HashMap<(Option<Hash>, AccountRef), PendingAddress>>>,
+    /// The deadlines of the addresses cached from the primary accounts
+    pub address_deadlines: ExpiryTable<(Option<Hash>, AccountRef)>,
+    /// The deadlines of the kinds' primary accounts cached from the primary accounts
+    pub primary_deadlines: ExpiryTable<Option<Hash>>,
+    /// The outgoing requests being sent or waiting for the responses
+    pub requests: RequestRegistry,
+}
+
+impl ClientState {
+    /// Creates a state on the address book, registering the primary account if given,
+    /// along with its address if known.
+    pub fn new(
+        router: RouterClient<String>,
+        account_primary: Option<AccountRef>,
+        account_primary_address: Option<String>,
+    ) -> Result<Self> {
+        let state = Self {
+            router,
+            serving: false,
+            static_routing: false,
+            resolve_retry: Default::default(),
+            retry_budget: None,
+            connect_timeout: DEFAULT_CONNECT_TIMEOUT,
+            max_resolution_depth: DEFAULT_MAX_RESOLUTION_DEPTH,
+            request_budget: None,
+            request_scheduler: None,
+            response_cache: None,
+            time_policy: Default::default(),
+            revocation_list: None,
+            byte_meter: None,
+            server_timing: false,
+            verification_audit: None,
+            raw_handlers: None,
+            authorizer: None,
+            admin_accounts: Default::default(),
+            advertised_address: None,
+            hello_registration: false,
+            wire_capture: None,
+            pending_addresses: Default::default(),
+            address_deadlines: Default::default(),
+            primary_deadlines: Default::default(),
+            requests: Default::default(),
+        };
+
+        // try to add the primary account's address
+        if let Some(account_primary) = account_primary {
+            state.router.set_primary(None, &account_primary)?;
+
+            if let Some(address) = account_primary_address {
+                state.router.set(None, &account_primary, &address)?;
+            }
+        }
+
+        Ok(state)
+    }
+
+    /// Rebinds the account, reusing the address book and the other options.
+    pub fn with_account(mut self, account_me: Account) -> Self {
+        self.router = self.router.with_account(account_me);
+        self
+    }
+
+    /// Flushes the address book periodically in background.
+    pub fn with_flush_interval(mut self, interval: Duration) -> Result<Self> {
+        self.router = self.router.with_flush_interval(interval)?;
+        Ok(self)
+    }
+
+    /// Sets the retry policy of the upstream address resolution.
+    pub fn with_resolve_retry(mut self, policy: RetryPolicy) -> Self {
+        self.resolve_retry = policy;
+        self
+    }
+
+    /// Seeds the complete routing table, resolving the accounts only from it.
+    pub fn with_static_routes(mut self, book: &RouteBook) -> Result<Self> {
+        self.router.import(book)?;
+        self.static_routing = true;
+        Ok(self)
+    }
+
+    /// Bounds the total volume of the retries across all the calls.
+    pub fn with_retry_budget(mut self, budget: RetryBudget) -> Self {
+        self.retry_budget = Some(budget);
+        self
+    }
+
+    /// Bounds establishing a connection to the peers.
+    pub fn with_connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = timeout;
+        self
+    }
+
+    /// Sets the time-sensitive behaviors.
+    pub fn with_time_policy(mut self, policy: TimePolicy) -> Self {
+        self.time_policy = policy;
+        self
+    }
+
+    /// Bounds the primaries forwarding a lookup of the kind's primary account.
+    pub fn with_max_resolution_depth(mut self, depth: u8) -> Self {
+        self.max_resolution_depth = depth;
+        self
+    }
+
+    /// Advertises the address to the peers on `Hello`.
+    pub fn with_advertised_address(mut self, address: String) -> Self {
+        self.advertised_address = Some(address);
+        self
+    }
+
+    /// Passes the exact bytes of each request sent to the callback.
+    pub fn with_wire_capture<F>(mut self, callback: F) -> Self
+    where
+        F: Fn(&[u8]) + Send + Sync + 'static,
+    {
+        self.wire_capture = Some(WireCapture::new(callback));
+        self
+    }
+
+    /// Whether the account may issue the protected requests, e.g. `SnapshotPrimaries`.
+    ///
+    /// The account of this client is always authorized,
+    /// and the others only by the authorizer.
+    pub fn is_authorized(&self, account: &AccountRef) -> bool {
+        *account == *self.router.account_ref
+            || self
+                .authorizer
+                .as_ref()
+                .map(|authorizer| authorizer(account))
+                .unwrap_or_default()
+    }
+
+    /// Whether the account may mutate the directory, e.g. `SetAddress`.
+    ///
+    /// The account of this client is always an admin.
+    pub fn is_admin(&self, account: &AccountRef) -> bool {
+        *account == *self.router.account_ref || self.admin_accounts.contains(account)
+    }
+
+    /// Lists the kinds which the account is primary for, in the local address book.
+    ///
+    /// Note that it scans all primary entries: O(n).
+    pub fn kinds_for_primary(&self, account: &AccountRef) -> Result<Vec<Option<Hash>>> {
+        self.router.kinds_for_primary(account)
+    }
+
+    /// Returns the outgoing requests waiting for the responses, in the order of issue.
+    pub fn in_flight(&self) -> Vec<RequestHandle> {
+        self.requests.in_flight()
+    }
+
+    /// Aborts the in-flight request, which fails with `IpiisError::Cancelled`.
+    ///
+    /// Returns `false` if there is no such request.
+    pub fn cancel(&self, id: u64) -> bool {
+        self.requests.cancel(id)
+    }
+
+    /// Returns the effective configuration of the client over the transport.
+    pub fn config_summary(
+        &self,
+        protocol: &'static str,
+        idle_timeout: Option<Duration>,
+    ) -> ClientConfigSummary {
+        ClientConfigSummary {
+            protocol,
+            account: self.router.account_ref.to_string(),
+            account_primary: self
+                .router
+                .get_primary(None)
+                .ok()
+                .flatten()
+                .map(|account| account.to_string()),
+            db_path: self.router.path().map(Into::into),
+            idle_timeout,
+            connect_timeout: self.connect_timeout,
+            serving: self.serving,
+            static_routing: self.static_routing,
+            resolve_attempts: self.resolve_retry.attempts,
+            resolve_backoff: self.resolve_retry.backoff,
+            retry_budget: self
+                .retry_budget
+                .as_ref()
+                .map(|budget| (budget.retries(), budget.window())),
+            max_resolution_depth: self.max_resolution_depth,
+        }
+    }
+
+    /// Returns the local address book, bypassing the directory.
+    ///
+    /// It is useful to seed a node before any network exists.
+    pub fn book(&self) -> &RouterClient<String> {
+        &self.router
+    }
+
+    /// Opens an auxiliary tree sharing the database of the address book.
+    ///
+    /// Note that the callers own the schema of their tree.
+    pub fn open_tree(&self, name: impl AsRef<[u8]>) -> Result<sled::Tree> {
+        self.router.open_tree(name)
+    }
+
+    /// Watches the local mutations of the address book, e.g. to update a UI.
+    ///
+    /// Only the changes made in this process are observed, from now on.
+    pub fn watch_local(&self) -> impl Stream<Item = BookChange> {
+        self.router.watch()
+    }
+
+    /// Evicts the expired records cached from the primary accounts,
+    /// and flushes the address book.
+    pub async fn maintenance(&self) -> Result<MaintenanceReport> {
+        let evicted_entries = self.evict_expired()?;
+
+        Ok(MaintenanceReport {
+            evicted_entries,
+            ..self.router.maintenance().await?
+        })
+    }
+
+    /// Evicts the expired records cached from the primary accounts, returning their number.
+    fn evict_expired(&self) -> Result<usize> {
+        let addresses = self.address_deadlines.take_all_expired();
+        for (kind, account) in &addresses {
+            self.router.delete(kind.as_ref(), account)?;
+        }
+
+        let primaries = self.primary_deadlines.take_all_expired();
+        for kind in &primaries {
+            self.router.delete_primary(kind.as_ref())?;
+        }
+
+        Ok(addresses.len() + primaries.len())
+    }
+
+    /// Returns the kind's primary account in the address book, forgetting the expired record.
+    pub fn get_cached_primary(&self, kind: Option<&Hash>) -> Result<Option<AccountRef>> {
+        if self.primary_deadlines.take_expired(&kind.copied()) {
+            self.router.delete_primary(kind)?;
+        }
+        self.router.get_primary(kind)
+    }
+
+    /// Returns the address in the address book, forgetting the expired record.
+    pub fn get_cached_address(
+        &self,
+        kind: Option<&Hash>,
+        target: &AccountRef,
+    ) -> Result<Option<String>> {
+        if self
+            .address_deadlines
+            .take_expired(&(kind.copied(), *target))
+        {
+            self.router.delete(kind, target)?;
+        }
+        self.router.get(kind, target)
+    }
+
+    /// Stores the kind's primary account resolved from the primary accounts,
+    /// with its address if given, bounding their lifetime.
+    pub fn store_primary(
+        &self,
+        kind: &Hash,
+        account: &AccountRef,
+        address: Option<&String>,
+        ttl_ms: Option<u64>,
+    ) -> Result<()> {
+        self.router.set_primary(Some(kind), account)?;
+        self.primary_deadlines
+            .set(Some(*kind), self.time_policy.bound_ttl_ms(ttl_ms));
+        if let Some(address) = address {
+            self.store_address(Some(kind), account, address, ttl_ms)?;
+        }
+        Ok(())
+    }
+
+    /// Stores the address resolved from the primary accounts, bounding its lifetime.
+    pub fn store_address(
+        &self,
+        kind: Option<&Hash>,
+        target: &AccountRef,
+        address: &String,
+        ttl_ms: Option<u64>,
+    ) -> Result<()> {
+        self.router.set(kind, target, address)?;
+        self.address_deadlines.set(
+            (kind.copied(), *target),
+            self.time_policy.bound_ttl_ms(ttl_ms),
+        );
+        Ok(())
+    }
+
+    /// Sets the kind's primary account locally, which never expires.
+    pub fn set_local_primary(&self, kind: Option<&Hash>, account: &AccountRef) -> Result<()> {
+        self.router.set_primary(kind, account)?;
+        self.primary_deadlines.set(kind.copied(), None);
+        Ok(())
+    }
+
+    /// Deletes the kind's primary account locally.
+    pub fn delete_local_primary(&self, kind: Option<&Hash>) -> Result<()> {
+        self.router.delete_primary(kind)?;
+        self.primary_deadlines.set(kind.copied(), None);
+        Ok(())
+    }
+
+    /// Sets the address locally, which never expires.
+    pub fn set_local_address(
+        &self,
+        kind: Option<&Hash>,
+        target: &AccountRef,
+        address: &String,
+    ) -> Result<()> {
+        self.router.set(kind, target, address)?;
+        self.address_deadlines.set((kind.copied(), *target), None);
+        Ok(())
+    }
+
+    /// Deletes the address locally.
+    pub fn delete_local_address(&self, kind: Option<&Hash>, target: &AccountRef) -> Result<()> {
+        self.router.delete(kind, target)?;
+        self.address_deadlines.set((kind.copied(), *target), None);
+        Ok(())
+    }
+
+    /// Lists the whole primary table of the address book, with the known addresses.
+    pub fn list_local_primaries(&self) -> Result<Vec<(Option<Hash>, AccountRef, Option<String>)>> {
+        self.router
+            .list_primaries()?
+            .into_iter()
+            .map(|(kind, account)| {
+                let address = self.router.get(kind.as_ref(), &account)?;
+                Ok((kind, account, address))
+            })
+            .collect()
+    }
+
+    /// Fails unless the address of the primary account is in the book,
+    /// as resolving it from the primary itself would never end.
+    pub fn ensure_primary_address(&self, primary: &AccountRef) -> Result<()> {
+        match self.router.get(None, primary)? {
+            Some(_) => Ok(()),
+            None => Err(IpiisError::PrimaryAddressUnknown(primary.to_string()).into()),
+        }
+    }
+
+    /// Whether this client holds the root's account, but is not the root server itself.
+    pub fn is_root_client(&self, primary: &AccountRef) -> bool {
+        !self.serving && *self.router.account_ref == *primary
+    }
+
+    /// Returns the root to verify the written addresses against,
+    /// failing unless this client is a root client.
+    pub fn root_to_verify(&self) -> Result<AccountRef> {
+        match self.router.get_primary(None)? {
+            Some(primary) if self.is_root_client(&primary) => Ok(primary),
+            Some(primary) => {
+                bail!("cannot verify against the root as a non-root client: {primary}")
+            }
+            None => Err(IpiisError::NotFound("root to verify against".to_string()).into()),
+        }
+    }
+
+    /// Fails if the client, embedded in a server, would connect to itself.
+    pub fn ensure_not_self(&self, target: &AccountRef) -> Result<()> {
+        if self.serving && *self.router.account_ref == *target {
+            return Err(IpiisError::SelfConnection(target.to_string()).into());
+        }
+        Ok(())
+    }
+}
+
+/// Resolves the address from the primary account.
+///
+/// The concurrent lookups of the same target share a single request,
+/// so that a cold target does not flood the primary.
+pub async fn resolve_address<C>(
+    client: &C,
+    kind: Option<&Hash>,
+    target: &AccountRef,
+    primary: AccountRef,
+) -> Result<String>
+where
+    C: Ipiis<Address = String> + AsRef<ClientState> + Clone + Send + Sync + 'static,
+{
+    let key = (kind.copied(), *target);
+
+    let pending = {
+        let mut pending_addresses = client.as_ref().pending_addresses.lock().await;
+        match pending_addresses.get(&key) {
+            Some(pending) => pending.clone(),
+            None => {
+                let client = client.clone();
+                let pending = async move {
+                    let (kind, target) = key;
+                    let res = fetch_address(&client, kind.as_ref(), &target, primary)
+                        .await
+                        .map_err(Arc::new);
+
+                    // evict the completed request
+                    client.as_ref().pending_addresses.lock().await.remove(&key);
+                    res
+                }
+                .boxed()
+                .shared();
+
+                pending_addresses.insert(key, pending.clone());
+                pending
+            }
+        }
+    };
+
+    pending.await.map_err(|e| anyhow!("{e}"))
+}
+
+/// Resolves the address from the primary account, without sharing the request,
+/// and stores it in the address book.
+pub async fn fetch_address<C>(
+    client: &C,
+    kind: Option<&Hash>,
+    target: &AccountRef,
+    primary: AccountRef,
+) -> Result<String>
+where
+    C: Ipiis<Address = String> + AsRef<ClientState>,
+{
+    let state = client.as_ref();
+
+    // external call
+    let (address, ttl_ms) = state
+        .resolve_retry
+        .run(state.retry_budget.as_ref(), || async move {
+            let res = external_call!(
+                client: client,
+                target: None => &primary,
+                request: ::ipiis_common::io => GetAddress,
+                sign: client.sign_owned(primary, (kind.copied(), *target))?,
+                inputs: { },
+                outputs: { address, ttl_ms, },
+            );
+            Result::<_, ::ipis::core::anyhow::Error>::Ok(res)
+        })
+        .await?;
+
+    // store response
+    state.store_address(kind, target, &address, ttl_ms)?;
+
+    // unpack response
+    Ok(address)
+}
+
+/// Pre-populates the address book from a directory server,
+/// returning the number of the cached addresses.
+pub async fn warm_from<C>(client: &C, source: &AccountRef, kinds: &[Option<Hash>]) -> Result<usize>
+where
+    C: Ipiis<Address = String> + AsRef<ClientState>,
+{
+    let mut count = 0;
+    for kind in kinds {
+        // external call
+        let (accounts,) = external_call!(
+            client: client,
+            target: None => source,
+            request: ::ipiis_common::io => ListAccounts,
+            sign: client.sign_owned(*source, *kind)?,
+            inputs: { },
+            outputs: { accounts, },
+        );
+
+        for account in accounts {
+            // external call
+            let (address, ttl_ms) = external_call!(
+                client: client,
+                target: None => source,
+                request: ::ipiis_common::io => GetAddress,
+                sign: client.sign_owned(*source, (*kind, account))?,
+                inputs: { },
+                outputs: { address, ttl_ms, },
+            );
+
+            // store response
+            client
+                .as_ref()
+                .store_address(kind.as_ref(), &account, &address, ttl_ms)?;
+            count += 1;
+        }
+    }
+    Ok(count)
+}
+
+/// Traces the resolution of the address along the primaries, like `traceroute`,
+/// asking each primary for the target.
+///
+/// It stops at the root, or at the first primary which has not answered.
+pub async fn trace_address<C>(
+    client: &C,
+    kind: Option<&Hash>,
+    target: &AccountRef,
+) -> Result<Vec<HopInfo>>
+where
+    C: Ipiis<Address = String> + AsRef<ClientState>,
+{
+    let mut hops: Vec<HopInfo> = vec![];
+    let mut next = client.as_ref().router.get_primary(None)?;
+
+    while let Some(hop) = next.take() {
+        // stop at a loop
+        if hops.len() >= MAX_HOPS || hops.iter().any(|e| e.account == hop) {
+            break;
+        }
+
+        // ask the hop for the target
+        let instant = Instant::now();
+        let result = async {
+            // external call
+            let (_address,) = external_call!(
+                client: client,
+                target: None => &hop,
+                request: ::ipiis_common::io => GetAddress,
+                sign: client.sign_owned(hop, (kind.copied(), *target))?,
+                inputs: { },
+                outputs: { address, },
+            );
+            Result::<_, ::ipis::core::anyhow::Error>::Ok(())
+        }
+        .await;
+
+        hops.push(HopInfo {
+            account: hop,
+            rtt: instant.elapsed(),
+            answered: result.is_ok(),
+            error: result.err().map(|e| e.to_string()),
+        });
+        if hops.last().map(|e| !e.answered).unwrap_or_default() {
+            break;
+        }
+
+        // find the next hop, where the root has no primary
+        next = async {
+            // external call
+            let (account, _address) = external_call!(
+                client: client,
+                target: None => &hop,
+                request: ::ipiis_common::io => GetAccountPrimary,
+                sign: client.sign_owned(hop, (Option::<Hash>::None, 1))?,
+                inputs: { },
+                outputs: { account, address, },
+            );
+            Result::<_, ::ipis::core::anyhow::Error>::Ok(account)
+        }
+        .await
+        .ok();
+    }
+    Ok(hops)
+}
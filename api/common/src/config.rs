@@ -0,0 +1,167 @@
+use core::time::Duration;
+use std::path::{Path, PathBuf};
+
+use ipis::{
+    core::{
+        account::{Account, AccountRef},
+        anyhow::{anyhow, bail, Result},
+    },
+    env::infer,
+};
+use serde::{Deserialize, Serialize};
+
+/// The config file that is loaded when `ipiis_config` is not given.
+pub const DEFAULT_CONFIG_PATH: &str = "ipiis.toml";
+
+/// Typed IPIIS configuration.
+///
+/// The values are loaded from a TOML/JSON config file first,
+/// and then overridden by the environment variables.
+///
+/// ## Example (`ipiis.toml`)
+///
+/// ```toml
+/// account_me = "..."
+/// account_primary = "..."
+/// account_primary_address = "127.0.0.1:9801"
+/// admin_accounts = ["...", "..."]
+/// revoked_accounts = ["..."]
+/// server_port = 9801
+/// ```
+///
+#[derive(Default)]
+pub struct IpiisConfig {
+    /// Env: `ipis_account_me`
+    pub account_me: Option<Account>,
+    /// Env: `ipiis_account_primary`
+    pub account_primary: Option<AccountRef>,
+    /// Env: `ipiis_account_primary_address`
+    pub account_primary_address: Option<String>,
+    /// Env: `ipiis_admin_accounts` (comma-separated)
+    pub admin_accounts: Vec<AccountRef>,
+    /// Env: `ipiis_revoked_accounts` (comma-separated)
+    pub revoked_accounts: Vec<AccountRef>,
+    /// Env: `ipiis_server_port`
+    pub server_port: Option<u16>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+struct IpiisConfigFile {
+    account_me: Option<String>,
+    account_primary: Option<String>,
+    account_primary_address: Option<String>,
+    admin_accounts: Vec<String>,
+    revoked_accounts: Vec<String>,
+    server_port: Option<u16>,
+}
+
+impl IpiisConfig {
+    /// Loads the config file from `ipiis_config` (or [`DEFAULT_CONFIG_PATH`] if exists),
+    /// and then applies the environment variables on it.
+    pub fn load() -> Result<Self> {
+        let path: Result<PathBuf> = infer("ipiis_config");
+        let file = match path {
+            Ok(path) => Self::load_file(&path)?,
+            Err(_) => {
+                let path = Path::new(DEFAULT_CONFIG_PATH);
+                if path.exists() {
+                    Self::load_file(path)?
+                } else {
+                    Default::default()
+                }
+            }
+        };
+
+        let admin_accounts = Self::load_accounts("ipiis_admin_accounts", &file.admin_accounts)?;
+        let revoked_accounts =
+            Self::load_accounts("ipiis_revoked_accounts", &file.revoked_accounts)?;
+
+        Ok(Self {
+            account_me: match infer("ipis_account_me").ok() {
+                Some(account) => Some(account),
+                None => file.account_me.as_deref().map(str::parse).transpose()?,
+            },
+            account_primary: match infer("ipiis_account_primary").ok() {
+                Some(account) => Some(account),
+                None => file
+                    .account_primary
+                    .as_deref()
+                    .map(str::parse)
+                    .transpose()?,
+            },
+            account_primary_address: infer("ipiis_account_primary_address")
+                .ok()
+                .or(file.account_primary_address),
+            admin_accounts,
+            revoked_accounts,
+            server_port: infer("ipiis_server_port").ok().or(file.server_port),
+        })
+    }
+
+    fn load_accounts(key: &str, file: &[String]) -> Result<Vec<AccountRef>> {
+        let accounts: Result<String> = infer(key);
+        match accounts {
+            Ok(accounts) => accounts
+                .split(',')
+                .map(str::trim)
+                .filter(|account| !account.is_empty())
+                .map(str::parse)
+                .collect::<Result<_, _>>()
+                .map_err(Into::into),
+            Err(_) => file
+                .iter()
+                .map(|account| account.parse())
+                .collect::<Result<_, _>>()
+                .map_err(Into::into),
+        }
+    }
+
+    fn load_file(path: &Path) -> Result<IpiisConfigFile> {
+        let data = ::std::fs::read_to_string(path)
+            .map_err(|e| anyhow!("failed to read the config file: {path:?}: {e}"))?;
+
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("json") => ::serde_json::from_str(&data)
+                .map_err(|e| anyhow!("failed to parse the config file: {path:?}: {e}")),
+            Some("toml") | None => ::toml::from_str(&data)
+                .map_err(|e| anyhow!("failed to parse the config file: {path:?}: {e}")),
+            Some(ext) => bail!("unsupported config file format: {ext}"),
+        }
+    }
+
+    pub fn account_me(&mut self) -> Result<Account> {
+        self.account_me
+            .take()
+            .ok_or_else(|| anyhow!("failed to infer the account: ipis_account_me"))
+    }
+
+    pub fn server_port(&self) -> Result<u16> {
+        self.server_port
+            .ok_or_else(|| anyhow!("failed to infer the server port: ipiis_server_port"))
+    }
+}
+
+/// The effective configuration of a client, after the config file,
+/// the environment variables and the builders are applied.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct ClientConfigSummary {
+    /// The name of the transport, e.g. `quic` or `tcp`
+    pub protocol: &'static str,
+    pub account: String,
+    pub account_primary: Option<String>,
+    /// The path of the address book, if opened by the client itself
+    pub db_path: Option<PathBuf>,
+    /// The idle timeout of the connections, if the transport has one
+    pub idle_timeout: Option<Duration>,
+    pub connect_timeout: Duration,
+    /// Whether the client is embedded in a server
+    pub serving: bool,
+    /// Whether the addresses are resolved only from the seeded routes
+    pub static_routing: bool,
+    pub resolve_attempts: u32,
+    pub resolve_backoff: Duration,
+    /// The retries per window shared by all the retry sites, if bounded
+    pub retry_budget: Option<(u32, Duration)>,
+    pub max_resolution_depth: u8,
+}
@@ -0,0 +1,76 @@
+use core::time::Duration;
+
+use ipis::tokio::{
+    self,
+    sync::{mpsc, watch},
+};
+
+/// The default time to wait for the requests in flight on shutdown.
+pub const DEFAULT_DRAIN_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Tracks the requests in flight of a server, to wait for them on shutdown.
+///
+/// Each task serving the requests holds a [`DrainGuard`],
+/// and the server is drained once all of the guards are dropped.
+#[derive(Debug)]
+pub struct Drain {
+    guard: DrainGuard,
+    drained: mpsc::Receiver<()>,
+    stopping: watch::Sender<bool>,
+}
+
+/// A task serving the requests, which is waited on shutdown while alive.
+#[derive(Clone, Debug)]
+pub struct DrainGuard {
+    _tracker: mpsc::Sender<()>,
+    stopping: watch::Receiver<bool>,
+}
+
+impl Default for Drain {
+    fn default() -> Self {
+        let (tracker, drained) = mpsc::channel(1);
+        let (stopping, stopping_rx) = watch::channel(false);
+
+        Self {
+            guard: DrainGuard {
+                _tracker: tracker,
+                stopping: stopping_rx,
+            },
+            drained,
+            stopping,
+        }
+    }
+}
+
+impl Drain {
+    pub fn guard(&self) -> DrainGuard {
+        self.guard.clone()
+    }
+
+    /// Tells the tasks to stop taking new requests, and waits for the ones in flight
+    /// within the timeout, returning whether all of them are done.
+    pub async fn drain(self, timeout: Duration) -> bool {
+        let Self {
+            guard,
+            mut drained,
+            stopping,
+        } = self;
+
+        let _ = stopping.send(true);
+        drop(guard);
+
+        // all the guards are dropped once nothing is received
+        tokio::time::timeout(timeout, drained.recv()).await.is_ok()
+    }
+}
+
+impl DrainGuard {
+    /// Waits until the server begins to shut down.
+    pub async fn stopping(&mut self) {
+        while !*self.stopping.borrow() {
+            if self.stopping.changed().await.is_err() {
+                break;
+            }
+        }
+    }
+}
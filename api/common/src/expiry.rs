@@ -0,0 +1,81 @@
+use core::{hash::Hash, time::Duration};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::Instant,
+};
+
+/// The deadlines of the records cached from the directory, kept in memory.
+///
+/// The records without a deadline never expire, e.g. the ones set locally.
+#[derive(Debug)]
+pub struct ExpiryTable<K> {
+    deadlines: Arc<Mutex<HashMap<K, Instant>>>,
+}
+
+impl<K> Clone for ExpiryTable<K> {
+    fn clone(&self) -> Self {
+        Self {
+            deadlines: self.deadlines.clone(),
+        }
+    }
+}
+
+impl<K> Default for ExpiryTable<K> {
+    fn default() -> Self {
+        Self {
+            deadlines: Default::default(),
+        }
+    }
+}
+
+impl<K> ExpiryTable<K>
+where
+    K: Eq + Hash,
+{
+    /// Sets the lifetime of the record given in milliseconds, from now on.
+    ///
+    /// The record without a lifetime never expires.
+    pub fn set(&self, key: K, ttl_ms: Option<u64>) {
+        let mut deadlines = self.deadlines.lock().unwrap();
+        match ttl_ms {
+            Some(ttl_ms) => {
+                deadlines.insert(key, Instant::now() + Duration::from_millis(ttl_ms));
+            }
+            None => {
+                deadlines.remove(&key);
+            }
+        }
+    }
+
+    /// Whether the record has expired, forgetting its deadline if so.
+    pub fn take_expired(&self, key: &K) -> bool {
+        let mut deadlines = self.deadlines.lock().unwrap();
+        match deadlines.get(key) {
+            Some(deadline) if *deadline <= Instant::now() => {
+                deadlines.remove(key);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Forgets all the expired deadlines, returning their records.
+    pub fn take_all_expired(&self) -> Vec<K>
+    where
+        K: Clone,
+    {
+        let now = Instant::now();
+        let mut deadlines = self.deadlines.lock().unwrap();
+
+        let expired: Vec<_> = deadlines
+            .iter()
+            .filter(|(_, deadline)| **deadline <= now)
+            .map(|(key, _)| key.clone())
+            .collect();
+        for key in &expired {
+            deadlines.remove(key);
+        }
+        expired
+    }
+}
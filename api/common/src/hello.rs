@@ -0,0 +1,62 @@
+use ipiis_common::{io::OpCode, recv_server_result, Ipiis, IpiisError};
+use ipis::{
+    core::{
+        account::{AccountRef, GuaranteeSigned, GuarantorSigned, Verifier},
+        anyhow::{bail, Result},
+        data::Data,
+    },
+    stream::DynStream,
+    tokio::io::AsyncWriteExt,
+};
+
+/// A record of the address advertised by an account, signed by the account itself.
+pub type HelloRecord = Data<GuaranteeSigned, Option<String>>;
+
+/// Verifies the record signed by its own account, returning the account.
+pub fn verify_record(record: &HelloRecord) -> Result<AccountRef> {
+    let account = record.metadata.guarantee;
+    record
+        .verify(Some(&account))
+        .map_err(|e| IpiisError::InvalidSignature(e.to_string()))?;
+    Ok(account)
+}
+
+/// Sends the record of the client to the peer over a fresh connection,
+/// returning the verified record of the peer.
+///
+/// The peer should sign the record of the client back with the same account as its own record.
+pub async fn exchange<C>(
+    client: &C,
+    mut send: <C as Ipiis>::Writer,
+    mut recv: <C as Ipiis>::Reader,
+    address: Option<String>,
+) -> Result<HelloRecord>
+where
+    C: Ipiis<Address = String>,
+{
+    // sign the record
+    let mut record = DynStream::Owned(client.sign_owned(*client.account_ref(), address.clone())?);
+    record.serialize_inner().await?;
+
+    // send request
+    send.write_all(&OpCode::Hello.to_bytes()).await?;
+    record.copy_to(&mut send).await?;
+
+    // recv response
+    recv_server_result(&mut recv).await?;
+    let sign: Data<GuarantorSigned, Option<String>> =
+        DynStream::recv(&mut recv).await?.into_owned().await?;
+    let record: HelloRecord = DynStream::recv(&mut recv).await?.into_owned().await?;
+
+    // verify data
+    let peer = verify_record(&record)?;
+    if &peer == client.account_ref() {
+        return Err(IpiisError::SelfConnection(peer.to_string()).into());
+    }
+    sign.verify(Some(&peer))?;
+    if sign.data != address {
+        bail!("the peer has signed another record: {peer}");
+    }
+
+    Ok(record)
+}
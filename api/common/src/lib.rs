@@ -1,4 +1,11 @@
 pub extern crate ipiis_modules_router as router;
 
+pub mod auth;
+pub mod client;
+pub mod config;
+pub mod drain;
+pub mod expiry;
 pub mod flag;
+pub mod hello;
+pub mod retry;
 pub mod server;
@@ -0,0 +1,106 @@
+use core::time::Duration;
+use std::io;
+
+use ipiis_common::{IpiisError, RetryBudget};
+use ipis::{
+    core::anyhow::{Error, Result},
+    futures::Future,
+    tokio,
+};
+
+/// The default bound of establishing a connection,
+/// so that the dead addresses are given up quickly rather than after the OS does.
+pub const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Retry policy of the upstream address resolution.
+///
+/// Only the transient network failures are retried,
+/// e.g. failing to connect or the connections closed by the overloaded peers;
+/// the others, e.g. the errors raised by the primary ("no such account") and
+/// the unacknowledged non-idempotent requests, are returned immediately.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Maximum number of attempts, including the first one
+    pub attempts: u32,
+
+    /// Delay before the first retry, doubled on every retry
+    pub backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            attempts: 3,
+            backoff: Duration::from_millis(100),
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub const NONE: Self = Self {
+        attempts: 1,
+        backoff: Duration::ZERO,
+    };
+
+    /// Runs the function, retrying it on the network failures.
+    ///
+    /// Each retry takes a token of the budget if given, failing fast once it is exhausted.
+    pub async fn run<F, Fut, T>(&self, budget: Option<&RetryBudget>, mut f: F) -> Result<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let mut attempt = 1;
+        let mut backoff = self.backoff;
+        loop {
+            match f().await {
+                Ok(value) => break Ok(value),
+                Err(e)
+                    if attempt < self.attempts
+                        && is_retriable(&e)
+                        && budget.map_or(true, RetryBudget::try_acquire) =>
+                {
+                    // honor the delay suggested by the overloaded server
+                    let delay = match e.downcast_ref() {
+                        Some(IpiisError::RetryAfter(delay)) => backoff.max(*delay),
+                        _ => backoff,
+                    };
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                    backoff *= 2;
+                }
+                Err(e) => break Err(e),
+            }
+        }
+    }
+}
+
+/// Whether the error is a transient network failure, worth retrying.
+///
+/// The other errors, e.g. the ones raised by the primary or the malformed responses,
+/// are never retried, as they would fail the same way again.
+fn is_retriable(error: &Error) -> bool {
+    match error.downcast_ref() {
+        Some(IpiisError::ConnectTimeout(_) | IpiisError::RetryAfter(_)) => return true,
+        Some(IpiisError::ConnectionClosed(code)) => return code.is_retriable(),
+        Some(_) => return false,
+        None => (),
+    }
+
+    error
+        .chain()
+        .filter_map(|e| e.downcast_ref::<io::Error>())
+        .any(|e| {
+            matches!(
+                e.kind(),
+                io::ErrorKind::ConnectionRefused
+                    | io::ErrorKind::ConnectionReset
+                    | io::ErrorKind::ConnectionAborted
+                    | io::ErrorKind::NotConnected
+                    | io::ErrorKind::AddrNotAvailable
+                    | io::ErrorKind::BrokenPipe
+                    | io::ErrorKind::TimedOut
+                    | io::ErrorKind::Interrupted
+            )
+        })
+}
@@ -7,7 +7,7 @@ macro_rules! impl_ipiis_server {
         const _: () = {
             use std::sync::Arc;
 
-            use ipiis_common::{handle_external_call, Ipiis, ServerResult};
+            use ipiis_common::{handle_external_call, Ipiis, IpiisError, ServerResult};
             use ipis::core::anyhow::Result;
 
             impl AsRef<Self> for $client {
@@ -37,6 +37,17 @@ macro_rules! impl_ipiis_server {
                     GetAddress => handle_get_address,
                     SetAddress => handle_set_address,
                     DeleteAddress => handle_delete_address,
+                    Ping => handle_ping,
+                    ListAccounts => handle_list_accounts,
+                    SnapshotPrimaries => handle_snapshot_primaries,
+                    ListRevokedAccounts => handle_list_revoked_accounts,
+                    Capabilities => handle_capabilities,
+                },
+                request_raw: ::ipiis_common::io => {
+                    Hello => handle_hello,
+                },
+                request_stream: ::ipiis_common::io => {
+                    Sink => handle_sink,
                 },
             );
 
@@ -47,6 +58,21 @@ macro_rules! impl_ipiis_server {
                     self.run(client, Self::__handle::<$client>).await
                 }
 
+                /// Runs [`Self::run_ipiis`] until the signal,
+                /// and then drains the requests in flight and flushes the address book.
+                pub async fn run_ipiis_with_shutdown<S>(
+                    self: Arc<Self>,
+                    signal: S,
+                ) -> Result<$crate::router::MaintenanceReport>
+                where
+                    S: ::ipis::futures::Future<Output = ()> + Send,
+                {
+                    let client = self.clone();
+
+                    self.run_with_shutdown(client, Self::__handle::<$client>, signal)
+                        .await
+                }
+
                 async fn handle_get_account_primary(
                     client: &$server,
                     req: ::ipiis_common::io::request::GetAccountPrimary<
@@ -63,11 +89,16 @@ macro_rules! impl_ipiis_server {
                     let sign_as_guarantee = req.__sign.into_owned().await?;
 
                     // unpack data
-                    let kind = &sign_as_guarantee.data;
+                    let (kind, depth) = sign_as_guarantee.data;
 
-                    // handle data
-                    let account = client.get_account_primary(kind.as_ref()).await?;
-                    let address = client.router.get(kind.as_ref(), &account)?;
+                    // handle data, forwarding the lookup one level deeper
+                    let account = ::ipiis_common::with_resolution_depth(
+                        depth,
+                        client.get_account_primary(kind.as_ref()),
+                    )
+                    .await?;
+                    let address = client.state.router.get(kind.as_ref(), &account)?;
+                    let ttl_ms = client.state.time_policy.cache_record_ttl_ms();
 
                     // sign data
                     let sign = client.sign_as_guarantor(sign_as_guarantee)?;
@@ -78,6 +109,7 @@ macro_rules! impl_ipiis_server {
                         __sign: ::ipis::stream::DynStream::Owned(sign),
                         account: ::ipis::stream::DynStream::Owned(account),
                         address: ::ipis::stream::DynStream::Owned(address),
+                        ttl_ms: ::ipis::stream::DynStream::Owned(ttl_ms),
                     })
                 }
 
@@ -88,8 +120,11 @@ macro_rules! impl_ipiis_server {
                     // unpack sign
                     let sign_as_guarantee = req.__sign.into_owned().await?;
 
-                    // verify as root
-                    sign_as_guarantee.metadata.ensure_self_signed()?;
+                    // verify as an admin
+                    let guarantee = &sign_as_guarantee.metadata.guarantee;
+                    if !client.is_admin(guarantee) {
+                        return Err(IpiisError::Unauthorized(guarantee.to_string()).into());
+                    }
 
                     // unpack data
                     let kind = sign_as_guarantee.data.0;
@@ -115,8 +150,11 @@ macro_rules! impl_ipiis_server {
                     // unpack sign
                     let sign_as_guarantee = req.__sign.into_owned().await?;
 
-                    // verify as root
-                    sign_as_guarantee.metadata.ensure_self_signed()?;
+                    // verify as an admin
+                    let guarantee = &sign_as_guarantee.metadata.guarantee;
+                    if !client.is_admin(guarantee) {
+                        return Err(IpiisError::Unauthorized(guarantee.to_string()).into());
+                    }
 
                     // unpack data
                     let kind = sign_as_guarantee.data;
@@ -152,6 +190,7 @@ macro_rules! impl_ipiis_server {
 
                     // handle data
                     let address = client.get_address(kind.as_ref(), &account).await?;
+                    let ttl_ms = client.state.time_policy.cache_record_ttl_ms();
 
                     // sign data
                     let sign = client.sign_as_guarantor(sign_as_guarantee)?;
@@ -161,6 +200,7 @@ macro_rules! impl_ipiis_server {
                         __lifetime: Default::default(),
                         __sign: ::ipis::stream::DynStream::Owned(sign),
                         address: ::ipis::stream::DynStream::Owned(address),
+                        ttl_ms: ::ipis::stream::DynStream::Owned(ttl_ms),
                     })
                 }
 
@@ -176,8 +216,11 @@ macro_rules! impl_ipiis_server {
                     // unpack sign
                     let sign_as_guarantee = req.__sign.into_owned().await?;
 
-                    // verify as root
-                    sign_as_guarantee.metadata.ensure_self_signed()?;
+                    // verify as an admin
+                    let guarantee = &sign_as_guarantee.metadata.guarantee;
+                    if !client.is_admin(guarantee) {
+                        return Err(IpiisError::Unauthorized(guarantee.to_string()).into());
+                    }
 
                     // unpack data
                     let kind = sign_as_guarantee.data.0;
@@ -204,8 +247,11 @@ macro_rules! impl_ipiis_server {
                     // unpack sign
                     let sign_as_guarantee = req.__sign.into_owned().await?;
 
-                    // verify as root
-                    sign_as_guarantee.metadata.ensure_self_signed()?;
+                    // verify as an admin
+                    let guarantee = &sign_as_guarantee.metadata.guarantee;
+                    if !client.is_admin(guarantee) {
+                        return Err(IpiisError::Unauthorized(guarantee.to_string()).into());
+                    }
 
                     // unpack data
                     let kind = sign_as_guarantee.data.0;
@@ -223,6 +269,232 @@ macro_rules! impl_ipiis_server {
                         __sign: ::ipis::stream::DynStream::Owned(sign),
                     })
                 }
+
+                async fn handle_ping(
+                    client: &$server,
+                    req: ::ipiis_common::io::request::Ping<'static>,
+                ) -> Result<::ipiis_common::io::response::Ping<'static>> {
+                    // unpack sign
+                    let sign_as_guarantee = req.__sign.into_owned().await?;
+
+                    // sign data
+                    let sign = client.sign_as_guarantor(sign_as_guarantee)?;
+
+                    // pack data
+                    Ok(::ipiis_common::io::response::Ping {
+                        __lifetime: Default::default(),
+                        __sign: ::ipis::stream::DynStream::Owned(sign),
+                    })
+                }
+
+                async fn handle_list_accounts(
+                    client: &$server,
+                    req: ::ipiis_common::io::request::ListAccounts<'static>,
+                ) -> Result<::ipiis_common::io::response::ListAccounts<'static>> {
+                    // unpack sign
+                    let sign_as_guarantee = req.__sign.into_owned().await?;
+
+                    // verify as an authorized account
+                    let account = &sign_as_guarantee.metadata.guarantee;
+                    if !client.is_authorized(account) {
+                        return Err(IpiisError::Unauthorized(account.to_string()).into());
+                    }
+
+                    // unpack data
+                    let kind = sign_as_guarantee.data;
+
+                    // handle data
+                    let accounts = client.list_accounts(kind.as_ref()).await?.into();
+
+                    // sign data
+                    let sign = client.sign_as_guarantor(sign_as_guarantee)?;
+
+                    // pack data
+                    Ok(::ipiis_common::io::response::ListAccounts {
+                        __lifetime: Default::default(),
+                        __sign: ::ipis::stream::DynStream::Owned(sign),
+                        accounts: ::ipis::stream::DynStream::Owned(accounts),
+                    })
+                }
+
+                async fn handle_snapshot_primaries(
+                    client: &$server,
+                    req: ::ipiis_common::io::request::SnapshotPrimaries<
+                        'static,
+                        <$client as Ipiis>::Address,
+                    >,
+                ) -> Result<
+                    ::ipiis_common::io::response::SnapshotPrimaries<
+                        'static,
+                        <$client as Ipiis>::Address,
+                    >,
+                > {
+                    // unpack sign
+                    let sign_as_guarantee = req.__sign.into_owned().await?;
+
+                    // verify as an authorized account
+                    let account = &sign_as_guarantee.metadata.guarantee;
+                    if !client.is_authorized(account) {
+                        return Err(IpiisError::Unauthorized(account.to_string()).into());
+                    }
+
+                    // handle data
+                    let primaries = client.snapshot_primaries().await?;
+
+                    // sign data
+                    let sign = client.sign_as_guarantor(sign_as_guarantee)?;
+
+                    // pack data
+                    Ok(::ipiis_common::io::response::SnapshotPrimaries {
+                        __lifetime: Default::default(),
+                        __sign: ::ipis::stream::DynStream::Owned(sign),
+                        primaries: ::ipis::stream::DynStream::Owned(primaries),
+                    })
+                }
+
+                async fn handle_list_revoked_accounts(
+                    client: &$server,
+                    req: ::ipiis_common::io::request::ListRevokedAccounts<'static>,
+                ) -> Result<::ipiis_common::io::response::ListRevokedAccounts<'static>> {
+                    // unpack sign
+                    let sign_as_guarantee = req.__sign.into_owned().await?;
+
+                    // handle data
+                    let accounts = client
+                        .revocation_list()
+                        .map(|list| list.to_vec())
+                        .unwrap_or_default()
+                        .into();
+
+                    // sign data
+                    let sign = client.sign_as_guarantor(sign_as_guarantee)?;
+
+                    // pack data
+                    Ok(::ipiis_common::io::response::ListRevokedAccounts {
+                        __lifetime: Default::default(),
+                        __sign: ::ipis::stream::DynStream::Owned(sign),
+                        accounts: ::ipis::stream::DynStream::Owned(accounts),
+                    })
+                }
+
+                async fn handle_capabilities(
+                    client: &$server,
+                    req: ::ipiis_common::io::request::Capabilities<'static>,
+                ) -> Result<::ipiis_common::io::response::Capabilities<'static>> {
+                    // unpack sign
+                    let sign_as_guarantee = req.__sign.into_owned().await?;
+
+                    // handle data
+                    let opcodes = Self::supported_opcodes()
+                        .iter()
+                        .map(ToString::to_string)
+                        .collect();
+
+                    // sign data
+                    let sign = client.sign_as_guarantor(sign_as_guarantee)?;
+
+                    // pack data
+                    Ok(::ipiis_common::io::response::Capabilities {
+                        __lifetime: Default::default(),
+                        __sign: ::ipis::stream::DynStream::Owned(sign),
+                        opcodes: ::ipis::stream::DynStream::Owned(opcodes),
+                    })
+                }
+
+                async fn handle_sink<R>(
+                    client: &$server,
+                    req: ::ipiis_common::io::request::Sink<'static>,
+                    mut stream: ::ipiis_common::InputStream<R>,
+                ) -> Result<::ipiis_common::io::response::Sink<'static>>
+                where
+                    R: ::ipis::tokio::io::AsyncRead + Send + Unpin,
+                {
+                    // unpack sign
+                    let sign_as_guarantee = req.__sign.into_owned().await?;
+
+                    // handle data
+                    let received = stream.drain().await?;
+
+                    // sign data
+                    let sign = client.sign_as_guarantor(sign_as_guarantee)?;
+
+                    // pack data
+                    Ok(::ipiis_common::io::response::Sink {
+                        __lifetime: Default::default(),
+                        __sign: ::ipis::stream::DynStream::Owned(sign),
+                        received: ::ipis::stream::DynStream::Owned(received),
+                    })
+                }
+
+                async fn handle_hello(
+                    client: &$server,
+                    mut recv: impl ::ipis::tokio::io::AsyncRead + Send + Unpin + 'static,
+                ) -> Result<
+                    ::ipiis_common::io::response::Hello<'static, <$client as Ipiis>::Address>,
+                > {
+                    // recv request, signed by the peer itself
+                    let req = ::ipiis_common::try_recv_request(
+                        ::ipiis_common::io::request::Hello::decode(&mut recv),
+                    )
+                    .await?;
+
+                    // unpack sign
+                    let sign_as_guarantee = req.__sign.into_owned().await?;
+
+                    // verify data, auditing the failure
+                    let account = match $crate::hello::verify_record(&sign_as_guarantee)
+                        .and_then(|account| {
+                            client
+                                .time_policy
+                                .verify_signed_at(&sign_as_guarantee.metadata.created_date)
+                                .map(|()| account)
+                        }) {
+                        Ok(account) => account,
+                        Err(e) => {
+                            if let Some(audit) = client.verification_audit() {
+                                audit.report(::ipiis_common::VerificationFailure {
+                                    account: sign_as_guarantee.metadata.guarantee,
+                                    opcode: "Hello",
+                                    reason: e.to_string(),
+                                });
+                            }
+                            return Err(e);
+                        }
+                    };
+                    ::ipiis_common::MeteredRequest::identify(&account)?;
+                    if let Some(list) = client.revocation_list() {
+                        if list.is_revoked(&account) {
+                            return Err(IpiisError::Revoked(account.to_string()).into());
+                        }
+                    }
+
+                    // handle data, refusing to overwrite the address without the authorization
+                    if let Some(address) = &sign_as_guarantee.data {
+                        let existing = client.state.router.get(None, &account)?;
+                        if existing.as_ref() != Some(address) {
+                            let is_registrable = client.is_authorized(&account)
+                                || (client.state.hello_registration && existing.is_none());
+                            if !is_registrable {
+                                return Err(IpiisError::Unauthorized(account.to_string()).into());
+                            }
+                            client.state.router.set(None, &account, address)?;
+                        }
+                    }
+
+                    // sign data
+                    let record = client.sign_owned(
+                        *client.account_ref(),
+                        client.state.advertised_address.clone(),
+                    )?;
+                    let sign = client.sign_as_guarantor(sign_as_guarantee)?;
+
+                    // pack data
+                    Ok(::ipiis_common::io::response::Hello {
+                        __lifetime: Default::default(),
+                        __sign: ::ipis::stream::DynStream::Owned(sign),
+                        record: ::ipis::stream::DynStream::Owned(record),
+                    })
+                }
             }
         };
     };
@@ -232,7 +232,7 @@ impl PingPongServer {
 }
 
 define_io! {
-    Ok {
+    Ok = 0 {
         inputs: {
             name: String,
             age: u32,
@@ -244,7 +244,7 @@ define_io! {
         output_sign: Data<GuarantorSigned, u8>,
         generics: { },
     },
-    Err {
+    Err = 1 {
         inputs: {
             name: String,
             age: u32,
@@ -256,7 +256,7 @@ define_io! {
         output_sign: Data<GuarantorSigned, u8>,
         generics: { },
     },
-    Raw {
+    Raw = 2 {
         inputs: {
             name: String,
             age: u32,
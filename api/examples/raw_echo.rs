@@ -0,0 +1,58 @@
+use core::time::Duration;
+use std::sync::Arc;
+
+use ipiis_api::{
+    client::IpiisClient,
+    common::{recv_server_result, send_raw_request_header, Ipiis, ServerResult},
+    server::IpiisServer,
+};
+use ipis::{
+    core::anyhow::Result,
+    env::Infer,
+    tokio::{
+        self,
+        io::{AsyncReadExt, AsyncWriteExt},
+    },
+};
+
+/// An opcode beyond the ones of the directory
+const OPCODE_ECHO: u16 = 0x100;
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    // init a server echoing the raw requests back
+    let server = Arc::new(IpiisServer::genesis(5004).await?);
+    server.on_raw(OPCODE_ECHO, |_client, mut recv| async move {
+        let len = recv.read_u8().await?;
+        let mut msg = vec![0; len as usize];
+        recv.read_exact(&mut msg).await?;
+
+        Ok([&[ServerResult::ACK_OK.bits(), len], msg.as_slice()].concat())
+    });
+
+    let server_ref = *server.account_ref();
+    tokio::spawn(server.run_ipiis());
+    tokio::time::sleep(Duration::from_secs(1)).await;
+
+    // init a client
+    let client = IpiisClient::genesis(None).await?;
+    client
+        .set_address(None, &server_ref, &"127.0.0.1:5004".parse()?)
+        .await?;
+
+    // send a raw request
+    let (mut send, mut recv) = client.call_raw(None, &server_ref).await?;
+    let msg = b"hello, world!";
+    send_raw_request_header(&client, &server_ref, OPCODE_ECHO, &mut send).await?;
+    send.write_u8(msg.len() as u8).await?;
+    send.write_all(msg).await?;
+    send.flush().await?;
+
+    // recv the echo
+    recv_server_result(&mut recv).await?;
+    let mut echo = vec![0; recv.read_u8().await? as usize];
+    recv.read_exact(&mut echo).await?;
+
+    assert_eq!(&echo, msg);
+    Ok(())
+}
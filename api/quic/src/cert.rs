@@ -1,20 +1,66 @@
-use std::{sync::Arc, time::SystemTime};
+use std::{
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
 
 use ipis::core::{
     account::{Account, AccountRef},
     anyhow::{anyhow, Result},
     ed25519_dalek::ed25519::{pkcs8::EncodePrivateKey, KeypairBytes},
 };
+use quinn::{ClientConfig, ServerConfig};
 use rustls::{
     client::{ServerCertVerified, ServerCertVerifier},
-    Certificate, Error, PrivateKey, ServerName,
+    server::{ClientCertVerified, ClientCertVerifier},
+    Certificate, DistinguishedNames, Error, PrivateKey, ServerName,
 };
+use x509_parser::{extensions::GeneralName, parse_x509_certificate};
+
+use crate::transport::{TransportOptions, DEFAULT_IDLE_TIMEOUT};
+
+/// Prefix of the DER-encoded Ed25519 public key (SubjectPublicKeyInfo)
+const ED25519_SPKI_PREFIX: &[u8] = &[
+    0x30, 0x2a, 0x30, 0x05, 0x06, 0x03, 0x2b, 0x65, 0x70, 0x03, 0x21, 0x00,
+];
+
+/// The name to dial a peer by its address only, accepting the certificate bound to any account.
+///
+/// The account of the peer is confirmed by the records exchanged right after, on `Hello`.
+pub(crate) const HELLO_NAME: &str = "hello.ipiis";
 
 pub fn get_name(account: &AccountRef) -> String {
     let account = account.to_string();
     format!("{account}.ipiis")
 }
 
+/// Extracts the account which the certificate is bound to.
+///
+/// The certificate should be named by [`get_name`], and hold the account's public key
+/// as its own SubjectPublicKeyInfo, not anywhere else in it.
+pub fn get_account(cert: &Certificate) -> Option<AccountRef> {
+    let (rest, cert) = parse_x509_certificate(&cert.0).ok()?;
+    if !rest.is_empty() {
+        return None;
+    }
+
+    // the public key
+    let key = cert.public_key().raw.strip_prefix(ED25519_SPKI_PREFIX)?;
+    let account = AccountRef::from_bytes(key).ok()?;
+
+    // the name
+    let name = get_name(&account);
+    cert.subject_alternative_name()
+        .ok()??
+        .value
+        .general_names
+        .iter()
+        .any(|general_name| match general_name {
+            GeneralName::DNSName(dns_name) => dns_name.eq_ignore_ascii_case(&name),
+            _ => false,
+        })
+        .then_some(account)
+}
+
 pub(crate) fn generate(account: &Account) -> Result<(PrivateKey, Vec<Certificate>)> {
     let keypair = KeypairBytes::from_bytes(&account.to_bytes())
         .to_pkcs8_der()
@@ -38,17 +84,188 @@ pub(crate) fn generate(account: &Account) -> Result<(PrivateKey, Vec<Certificate
     Ok((priv_key, cert_chain))
 }
 
+/// How the clients verify the server certificates.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum ServerAuth {
+    /// Accepts the certificates bound to the dialed account only.
+    #[default]
+    AccountBound,
+    /// Accepts any certificate.
+    ///
+    /// FIXME: such verification is vulnerable to MITM attacks, but convenient for testing.
+    #[cfg(feature = "insecure-dangerous")]
+    Insecure,
+}
+
+/// Builds the client config accepting the certificate bound to any account,
+/// e.g. to dial a peer by its address only on `Hello`.
+pub(crate) fn client_config(
+    account: Option<&Account>,
+    server_auth: ServerAuth,
+    transport: &TransportOptions,
+) -> Result<ClientConfig> {
+    client_config_with_verifier(account, server_auth, transport, ServerVerification::new())
+}
+
+/// Builds the client config of a connection to the target,
+/// verifying the certificate against the target whatever the server name (SNI) is,
+/// e.g. a hostname routed by a proxy.
+pub(crate) fn client_config_bound_to(
+    account: Option<&Account>,
+    server_auth: ServerAuth,
+    transport: &TransportOptions,
+    target: &AccountRef,
+) -> Result<ClientConfig> {
+    client_config_with_verifier(
+        account,
+        server_auth,
+        transport,
+        ServerVerification::bound_to(*target),
+    )
+}
+
+fn client_config_with_verifier(
+    account: Option<&Account>,
+    server_auth: ServerAuth,
+    transport: &TransportOptions,
+    account_bound: Arc<ServerVerification>,
+) -> Result<ClientConfig> {
+    let verifier: Arc<dyn ServerCertVerifier> = match server_auth {
+        ServerAuth::AccountBound => account_bound,
+        #[cfg(feature = "insecure-dangerous")]
+        ServerAuth::Insecure => InsecureServerVerification::new(),
+    };
+
+    let crypto = ::rustls::ClientConfig::builder()
+        .with_safe_defaults()
+        .with_custom_certificate_verifier(verifier);
+    let crypto = match account {
+        // present the account-derived certificate
+        Some(account) => {
+            let (priv_key, cert_chain) = generate(account)?;
+            crypto.with_single_cert(cert_chain, priv_key)?
+        }
+        None => crypto.with_no_client_auth(),
+    };
+
+    let mut config = ClientConfig::new(Arc::new(crypto));
+    config.transport = {
+        let mut config = Arc::try_unwrap(config.transport).unwrap();
+        config.max_idle_timeout(Some(DEFAULT_IDLE_TIMEOUT.try_into()?));
+        transport.apply(&mut config)?;
+        config.into()
+    };
+    Ok(config)
+}
+
+pub(crate) fn server_config(
+    account: &Account,
+    client_auth: bool,
+    migration: bool,
+    transport: &TransportOptions,
+) -> Result<ServerConfig> {
+    let (priv_key, cert_chain) = generate(account)?;
+
+    let mut config = if client_auth {
+        let mut crypto = ::rustls::ServerConfig::builder()
+            .with_safe_defaults()
+            .with_client_cert_verifier(ClientVerification::new())
+            .with_single_cert(cert_chain, priv_key)?;
+        crypto.max_early_data_size = u32::MAX;
+
+        ServerConfig::with_crypto(Arc::new(crypto))
+    } else {
+        ServerConfig::with_single_cert(cert_chain, priv_key)?
+    };
+    config.transport = {
+        let mut config = Arc::try_unwrap(config.transport).unwrap();
+        config.max_idle_timeout(Some(DEFAULT_IDLE_TIMEOUT.try_into()?));
+        config.keep_alive_interval(Some(Duration::from_secs(5)));
+        transport.apply(&mut config)?;
+        config.into()
+    };
+    config.migration(migration);
+    Ok(config)
+}
+
+/// Server certificate verifier that accepts the certificates bound to the dialed account only.
+///
+/// The handshake signature is verified by rustls with the certificate's key,
+/// so the server should own the account.
+pub(crate) struct ServerVerification {
+    /// The account dialed, regardless of the server name, or none if dialed by the address only
+    target: Option<AccountRef>,
+}
+
+impl ServerVerification {
+    pub(crate) fn new() -> Arc<Self> {
+        Arc::new(Self { target: None })
+    }
+
+    pub(crate) fn bound_to(target: AccountRef) -> Arc<Self> {
+        Arc::new(Self {
+            target: Some(target),
+        })
+    }
+}
+
+impl ServerCertVerifier for ServerVerification {
+    fn verify_server_cert(
+        &self,
+        end_entity: &Certificate,
+        _intermediates: &[Certificate],
+        server_name: &ServerName,
+        _scts: &mut dyn Iterator<Item = &[u8]>,
+        _ocsp_response: &[u8],
+        _now: SystemTime,
+    ) -> Result<ServerCertVerified, Error> {
+        // the server name is only used for routing
+        if let Some(target) = &self.target {
+            return match get_account(end_entity) {
+                Some(account) if &account == target => Ok(ServerCertVerified::assertion()),
+                _ => Err(Error::InvalidCertificateData(format!(
+                    "the certificate is not bound to the account: {target}"
+                ))),
+            };
+        }
+
+        let server_name = match server_name {
+            ServerName::DnsName(name) => name.as_ref(),
+            _ => {
+                return Err(Error::General(
+                    "the server should be named by its account".to_string(),
+                ))
+            }
+        };
+
+        match get_account(end_entity) {
+            Some(_) if server_name.eq_ignore_ascii_case(HELLO_NAME) => {
+                Ok(ServerCertVerified::assertion())
+            }
+            Some(account) if get_name(&account).eq_ignore_ascii_case(server_name) => {
+                Ok(ServerCertVerified::assertion())
+            }
+            _ => Err(Error::InvalidCertificateData(format!(
+                "the certificate is not bound to the account: {server_name}"
+            ))),
+        }
+    }
+}
+
 /// Dummy certificate verifier that treats any certificate as valid.
 /// FIXME: such verification is vulnerable to MITM attacks, but convenient for testing.
-pub(crate) struct ServerVerification;
+#[cfg(feature = "insecure-dangerous")]
+pub(crate) struct InsecureServerVerification;
 
-impl ServerVerification {
+#[cfg(feature = "insecure-dangerous")]
+impl InsecureServerVerification {
     pub(crate) fn new() -> Arc<Self> {
         Arc::new(Self)
     }
 }
 
-impl ServerCertVerifier for ServerVerification {
+#[cfg(feature = "insecure-dangerous")]
+impl ServerCertVerifier for InsecureServerVerification {
     fn verify_server_cert(
         &self,
         _end_entity: &Certificate,
@@ -61,3 +278,35 @@ impl ServerCertVerifier for ServerVerification {
         Ok(ServerCertVerified::assertion())
     }
 }
+
+/// Client certificate verifier that accepts the account-bound certificates only.
+///
+/// The handshake signature is verified by rustls with the certificate's key,
+/// so the client should own the account.
+pub(crate) struct ClientVerification;
+
+impl ClientVerification {
+    pub(crate) fn new() -> Arc<Self> {
+        Arc::new(Self)
+    }
+}
+
+impl ClientCertVerifier for ClientVerification {
+    fn client_auth_root_subjects(&self) -> Option<DistinguishedNames> {
+        Some(DistinguishedNames::new())
+    }
+
+    fn verify_client_cert(
+        &self,
+        end_entity: &Certificate,
+        _intermediates: &[Certificate],
+        _now: SystemTime,
+    ) -> Result<ClientCertVerified, Error> {
+        match get_account(end_entity) {
+            Some(_) => Ok(ClientCertVerified::assertion()),
+            None => Err(Error::InvalidCertificateData(
+                "the certificate is not bound to any account".to_string(),
+            )),
+        }
+    }
+}
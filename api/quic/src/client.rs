@@ -1,23 +1,89 @@
-use std::{net::ToSocketAddrs, sync::Arc, time::Duration};
+use std::{
+    collections::HashMap,
+    io,
+    net::{SocketAddr, ToSocketAddrs, UdpSocket},
+    sync::Arc,
+    time::Duration,
+};
 
-use ipiis_api_common::router::RouterClient;
-use ipiis_common::{external_call, Ipiis};
+use ipiis_api_common::{
+    client::ClientState,
+    config::{ClientConfigSummary, IpiisConfig},
+    hello,
+    retry::RetryPolicy,
+    router::{sled, MaintenanceReport, RouterClient},
+};
+use ipiis_common::{
+    external_call, next_resolution_depth, ByteMeter, CloseCode, ConnectionDiagnostics, Diagnostics,
+    HopInfo, Ipiis, IpiisError, RawHandlers, RequestBudget, RequestHandle, RequestRegistry,
+    RequestScheduler, ResponseCache, RetryBudget, RevocationList, TimePolicy, VerificationAudit,
+    WireCapture, CLIENT_DUMMY,
+};
 use ipis::{
     async_trait::async_trait,
     core::{
         account::{Account, AccountRef},
-        anyhow::{anyhow, bail, Result},
+        anyhow::{anyhow, bail, Error, Result},
         value::hash::Hash,
     },
-    env::{infer, Infer},
+    env::Infer,
+    futures::{FutureExt, Stream, StreamExt},
+    log::{debug, warn},
     resource::Resource,
+    tokio::{self, sync::Mutex},
+};
+use quinn::{ClientConfig, Connection, Endpoint, VarInt};
+
+use crate::{
+    cert::ServerAuth,
+    transport::{TransportOptions, DEFAULT_IDLE_TIMEOUT},
 };
-use quinn::{Connection, Endpoint};
+
+pub use ipiis_api_common::router::{BookChange, RouteBook};
+
+/// A connection kept open to a peer, with its address.
+struct PooledConnection {
+    address: String,
+    conn: Connection,
+    /// The streams initiated by the peer, telling whether the connection has been closed
+    incoming: quinn::IncomingBiStreams,
+}
+
+impl PooledConnection {
+    /// Returns why the connection has been closed, e.g. by the peer or the idle timeout.
+    fn close_reason(&mut self) -> Option<quinn::ConnectionError> {
+        loop {
+            match self.incoming.next().now_or_never() {
+                // the peers do not initiate any streams, so drop them
+                Some(Some(Ok(_))) => continue,
+                Some(Some(Err(e))) => break Some(e),
+                Some(None) => break Some(quinn::ConnectionError::LocallyClosed),
+                None => break None,
+            }
+        }
+    }
+}
 
 #[derive(Clone)]
 pub struct IpiisClient {
-    pub(crate) router: RouterClient<<Self as Ipiis>::Address>,
-    endpoint: Endpoint,
+    /// The address book and the options shared with the other transports
+    pub(crate) state: ClientState,
+    /// The server names (SNI) dialing the peers, overriding the account-derived ones
+    server_names: HashMap<AccountRef, String>,
+    /// The server name (SNI) dialing the other peers, overriding the account-derived ones
+    server_name: Option<String>,
+    /// The connections kept open to the peers, with their addresses
+    connections: Arc<Mutex<HashMap<(Option<Hash>, AccountRef), PooledConnection>>>,
+    client_auth: bool,
+    server_auth: ServerAuth,
+    transport: TransportOptions,
+    pub(crate) endpoint: Endpoint,
+}
+
+impl AsRef<ClientState> for IpiisClient {
+    fn as_ref(&self) -> &ClientState {
+        &self.state
+    }
 }
 
 #[async_trait]
@@ -26,22 +92,25 @@ impl<'a> Infer<'a> for IpiisClient {
     type GenesisResult = Self;
 
     async fn try_infer() -> Result<Self> {
-        let account_me = infer("ipis_account_me")?;
-        let account_primary = infer("ipiis_account_primary").ok();
+        let mut config = IpiisConfig::load()?;
+        let account_me = config.account_me()?;
 
-        Self::new(account_me, account_primary, None).await
+        Self::with_config(account_me, &config).await
     }
 
     async fn genesis(
         account_primary: <Self as Infer>::GenesisArgs,
     ) -> Result<<Self as Infer<'a>>::GenesisResult> {
-        let account_primary = account_primary.or_else(|| infer("ipiis_account_primary").ok());
+        let mut config = IpiisConfig::load()?;
+        if account_primary.is_some() {
+            config.account_primary = account_primary;
+        }
 
         // generate an account
         let account = Account::generate();
 
         // init an endpoint
-        Self::new(account, account_primary, None).await
+        Self::with_config(account, &config).await
     }
 }
 
@@ -54,44 +123,491 @@ impl IpiisClient {
         let endpoint = match endpoint {
             Some(endpoint) => endpoint,
             None => {
-                let crypto = ::rustls::ClientConfig::builder()
-                    .with_safe_defaults()
-                    .with_custom_certificate_verifier(crate::cert::ServerVerification::new())
-                    .with_no_client_auth();
-                let client_config = {
-                    let mut config = ::quinn::ClientConfig::new(Arc::new(crypto));
-                    config.transport = {
-                        let mut config = Arc::try_unwrap(config.transport).unwrap();
-                        config.max_idle_timeout(Some(Duration::from_secs(10).try_into()?));
-                        config.into()
-                    };
-                    config
-                };
-
                 let addr = "0.0.0.0:0".parse()?;
 
-                let mut endpoint = Endpoint::client(addr)?;
-                endpoint.set_default_client_config(client_config);
-
-                endpoint
+                Endpoint::client(addr)?
             }
         };
 
-        let client = Self {
-            router: RouterClient::new(account_me)?,
+        Self::with_endpoint(account_me, account_primary, endpoint).await
+    }
+
+    /// Creates a client on a fully-configured endpoint,
+    /// e.g. bound to a pre-opened UDP socket with custom options.
+    ///
+    /// Note that the default client config of the endpoint is not used:
+    /// each client dials with its own one, so the clones never share an identity.
+    pub async fn with_endpoint(
+        account_me: Account,
+        account_primary: Option<AccountRef>,
+        endpoint: Endpoint,
+    ) -> Result<Self> {
+        Self::with_router(
+            RouterClient::new(account_me)?,
+            account_primary,
+            None,
             endpoint,
+        )
+        .await
+    }
+
+    /// Creates a client as [`Self::new`], registering the address of the primary account as well.
+    async fn with_config(account_me: Account, config: &IpiisConfig) -> Result<Self> {
+        let addr = "0.0.0.0:0".parse()?;
+        let endpoint = Endpoint::client(addr)?;
+
+        Self::with_router(
+            RouterClient::new(account_me)?,
+            config.account_primary,
+            config.account_primary_address.clone(),
+            endpoint,
+        )
+        .await
+    }
+
+    /// Creates a client on an already-opened address book, e.g. [`Self::book`] of another one,
+    /// so that several endpoints of a process share a single routing table.
+    ///
+    /// The client acts as the account of the book, which can be rebound with
+    /// [`RouterClient::with_account`].
+    pub async fn with_book(
+        account_primary: Option<AccountRef>,
+        endpoint: Option<Endpoint>,
+        book: RouterClient<<Self as Ipiis>::Address>,
+    ) -> Result<Self> {
+        let endpoint = match endpoint {
+            Some(endpoint) => endpoint,
+            None => {
+                let addr = "0.0.0.0:0".parse()?;
+
+                Endpoint::client(addr)?
+            }
         };
 
-        // try to add the primary account's address
-        if let Some(account_primary) = account_primary {
-            client.router.set_primary(None, &account_primary)?;
+        Self::with_router(book, account_primary, None, endpoint).await
+    }
+
+    pub(crate) async fn with_router(
+        router: RouterClient<<Self as Ipiis>::Address>,
+        account_primary: Option<AccountRef>,
+        account_primary_address: Option<String>,
+        endpoint: Endpoint,
+    ) -> Result<Self> {
+        Ok(Self {
+            state: ClientState::new(router, account_primary, account_primary_address)?,
+            server_names: Default::default(),
+            server_name: None,
+            connections: Default::default(),
+            client_auth: false,
+            server_auth: Default::default(),
+            transport: Default::default(),
+            endpoint,
+        })
+    }
+
+    /// Rebinds the identity of the client.
+    ///
+    /// It is cheap: the address book and the other options are reused,
+    /// so only the following requests are signed by the new account.
+    ///
+    /// The endpoint is reused as well, but the connections are not:
+    /// the client dials with its own config, presenting the certificate of the new account
+    /// if [`Self::with_client_auth`] is enabled, so the other clones keep their identity.
+    pub fn with_account(mut self, account_me: Account) -> Result<Self> {
+        self.state = self.state.with_account(account_me);
+        self.connections = Default::default();
+        Ok(self)
+    }
+
+    /// Presents the account-derived certificate to the servers (mutual TLS).
+    ///
+    /// It is required by the servers built with
+    /// [`crate::server::IpiisServer::with_client_auth_required`].
+    pub fn with_client_auth(mut self) -> Result<Self> {
+        self.client_auth = true;
+        self.connections = Default::default();
+        Ok(self)
+    }
+
+    /// Accepts any server certificate, without checking the account binding.
+    ///
+    /// FIXME: such verification is vulnerable to MITM attacks, but convenient for testing.
+    #[cfg(feature = "insecure-dangerous")]
+    pub fn with_insecure_server_auth(mut self) -> Result<Self> {
+        self.server_auth = ServerAuth::Insecure;
+        self.connections = Default::default();
+        Ok(self)
+    }
+
+    fn client_account(&self) -> Option<&Account> {
+        if self.client_auth {
+            Some(&*self.state.router.account_me)
+        } else {
+            None
+        }
+    }
+
+    /// Moves the endpoint to a new UDP socket, e.g. after a network change.
+    ///
+    /// The pooled connections migrate to the new address without reconnecting,
+    /// unless the server disables migration. The peers are pinged right after,
+    /// so the migration completes well within the idle timeout (10 seconds by default).
+    ///
+    /// Note that a server shares the socket with its client,
+    /// so rebinding a server moves its listening address as well.
+    pub fn rebind(&self, socket: UdpSocket) -> Result<()> {
+        self.endpoint.rebind(socket).map_err(Into::into)
+    }
+
+    /// Sets the flow control windows and the idle timeout of the outgoing connections.
+    pub fn with_transport(mut self, transport: TransportOptions) -> Result<Self> {
+        self.transport = transport;
+        self.connections = Default::default();
+        Ok(self)
+    }
+
+    /// Returns the flow control windows and the idle timeout of the outgoing connections.
+    pub fn transport(&self) -> &TransportOptions {
+        &self.transport
+    }
+
+    /// Flushes the address book periodically in background (opt-in).
+    ///
+    /// It can also be enabled by `ipiis_router_flush_interval_ms`.
+    pub fn with_flush_interval(mut self, interval: Duration) -> Result<Self> {
+        self.state = self.state.with_flush_interval(interval)?;
+        Ok(self)
+    }
+
+    /// Sets the retry policy of the upstream address resolution.
+    pub fn with_resolve_retry(mut self, policy: RetryPolicy) -> Self {
+        self.state = self.state.with_resolve_retry(policy);
+        self
+    }
+
+    /// Seeds the complete routing table, resolving the accounts only from it.
+    ///
+    /// The primaries are never asked for the addresses and the kinds' primary accounts,
+    /// so a miss is a hard error, e.g. for the edge nodes with a fixed topology.
+    pub fn with_static_routes(mut self, book: &RouteBook) -> Result<Self> {
+        self.state = self.state.with_static_routes(book)?;
+        Ok(self)
+    }
+
+    /// Bounds the total volume of the retries across all the calls,
+    /// failing fast instead of retrying once the budget is exhausted.
+    pub fn with_retry_budget(mut self, budget: RetryBudget) -> Self {
+        self.state = self.state.with_retry_budget(budget);
+        self
+    }
+
+    /// Bounds establishing a connection to the peers, failing with
+    /// [`IpiisError::ConnectTimeout`] beyond it, e.g. if the address is dead.
+    ///
+    /// The default is [`DEFAULT_CONNECT_TIMEOUT`](ipiis_api_common::retry::DEFAULT_CONNECT_TIMEOUT).
+    pub fn with_connect_timeout(mut self, timeout: Duration) -> Self {
+        self.state = self.state.with_connect_timeout(timeout);
+        self
+    }
+
+    /// Sets the time-sensitive behaviors, e.g. the tolerance of the clock skew
+    /// verifying the requests when serving, and the lifetime of the cached records.
+    pub fn with_time_policy(mut self, policy: TimePolicy) -> Self {
+        self.state = self.state.with_time_policy(policy);
+        self
+    }
+
+    /// Dials all the peers by the given server name (SNI) instead of the account-derived one,
+    /// e.g. if a proxy in front of them routes by a real hostname.
+    ///
+    /// The certificates are still verified against the dialed accounts.
+    pub fn with_server_name(mut self, server_name: impl Into<String>) -> Self {
+        self.server_name = Some(server_name.into());
+        self
+    }
+
+    /// Dials the target by the given server name (SNI) instead of the account-derived one,
+    /// taking precedence over [`Self::with_server_name`].
+    ///
+    /// The certificate is still verified against the target.
+    pub fn with_server_name_for(
+        mut self,
+        target: AccountRef,
+        server_name: impl Into<String>,
+    ) -> Self {
+        self.server_names.insert(target, server_name.into());
+        self
+    }
+
+    /// Bounds the primaries forwarding a lookup of the kind's primary account,
+    /// failing with [`IpiisError::KindResolutionTooDeep`] beyond it,
+    /// e.g. if the primaries form a loop.
+    ///
+    /// The default is [`DEFAULT_MAX_RESOLUTION_DEPTH`](ipiis_common::DEFAULT_MAX_RESOLUTION_DEPTH).
+    pub fn with_max_resolution_depth(mut self, depth: u8) -> Self {
+        self.state = self.state.with_max_resolution_depth(depth);
+        self
+    }
+
+    /// Advertises the address to the peers on [`Self::hello`], e.g. the public address of a server.
+    pub fn with_advertised_address(mut self, address: <Self as Ipiis>::Address) -> Self {
+        self.state = self.state.with_advertised_address(address);
+        self
+    }
+
+    /// Returns the local address of the endpoint.
+    ///
+    /// When embedded in a server, it is the listening address,
+    /// as the incoming and outgoing connections share the same UDP socket.
+    pub fn local_addr(&self) -> Result<SocketAddr> {
+        self.endpoint.local_addr().map_err(Into::into)
+    }
+
+    /// Passes the exact bytes of each request sent to the callback,
+    /// e.g. to dump them for a later replay with [`ipiis_common::replay_bytes`].
+    pub fn with_wire_capture<F>(mut self, callback: F) -> Self
+    where
+        F: Fn(&[u8]) + Send + Sync + 'static,
+    {
+        self.state = self.state.with_wire_capture(callback);
+        self
+    }
+
+    /// Connects to a peer by its address only, e.g. to bootstrap,
+    /// exchanging the signed records of the advertised addresses,
+    /// so that both peers can resolve each other afterwards.
+    ///
+    /// The peer is stored with its advertised address, or the dialed one if it has none.
+    /// Returns the account of the peer.
+    pub async fn hello(&self, address: &<Self as Ipiis>::Address) -> Result<AccountRef> {
+        // connect to the peer, whose account is not known yet
+        let config =
+            crate::cert::client_config(self.client_account(), self.server_auth, &self.transport)?;
+        let quinn::NewConnection {
+            connection: conn, ..
+        } = self
+            .connect(address, crate::cert::HELLO_NAME, config)
+            .await?;
+        let (send, recv) = conn
+            .open_bi()
+            .await
+            .map_err(|e| anyhow!("failed to open stream: {e}"))?;
+
+        // exchange the records
+        let record =
+            hello::exchange(self, send, recv, self.state.advertised_address.clone()).await?;
+        let account = record.metadata.guarantee;
 
-            if let Ok(address) = infer("ipiis_account_primary_address") {
-                client.router.set(None, &account_primary, &address)?;
+        // the certificate should be bound to the same account
+        let bound = conn
+            .peer_identity()
+            .and_then(|certs| certs.downcast::<Vec<::rustls::Certificate>>().ok())
+            .and_then(|certs| certs.first().and_then(crate::cert::get_account));
+        if bound != Some(account) {
+            bail!("the certificate is not bound to the account: {account}");
+        }
+
+        // store response
+        self.state
+            .router
+            .set(None, &account, record.data.as_ref().unwrap_or(address))?;
+        Ok(account)
+    }
+
+    /// Pre-populates the address book from a directory server,
+    /// returning the number of the cached addresses.
+    pub async fn warm_from(&self, source: &AccountRef, kinds: &[Option<Hash>]) -> Result<usize> {
+        ipiis_api_common::client::warm_from(self, source, kinds).await
+    }
+
+    /// Returns the whole primary table with the known addresses,
+    /// asking the root if there is one.
+    ///
+    /// The root only answers the accounts approved by its authorizer.
+    pub async fn snapshot_primaries(
+        &self,
+    ) -> Result<Vec<(Option<Hash>, AccountRef, Option<<Self as Ipiis>::Address>)>> {
+        match self.state.router.get_primary(None)? {
+            // ask the root
+            Some(primary) if !self.state.serving => {
+                // external call
+                let (primaries,) = external_call!(
+                    client: self,
+                    target: None => &primary,
+                    request: ::ipiis_common::io => SnapshotPrimaries,
+                    sign: self.sign_owned(primary, CLIENT_DUMMY)?,
+                    inputs: { },
+                    outputs: { primaries, },
+                );
+
+                // unpack response
+                Ok(primaries)
             }
+            _ => self.state.list_local_primaries(),
         }
+    }
 
-        Ok(client)
+    /// Fetches the accounts revoked by the authority.
+    pub async fn list_revoked_accounts(&self, authority: &AccountRef) -> Result<Vec<AccountRef>> {
+        // external call
+        let (accounts,) = external_call!(
+            client: self,
+            target: None => authority,
+            request: ::ipiis_common::io => ListRevokedAccounts,
+            sign: self.sign_owned(*authority, CLIENT_DUMMY)?,
+            inputs: { },
+            outputs: { accounts, },
+        );
+
+        // unpack response
+        Ok(accounts.into_vec())
+    }
+
+    /// Fetches the names of the opcodes handled by the target,
+    /// e.g. to check whether it supports a request before sending it.
+    pub async fn capabilities(&self, target: &AccountRef) -> Result<Vec<String>> {
+        // external call
+        let (opcodes,) = external_call!(
+            client: self,
+            target: None => target,
+            request: ::ipiis_common::io => Capabilities,
+            sign: self.sign_owned(*target, CLIENT_DUMMY)?,
+            inputs: { },
+            outputs: { opcodes, },
+        );
+
+        // unpack response
+        Ok(opcodes)
+    }
+
+    /// Resolves the address from the primary account like `get_address`,
+    /// but bypassing the local address book, e.g. to measure the lookup latency.
+    ///
+    /// The resolved address is stored as usual.
+    pub async fn get_address_uncached(
+        &self,
+        kind: Option<&Hash>,
+        target: &AccountRef,
+    ) -> Result<<Self as Ipiis>::Address> {
+        if self.state.static_routing {
+            bail!("no upstream to resolve the address of {target}: static routing");
+        }
+
+        match self.state.router.get_primary(None)? {
+            Some(primary) => {
+                self.state.ensure_primary_address(&primary)?;
+                ipiis_api_common::client::fetch_address(self, kind, target, primary).await
+            }
+            None => Err(IpiisError::NotFound(format!("address of {target}")).into()),
+        }
+    }
+
+    /// Traces the resolution of the address along the primaries, like `traceroute`,
+    /// asking each primary for the target.
+    ///
+    /// It stops at the root, or at the first primary which has not answered.
+    pub async fn trace_address(
+        &self,
+        kind: Option<&Hash>,
+        target: &AccountRef,
+    ) -> Result<Vec<HopInfo>> {
+        ipiis_api_common::client::trace_address(self, kind, target).await
+    }
+
+    /// Whether the account may issue the protected requests, e.g. `SnapshotPrimaries`.
+    ///
+    /// The account of this client is always authorized,
+    /// and the others only by the authorizer.
+    pub fn is_authorized(&self, account: &AccountRef) -> bool {
+        self.state.is_authorized(account)
+    }
+
+    /// Whether the account may mutate the directory, e.g. `SetAddress`.
+    ///
+    /// The account of this client is always an admin.
+    pub fn is_admin(&self, account: &AccountRef) -> bool {
+        self.state.is_admin(account)
+    }
+
+    /// Lists the kinds which the account is primary for, in the local address book.
+    ///
+    /// Note that it scans all primary entries: O(n).
+    pub fn kinds_for_primary(&self, account: &AccountRef) -> Result<Vec<Option<Hash>>> {
+        self.state.kinds_for_primary(account)
+    }
+
+    /// Returns the outgoing requests waiting for the responses, in the order of issue.
+    pub fn in_flight(&self) -> Vec<RequestHandle> {
+        self.state.in_flight()
+    }
+
+    /// Aborts the in-flight request, which fails with `IpiisError::Cancelled`.
+    ///
+    /// Returns `false` if there is no such request.
+    pub fn cancel(&self, id: u64) -> bool {
+        self.state.cancel(id)
+    }
+
+    /// Returns the effective configuration of the client, e.g. for debugging.
+    pub fn config_summary(&self) -> ClientConfigSummary {
+        let idle_timeout = self.transport.idle_timeout.unwrap_or(DEFAULT_IDLE_TIMEOUT);
+        self.state
+            .config_summary(self.protocol(), Some(idle_timeout))
+    }
+
+    /// Returns the local address book, bypassing the directory.
+    ///
+    /// It is useful to seed a node before any network exists.
+    pub fn book(&self) -> &RouterClient<<Self as Ipiis>::Address> {
+        self.state.book()
+    }
+
+    /// Opens an auxiliary tree sharing the database of the address book.
+    ///
+    /// Note that the callers own the schema of their tree.
+    pub fn open_tree(&self, name: impl AsRef<[u8]>) -> Result<sled::Tree> {
+        self.state.open_tree(name)
+    }
+
+    /// Watches the local mutations of the address book, e.g. to update a UI.
+    ///
+    /// Only the changes made in this process are observed, from now on.
+    pub fn watch_local(&self) -> impl Stream<Item = BookChange> {
+        self.state.watch_local()
+    }
+
+    /// Runs the housekeeping of the client.
+    ///
+    /// It evicts the expired records cached from the primary accounts,
+    /// prunes the pooled connections closed by the peers or the idle timeout,
+    /// and flushes the address book.
+    pub async fn maintenance(&self) -> Result<MaintenanceReport> {
+        let pruned_connections = {
+            let mut connections = self.connections.lock().await;
+            let count = connections.len();
+            connections.retain(|_, pooled| pooled.close_reason().is_none());
+            count - connections.len()
+        };
+
+        Ok(MaintenanceReport {
+            pruned_connections,
+            ..self.state.maintenance().await?
+        })
+    }
+
+    /// Runs [`Self::maintenance`] periodically in background.
+    pub fn spawn_maintenance(&self, interval: Duration) -> tokio::task::JoinHandle<()> {
+        let client = self.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(interval);
+            loop {
+                interval.tick().await;
+                match client.maintenance().await {
+                    Ok(report) => debug!("maintenance: {report:?}"),
+                    Err(e) => warn!("maintenance error: {e}"),
+                }
+            }
+        })
     }
 }
 
@@ -102,51 +618,63 @@ impl Ipiis for IpiisClient {
     type Writer = ::quinn::SendStream;
 
     unsafe fn account_me(&self) -> Result<&Account> {
-        Ok(&self.router.account_me)
+        Ok(&self.state.router.account_me)
     }
 
     fn account_ref(&self) -> &AccountRef {
-        &self.router.account_ref
+        &self.state.router.account_ref
     }
 
     async fn get_account_primary(&self, kind: Option<&Hash>) -> Result<AccountRef> {
-        match self.router.get_primary(kind)? {
+        match self.state.get_cached_primary(kind)? {
             Some(address) => Ok(address),
+            // no upstream to ask
+            None if self.state.static_routing => {
+                Err(IpiisError::NotFound("primary address".to_string()).into())
+            }
             None => match kind {
                 Some(kind) => {
+                    // bound the primaries forwarding the lookup
+                    let depth = next_resolution_depth(kind, self.state.max_resolution_depth)?;
+
                     // next target
                     let primary = self.get_account_primary(None).await?;
 
                     // external call
-                    let (account, address) = external_call!(
-                        client: self,
-                        target: None => &primary,
-                        request: ::ipiis_common::io => GetAccountPrimary,
-                        sign: self.sign_owned(primary, Some(*kind))?,
-                        inputs: { },
-                        outputs: { account, address, },
-                    );
+                    let (account, address, ttl_ms) = self
+                        .state
+                        .resolve_retry
+                        .run(self.state.retry_budget.as_ref(), || async move {
+                            let res = external_call!(
+                                client: self,
+                                target: None => &primary,
+                                request: ::ipiis_common::io => GetAccountPrimary,
+                                sign: self.sign_owned(primary, (Some(*kind), depth))?,
+                                inputs: { },
+                                outputs: { account, address, ttl_ms, },
+                            );
+                            Result::<_, ::ipis::core::anyhow::Error>::Ok(res)
+                        })
+                        .await?;
 
                     // store response
-                    self.router.set_primary(Some(kind), &account)?;
-                    if let Some(address) = address {
-                        self.router.set(Some(kind), &account, &address)?;
-                    }
+                    self.state
+                        .store_primary(kind, &account, address.as_ref(), ttl_ms)?;
 
                     // unpack response
                     Ok(account)
                 }
-                None => bail!("failed to get primary address"),
+                None => Err(IpiisError::NotFound("primary address".to_string()).into()),
             },
         }
     }
 
     async fn set_account_primary(&self, kind: Option<&Hash>, account: &AccountRef) -> Result<()> {
-        self.router.set_primary(kind, account)?;
+        self.state.set_local_primary(kind, account)?;
 
         // update server-side if you are a root
-        if let Some(primary) = self.router.get_primary(None)? {
-            if self.account_ref() == &primary {
+        if let Some(primary) = self.state.router.get_primary(None)? {
+            if self.state.is_root_client(&primary) {
                 // external call
                 external_call!(
                     client: self,
@@ -161,11 +689,11 @@ impl Ipiis for IpiisClient {
     }
 
     async fn delete_account_primary(&self, kind: Option<&Hash>) -> Result<()> {
-        self.router.delete_primary(kind)?;
+        self.state.delete_local_primary(kind)?;
 
         // update server-side if you are a root
-        if let Some(primary) = self.router.get_primary(None)? {
-            if self.account_ref() == &primary {
+        if let Some(primary) = self.state.router.get_primary(None)? {
+            if self.state.is_root_client(&primary) {
                 // external call
                 external_call!(
                     client: self,
@@ -184,30 +712,18 @@ impl Ipiis for IpiisClient {
         kind: Option<&Hash>,
         target: &AccountRef,
     ) -> Result<<Self as Ipiis>::Address> {
-        match self.router.get(kind, target)? {
+        match self.state.get_cached_address(kind, target)? {
             Some(address) => Ok(address),
-            None => match self.router.get_primary(None)? {
+            // no upstream to ask
+            None if self.state.static_routing => {
+                Err(IpiisError::NotFound(format!("address of {target}")).into())
+            }
+            None => match self.state.router.get_primary(None)? {
                 Some(primary) => {
-                    // external call
-                    let (address,) = external_call!(
-                        client: self,
-                        target: None => &primary,
-                        request: ::ipiis_common::io => GetAddress,
-                        sign: self.sign_owned(primary, (kind.copied(), *target))?,
-                        inputs: { },
-                        outputs: { address, },
-                    );
-
-                    // store response
-                    self.router.set(kind, target, &address)?;
-
-                    // unpack response
-                    Ok(address)
-                }
-                None => {
-                    let addr = target.to_string();
-                    bail!("failed to get address: {addr}")
+                    self.state.ensure_primary_address(&primary)?;
+                    ipiis_api_common::client::resolve_address(self, kind, target, primary).await
                 }
+                None => Err(IpiisError::NotFound(format!("address of {target}")).into()),
             },
         }
     }
@@ -218,11 +734,11 @@ impl Ipiis for IpiisClient {
         target: &AccountRef,
         address: &<Self as Ipiis>::Address,
     ) -> Result<()> {
-        self.router.set(kind, target, address)?;
+        self.state.set_local_address(kind, target, address)?;
 
         // update server-side if you are a root
-        if let Some(primary) = self.router.get_primary(None)? {
-            if self.account_ref() == &primary {
+        if let Some(primary) = self.state.router.get_primary(None)? {
+            if self.state.is_root_client(&primary) {
                 // external call
                 external_call!(
                     client: self,
@@ -236,12 +752,41 @@ impl Ipiis for IpiisClient {
         Ok(())
     }
 
+    async fn set_address_verified(
+        &self,
+        kind: Option<&Hash>,
+        target: &AccountRef,
+        address: &<Self as Ipiis>::Address,
+    ) -> Result<()> {
+        let primary = self.state.root_to_verify()?;
+        self.set_address(kind, target, address).await?;
+
+        // re-read from the root
+        let (address_root,) = external_call!(
+            client: self,
+            target: None => &primary,
+            request: ::ipiis_common::io => GetAddress,
+            sign: self.sign_owned(primary, (kind.copied(), *target))?,
+            inputs: { },
+            outputs: { address, },
+        );
+
+        // compare with the requested address
+        if &address_root != address {
+            let addr = target.to_string();
+            bail!(
+                "address diverged from the root: {addr}: requested={address:?}, root={address_root:?}"
+            )
+        }
+        Ok(())
+    }
+
     async fn delete_address(&self, kind: Option<&Hash>, target: &AccountRef) -> Result<()> {
-        self.router.delete(kind, target)?;
+        self.state.delete_local_address(kind, target)?;
 
         // update server-side if you are a root
-        if let Some(primary) = self.router.get_primary(None)? {
-            if self.account_ref() == &primary {
+        if let Some(primary) = self.state.router.get_primary(None)? {
+            if self.state.is_root_client(&primary) {
                 // external call
                 external_call!(
                     client: self,
@@ -255,8 +800,95 @@ impl Ipiis for IpiisClient {
         Ok(())
     }
 
-    fn protocol(&self) -> Result<String> {
-        Ok("quic".to_string())
+    async fn list_accounts(&self, kind: Option<&Hash>) -> Result<Vec<AccountRef>> {
+        match self.state.router.get_primary(None)? {
+            // ask the root
+            Some(primary) if !self.state.serving => {
+                // external call
+                let (accounts,) = external_call!(
+                    client: self,
+                    target: None => &primary,
+                    request: ::ipiis_common::io => ListAccounts,
+                    sign: self.sign_owned(primary, kind.copied())?,
+                    inputs: { },
+                    outputs: { accounts, },
+                );
+
+                // unpack response
+                Ok(accounts.into_vec())
+            }
+            _ => self.state.router.list(kind),
+        }
+    }
+
+    fn protocol(&self) -> &'static str {
+        "quic"
+    }
+
+    fn request_budget(&self) -> Option<&RequestBudget> {
+        self.state.request_budget.as_ref()
+    }
+
+    fn request_scheduler(&self) -> Option<&RequestScheduler> {
+        self.state.request_scheduler.as_ref()
+    }
+
+    fn response_cache(&self) -> Option<&ResponseCache> {
+        self.state.response_cache.as_ref()
+    }
+
+    fn revocation_list(&self) -> Option<&RevocationList> {
+        self.state.revocation_list.as_ref()
+    }
+
+    fn byte_meter(&self) -> Option<&ByteMeter> {
+        self.state.byte_meter.as_ref()
+    }
+
+    fn reports_server_time(&self) -> bool {
+        self.state.server_timing
+    }
+
+    fn wire_capture(&self) -> Option<&WireCapture> {
+        self.state.wire_capture.as_ref()
+    }
+
+    fn verification_audit(&self) -> Option<&VerificationAudit> {
+        self.state.verification_audit.as_ref()
+    }
+
+    fn raw_handlers(&self) -> Option<&RawHandlers> {
+        self.state.raw_handlers.as_ref()
+    }
+
+    fn time_policy(&self) -> &TimePolicy {
+        &self.state.time_policy
+    }
+
+    fn request_registry(&self) -> Option<&RequestRegistry> {
+        Some(&self.state.requests)
+    }
+
+    fn retry_budget(&self) -> Option<&RetryBudget> {
+        self.state.retry_budget.as_ref()
+    }
+
+    async fn diagnostics(&self) -> Result<Diagnostics> {
+        let mut diagnostics = Diagnostics::new(self);
+        diagnostics.book_entries = Some(self.state.router.count()?);
+        diagnostics.connections = self
+            .connections
+            .lock()
+            .await
+            .iter()
+            .map(|((kind, account), pooled)| ConnectionDiagnostics {
+                kind: *kind,
+                account: *account,
+                address: pooled.address.clone(),
+                rtt: pooled.conn.rtt(),
+            })
+            .collect();
+        Ok(diagnostics)
     }
 
     async fn call_raw(
@@ -264,8 +896,45 @@ impl Ipiis for IpiisClient {
         kind: Option<&Hash>,
         target: &AccountRef,
     ) -> Result<(<Self as Ipiis>::Writer, <Self as Ipiis>::Reader)> {
-        // connect to the target
-        let conn = self.get_connection(kind, target).await?;
+        // reuse the connection to the target
+        let mut closed = None;
+        if let Some(pooled) = self.get_pooled_connection(kind, target).await? {
+            let error = match pooled {
+                Ok(conn) => match conn.open_bi().await {
+                    Ok((send, recv)) => return Ok((send, recv)),
+                    Err(e) => e,
+                },
+                Err(e) => e,
+            };
+            if let quinn::ConnectionError::ApplicationClosed(close) = &error {
+                closed = CloseCode::from_code(close.error_code.into_inner());
+            }
+
+            // reconnect only within the retry budget
+            if !self
+                .state
+                .retry_budget
+                .as_ref()
+                .map_or(true, RetryBudget::try_acquire)
+            {
+                return Err(match closed {
+                    Some(code) => IpiisError::ConnectionClosed(code).into(),
+                    None => anyhow!("failed to open stream: {error}"),
+                });
+            }
+        }
+
+        // connect to the target, telling why the pooled connection has been closed on failure
+        let conn = match self.get_connection(kind, target).await {
+            Ok(conn) => conn,
+            Err(e) => match closed {
+                Some(code) => {
+                    return Err(Error::new(IpiisError::ConnectionClosed(code))
+                        .context(format!("failed to reconnect: {e}")))
+                }
+                None => return Err(e),
+            },
+        };
 
         // open stream
         let (send, recv) = conn
@@ -276,30 +945,115 @@ impl Ipiis for IpiisClient {
         // send data
         Ok((send, recv))
     }
+
+    async fn disconnect(&self, target: &AccountRef, reason: u32) -> Result<()> {
+        let code = VarInt::from_u32(reason);
+
+        let mut connections = self.connections.lock().await;
+        connections.retain(|(_, account), pooled| {
+            if account == target {
+                pooled.conn.close(code, b"disconnect");
+                false
+            } else {
+                true
+            }
+        });
+        Ok(())
+    }
 }
 
 impl IpiisClient {
+    /// Returns the pooled connection to the target, if its address is still up to date,
+    /// or why it has been closed, pruning it from the pool.
+    async fn get_pooled_connection(
+        &self,
+        kind: Option<&Hash>,
+        target: &AccountRef,
+    ) -> Result<Option<Result<Connection, quinn::ConnectionError>>> {
+        let key = (kind.copied(), *target);
+        let (addr, conn) = {
+            let mut connections = self.connections.lock().await;
+            let pooled = match connections.get_mut(&key) {
+                Some(pooled) => pooled,
+                None => return Ok(None),
+            };
+            if let Some(reason) = pooled.close_reason() {
+                connections.remove(&key);
+                return Ok(Some(Err(reason)));
+            }
+            (pooled.address.clone(), pooled.conn.clone())
+        };
+
+        if addr == self.get_address(kind, target).await? {
+            Ok(Some(Ok(conn)))
+        } else {
+            Ok(None)
+        }
+    }
+
     async fn get_connection(&self, kind: Option<&Hash>, target: &AccountRef) -> Result<Connection> {
-        let addr = self.get_address(kind, target).await?;
-        let server_name = crate::cert::get_name(target);
+        // reject connecting to itself
+        self.state.ensure_not_self(target)?;
 
-        let new_conn = self
-            .endpoint
-            .connect(
-                addr.to_socket_addrs()?
-                    .next()
-                    .ok_or_else(|| anyhow!("failed to parse the socket address: {addr}"))?,
-                &server_name,
-            )?
-            .await
-            .map_err(|e| anyhow!("failed to connect: {e}"))?;
+        let addr = self.get_address(kind, target).await?;
 
+        // verify the certificate against the target, whatever the server name is
+        let config = crate::cert::client_config_bound_to(
+            self.client_account(),
+            self.server_auth,
+            &self.transport,
+            target,
+        )?;
+        let server_name = match self.server_names.get(target).or(self.server_name.as_ref()) {
+            Some(server_name) => server_name.clone(),
+            None => crate::cert::get_name(target),
+        };
         let quinn::NewConnection {
-            connection: conn, ..
-        } = new_conn;
+            connection: conn,
+            bi_streams: incoming,
+            ..
+        } = self.connect(&addr, &server_name, config).await?;
+
+        // store the connection
+        self.connections.lock().await.insert(
+            (kind.copied(), *target),
+            PooledConnection {
+                address: addr,
+                conn: conn.clone(),
+                incoming,
+            },
+        );
 
         Ok(conn)
     }
+
+    async fn connect(
+        &self,
+        addr: &str,
+        server_name: &str,
+        config: ClientConfig,
+    ) -> Result<quinn::NewConnection> {
+        let socket_addr = addr
+            .to_socket_addrs()?
+            .next()
+            .ok_or_else(|| anyhow!("failed to parse the socket address: {addr}"))?;
+        let connecting = self
+            .endpoint
+            .connect_with(config, socket_addr, server_name)?;
+
+        let new_conn = tokio::time::timeout(self.state.connect_timeout, connecting)
+            .await
+            .map_err(|_| IpiisError::ConnectTimeout(addr.to_string()))?
+            .map_err(|e| {
+                let message = format!("failed to connect: {e}");
+                let kind = match e {
+                    quinn::ConnectionError::TimedOut => io::ErrorKind::TimedOut,
+                    _ => io::ErrorKind::NotConnected,
+                };
+                Error::new(io::Error::new(kind, e)).context(message)
+            })?;
+        Ok(new_conn)
+    }
 }
 
 #[async_trait]
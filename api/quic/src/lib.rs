@@ -1,5 +1,18 @@
 pub extern crate rustls;
 
 pub mod cert;
+#[cfg_attr(
+    not(feature = "insecure-dangerous"),
+    doc = "The insecure server verification is unavailable without the `insecure-dangerous` feature:",
+    doc = "",
+    doc = "```compile_fail",
+    doc = "use ipiis_api_quic::client::IpiisClient;",
+    doc = "",
+    doc = "fn insecure(client: IpiisClient) {",
+    doc = "    client.with_insecure_server_auth().unwrap();",
+    doc = "}",
+    doc = "```"
+)]
 pub mod client;
 pub mod server;
+pub mod transport;
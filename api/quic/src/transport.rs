@@ -0,0 +1,53 @@
+use core::time::Duration;
+
+use ipis::core::anyhow::{anyhow, Result};
+use quinn::{TransportConfig, VarInt};
+
+/// The duration of a connection without any activity before it is closed, if not given.
+pub const DEFAULT_IDLE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Flow control windows and timeouts of the QUIC transport.
+///
+/// The unset values fall back to the defaults.
+/// Bulk transfers on high bandwidth-delay product (BDP) links should raise them
+/// to about `bandwidth * RTT`: e.g. `16MB` per stream for 1Gbps with 100ms RTT.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct TransportOptions {
+    /// Maximum bytes the peer may send on a stream without being acknowledged
+    pub stream_receive_window: Option<u32>,
+
+    /// Maximum bytes the peer may send across all streams of a connection
+    pub receive_window: Option<u64>,
+
+    /// Maximum bytes to send to the peer without being acknowledged
+    pub send_window: Option<u64>,
+
+    /// Duration of a connection without any activity before it is closed
+    /// (default: [`DEFAULT_IDLE_TIMEOUT`])
+    pub idle_timeout: Option<Duration>,
+}
+
+impl TransportOptions {
+    pub fn apply(&self, config: &mut TransportConfig) -> Result<()> {
+        if let Some(value) = self.stream_receive_window {
+            config.stream_receive_window(VarInt::from_u32(value));
+        }
+        if let Some(value) = self.receive_window {
+            config.receive_window(
+                VarInt::from_u64(value)
+                    .map_err(|_| anyhow!("too large receive window: {value}"))?,
+            );
+        }
+        if let Some(value) = self.send_window {
+            config.send_window(value);
+        }
+        if let Some(value) = self.idle_timeout {
+            config.max_idle_timeout(Some(
+                value
+                    .try_into()
+                    .map_err(|_| anyhow!("too long idle timeout: {value:?}"))?,
+            ));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,51 @@
+mod common;
+
+use ipiis_common::{external_call, is_unauthorized, Ipiis, Nonce};
+use ipis::{
+    core::{account::Account, anyhow::Result},
+    tokio,
+};
+
+use self::common::TestPeer;
+
+#[tokio::test]
+async fn test_cert_binding() {
+    // deploy a server binding the requests to the client certificates
+    let server = TestPeer::deploy_with("cert-binding", |server| server.with_cert_binding())
+        .await
+        .unwrap();
+    let server_ref = server.account;
+
+    // create a client presenting the certificate of its own account
+    let client = server
+        .client("cert-binding")
+        .await
+        .unwrap()
+        .with_client_auth()
+        .unwrap();
+
+    // the requests signed by the account of the certificate should be accepted
+    let report = client.ping(None, &server_ref).await.unwrap();
+    assert!(report.identity_confirmed);
+
+    // the requests signed by another account should be rejected
+    let other = Account::generate();
+    let error = async {
+        external_call!(
+            client: client,
+            target: None => &server_ref,
+            request: ::ipiis_common::io => Ping,
+            sign: client.sign_owned_as(&other, server_ref, Nonce::generate())?,
+            inputs: { },
+            outputs: send,
+        );
+        Result::<_, ::ipis::core::anyhow::Error>::Ok(())
+    }
+    .await
+    .unwrap_err();
+    assert!(is_unauthorized(&error), "{error:#}");
+
+    // the clients presenting no certificates should not skip the binding
+    let anonymous = server.client("cert-binding-anonymous").await.unwrap();
+    assert!(anonymous.ping(None, &server_ref).await.is_err());
+}
@@ -0,0 +1,57 @@
+mod common;
+
+use ipiis_api_quic::{
+    cert::{get_account, get_name},
+    rustls::Certificate,
+};
+use ipiis_common::Ipiis;
+use ipis::{core::account::Account, tokio};
+
+use self::common::{introduce, test_client, TestPeer};
+
+/// Prefix of the DER-encoded Ed25519 public key (SubjectPublicKeyInfo)
+const ED25519_SPKI_PREFIX: &[u8] = &[
+    0x30, 0x2a, 0x30, 0x05, 0x06, 0x03, 0x2b, 0x65, 0x70, 0x03, 0x21, 0x00,
+];
+
+#[tokio::test]
+async fn test_cert_pinning() {
+    // deploy two servers, each holding the certificate of its own account
+    let server_a = TestPeer::deploy("cert-pinning-a").await.unwrap();
+    let server_b = TestPeer::deploy("cert-pinning-b").await.unwrap();
+
+    // swap the addresses, so that each server presents the certificate of the other account
+    let client = test_client("cert-pinning").await.unwrap();
+    introduce(&client, &server_a.account, &server_b.address).unwrap();
+    introduce(&client, &server_b.account, &server_a.address).unwrap();
+
+    // the handshakes should fail
+    assert!(client.ping(None, &server_a.account).await.is_err());
+    assert!(client.ping(None, &server_b.account).await.is_err());
+    assert!(client.diagnostics().await.unwrap().connections.is_empty());
+
+    // the correct addresses should be accepted
+    introduce(&client, &server_a.account, &server_a.address).unwrap();
+    let report = client.ping(None, &server_a.account).await.unwrap();
+    assert!(report.identity_confirmed);
+}
+
+#[test]
+fn test_cert_key_outside_spki() {
+    let victim = Account::generate().account_ref();
+
+    // an attacker's certificate, named by the victim and embedding its key in an extension
+    let mut params = ::rcgen::CertificateParams::new(vec![get_name(&victim)]);
+    params.alg = &::rcgen::PKCS_ED25519;
+    params
+        .custom_extensions
+        .push(::rcgen::CustomExtension::from_oid_content(
+            &[1, 3, 6, 1, 4, 1, 32473, 1],
+            [ED25519_SPKI_PREFIX, victim.as_bytes().as_ref()].concat(),
+        ));
+    let cert = ::rcgen::Certificate::from_params(params).unwrap();
+    let cert = Certificate(cert.serialize_der().unwrap());
+
+    // only the SubjectPublicKeyInfo should bind the account
+    assert_eq!(get_account(&cert), None);
+}
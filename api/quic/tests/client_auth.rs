@@ -0,0 +1,26 @@
+mod common;
+
+use ipiis_common::Ipiis;
+use ipis::tokio;
+
+use self::common::TestPeer;
+
+#[tokio::test]
+async fn test_client_auth_required() {
+    // deploy a server requiring client certificates
+    let server = TestPeer::deploy_with("client-auth", |server| server.with_client_auth_required())
+        .await
+        .unwrap();
+
+    // a client presenting no certificates should be rejected
+    let client = server.client("client-auth").await.unwrap();
+    assert!(client.ping(None, &server.account).await.is_err());
+
+    // a client presenting its certificate should be accepted
+    let client_auth = client.clone().with_client_auth().unwrap();
+    let report = client_auth.ping(None, &server.account).await.unwrap();
+    assert!(report.identity_confirmed);
+
+    // the clone sharing the endpoint should still present no certificates
+    assert!(client.ping(None, &server.account).await.is_err());
+}
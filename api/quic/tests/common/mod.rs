@@ -0,0 +1,88 @@
+// not every test uses every helper
+#![allow(dead_code)]
+
+use std::{net::SocketAddr, path::PathBuf, sync::Arc};
+
+use ipiis_api_quic::{client::IpiisClient, server::IpiisServer};
+use ipiis_common::Ipiis;
+use ipis::{
+    core::{
+        account::{Account, AccountRef},
+        anyhow::Result,
+    },
+    env::Infer,
+    tokio,
+};
+
+/// A root server running in background, with its own address book.
+pub struct TestPeer {
+    pub server: Arc<IpiisServer>,
+    pub account: AccountRef,
+    pub address: SocketAddr,
+}
+
+impl TestPeer {
+    /// Deploys a server on an ephemeral port.
+    pub async fn deploy(name: &str) -> Result<Self> {
+        Self::deploy_with(name, Ok).await
+    }
+
+    /// Deploys a server as [`Self::deploy`], configuring it before running.
+    pub async fn deploy_with(
+        name: &str,
+        configure: impl FnOnce(IpiisServer) -> Result<IpiisServer>,
+    ) -> Result<Self> {
+        use_router_db(&format!("peer-{name}"));
+
+        // create a server
+        let server = IpiisServer::new(Account::generate(), None, 0).await?;
+        let server = Arc::new(configure(server)?);
+        let account = *server.account_ref();
+        let address = loopback(server.local_addr()?);
+
+        // deploy the server
+        tokio::spawn(server.clone().run_ipiis());
+
+        Ok(Self {
+            server,
+            account,
+            address,
+        })
+    }
+
+    /// Creates a client with its own address book, knowing the address of the peer.
+    pub async fn client(&self, name: &str) -> Result<IpiisClient> {
+        let client = test_client(name).await?;
+        introduce(&client, &self.account, &self.address)?;
+        Ok(client)
+    }
+}
+
+/// Points the following clients and servers to a fresh address book of the name,
+/// without any primary account.
+pub fn use_router_db(name: &str) -> PathBuf {
+    let path = ::std::env::temp_dir().join(format!("ipiis-test-quic-{name}"));
+    let _ = ::std::fs::remove_dir_all(&path);
+
+    ::std::env::set_var("ipiis_router_db", &path);
+    ::std::env::remove_var("ipiis_account_primary");
+    ::std::env::remove_var("ipiis_account_primary_address");
+    path
+}
+
+/// Creates a client with its own address book, without any primary account.
+pub async fn test_client(name: &str) -> Result<IpiisClient> {
+    use_router_db(&format!("client-{name}"));
+
+    IpiisClient::genesis(None).await
+}
+
+/// Lets the client know the address of the account.
+pub fn introduce(client: &IpiisClient, account: &AccountRef, address: &SocketAddr) -> Result<()> {
+    client.book().set(None, account, &address.to_string())
+}
+
+/// Returns the address on the loopback interface, of the same port.
+pub fn loopback(address: SocketAddr) -> SocketAddr {
+    SocketAddr::from(([127, 0, 0, 1], address.port()))
+}
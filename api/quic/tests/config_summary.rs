@@ -0,0 +1,40 @@
+mod common;
+
+use core::time::Duration;
+
+use ipiis_api_quic::{
+    client::IpiisClient,
+    transport::{TransportOptions, DEFAULT_IDLE_TIMEOUT},
+};
+use ipiis_common::Ipiis;
+use ipis::{env::Infer, tokio};
+
+use self::common::use_router_db;
+
+#[tokio::test]
+async fn test_config_summary() {
+    let path = use_router_db("client-config-summary");
+
+    // the defaults should be reported
+    let client = IpiisClient::genesis(None).await.unwrap();
+    let summary = client.config_summary();
+    assert_eq!(summary.protocol, "quic");
+    assert_eq!(summary.account, client.account_ref().to_string());
+    assert_eq!(summary.db_path.as_ref(), Some(&path));
+    assert_eq!(summary.idle_timeout, Some(DEFAULT_IDLE_TIMEOUT));
+    assert!(!summary.serving);
+
+    // the builder-set options should be reported
+    let idle_timeout = Duration::from_secs(30);
+    let client = client
+        .with_transport(TransportOptions {
+            idle_timeout: Some(idle_timeout),
+            ..Default::default()
+        })
+        .unwrap()
+        .with_max_resolution_depth(3);
+    let summary = client.config_summary();
+    assert_eq!(summary.db_path.as_ref(), Some(&path));
+    assert_eq!(summary.idle_timeout, Some(idle_timeout));
+    assert_eq!(summary.max_resolution_depth, 3);
+}
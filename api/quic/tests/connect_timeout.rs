@@ -0,0 +1,33 @@
+mod common;
+
+use core::time::Duration;
+use std::time::Instant;
+
+use ipiis_common::{is_timeout, Ipiis};
+use ipis::{core::account::Account, tokio};
+
+use self::common::test_client;
+
+#[tokio::test]
+async fn test_connect_timeout() {
+    // create a client failing fast
+    let connect_timeout = Duration::from_millis(500);
+    let client = test_client("connect-timeout")
+        .await
+        .unwrap()
+        .with_connect_timeout(connect_timeout);
+    assert_eq!(client.config_summary().connect_timeout, connect_timeout);
+
+    // no one answers the handshake on the address
+    let target = Account::generate().account_ref();
+    client
+        .set_address(None, &target, &"127.0.0.1:5054".to_string())
+        .await
+        .unwrap();
+
+    // the connect timeout should fire long before the idle timeout
+    let instant = Instant::now();
+    let error = client.ping(None, &target).await.unwrap_err();
+    assert!(is_timeout(&error), "{error:#}");
+    assert!(instant.elapsed() < Duration::from_secs(3));
+}
@@ -0,0 +1,38 @@
+mod common;
+
+use core::time::Duration;
+
+use ipiis_common::Ipiis;
+use ipis::tokio;
+
+use self::common::TestPeer;
+
+#[tokio::test]
+async fn test_diagnostics() {
+    // deploy a server
+    let server = TestPeer::deploy("diagnostics").await.unwrap();
+
+    // create a client
+    let client = server.client("diagnostics").await.unwrap();
+
+    // open a connection
+    client.ping(None, &server.account).await.unwrap();
+
+    let diagnostics = client.diagnostics().await.unwrap();
+    assert_eq!(diagnostics.protocol, "quic");
+    assert_eq!(&diagnostics.account, client.account_ref());
+    assert!(diagnostics.book_entries.unwrap() >= 1);
+    assert_eq!(diagnostics.in_flight_requests, 0);
+
+    // the pooled connection to the server
+    assert_eq!(diagnostics.connections.len(), 1);
+    let connection = &diagnostics.connections[0];
+    assert_eq!(connection.kind, None);
+    assert_eq!(connection.account, server.account);
+    assert_eq!(connection.address, server.address.to_string());
+    assert!(connection.rtt > Duration::ZERO);
+
+    // dump it
+    let dump = diagnostics.to_string();
+    assert!(dump.contains(&server.account.to_string()));
+}
@@ -0,0 +1,64 @@
+mod common;
+
+use core::time::Duration;
+use std::sync::Mutex;
+
+use ipiis_common::Ipiis;
+use ipis::{
+    log::{self, Log, Metadata, Record},
+    tokio,
+};
+
+use self::common::TestPeer;
+
+const REASON: u32 = 42;
+
+static LOGGER: Recorder = Recorder {
+    records: Mutex::new(Vec::new()),
+};
+
+#[tokio::test]
+async fn test_disconnect() {
+    log::set_logger(&LOGGER).unwrap();
+    log::set_max_level(log::LevelFilter::Info);
+
+    // deploy a server
+    let server = TestPeer::deploy("disconnect").await.unwrap();
+    let server_ref = server.account;
+
+    // connect to the server
+    let client = server.client("disconnect").await.unwrap();
+    client.ping(None, &server_ref).await.unwrap();
+
+    // close the connection explicitly
+    client.disconnect(&server_ref, REASON).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(500)).await;
+
+    // the server should observe the reason code
+    let expected = format!("code={REASON}");
+    assert!(LOGGER
+        .records
+        .lock()
+        .unwrap()
+        .iter()
+        .any(|record| record.starts_with("connection closed:") && record.ends_with(&expected)));
+
+    // the client should reconnect on demand
+    client.ping(None, &server_ref).await.unwrap();
+}
+
+struct Recorder {
+    records: Mutex<Vec<String>>,
+}
+
+impl Log for Recorder {
+    fn enabled(&self, _metadata: &Metadata) -> bool {
+        true
+    }
+
+    fn log(&self, record: &Record) {
+        self.records.lock().unwrap().push(record.args().to_string());
+    }
+
+    fn flush(&self) {}
+}
@@ -0,0 +1,34 @@
+mod common;
+
+use std::{net::UdpSocket, sync::Arc};
+
+use ipiis_api_quic::server::IpiisServer;
+use ipiis_common::Ipiis;
+use ipis::{core::account::Account, tokio};
+use quinn::Endpoint;
+
+use self::common::{introduce, test_client, use_router_db};
+
+#[tokio::test]
+async fn test_with_endpoint() {
+    // bind a socket outside of ipiis, e.g. as the socket activation does
+    let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let address = socket.local_addr().unwrap();
+    let (endpoint, incoming) = Endpoint::new(Default::default(), None, socket).unwrap();
+
+    // create a server on the endpoint
+    use_router_db("server-endpoint");
+    let server = IpiisServer::with_endpoint(Account::generate(), None, endpoint, incoming)
+        .await
+        .unwrap();
+    let server_ref = *server.account_ref();
+    assert_eq!(server.local_addr().unwrap(), address);
+    tokio::spawn(Arc::new(server).run_ipiis());
+
+    // serve a request
+    let client = test_client("endpoint").await.unwrap();
+    introduce(&client, &server_ref, &address).unwrap();
+
+    let report = client.ping(None, &server_ref).await.unwrap();
+    assert!(report.identity_confirmed);
+}
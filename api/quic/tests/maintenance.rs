@@ -0,0 +1,32 @@
+mod common;
+
+use core::time::Duration;
+
+use ipiis_common::Ipiis;
+use ipis::tokio;
+
+use self::common::TestPeer;
+
+#[tokio::test]
+async fn test_maintenance_prunes_closed_connections() {
+    // deploy a server
+    let server = TestPeer::deploy("maintenance").await.unwrap();
+
+    // pool a connection to the server
+    let client = server.client("maintenance").await.unwrap();
+    client.ping(None, &server.account).await.unwrap();
+
+    // the live connection should be kept
+    let report = client.maintenance().await.unwrap();
+    assert_eq!(report.pruned_connections, 0);
+    assert_eq!(client.diagnostics().await.unwrap().connections.len(), 1);
+
+    // close the connection by the server
+    server.server.shutdown();
+    tokio::time::sleep(Duration::from_millis(500)).await;
+
+    // the closed connection should be pruned
+    let report = client.maintenance().await.unwrap();
+    assert_eq!(report.pruned_connections, 1);
+    assert!(client.diagnostics().await.unwrap().connections.is_empty());
+}
@@ -0,0 +1,64 @@
+mod common;
+
+use core::time::Duration;
+use std::{net::UdpSocket, sync::Mutex};
+
+use ipiis_common::Ipiis;
+use ipis::{
+    log::{self, Log, Metadata, Record},
+    tokio,
+};
+
+use self::common::TestPeer;
+
+static LOGGER: Recorder = Recorder {
+    records: Mutex::new(Vec::new()),
+};
+
+#[tokio::test]
+async fn test_migration() {
+    log::set_logger(&LOGGER).unwrap();
+    log::set_max_level(log::LevelFilter::Info);
+
+    // deploy a server
+    let server = TestPeer::deploy("migration").await.unwrap();
+    let server_ref = server.account;
+
+    // connect to the server
+    let client = server.client("migration").await.unwrap();
+    client.ping(None, &server_ref).await.unwrap();
+
+    // move to a new socket, as if the network is changed
+    let socket = UdpSocket::bind("0.0.0.0:0").unwrap();
+    client.rebind(socket).unwrap();
+    tokio::time::sleep(Duration::from_millis(500)).await;
+
+    // the connection should survive
+    let report = client.ping(None, &server_ref).await.unwrap();
+    assert!(report.identity_confirmed);
+
+    let connections = LOGGER
+        .records
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|record| record.starts_with("incoming connection:"))
+        .count();
+    assert_eq!(connections, 1);
+}
+
+struct Recorder {
+    records: Mutex<Vec<String>>,
+}
+
+impl Log for Recorder {
+    fn enabled(&self, _metadata: &Metadata) -> bool {
+        true
+    }
+
+    fn log(&self, record: &Record) {
+        self.records.lock().unwrap().push(record.args().to_string());
+    }
+
+    fn flush(&self) {}
+}
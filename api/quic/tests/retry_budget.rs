@@ -0,0 +1,59 @@
+mod common;
+
+use core::time::Duration;
+use std::time::Instant;
+
+use ipiis_api_common::retry::RetryPolicy;
+use ipiis_common::{Ipiis, RetryBudget};
+use ipis::{core::account::Account, tokio};
+
+use self::common::test_client;
+
+const CONNECT_TIMEOUT: Duration = Duration::from_millis(300);
+const RETRIES: u32 = 2;
+const WINDOW: Duration = Duration::from_secs(5);
+
+#[tokio::test]
+async fn test_retry_budget() {
+    // no one answers the handshake on the address of the primary
+    let primary = Account::generate().account_ref();
+    let budget = RetryBudget::new(RETRIES, WINDOW);
+    let client = test_client("retry-budget")
+        .await
+        .unwrap()
+        .with_connect_timeout(CONNECT_TIMEOUT)
+        .with_resolve_retry(RetryPolicy {
+            attempts: 1 + RETRIES,
+            backoff: Duration::from_millis(10),
+        })
+        .with_retry_budget(budget.clone());
+    client
+        .book()
+        .set_primary_with_address(None, &primary, &"127.0.0.1:5067".to_string())
+        .unwrap();
+
+    // saturate the budget
+    let instant = Instant::now();
+    let target = Account::generate().account_ref();
+    client.get_address(None, &target).await.unwrap_err();
+    assert!(instant.elapsed() >= CONNECT_TIMEOUT * (1 + RETRIES));
+    assert_eq!(budget.available(), 0);
+    assert_eq!(budget.denied(), 0);
+
+    // the subsequent failure should not be retried
+    let instant = Instant::now();
+    let target = Account::generate().account_ref();
+    client.get_address(None, &target).await.unwrap_err();
+    assert!(instant.elapsed() < CONNECT_TIMEOUT * 2);
+    assert_eq!(budget.denied(), 1);
+
+    // the failures should be retried again once the budget is refilled
+    tokio::time::sleep(WINDOW).await;
+    assert_eq!(budget.available(), RETRIES);
+
+    let instant = Instant::now();
+    let target = Account::generate().account_ref();
+    client.get_address(None, &target).await.unwrap_err();
+    assert!(instant.elapsed() >= CONNECT_TIMEOUT * (1 + RETRIES));
+    assert_eq!(budget.denied(), 1);
+}
@@ -0,0 +1,28 @@
+mod common;
+
+use ipiis_common::Ipiis;
+use ipis::{core::account::Account, tokio};
+
+use self::common::{introduce, TestPeer};
+
+#[tokio::test]
+async fn test_server_name_override() {
+    // deploy a server whose certificate is bound to its account
+    let server = TestPeer::deploy("server-name").await.unwrap();
+
+    // dial the server by a routing hostname instead of its account
+    let client = server
+        .client("server-name")
+        .await
+        .unwrap()
+        .with_server_name_for(server.account, "ipiis.example.com");
+
+    let report = client.ping(None, &server.account).await.unwrap();
+    assert!(report.identity_confirmed);
+
+    // the certificate should still be verified against the dialed account
+    let impostor = Account::generate().account_ref();
+    let client = client.with_server_name("ipiis.example.com");
+    introduce(&client, &impostor, &server.address).unwrap();
+    assert!(client.ping(None, &impostor).await.is_err());
+}
@@ -0,0 +1,18 @@
+mod common;
+
+use ipiis_common::Ipiis;
+use ipis::tokio;
+
+use self::common::{introduce, TestPeer};
+
+#[tokio::test]
+async fn test_shared_endpoint() {
+    // deploy two servers
+    let server_a = TestPeer::deploy("shared-endpoint-a").await.unwrap();
+    let server_b = TestPeer::deploy("shared-endpoint-b").await.unwrap();
+
+    // the server-role node dials out from its listening endpoint
+    introduce(&server_a.server, &server_b.account, &server_b.address).unwrap();
+    let report = server_a.server.ping(None, &server_b.account).await.unwrap();
+    assert!(report.identity_confirmed);
+}
@@ -0,0 +1,26 @@
+mod common;
+
+use core::time::Duration;
+
+use ipiis_common::{close_code_of, CloseCode, Ipiis};
+use ipis::tokio;
+
+use self::common::TestPeer;
+
+#[tokio::test]
+async fn test_shutdown() {
+    // deploy a server
+    let server = TestPeer::deploy("shutdown").await.unwrap();
+
+    // connect to the server
+    let client = server.client("shutdown").await.unwrap();
+    client.ping(None, &server.account).await.unwrap();
+
+    // shut the server down gracefully
+    server.server.shutdown();
+    tokio::time::sleep(Duration::from_millis(500)).await;
+
+    // the client should observe why the connection has been closed
+    let error = client.ping(None, &server.account).await.unwrap_err();
+    assert_eq!(close_code_of(&error), Some(CloseCode::ShuttingDown));
+}
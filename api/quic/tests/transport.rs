@@ -0,0 +1,51 @@
+mod common;
+
+use ipiis_api_quic::transport::TransportOptions;
+use ipiis_common::Ipiis;
+use ipis::tokio;
+use quinn::TransportConfig;
+
+use self::common::TestPeer;
+
+const OPTIONS: TransportOptions = TransportOptions {
+    stream_receive_window: Some(16 * 1024 * 1024),
+    receive_window: Some(64 * 1024 * 1024),
+    send_window: Some(64 * 1024 * 1024),
+    idle_timeout: None,
+};
+
+#[test]
+fn test_transport_options_validated() {
+    let mut config = TransportConfig::default();
+    OPTIONS.apply(&mut config).unwrap();
+
+    // the windows beyond the QUIC varint range should be rejected
+    let options = TransportOptions {
+        receive_window: Some(u64::MAX),
+        ..OPTIONS
+    };
+    assert!(options.apply(&mut config).is_err());
+}
+
+#[tokio::test]
+async fn test_transport_options_connect() {
+    // deploy a server with large windows
+    let server = TestPeer::deploy_with("transport", |server| server.with_transport(OPTIONS))
+        .await
+        .unwrap();
+
+    // create a client with large windows
+    let client = server
+        .client("transport")
+        .await
+        .unwrap()
+        .with_transport(OPTIONS)
+        .unwrap();
+
+    // the options should be applied to both sides
+    assert_eq!(server.server.transport(), &OPTIONS);
+    assert_eq!(client.transport(), &OPTIONS);
+
+    let report = client.ping(None, &server.account).await.unwrap();
+    assert!(report.identity_confirmed);
+}
@@ -0,0 +1,153 @@
+use core::time::Duration;
+use std::{net::SocketAddr, sync::Arc};
+
+use ipiis_common::{is_not_found, Ipiis};
+use ipis::{
+    core::anyhow::{bail, Result},
+    env::infer,
+    log::warn,
+    tokio::{
+        self,
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::{TcpListener, TcpStream},
+    },
+};
+
+/// The time to wait for a probe to send its request, or to check the readiness.
+pub const HEALTH_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The maximum size of the request header of a probe.
+const MAX_REQUEST_SIZE: usize = 8 * 1024;
+
+/// A lightweight HTTP server probing a node, e.g. by an orchestrator such as kubernetes.
+///
+/// * `/healthz`: the process is alive
+/// * `/readyz`: the address book is openable and the primary, if any, is resolvable
+/// * `/diagz`: the dump of the pooled connections and the address book, as a plain text
+///
+/// The dump is not authenticated, so do not expose the port beyond the trusted network.
+///
+/// The endpoint of the node is bound once the server is created,
+/// so it is ready as soon as the others are.
+pub struct HealthServer<C> {
+    client: Arc<C>,
+    incoming: TcpListener,
+}
+
+impl<C> HealthServer<C>
+where
+    C: Ipiis + Send + Sync + 'static,
+{
+    pub async fn new(client: Arc<C>, port: u16) -> Result<Self> {
+        let addr: SocketAddr = format!("0.0.0.0:{port}").parse()?;
+
+        Ok(Self {
+            client,
+            incoming: TcpListener::bind(addr).await?,
+        })
+    }
+
+    /// Creates a server on the port of `ipiis_health_port`, or none if it is not given.
+    pub async fn try_infer(client: Arc<C>) -> Result<Option<Self>> {
+        let port: Result<u16> = infer("ipiis_health_port");
+        match port {
+            Ok(port) => Self::new(client, port).await.map(Some),
+            Err(_) => Ok(None),
+        }
+    }
+
+    /// Returns the listening address, e.g. to find the ephemeral port bound by `0`.
+    pub fn local_addr(&self) -> Result<SocketAddr> {
+        self.incoming.local_addr().map_err(Into::into)
+    }
+
+    pub async fn run(self) {
+        loop {
+            match self.incoming.accept().await {
+                Ok((stream, _)) => {
+                    let client = self.client.clone();
+                    tokio::spawn(async move {
+                        if let Err(e) = handle(&*client, stream).await {
+                            warn!("failed to answer the health probe: {e}");
+                        }
+                    });
+                }
+                Err(e) => warn!("failed to accept the health probe: {e}"),
+            }
+        }
+    }
+}
+
+async fn handle<C>(client: &C, mut stream: TcpStream) -> Result<()>
+where
+    C: Ipiis,
+{
+    let path = tokio::time::timeout(HEALTH_TIMEOUT, recv_path(&mut stream)).await??;
+
+    let (status, body) = match path.as_str() {
+        "/healthz" => ("200 OK", String::new()),
+        "/readyz" => match tokio::time::timeout(HEALTH_TIMEOUT, check_ready(client)).await {
+            Ok(Ok(())) => ("200 OK", String::new()),
+            Ok(Err(e)) => {
+                warn!("the node is not ready: {e}");
+                ("503 Service Unavailable", String::new())
+            }
+            Err(_) => {
+                warn!("the node is not ready: the readiness check has timed out");
+                ("503 Service Unavailable", String::new())
+            }
+        },
+        "/diagz" => match client.diagnostics().await {
+            Ok(diagnostics) => ("200 OK", diagnostics.to_string()),
+            Err(e) => {
+                warn!("failed to collect the diagnostics: {e}");
+                ("500 Internal Server Error", String::new())
+            }
+        },
+        _ => ("404 Not Found", String::new()),
+    };
+
+    let response = format!(
+        "HTTP/1.1 {status}\r\nContent-Type: text/plain; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len(),
+    );
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await.map_err(Into::into)
+}
+
+/// Reads the header of the request, returning its path.
+async fn recv_path(stream: &mut TcpStream) -> Result<String> {
+    let mut buf = Vec::with_capacity(1024);
+    while !buf.ends_with(b"\r\n\r\n") {
+        if buf.len() >= MAX_REQUEST_SIZE {
+            bail!("too large health probe");
+        }
+        if stream.read_buf(&mut buf).await? == 0 {
+            break;
+        }
+    }
+
+    // parse the request line, e.g. `GET /healthz HTTP/1.1`
+    let header = String::from_utf8_lossy(&buf);
+    let mut request_line = header.lines().next().unwrap_or_default().split_whitespace();
+    match (request_line.next(), request_line.next()) {
+        (Some("GET" | "HEAD"), Some(path)) => Ok(path.to_string()),
+        _ => bail!("malformed health probe"),
+    }
+}
+
+/// Checks whether the address book is openable and the primary, if any, is resolvable.
+async fn check_ready<C>(client: &C) -> Result<()>
+where
+    C: Ipiis,
+{
+    match client.get_account_primary(None).await {
+        Ok(primary) if &primary != client.account_ref() => {
+            client.get_address(None, &primary).await.map(drop)
+        }
+        Ok(_) => Ok(()),
+        // a root has no primary
+        Err(e) if is_not_found(&e) => Ok(()),
+        Err(e) => Err(e),
+    }
+}
@@ -7,6 +7,19 @@ pub use ipiis_api_quic::*;
 #[cfg(feature = "tcp")]
 pub use ipiis_api_tcp::*;
 
+#[cfg(not(target_os = "wasi"))]
+#[cfg(feature = "quic")]
+pub extern crate ipiis_api_quic as quic;
+#[cfg(not(target_os = "wasi"))]
+#[cfg(feature = "tcp")]
+pub extern crate ipiis_api_tcp as tcp;
+
+#[cfg(not(target_os = "wasi"))]
+pub mod health;
+#[cfg(not(target_os = "wasi"))]
+#[cfg(any(feature = "quic", feature = "tcp"))]
+pub mod multi;
+
 #[cfg(target_os = "wasi")]
 pub mod client {
     pub use ipiis_api_wasi::IpiisClient;
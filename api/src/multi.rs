@@ -0,0 +1,104 @@
+use std::sync::Arc;
+
+use ipiis_common::Ipiis;
+use ipis::{
+    core::{
+        account::AccountRef,
+        anyhow::{bail, Result},
+    },
+    futures::future::{join_all, BoxFuture, FutureExt},
+};
+
+/// A node serving several transports at once, sharing a single address book and account.
+///
+/// The clients reach the node over whichever transport they support,
+/// at the same address in the book, as QUIC (UDP) and TCP do not share the ports.
+#[derive(Default)]
+pub struct MultiServer {
+    account: Option<AccountRef>,
+    #[cfg(feature = "quic")]
+    quic: Option<Arc<::ipiis_api_quic::server::IpiisServer>>,
+    #[cfg(feature = "tcp")]
+    tcp: Option<Arc<::ipiis_api_tcp::server::IpiisServer>>,
+}
+
+impl MultiServer {
+    /// Creates a node listening on both QUIC and TCP of the same port,
+    /// sharing the address book of the QUIC server.
+    #[cfg(all(feature = "quic", feature = "tcp"))]
+    pub async fn new(
+        account_me: ::ipis::core::account::Account,
+        account_primary: Option<AccountRef>,
+        port: u16,
+    ) -> Result<Self> {
+        let quic =
+            ::ipiis_api_quic::server::IpiisServer::new(account_me, account_primary, port).await?;
+        let tcp = ::ipiis_api_tcp::server::IpiisServer::with_book(
+            account_primary,
+            port,
+            quic.book().clone(),
+        )
+        .await?;
+
+        Self::default().with_quic(quic)?.with_tcp(tcp)
+    }
+
+    /// Adds a QUIC server, which should act as the same account as the others.
+    #[cfg(feature = "quic")]
+    pub fn with_quic(mut self, server: ::ipiis_api_quic::server::IpiisServer) -> Result<Self> {
+        self.bind_account(server.account_ref())?;
+        self.quic = Some(server.into());
+        Ok(self)
+    }
+
+    /// Adds a TCP server, which should act as the same account as the others.
+    #[cfg(feature = "tcp")]
+    pub fn with_tcp(mut self, server: ::ipiis_api_tcp::server::IpiisServer) -> Result<Self> {
+        self.bind_account(server.account_ref())?;
+        self.tcp = Some(server.into());
+        Ok(self)
+    }
+
+    fn bind_account(&mut self, account: &AccountRef) -> Result<()> {
+        match &self.account {
+            Some(expected) if expected != account => {
+                bail!("the servers should act as the same account: expected {expected}, got {account}")
+            }
+            _ => {
+                self.account = Some(*account);
+                Ok(())
+            }
+        }
+    }
+
+    /// Returns the account of the node, if any server is added.
+    pub fn account_ref(&self) -> Option<&AccountRef> {
+        self.account.as_ref()
+    }
+
+    #[cfg(feature = "quic")]
+    pub fn quic(&self) -> Option<&Arc<::ipiis_api_quic::server::IpiisServer>> {
+        self.quic.as_ref()
+    }
+
+    #[cfg(feature = "tcp")]
+    pub fn tcp(&self) -> Option<&Arc<::ipiis_api_tcp::server::IpiisServer>> {
+        self.tcp.as_ref()
+    }
+
+    /// Drives the accept loops of all the servers concurrently.
+    pub async fn run_all(&self) {
+        let mut servers: Vec<BoxFuture<'static, ()>> = vec![];
+
+        #[cfg(feature = "quic")]
+        if let Some(server) = &self.quic {
+            servers.push(server.clone().run_ipiis().boxed());
+        }
+        #[cfg(feature = "tcp")]
+        if let Some(server) = &self.tcp {
+            servers.push(server.clone().run_ipiis().boxed());
+        }
+
+        join_all(servers).await;
+    }
+}
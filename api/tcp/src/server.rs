@@ -1,24 +1,38 @@
-use std::{net::SocketAddr, sync::Arc};
+use std::{net::SocketAddr, sync::Arc, time::Duration};
 
-use ipiis_api_common::impl_ipiis_server;
-use ipiis_common::Ipiis;
+use ipiis_api_common::{
+    config::IpiisConfig,
+    drain::{Drain, DEFAULT_DRAIN_TIMEOUT},
+    impl_ipiis_server,
+    retry::RetryPolicy,
+    router::{MaintenanceReport, RouteBook, RouterClient},
+};
+use ipiis_common::{
+    ByteMeter, ByteStats, Ipiis, RawReader, RequestBudget, RequestScheduler, ResponseCache,
+    RetryBudget, TimePolicy, VerificationAudit,
+};
 use ipis::{
     async_trait::async_trait,
     core::{
         account::{Account, AccountRef},
-        anyhow::Result,
+        anyhow::{bail, Result},
     },
-    env::{infer, Infer},
-    futures::Future,
-    log::{error, info, warn},
+    env::Infer,
+    futures::{future, Future},
+    log::{debug, error, info, warn},
     tokio,
 };
 
 impl_ipiis_server!(client: crate::client::IpiisClient, server: IpiisServer,);
 
+/// The default time to wait for the first request of a connection.
+pub const DEFAULT_READ_TIMEOUT: Duration = Duration::from_secs(30);
+
 pub struct IpiisServer {
     pub(crate) client: crate::client::IpiisClient,
     incoming: tokio::net::TcpListener,
+    read_timeout: Duration,
+    drain_timeout: Duration,
 }
 
 impl ::core::ops::Deref for IpiisServer {
@@ -35,22 +49,23 @@ impl<'a> Infer<'a> for IpiisServer {
     type GenesisResult = Self;
 
     async fn try_infer() -> Result<Self> {
-        let account_me = infer("ipis_account_me")?;
-        let account_primary = infer("ipiis_account_primary").ok();
-        let account_port = infer("ipiis_server_port")?;
+        let mut config = IpiisConfig::load()?;
+        let account_me = config.account_me()?;
+        let account_port = config.server_port()?;
 
-        Self::new(account_me, account_primary, account_port).await
+        Self::with_config(account_me, account_port, config).await
     }
 
     async fn genesis(
         port: <Self as Infer<'a>>::GenesisArgs,
     ) -> Result<<Self as Infer<'a>>::GenesisResult> {
+        let config = IpiisConfig::load()?;
+
         // generate an account
         let account = Account::generate();
-        let account_primary = infer("ipiis_account_primary").ok();
 
         // init a server
-        let server = Self::new(account, account_primary, port).await?;
+        let server = Self::with_config(account, port, config).await?;
 
         Ok(server)
     }
@@ -62,18 +77,311 @@ impl IpiisServer {
         account_primary: Option<AccountRef>,
         port: u16,
     ) -> Result<Self> {
+        let client = crate::client::IpiisClient::new(account_me, account_primary).await?;
+
+        Self::with_client(client, port).await
+    }
+
+    /// Creates a server as [`Self::new`], registering the address of the primary account,
+    /// the admin accounts and the revoked accounts of the config as well.
+    async fn with_config(account_me: Account, port: u16, config: IpiisConfig) -> Result<Self> {
+        let client = crate::client::IpiisClient::with_config(account_me, &config).await?;
+        let server = Self::with_client(client, port)
+            .await?
+            .with_admin_accounts(config.admin_accounts);
+
+        if config.revoked_accounts.is_empty() {
+            Ok(server)
+        } else {
+            Ok(server.with_revoked_accounts(config.revoked_accounts))
+        }
+    }
+
+    /// Creates a server on an already-opened address book, e.g. [`IpiisClient::book`] of another one,
+    /// so that several endpoints of a process share a single routing table.
+    ///
+    /// The server acts as the account of the book, which can be rebound with
+    /// [`RouterClient::with_account`].
+    ///
+    /// [`IpiisClient::book`]: crate::client::IpiisClient::book
+    pub async fn with_book(
+        account_primary: Option<AccountRef>,
+        port: u16,
+        book: RouterClient<<crate::client::IpiisClient as Ipiis>::Address>,
+    ) -> Result<Self> {
+        let client = crate::client::IpiisClient::with_book(account_primary, book).await?;
+
+        Self::with_client(client, port).await
+    }
+
+    async fn with_client(mut client: crate::client::IpiisClient, port: u16) -> Result<Self> {
         let incoming = {
             let addr: SocketAddr = format!("0.0.0.0:{port}").parse()?;
 
             tokio::net::TcpListener::bind(addr).await?
         };
 
+        client.state.serving = true;
+        client.state.raw_handlers = Some(Default::default());
+
         Ok(Self {
-            client: crate::client::IpiisClient::new(account_me, account_primary).await?,
+            client,
             incoming,
+            read_timeout: DEFAULT_READ_TIMEOUT,
+            drain_timeout: DEFAULT_DRAIN_TIMEOUT,
         })
     }
 
+    /// Returns the listening address, e.g. to find the ephemeral port bound by `0`.
+    pub fn local_addr(&self) -> Result<SocketAddr> {
+        self.incoming.local_addr().map_err(Into::into)
+    }
+
+    /// Sets the time to wait for the first request of a connection.
+    ///
+    /// The connections sending nothing within the timeout are dropped,
+    /// as QUIC does with its idle timeout.
+    pub fn with_read_timeout(mut self, timeout: Duration) -> Self {
+        self.read_timeout = timeout;
+        self
+    }
+
+    /// Sets the time to wait for the requests in flight on shutdown.
+    pub fn with_drain_timeout(mut self, timeout: Duration) -> Self {
+        self.drain_timeout = timeout;
+        self
+    }
+
+    /// Flushes the address book periodically in background (opt-in).
+    pub fn with_flush_interval(mut self, interval: Duration) -> Result<Self> {
+        self.client = self.client.with_flush_interval(interval)?;
+        Ok(self)
+    }
+
+    /// Bounds the total bytes of the requests being received or handled,
+    /// so that a flood of large requests cannot exhaust the memory.
+    ///
+    /// The requests are read only after reserving their bytes from the budget.
+    pub fn with_request_budget(mut self, bytes: u32) -> Self {
+        self.client.state.request_budget = Some(RequestBudget::new(bytes));
+        self
+    }
+
+    /// Bounds the number of the requests being handled concurrently.
+    ///
+    /// When saturated, the queued requests are dispatched by the priority class
+    /// of their opcodes, e.g. `Ping` is handled ahead of the bulk transfers.
+    pub fn with_max_concurrent_requests(mut self, limit: usize) -> Self {
+        self.client.state.request_scheduler = Some(RequestScheduler::new(limit));
+        self
+    }
+
+    /// Sheds the requests beyond `max_queued` waiting ones, instead of queueing them,
+    /// suggesting their clients to re-send them after the delay (`RETRY_AFTER`).
+    ///
+    /// It requires a limit of the concurrent requests, given by `with_max_concurrent_requests`.
+    pub fn with_load_shedding(mut self, max_queued: usize, retry_after: Duration) -> Result<Self> {
+        match self.client.state.request_scheduler.take() {
+            Some(scheduler) => {
+                self.client.state.request_scheduler =
+                    Some(scheduler.with_shedding(max_queued, retry_after));
+                Ok(self)
+            }
+            None => bail!("the load shedding requires a limit of the concurrent requests"),
+        }
+    }
+
+    /// Replays the response of a mutation re-sent within the window, instead of re-executing it,
+    /// keeping at most `capacity` responses.
+    ///
+    /// A client retrying after a lost response should re-send the same signed request.
+    pub fn with_dedup_window(mut self, window: Duration, capacity: usize) -> Self {
+        self.client.state.response_cache = Some(ResponseCache::new(window, capacity));
+        self
+    }
+
+    /// Limits the lifetime of the records served on `GetAddress` and `GetAccountPrimary`,
+    /// so that the clients re-fetch them after the TTL, instead of caching them forever.
+    pub fn with_response_ttl(mut self, ttl: Duration) -> Self {
+        self.client.state.time_policy.cache_record_ttl = Some(ttl);
+        self
+    }
+
+    /// Sets the time-sensitive behaviors, e.g. the tolerance of the clock skew verifying the requests.
+    pub fn with_time_policy(mut self, policy: TimePolicy) -> Self {
+        self.client = self.client.with_time_policy(policy);
+        self
+    }
+
+    /// Sets who may issue the protected requests, e.g. `SnapshotPrimaries`.
+    ///
+    /// Without it, only the account of the server itself is authorized.
+    pub fn with_authorizer(
+        mut self,
+        authorizer: impl Fn(&AccountRef) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        self.client.state.authorizer = Some(Arc::new(authorizer));
+        self
+    }
+
+    /// Adds the accounts allowed to mutate the directory, without sharing the server's key.
+    ///
+    /// The admin accounts are loaded from the config as well.
+    pub fn with_admin_accounts(mut self, accounts: impl IntoIterator<Item = AccountRef>) -> Self {
+        self.client.state.admin_accounts.extend(accounts);
+        self
+    }
+
+    /// Accounts the bytes transferred by each verified account (opt-in).
+    pub fn with_byte_accounting(mut self) -> Self {
+        self.client
+            .state
+            .byte_meter
+            .get_or_insert_with(Default::default);
+        self
+    }
+
+    /// Reports the processing time of each request in its response (opt-in),
+    /// so that the clients can tell it from the network latency.
+    pub fn with_server_timing(mut self) -> Self {
+        self.client.state.server_timing = true;
+        self
+    }
+
+    /// Logs the requests failing the signature verification, rate-limited by the audit.
+    pub fn with_verification_audit(mut self, audit: VerificationAudit) -> Self {
+        self.client.state.verification_audit = Some(audit);
+        self
+    }
+
+    /// Rejects the requests of an account once it has transferred `bytes` within the window,
+    /// until the next window.
+    ///
+    /// The transferred bytes are accounted as well.
+    pub fn with_byte_quota(mut self, bytes: u64, window: Duration) -> Self {
+        self.client.state.byte_meter = Some(ByteMeter::new().with_quota(bytes, window));
+        self
+    }
+
+    /// Returns the bytes transferred by the account, if accounted.
+    pub fn byte_usage(&self, account: &AccountRef) -> ByteStats {
+        self.client
+            .state
+            .byte_meter
+            .as_ref()
+            .map(|meter| meter.usage(account))
+            .unwrap_or_default()
+    }
+
+    /// Rejects the requests signed by the revoked accounts, even if their keys are still valid.
+    ///
+    /// The revoked accounts are loaded from the config as well.
+    pub fn with_revoked_accounts(mut self, accounts: impl IntoIterator<Item = AccountRef>) -> Self {
+        self.client
+            .state
+            .revocation_list
+            .get_or_insert_with(Default::default)
+            .extend(accounts);
+        self
+    }
+
+    /// Fetches the revoked accounts from the authority periodically in background,
+    /// in addition to the local ones.
+    pub fn with_revocation_authority(
+        mut self,
+        authority: AccountRef,
+        interval: Duration,
+    ) -> Result<Self> {
+        let client = self.client.clone();
+        let list = self.client.state.revocation_list.take().unwrap_or_default();
+        self.client.state.revocation_list = Some(list.with_refresh(interval, move || {
+            let client = client.clone();
+            async move { client.list_revoked_accounts(&authority).await }
+        })?);
+        Ok(self)
+    }
+
+    /// Sets the retry policy of the upstream address resolution.
+    pub fn with_resolve_retry(mut self, policy: RetryPolicy) -> Self {
+        self.client = self.client.with_resolve_retry(policy);
+        self
+    }
+
+    /// Seeds the complete routing table, resolving the accounts only from it.
+    pub fn with_static_routes(mut self, book: &RouteBook) -> Result<Self> {
+        self.client = self.client.with_static_routes(book)?;
+        Ok(self)
+    }
+
+    /// Bounds the total volume of the retries across all the outgoing calls.
+    pub fn with_retry_budget(mut self, budget: RetryBudget) -> Self {
+        self.client = self.client.with_retry_budget(budget);
+        self
+    }
+
+    /// Bounds establishing a connection to the peers, e.g. if the address is dead.
+    pub fn with_connect_timeout(mut self, timeout: Duration) -> Self {
+        self.client = self.client.with_connect_timeout(timeout);
+        self
+    }
+
+    /// Bounds the primaries forwarding a lookup of the kind's primary account,
+    /// e.g. if the primaries form a loop.
+    pub fn with_max_resolution_depth(mut self, depth: u8) -> Self {
+        self.client = self.client.with_max_resolution_depth(depth);
+        self
+    }
+
+    /// Passes the exact bytes of each request sent by the server to the callback.
+    pub fn with_wire_capture<F>(mut self, callback: F) -> Self
+    where
+        F: Fn(&[u8]) + Send + Sync + 'static,
+    {
+        self.client = self.client.with_wire_capture(callback);
+        self
+    }
+
+    /// Advertises the address to the peers on `Hello`, e.g. its public address.
+    pub fn with_advertised_address(
+        mut self,
+        address: <crate::client::IpiisClient as Ipiis>::Address,
+    ) -> Self {
+        self.client = self.client.with_advertised_address(address);
+        self
+    }
+
+    /// Lets any peer register its own address on `Hello`, unless the account is already in the address book.
+    ///
+    /// Without it, only the accounts approved by the authorizer are registered,
+    /// and only they may overwrite their existing addresses.
+    pub fn with_hello_registration(mut self) -> Self {
+        self.client.state.hello_registration = true;
+        self
+    }
+
+    /// Registers a handler of the raw requests of the opcode at runtime,
+    /// served by [`Self::run_ipiis`] along with the directory.
+    ///
+    /// The request is verified, metered and checked against the revoked accounts
+    /// as the typed ones, so the clients should start it with
+    /// [`ipiis_common::send_raw_request_header`].
+    /// The handler receives the request right after its header,
+    /// and returns the bytes of the response, starting with its result flag.
+    /// Note that the opcodes of the directory take precedence.
+    pub fn on_raw<F, Fut>(&self, opcode: u16, handler: F)
+    where
+        F: Fn(crate::client::IpiisClient, RawReader) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<Vec<u8>>> + Send + 'static,
+    {
+        // do not let the handlers own themselves
+        let mut client = self.client.clone();
+        client.state.raw_handlers = None;
+
+        if let Some(handlers) = &self.client.state.raw_handlers {
+            handlers.insert(opcode, move |recv| handler(client.clone(), recv));
+        }
+    }
+
     pub async fn run<C, F, Fut>(&self, client: Arc<C>, handler: F)
     where
         C: AsRef<crate::client::IpiisClient> + Send + Sync + 'static,
@@ -87,18 +395,62 @@ impl IpiisServer {
             + 'static,
         Fut: Future<Output = Result<()>> + Send,
     {
+        // never shut down
+        let _ = self
+            .run_with_shutdown(client, handler, future::pending())
+            .await;
+    }
+
+    /// Serves the requests until the signal, and then stops accepting the new connections,
+    /// waits for the requests in flight within the drain timeout and flushes the address book,
+    /// returning how much has been flushed.
+    pub async fn run_with_shutdown<C, F, Fut, S>(
+        &self,
+        client: Arc<C>,
+        handler: F,
+        signal: S,
+    ) -> Result<MaintenanceReport>
+    where
+        C: AsRef<crate::client::IpiisClient> + Send + Sync + 'static,
+        F: Fn(
+                Arc<C>,
+                <crate::client::IpiisClient as Ipiis>::Writer,
+                <crate::client::IpiisClient as Ipiis>::Reader,
+            ) -> Fut
+            + Copy
+            + Send
+            + 'static,
+        Fut: Future<Output = Result<()>> + Send,
+        S: Future<Output = ()> + Send,
+    {
+        let drain = Drain::default();
+        tokio::pin!(signal);
+
         loop {
-            match self.incoming.accept().await {
+            let accepted = tokio::select! {
+                () = &mut signal => break,
+                accepted = self.incoming.accept() => accepted,
+            };
+
+            match accepted {
                 Ok((stream, addr)) => {
                     info!("incoming connection: addr={addr}");
 
                     {
                         // Each stream initiated by the client constitutes a new request.
                         let client = client.clone();
-
-                        let (recv, send) = tokio::io::split(stream);
+                        let read_timeout = self.read_timeout;
+                        let guard = drain.guard();
 
                         ::ipis::tokio::spawn(async move {
+                            let _guard = guard;
+
+                            // wait for the first request
+                            if !Self::wait_request(&stream, addr, read_timeout).await {
+                                return;
+                            }
+
+                            let (recv, send) = tokio::io::split(stream);
                             Self::handle(client, addr, (send, recv), handler).await
                         });
                     }
@@ -108,6 +460,36 @@ impl IpiisServer {
                 }
             }
         }
+
+        info!("shutting down: draining the requests in flight");
+        if !drain.drain(self.drain_timeout).await {
+            warn!("shutting down: timed out draining the requests in flight");
+        }
+
+        // flush the address book
+        self.client.maintenance().await
+    }
+
+    async fn wait_request(
+        stream: &tokio::net::TcpStream,
+        addr: SocketAddr,
+        read_timeout: Duration,
+    ) -> bool {
+        match tokio::time::timeout(read_timeout, stream.peek(&mut [0; 1])).await {
+            Ok(Ok(0)) => {
+                debug!("connection closed without requests: addr={addr}");
+                false
+            }
+            Ok(Ok(_)) => true,
+            Ok(Err(e)) => {
+                warn!("incoming stream error: addr={addr}, {e}");
+                false
+            }
+            Err(_) => {
+                debug!("reaped an idle connection: addr={addr}");
+                false
+            }
+        }
     }
 
     async fn handle<C, F, Fut>(
@@ -0,0 +1,13 @@
+use ipiis_api::{client::IpiisClient, common::Ipiis};
+use ipis::{env::Infer, tokio};
+
+#[tokio::test]
+async fn test_account_me_ref() {
+    let client = IpiisClient::genesis(None).await.unwrap();
+
+    assert_eq!(&client.account_me_ref(), client.account_ref());
+    assert_eq!(
+        client.account_me_ref(),
+        unsafe { client.account_me() }.unwrap().account_ref(),
+    );
+}
@@ -0,0 +1,49 @@
+mod common;
+
+use std::time::{Duration, Instant};
+
+use ipiis_api::common::Ipiis;
+use ipis::{core::account::Account, tokio};
+
+use self::common::test_client;
+
+#[tokio::test]
+async fn test_address_validation() {
+    // create a client
+    let client = test_client("address-validation").await.unwrap();
+    let target = Account::generate().account_ref();
+
+    // malformed addresses should be rejected up front
+    for (address, reason) in [
+        ("not an address", "expected `host:port`"),
+        ("not an address:80", "invalid host"),
+        ("127.0.0.1:port", "invalid port"),
+        ("127.0.0.1:65536", "invalid port"),
+    ] {
+        let instant = Instant::now();
+        let error = client
+            .set_address(None, &target, &address.to_string())
+            .await
+            .unwrap_err();
+        assert!(instant.elapsed() < Duration::from_secs(1));
+        assert!(error.to_string().contains(reason), "{address}: {error}");
+        assert!(client.get_address(None, &target).await.is_err());
+    }
+
+    // literal IPs should be stored as-is
+    for address in ["127.0.0.1:5031", "[::1]:5031"] {
+        client
+            .set_address(None, &target, &address.to_string())
+            .await
+            .unwrap();
+        assert_eq!(client.get_address(None, &target).await.unwrap(), address);
+    }
+
+    // hostnames should be resolved
+    client
+        .set_address(None, &target, &"localhost:5031".to_string())
+        .await
+        .unwrap();
+    let address = client.get_address(None, &target).await.unwrap();
+    assert!(["127.0.0.1:5031", "[::1]:5031"].contains(&address.as_str()));
+}
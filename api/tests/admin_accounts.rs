@@ -0,0 +1,74 @@
+mod common;
+
+use ipiis_api::{
+    client::IpiisClient,
+    common::{external_call, Ipiis},
+};
+use ipis::{
+    core::{
+        account::{Account, AccountRef},
+        anyhow::Result,
+    },
+    tokio,
+};
+
+use self::common::{introduce, test_client, use_router_db, TestPeer};
+
+#[tokio::test]
+async fn test_admin_accounts() {
+    let admin = Account::generate();
+    let admin_ref = admin.account_ref();
+
+    // deploy a server delegating the administration
+    let server = TestPeer::deploy_with("admin-accounts", None, move |server| {
+        Ok(server.with_admin_accounts([admin_ref]))
+    })
+    .await
+    .unwrap();
+
+    // create clients
+    use_router_db("client-admin-accounts-admin");
+    let client_admin = IpiisClient::new(admin, None).await.unwrap();
+    let client_other = test_client("admin-accounts-other").await.unwrap();
+
+    for client in [&client_admin, &client_other] {
+        introduce(client, &server.account, &server.address).unwrap();
+    }
+
+    // an admin account should be allowed to mutate the directory
+    let target = Account::generate().account_ref();
+    let address = "127.0.0.1:9801".to_string();
+    set_address(&client_admin, &server.account, &target, &address)
+        .await
+        .unwrap();
+    assert_eq!(
+        server.server.get_address(None, &target).await.unwrap(),
+        address,
+    );
+
+    // the others should be rejected
+    let target = Account::generate().account_ref();
+    assert!(
+        set_address(&client_other, &server.account, &target, &address)
+            .await
+            .is_err()
+    );
+    assert!(server.server.get_address(None, &target).await.is_err());
+}
+
+async fn set_address(
+    client: &IpiisClient,
+    server: &AccountRef,
+    target: &AccountRef,
+    address: &str,
+) -> Result<()> {
+    // external call
+    external_call!(
+        client: client,
+        target: None => server,
+        request: ::ipiis_api::common::io => SetAddress,
+        sign: client.sign_owned(*server, (None, *target, address.to_string()))?,
+        inputs: { },
+    );
+    Ok(())
+}
@@ -0,0 +1,48 @@
+mod common;
+
+use core::time::Duration;
+
+use ipiis_api::common::{bench_resolve, Ipiis};
+use ipis::{core::account::Account, tokio};
+
+use self::common::TestPeer;
+
+#[tokio::test]
+async fn test_bench_resolve() {
+    // deploy a root server
+    let server = TestPeer::deploy("bench-resolve", None).await.unwrap();
+
+    // let it know the target
+    let target = Account::generate().account_ref();
+    server
+        .server
+        .set_address(None, &target, &"127.0.0.1:9001".parse().unwrap())
+        .await
+        .unwrap();
+
+    // create a client
+    let client = server.child("bench-resolve").await.unwrap();
+
+    // the lookups should bypass the local address book
+    let cached = client.get_address(None, &target).await.unwrap();
+    let moved = "127.0.0.1:9002".parse().unwrap();
+    server
+        .server
+        .set_address(None, &target, &moved)
+        .await
+        .unwrap();
+    assert_eq!(client.get_address(None, &target).await.unwrap(), cached);
+    assert_eq!(
+        client.get_address_uncached(None, &target).await.unwrap(),
+        moved,
+    );
+
+    // the latencies of the lookups should be reported
+    let count = 20;
+    let report = bench_resolve(count, || client.get_address_uncached(None, &target)).await;
+    assert_eq!(report.succeeded, count);
+    assert_eq!(report.failed, 0);
+    assert!(report.p50 > Duration::ZERO);
+    assert!(report.p50 <= report.p99);
+    assert!(report.p99 < Duration::from_secs(5));
+}
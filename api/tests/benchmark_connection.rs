@@ -0,0 +1,31 @@
+mod common;
+
+use core::time::Duration;
+
+use ipiis_api::common::Ipiis;
+use ipis::tokio;
+
+use self::common::TestPeer;
+
+const BYTES: usize = 16 * 1024 * 1024;
+
+#[tokio::test]
+async fn test_benchmark_connection() {
+    // deploy a server
+    let server = TestPeer::deploy("benchmark-connection", None)
+        .await
+        .unwrap();
+
+    // create a client
+    let client = server.client("benchmark-connection").await.unwrap();
+
+    // measure the throughput over loopback
+    let report = client
+        .benchmark_connection(None, &server.account, BYTES)
+        .await
+        .unwrap();
+    assert_eq!(report.bytes, BYTES as u64);
+    assert!(report.elapsed > Duration::ZERO);
+    assert!(report.rtt > Duration::ZERO);
+    assert!(report.bps > 0.0);
+}
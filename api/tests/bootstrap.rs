@@ -0,0 +1,31 @@
+use ipiis_api::{client::IpiisClient, common::Ipiis};
+use ipis::{
+    core::{account::Account, value::hash::Hash},
+    env::Infer,
+    tokio,
+};
+
+#[tokio::test]
+async fn test_bootstrap_local() {
+    // create a client without any network
+    let client = IpiisClient::genesis(None).await.unwrap();
+
+    // bootstrap a brand-new kind locally
+    let kind = Hash::with_str("__ipiis__test__bootstrap__");
+    let account = Account::generate().account_ref();
+    let address = "127.0.0.1:5020".to_string();
+    client
+        .book()
+        .set_primary_with_address(Some(&kind), &account, &address)
+        .unwrap();
+
+    // read it back, without asking any directory
+    assert_eq!(
+        client.get_account_primary(Some(&kind)).await.unwrap(),
+        account,
+    );
+    assert_eq!(
+        client.get_address(Some(&kind), &account).await.unwrap(),
+        address,
+    );
+}
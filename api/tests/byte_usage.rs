@@ -0,0 +1,74 @@
+mod common;
+
+use core::time::Duration;
+
+use ipiis_api::common::{is_quota_exceeded, ByteStats, Ipiis};
+use ipis::tokio;
+
+use self::common::{introduce, TestPeer};
+
+#[tokio::test]
+async fn test_byte_usage() {
+    // deploy a server accounting the transferred bytes
+    let server = TestPeer::deploy_with("byte-usage", None, |server| {
+        Ok(server.with_byte_accounting())
+    })
+    .await
+    .unwrap();
+
+    // create clients
+    let client_a = server.client("byte-usage-a").await.unwrap();
+    let client_b = server.client("byte-usage-b").await.unwrap();
+
+    // transfer the same requests, in different numbers
+    for _ in 0..3 {
+        client_a.ping(None, &server.account).await.unwrap();
+    }
+    client_b.ping(None, &server.account).await.unwrap();
+
+    // the bytes should be accounted per account
+    let usage_a = server.server.byte_usage(client_a.account_ref());
+    let usage_b = server.server.byte_usage(client_b.account_ref());
+    assert!(usage_b.bytes_in > 0);
+    assert!(usage_b.bytes_out > 0);
+    assert_eq!(
+        usage_a,
+        ByteStats {
+            bytes_in: 3 * usage_b.bytes_in,
+            bytes_out: 3 * usage_b.bytes_out,
+        },
+    );
+
+    // the unknown accounts have transferred nothing
+    assert_eq!(
+        server.server.byte_usage(&server.account),
+        ByteStats::default()
+    );
+
+    // deploy a server allowing a single request per window
+    let server = TestPeer::deploy_with("byte-usage-quota", None, |server| {
+        Ok(server.with_byte_quota(1, Duration::from_secs(1)))
+    })
+    .await
+    .unwrap();
+
+    for client in [&client_a, &client_b] {
+        introduce(client, &server.account, &server.address).unwrap();
+    }
+
+    // the further requests should be rejected once the quota is exceeded
+    client_a.ping(None, &server.account).await.unwrap();
+    let error = client_a.ping(None, &server.account).await.unwrap_err();
+    assert!(is_quota_exceeded(&error));
+
+    // the quota should be enforced per account
+    client_b.ping(None, &server.account).await.unwrap();
+
+    // the rejected request should not be accounted
+    let usage = server.server.byte_usage(client_a.account_ref());
+    assert_eq!(usage, server.server.byte_usage(client_b.account_ref()));
+
+    // the quota should be restored in the next window
+    tokio::time::sleep(Duration::from_secs(1)).await;
+    client_a.ping(None, &server.account).await.unwrap();
+}
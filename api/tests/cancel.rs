@@ -0,0 +1,61 @@
+mod common;
+
+use core::time::Duration;
+use std::sync::Arc;
+
+use ipiis_api::common::{external_call, io::OpCode, Ipiis, IpiisError, Nonce};
+use ipis::{core::account::Account, tokio};
+
+use self::common::{introduce, test_client};
+
+#[tokio::test]
+async fn test_cancel() {
+    // deploy a server accepting connections, but never responding
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        let mut streams = vec![];
+        while let Ok((stream, _)) = listener.accept().await {
+            streams.push(stream);
+        }
+    });
+    let target = Account::generate().account_ref();
+
+    // create a client
+    let client = Arc::new(test_client("cancel").await.unwrap());
+    introduce(&client, &target, &address).unwrap();
+
+    // issue a call which hangs
+    let task = {
+        let client = client.clone();
+        tokio::spawn(async move {
+            // external call
+            external_call!(
+                client: client,
+                target: None => &target,
+                request: ::ipiis_api::common::io => Ping,
+                sign: client.sign_owned(target, Nonce::generate())?,
+                inputs: { },
+            );
+            Result::<_, ::ipis::core::anyhow::Error>::Ok(())
+        })
+    };
+    tokio::time::sleep(Duration::from_millis(500)).await;
+
+    // the call should be in flight
+    let in_flight = client.in_flight();
+    assert_eq!(in_flight.len(), 1);
+    assert_eq!(in_flight[0].target, target);
+    assert_eq!(in_flight[0].opcode, OpCode::Ping as u16);
+    assert!(in_flight[0].elapsed >= Duration::from_millis(500));
+
+    // cancel the call
+    assert!(client.cancel(in_flight[0].id));
+    let error = task.await.unwrap().unwrap_err();
+    assert!(matches!(
+        error.downcast_ref::<IpiisError>(),
+        Some(IpiisError::Cancelled(id)) if *id == in_flight[0].id,
+    ));
+    assert!(client.in_flight().is_empty());
+    assert!(!client.cancel(in_flight[0].id));
+}
@@ -0,0 +1,37 @@
+mod common;
+
+use ipiis_api::server::IpiisServer;
+use ipis::tokio;
+
+use self::common::TestPeer;
+
+#[tokio::test]
+async fn test_capabilities() {
+    // the opcodes should be reported as registered by `handle_external_call!`
+    let expected = [
+        "GetAccountPrimary",
+        "SetAccountPrimary",
+        "DeleteAccountPrimary",
+        "GetAddress",
+        "SetAddress",
+        "DeleteAddress",
+        "Ping",
+        "ListAccounts",
+        "SnapshotPrimaries",
+        "ListRevokedAccounts",
+        "Capabilities",
+        "Hello",
+        "Sink",
+    ];
+    assert_eq!(IpiisServer::supported_opcodes(), expected);
+
+    // deploy a server
+    let server = TestPeer::deploy("capabilities", None).await.unwrap();
+
+    // create a client
+    let client = server.client("capabilities").await.unwrap();
+
+    // discover the opcodes over the wire
+    let opcodes = client.capabilities(&server.account).await.unwrap();
+    assert_eq!(opcodes, expected);
+}
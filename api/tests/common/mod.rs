@@ -0,0 +1,228 @@
+// not every test uses every helper
+#![allow(dead_code)]
+
+use std::{net::SocketAddr, path::PathBuf, sync::Arc};
+
+use ipiis_api::{
+    common::Ipiis,
+    tcp::{client::IpiisClient, server::IpiisServer},
+};
+use ipis::{
+    core::{
+        account::{Account, AccountRef},
+        anyhow::Result,
+    },
+    env::Infer,
+    tokio::{self, task::JoinHandle},
+};
+
+/// Configures a server before running it, e.g. enabling its options.
+type Configure = Arc<dyn Fn(IpiisServer) -> Result<IpiisServer> + Send + Sync>;
+
+/// A server running in background, with its own address book.
+pub struct TestPeer {
+    pub server: Arc<IpiisServer>,
+    pub account: AccountRef,
+    pub address: SocketAddr,
+    account_me: String,
+    name: String,
+    parent: Option<(AccountRef, SocketAddr)>,
+    configure: Configure,
+    task: JoinHandle<()>,
+}
+
+impl TestPeer {
+    /// Deploys a server on an ephemeral port, routing through the parent if given.
+    pub async fn deploy(name: &str, parent: Option<&TestPeer>) -> Result<Self> {
+        Self::deploy_with(name, parent, Ok).await
+    }
+
+    /// Deploys a server as [`Self::deploy`], configuring it before running, even on restart.
+    pub async fn deploy_with(
+        name: &str,
+        parent: Option<&TestPeer>,
+        configure: impl Fn(IpiisServer) -> Result<IpiisServer> + Send + Sync + 'static,
+    ) -> Result<Self> {
+        Self::deploy_as_with(name, Account::generate(), parent, configure).await
+    }
+
+    /// Deploys a server as [`Self::deploy_with`], acting as the given account.
+    pub async fn deploy_as_with(
+        name: &str,
+        account_me: Account,
+        parent: Option<&TestPeer>,
+        configure: impl Fn(IpiisServer) -> Result<IpiisServer> + Send + Sync + 'static,
+    ) -> Result<Self> {
+        let parent = parent.map(|parent| (parent.account, parent.address));
+
+        // start from an empty address book
+        let _ = ::std::fs::remove_dir_all(Self::db_path(name));
+
+        Self::deploy_as(name, account_me, 0, parent, Arc::new(configure)).await
+    }
+
+    /// Creates a client with its own address book, knowing the address of the peer.
+    pub async fn client(&self, name: &str) -> Result<IpiisClient> {
+        let client = test_client(name).await?;
+        introduce(&client, &self.account, &self.address)?;
+        Ok(client)
+    }
+
+    /// Creates a client with its own address book, routing through the peer as its primary.
+    pub async fn child(&self, name: &str) -> Result<IpiisClient> {
+        let client = test_client(name).await?;
+        self.route(&client)?;
+        Ok(client)
+    }
+
+    /// Routes the client through the peer, as its primary account.
+    pub fn route(&self, client: &IpiisClient) -> Result<()> {
+        client
+            .book()
+            .set_primary_with_address(None, &self.account, &self.address.to_string())
+    }
+
+    /// Returns the account the peer acts as, e.g. to rebind a client.
+    pub fn account_me(&self) -> Result<Account> {
+        self.account_me.parse().map_err(Into::into)
+    }
+
+    /// Stops the server, releasing its port and address book.
+    pub async fn shutdown(self) -> Result<Restart> {
+        self.task.abort();
+        let _ = self.task.await;
+
+        Ok(Restart {
+            account_me: self.account_me,
+            name: self.name,
+            port: self.address.port(),
+            parent: self.parent,
+            configure: self.configure,
+        })
+    }
+
+    fn db_path(name: &str) -> PathBuf {
+        router_db_path(&format!("peer-{name}"))
+    }
+
+    async fn deploy_as(
+        name: &str,
+        account_me: Account,
+        port: u16,
+        parent: Option<(AccountRef, SocketAddr)>,
+        configure: Configure,
+    ) -> Result<Self> {
+        // each peer owns its address book
+        ::std::env::set_var("ipiis_router_db", Self::db_path(name));
+
+        // create a server
+        let account_me_str = account_me.to_string();
+        let server = IpiisServer::new(account_me, None, port).await?;
+
+        // register the parent account
+        if let Some((account, address)) = parent {
+            server
+                .book()
+                .set_primary_with_address(None, &account, &address.to_string())?;
+        }
+
+        let server = Arc::new(configure(server)?);
+        let account = *server.account_ref();
+        let address = loopback(&server)?;
+
+        // deploy the server
+        let task = tokio::spawn(server.clone().run_ipiis());
+
+        Ok(Self {
+            server,
+            account,
+            address,
+            account_me: account_me_str,
+            name: name.to_string(),
+            parent,
+            configure,
+            task,
+        })
+    }
+}
+
+/// A stopped [`TestPeer`], which can be deployed again with the same account and port.
+pub struct Restart {
+    account_me: String,
+    name: String,
+    port: u16,
+    parent: Option<(AccountRef, SocketAddr)>,
+    configure: Configure,
+}
+
+impl Restart {
+    pub async fn deploy(self) -> Result<TestPeer> {
+        TestPeer::deploy_as(
+            &self.name,
+            self.account_me.parse()?,
+            self.port,
+            self.parent,
+            self.configure,
+        )
+        .await
+    }
+}
+
+/// Points the following clients and servers to a fresh address book of the name,
+/// without any primary account.
+pub fn use_router_db(name: &str) {
+    let path = router_db_path(name);
+    let _ = ::std::fs::remove_dir_all(&path);
+
+    ::std::env::set_var("ipiis_router_db", path);
+    ::std::env::remove_var("ipiis_account_primary");
+    ::std::env::remove_var("ipiis_account_primary_address");
+}
+
+/// Creates a server on an ephemeral port with its own address book, not running yet,
+/// e.g. to be served by the custom handlers.
+///
+/// Returns the server with its loopback address.
+pub async fn test_server(name: &str) -> Result<(IpiisServer, SocketAddr)> {
+    test_server_as(name, Account::generate()).await
+}
+
+/// Creates a server as [`test_server`], acting as the given account.
+pub async fn test_server_as(name: &str, account_me: Account) -> Result<(IpiisServer, SocketAddr)> {
+    use_router_db(&format!("server-{name}"));
+
+    let server = IpiisServer::new(account_me, None, 0).await?;
+    let address = loopback(&server)?;
+    Ok((server, address))
+}
+
+/// Creates a client with its own address book, without any primary account.
+pub async fn test_client(name: &str) -> Result<IpiisClient> {
+    use_router_db(&format!("client-{name}"));
+
+    IpiisClient::genesis(None).await
+}
+
+/// Opens the address book of the client created by [`test_client`] again, keeping its entries,
+/// e.g. to check what has survived the previous client.
+pub async fn reopen_client(name: &str) -> Result<IpiisClient> {
+    ::std::env::set_var("ipiis_router_db", router_db_path(&format!("client-{name}")));
+
+    IpiisClient::genesis(None).await
+}
+
+/// Lets the client know the address of the account.
+pub fn introduce(client: &IpiisClient, account: &AccountRef, address: &SocketAddr) -> Result<()> {
+    client.book().set(None, account, &address.to_string())
+}
+
+/// Returns the address of the server on the loopback interface.
+fn loopback(server: &IpiisServer) -> Result<SocketAddr> {
+    let mut address = server.local_addr()?;
+    address.set_ip([127, 0, 0, 1].into());
+    Ok(address)
+}
+
+fn router_db_path(name: &str) -> PathBuf {
+    ::std::env::temp_dir().join(format!("ipiis-test-{name}"))
+}
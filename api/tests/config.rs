@@ -0,0 +1,60 @@
+use ipiis_api::{client::IpiisClient, common::Ipiis};
+use ipis::{core::account::Account, env::Infer, tokio};
+
+#[tokio::test]
+async fn test_config_file() {
+    // create accounts
+    let account = Account::generate();
+    let account_primary_file = Account::generate().account_ref();
+    let account_primary_env = Account::generate().account_ref();
+
+    // write a config file
+    let dir = ::std::env::temp_dir().join(format!("ipiis-test-config-{account_primary_file}"));
+    ::std::fs::create_dir_all(&dir).unwrap();
+
+    let path = dir.join("ipiis.toml");
+    ::std::fs::write(
+        &path,
+        format!(
+            r#"
+account_me = "{account}"
+account_primary = "{account_primary_file}"
+account_primary_address = "127.0.0.1:9801"
+"#,
+        ),
+    )
+    .unwrap();
+
+    // register the environment variables
+    ::std::env::set_var("ipiis_config", &path);
+    ::std::env::set_var("ipiis_router_db", dir.join("db"));
+    ::std::env::set_var("ipiis_account_primary", account_primary_env.to_string());
+
+    // try creating a client
+    let client = IpiisClient::infer().await;
+
+    // compare the accounts
+    assert_eq!(
+        account.account_ref().to_string(),
+        client.account_ref().to_string(),
+    );
+
+    // the environment variables should override the config file
+    assert_eq!(
+        client.get_account_primary(None).await.unwrap(),
+        account_primary_env,
+    );
+    assert_eq!(
+        client.book().get(None, &account_primary_env).unwrap(),
+        Some("127.0.0.1:9801".to_string()),
+    );
+    drop(client);
+
+    // the constructors should not load the config file
+    ::std::env::set_var("ipiis_router_db", dir.join("db-new"));
+    let account = Account::generate();
+    let client = IpiisClient::new(account, Some(account_primary_env))
+        .await
+        .unwrap();
+    assert_eq!(client.book().get(None, &account_primary_env).unwrap(), None);
+}
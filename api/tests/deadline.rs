@@ -0,0 +1,175 @@
+mod common;
+
+use core::time::Duration;
+use std::sync::Arc;
+
+use ipiis_api::{
+    client::IpiisClient,
+    common::{define_io, external_call, handle_external_call, is_timeout, Ipiis, ServerResult},
+    server::IpiisServer,
+};
+use ipis::{
+    async_trait::async_trait,
+    core::{
+        account::{GuaranteeSigned, GuarantorSigned},
+        anyhow::Result,
+        data::Data,
+    },
+    env::Infer,
+    tokio,
+};
+
+use self::common::{introduce, test_client, test_server};
+
+/// How long each handler takes
+const WORK: Duration = Duration::from_millis(300);
+
+#[tokio::test]
+async fn test_deadline() {
+    // deploy a server
+    let (server, address) = test_server("deadline").await.unwrap();
+    let server = DeadlineServer {
+        client: server.into(),
+    };
+    let server_ref = *server.client.account_ref();
+    tokio::spawn(server.run());
+
+    // create a client
+    let client = test_client("deadline").await.unwrap();
+    introduce(&client, &server_ref, &address).unwrap();
+
+    // the timeouts should be declared per opcode
+    assert_eq!(io::OpCode::Fast.timeout(), Some(Duration::from_millis(100)));
+    assert_eq!(io::OpCode::Slow.timeout(), Some(Duration::from_secs(5)));
+
+    // the fast opcode should time out
+    let error = async {
+        external_call!(
+            client: client,
+            target: None => &server_ref,
+            request: self::io => Fast,
+            sign: client.sign_owned(server_ref, 0)?,
+            inputs: { },
+            outputs: { },
+        );
+        Result::<_, ::ipis::core::anyhow::Error>::Ok(())
+    }
+    .await
+    .unwrap_err();
+    assert!(is_timeout(&error), "{error:#}");
+
+    // the slow opcode should complete within its limit
+    async {
+        external_call!(
+            client: client,
+            target: None => &server_ref,
+            request: self::io => Slow,
+            sign: client.sign_owned(server_ref, 0)?,
+            inputs: { },
+            outputs: { },
+        );
+        Result::<_, ::ipis::core::anyhow::Error>::Ok(())
+    }
+    .await
+    .unwrap();
+}
+
+define_io! {
+    Fast = 0 {
+        timeout_ms: 100,
+        inputs: { },
+        input_sign: Data<GuaranteeSigned, u8>,
+        outputs: { },
+        output_sign: Data<GuarantorSigned, u8>,
+        generics: { },
+    },
+    Slow = 1 {
+        timeout_ms: 5000,
+        inputs: { },
+        input_sign: Data<GuaranteeSigned, u8>,
+        outputs: { },
+        output_sign: Data<GuarantorSigned, u8>,
+        generics: { },
+    },
+}
+
+pub struct DeadlineServer {
+    client: Arc<IpiisServer>,
+}
+
+impl AsRef<IpiisClient> for DeadlineServer {
+    fn as_ref(&self) -> &IpiisClient {
+        &self.client
+    }
+}
+
+#[async_trait]
+impl<'a> Infer<'a> for DeadlineServer {
+    type GenesisArgs = <IpiisServer as Infer<'a>>::GenesisArgs;
+    type GenesisResult = Self;
+
+    async fn try_infer() -> Result<Self> {
+        Ok(Self {
+            client: IpiisServer::try_infer().await?.into(),
+        })
+    }
+
+    async fn genesis(
+        args: <Self as Infer<'a>>::GenesisArgs,
+    ) -> Result<<Self as Infer<'a>>::GenesisResult> {
+        Ok(Self {
+            client: IpiisServer::genesis(args).await?.into(),
+        })
+    }
+}
+
+handle_external_call!(
+    server: DeadlineServer => IpiisServer,
+    name: run,
+    request: self::io => {
+        Fast => handle_fast,
+        Slow => handle_slow,
+    },
+);
+
+impl DeadlineServer {
+    async fn handle_fast(
+        client: &IpiisServer,
+        req: self::io::request::Fast<'static>,
+    ) -> Result<self::io::response::Fast<'static>> {
+        // unpack sign
+        let sign_as_guarantee = req.__sign.into_owned().await?;
+
+        // handle data
+        tokio::time::sleep(WORK).await;
+
+        // sign data
+        let sign = client.sign_as_guarantor(sign_as_guarantee)?;
+
+        // pack data
+        Ok(self::io::response::Fast {
+            __lifetime: Default::default(),
+            __sign: ::ipis::stream::DynStream::Owned(sign),
+        })
+    }
+
+    async fn handle_slow(
+        client: &IpiisServer,
+        req: self::io::request::Slow<'static>,
+    ) -> Result<self::io::response::Slow<'static>> {
+        // unpack sign
+        let sign_as_guarantee = req.__sign.into_owned().await?;
+
+        // handle data
+        tokio::time::sleep(WORK).await;
+
+        // sign data
+        let sign = client.sign_as_guarantor(sign_as_guarantee)?;
+
+        // pack data
+        Ok(self::io::response::Slow {
+            __lifetime: Default::default(),
+            __sign: ::ipis::stream::DynStream::Owned(sign),
+        })
+    }
+}
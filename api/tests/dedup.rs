@@ -0,0 +1,148 @@
+mod common;
+
+use core::{
+    sync::atomic::{AtomicU64, Ordering},
+    time::Duration,
+};
+use std::sync::Arc;
+
+use ipiis_api::{
+    client::IpiisClient,
+    common::{define_io, external_call, handle_external_call, Ipiis, ServerResult},
+    server::IpiisServer,
+};
+use ipis::{
+    async_trait::async_trait,
+    core::{
+        account::{AccountRef, GuaranteeSigned, GuarantorSigned},
+        anyhow::Result,
+        data::Data,
+    },
+    env::Infer,
+    tokio,
+};
+
+use self::common::{introduce, test_client, test_server};
+
+static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+#[tokio::test]
+async fn test_dedup() {
+    // deploy a server replaying the duplicated requests
+    let (server, address) = test_server("dedup").await.unwrap();
+    let server = CounterServer {
+        client: server.with_dedup_window(Duration::from_secs(60), 16).into(),
+    };
+    let server_ref = *server.client.account_ref();
+    tokio::spawn(server.run());
+
+    // create a client
+    let client = test_client("dedup").await.unwrap();
+    introduce(&client, &server_ref, &address).unwrap();
+
+    // re-send the same signed request, as if the first response is lost
+    let sign = client.sign_owned(server_ref, 0).unwrap();
+    let first = increment(&client, server_ref, sign.clone()).await.unwrap();
+    let second = increment(&client, server_ref, sign).await.unwrap();
+
+    // the handler should run once, but both calls should get the same response
+    assert_eq!(first, 1);
+    assert_eq!(second, 1);
+    assert_eq!(COUNTER.load(Ordering::SeqCst), 1);
+
+    // a new request should be handled
+    let sign = client.sign_owned(server_ref, 0).unwrap();
+    assert_eq!(increment(&client, server_ref, sign).await.unwrap(), 2);
+}
+
+async fn increment(
+    client: &IpiisClient,
+    target: AccountRef,
+    sign: Data<GuaranteeSigned, u8>,
+) -> Result<u64> {
+    // external call
+    let (count,) = external_call!(
+        client: client,
+        target: None => &target,
+        request: self::io => Increment,
+        sign: sign,
+        inputs: { },
+        outputs: { count, },
+    );
+
+    // unpack response
+    Ok(count)
+}
+
+define_io! {
+    Increment = 0 {
+        inputs: { },
+        input_sign: Data<GuaranteeSigned, u8>,
+        outputs: {
+            count: u64,
+        },
+        output_sign: Data<GuarantorSigned, u8>,
+        generics: { },
+    },
+}
+
+pub struct CounterServer {
+    client: Arc<IpiisServer>,
+}
+
+impl AsRef<IpiisClient> for CounterServer {
+    fn as_ref(&self) -> &IpiisClient {
+        &self.client
+    }
+}
+
+#[async_trait]
+impl<'a> Infer<'a> for CounterServer {
+    type GenesisArgs = <IpiisServer as Infer<'a>>::GenesisArgs;
+    type GenesisResult = Self;
+
+    async fn try_infer() -> Result<Self> {
+        Ok(Self {
+            client: IpiisServer::try_infer().await?.into(),
+        })
+    }
+
+    async fn genesis(
+        args: <Self as Infer<'a>>::GenesisArgs,
+    ) -> Result<<Self as Infer<'a>>::GenesisResult> {
+        Ok(Self {
+            client: IpiisServer::genesis(args).await?.into(),
+        })
+    }
+}
+
+handle_external_call!(
+    server: CounterServer => IpiisServer,
+    name: run,
+    request: self::io => {
+        Increment => handle_increment,
+    },
+);
+
+impl CounterServer {
+    async fn handle_increment(
+        client: &IpiisServer,
+        req: self::io::request::Increment<'static>,
+    ) -> Result<self::io::response::Increment<'static>> {
+        // unpack sign
+        let sign_as_guarantee = req.__sign.into_owned().await?;
+
+        // handle data
+        let count = COUNTER.fetch_add(1, Ordering::SeqCst) + 1;
+
+        // sign data
+        let sign = client.sign_as_guarantor(sign_as_guarantee)?;
+
+        // pack data
+        Ok(self::io::response::Increment {
+            __lifetime: Default::default(),
+            __sign: ::ipis::stream::DynStream::Owned(sign),
+            count: ::ipis::stream::DynStream::Owned(count),
+        })
+    }
+}
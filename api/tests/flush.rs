@@ -0,0 +1,38 @@
+mod common;
+
+use core::time::Duration;
+
+use ipiis_api::common::Ipiis;
+use ipis::{core::account::Account, tokio};
+
+use self::common::{reopen_client, test_client};
+
+#[tokio::test]
+async fn test_flush_interval() {
+    // create a client flushing in background
+    let client = test_client("flush")
+        .await
+        .unwrap()
+        .with_flush_interval(Duration::from_millis(50))
+        .unwrap();
+
+    // set a value without flushing
+    let account = Account::generate().account_ref();
+    let address = "127.0.0.1:9001".to_string();
+    client.set_address(None, &account, &address).await.unwrap();
+
+    // the value should be written to the disk after the interval
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    let report = client.maintenance().await.unwrap();
+    assert_eq!(report.flushed_bytes, 0);
+
+    // crash without releasing the client
+    drop(client);
+
+    // let the cancelled flush task release the address book
+    tokio::task::yield_now().await;
+
+    // the value should survive it
+    let client = reopen_client("flush").await.unwrap();
+    assert_eq!(client.book().get(None, &account).unwrap(), Some(address));
+}
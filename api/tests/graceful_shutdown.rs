@@ -0,0 +1,71 @@
+mod common;
+
+use core::time::Duration;
+use std::sync::Arc;
+
+use ipiis_api::common::{recv_server_result, Ipiis, ServerResult};
+use ipis::{
+    core::account::Account,
+    tokio::{self, io::AsyncWriteExt, sync::oneshot},
+};
+
+use self::common::{introduce, test_client, test_server};
+
+const OPCODE_SLOW: u16 = 0x100;
+
+/// How long the request in flight takes
+const WORK: Duration = Duration::from_millis(500);
+
+#[tokio::test]
+async fn test_graceful_shutdown() {
+    // leave the flush to the shutdown
+    ::std::env::set_var("ipiis_router_flush_every_ms", "0");
+
+    // deploy a server
+    let (server, address) = test_server("graceful-shutdown").await.unwrap();
+    let server = Arc::new(server);
+    server.on_raw(OPCODE_SLOW, |_client, _recv| async move {
+        tokio::time::sleep(WORK).await;
+        Ok(vec![ServerResult::ACK_OK.bits()])
+    });
+
+    let server_ref = *server.account_ref();
+    let (shutdown, signal) = oneshot::channel::<()>();
+    let task = tokio::spawn(server.clone().run_ipiis_with_shutdown(async move {
+        let _ = signal.await;
+    }));
+
+    // create a client
+    let client = test_client("graceful-shutdown").await.unwrap();
+    introduce(&client, &server_ref, &address).unwrap();
+
+    // send a request, which is still in flight on shutdown
+    let request = {
+        let client = client.clone();
+        tokio::spawn(async move {
+            let (mut send, mut recv) = client.call_raw(None, &server_ref).await?;
+            send.write_all(&OPCODE_SLOW.to_le_bytes()).await?;
+            send.flush().await?;
+
+            recv_server_result(&mut recv).await
+        })
+    };
+    tokio::time::sleep(WORK / 5).await;
+
+    // leave a change in the address book
+    let target = Account::generate().account_ref();
+    server
+        .set_address(None, &target, &"127.0.0.1:9804".to_string())
+        .await
+        .unwrap();
+
+    // shut the server down, as on SIGTERM
+    shutdown.send(()).unwrap();
+
+    // the request in flight should be drained
+    request.await.unwrap().unwrap();
+
+    // and the address book should be flushed before exit
+    let report = task.await.unwrap().unwrap();
+    assert!(report.flushed_bytes > 0, "{report:?}");
+}
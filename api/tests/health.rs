@@ -0,0 +1,37 @@
+mod common;
+
+use ipiis_api::health::HealthServer;
+use ipis::tokio::{
+    self,
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpStream,
+};
+
+use self::common::TestPeer;
+
+#[tokio::test]
+async fn test_health() {
+    // deploy a server
+    let server = TestPeer::deploy("health", None).await.unwrap();
+
+    // probe it
+    let health = HealthServer::new(server.server.clone(), 0).await.unwrap();
+    let port = health.local_addr().unwrap().port();
+    tokio::spawn(health.run());
+
+    assert_eq!(probe(port, "/healthz").await, "HTTP/1.1 200 OK");
+    assert_eq!(probe(port, "/readyz").await, "HTTP/1.1 200 OK");
+    assert_eq!(probe(port, "/unknown").await, "HTTP/1.1 404 Not Found");
+}
+
+async fn probe(port: u16, path: &str) -> String {
+    let mut stream = TcpStream::connect(("127.0.0.1", port)).await.unwrap();
+    stream
+        .write_all(format!("GET {path} HTTP/1.1\r\nHost: localhost\r\n\r\n").as_bytes())
+        .await
+        .unwrap();
+
+    let mut response = String::new();
+    stream.read_to_string(&mut response).await.unwrap();
+    response.lines().next().unwrap().to_string()
+}
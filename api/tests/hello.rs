@@ -0,0 +1,84 @@
+mod common;
+
+use ipiis_api::{common::Ipiis, server::IpiisServer};
+use ipis::{core::anyhow::Result, tokio};
+
+use self::common::TestPeer;
+
+#[tokio::test]
+async fn test_hello() {
+    // deploy two fresh peers, advertising their addresses
+    let peer_a = TestPeer::deploy_with("hello-a", None, advertise)
+        .await
+        .unwrap();
+    let peer_b = TestPeer::deploy_with("hello-b", None, advertise)
+        .await
+        .unwrap();
+
+    // connect once by the address only
+    assert_eq!(
+        peer_a
+            .server
+            .hello(&peer_b.address.to_string())
+            .await
+            .unwrap(),
+        peer_b.account,
+    );
+
+    // each peer should resolve the other from its local book
+    assert_eq!(
+        peer_a.server.book().get(None, &peer_b.account).unwrap(),
+        Some(peer_b.address.to_string()),
+    );
+    assert_eq!(
+        peer_b.server.book().get(None, &peer_a.account).unwrap(),
+        Some(peer_a.address.to_string()),
+    );
+
+    // so that they can call each other by the account
+    assert!(
+        peer_a
+            .server
+            .ping(None, &peer_b.account)
+            .await
+            .unwrap()
+            .identity_confirmed
+    );
+    assert!(
+        peer_b
+            .server
+            .ping(None, &peer_a.account)
+            .await
+            .unwrap()
+            .identity_confirmed
+    );
+
+    // dialing itself should be rejected
+    assert!(peer_a
+        .server
+        .hello(&peer_a.address.to_string())
+        .await
+        .is_err());
+
+    // a peer without the registration should not store the unauthorized address
+    let peer_c = TestPeer::deploy("hello-c", None).await.unwrap();
+
+    assert!(peer_a
+        .server
+        .hello(&peer_c.address.to_string())
+        .await
+        .is_err());
+    assert_eq!(
+        peer_c.server.book().get(None, &peer_a.account).unwrap(),
+        None
+    );
+}
+
+/// Advertises the loopback address of the server, registering the greeting peers.
+fn advertise(server: IpiisServer) -> Result<IpiisServer> {
+    let port = server.local_addr()?.port();
+
+    Ok(server
+        .with_advertised_address(format!("127.0.0.1:{port}"))
+        .with_hello_registration())
+}
@@ -0,0 +1,184 @@
+mod common;
+
+use core::time::Duration;
+use std::sync::Arc;
+
+use ipiis_api::{
+    client::IpiisClient,
+    common::{define_io, external_call, handle_external_call, InputStream, Ipiis, ServerResult},
+    server::IpiisServer,
+};
+use ipis::{
+    async_trait::async_trait,
+    core::{
+        account::{GuaranteeSigned, GuarantorSigned},
+        anyhow::Result,
+        data::Data,
+    },
+    env::Infer,
+    tokio::{
+        self,
+        io::{AsyncRead, AsyncReadExt, AsyncWriteExt},
+        sync::Notify,
+    },
+};
+
+use self::common::{introduce, test_client, test_server};
+
+/// The size of the streamed input, far beyond the buffer of the handler
+const INPUT_SIZE: usize = 32 * 1024 * 1024;
+
+/// The size of the buffer of the handler
+const BUFFER_SIZE: usize = 8 * 1024;
+
+::ipis::lazy_static::lazy_static! {
+    /// Notified when the handler has begun to consume the input
+    static ref STARTED: Notify = Notify::new();
+}
+
+#[tokio::test]
+async fn test_input_stream() {
+    // deploy a server
+    let (server, address) = test_server("input-stream").await.unwrap();
+    let server = UploadServer {
+        client: server.into(),
+    };
+    let server_ref = *server.client.account_ref();
+    tokio::spawn(server.run());
+
+    // create a client
+    let client = test_client("input-stream").await.unwrap();
+    introduce(&client, &server_ref, &address).unwrap();
+
+    assert!(io::OpCode::Upload.has_input_stream());
+
+    // produce the input, holding the rest back until the handler has begun,
+    // which never happens if the server buffers the whole input first
+    let (reader, mut writer) = tokio::io::duplex(BUFFER_SIZE);
+    tokio::spawn(async move {
+        let chunk = vec![7u8; 1024 * 1024];
+        writer.write_all(&chunk).await.unwrap();
+        STARTED.notified().await;
+        for _ in 1..INPUT_SIZE / chunk.len() {
+            writer.write_all(&chunk).await.unwrap();
+        }
+    });
+
+    let (size, sum) = tokio::time::timeout(Duration::from_secs(30), async {
+        let (size, sum) = external_call!(
+            client: client,
+            target: None => &server_ref,
+            request: self::io => Upload,
+            sign: client.sign_owned(server_ref, 0)?,
+            inputs: {
+                name: "large".to_string(),
+            },
+            input_stream: reader,
+            outputs: { size, sum, },
+        );
+        Result::<_, ::ipis::core::anyhow::Error>::Ok((size, sum))
+    })
+    .await
+    .expect("the input should be consumed incrementally")
+    .unwrap();
+
+    assert_eq!(size, INPUT_SIZE as u64);
+    assert_eq!(sum, 7 * INPUT_SIZE as u64);
+}
+
+define_io! {
+    Upload = 0 {
+        input_stream: true,
+        inputs: {
+            name: String,
+        },
+        input_sign: Data<GuaranteeSigned, u8>,
+        outputs: {
+            size: u64,
+            sum: u64,
+        },
+        output_sign: Data<GuarantorSigned, u8>,
+        generics: { },
+    },
+}
+
+pub struct UploadServer {
+    client: Arc<IpiisServer>,
+}
+
+impl AsRef<IpiisClient> for UploadServer {
+    fn as_ref(&self) -> &IpiisClient {
+        &self.client
+    }
+}
+
+#[async_trait]
+impl<'a> Infer<'a> for UploadServer {
+    type GenesisArgs = <IpiisServer as Infer<'a>>::GenesisArgs;
+    type GenesisResult = Self;
+
+    async fn try_infer() -> Result<Self> {
+        Ok(Self {
+            client: IpiisServer::try_infer().await?.into(),
+        })
+    }
+
+    async fn genesis(
+        args: <Self as Infer<'a>>::GenesisArgs,
+    ) -> Result<<Self as Infer<'a>>::GenesisResult> {
+        Ok(Self {
+            client: IpiisServer::genesis(args).await?.into(),
+        })
+    }
+}
+
+handle_external_call!(
+    server: UploadServer => IpiisServer,
+    name: run,
+    request: self::io => { },
+    request_stream: self::io => {
+        Upload => handle_upload,
+    },
+);
+
+impl UploadServer {
+    async fn handle_upload<R>(
+        client: &IpiisServer,
+        req: self::io::request::Upload<'static>,
+        mut stream: InputStream<R>,
+    ) -> Result<self::io::response::Upload<'static>>
+    where
+        R: AsyncRead + Send + Unpin,
+    {
+        // unpack sign
+        let sign_as_guarantee = req.__sign.into_owned().await?;
+
+        // handle data, with a fixed buffer
+        let mut buf = vec![0; BUFFER_SIZE];
+        let mut size = 0u64;
+        let mut sum = 0u64;
+        loop {
+            let len = stream.read(&mut buf).await?;
+            if len == 0 {
+                break;
+            }
+            if size == 0 {
+                STARTED.notify_one();
+            }
+            size += len as u64;
+            sum += buf[..len].iter().map(|e| *e as u64).sum::<u64>();
+        }
+        assert!(stream.is_finished());
+
+        // sign data
+        let sign = client.sign_as_guarantor(sign_as_guarantee)?;
+
+        // pack data
+        Ok(self::io::response::Upload {
+            __lifetime: Default::default(),
+            __sign: ::ipis::stream::DynStream::Owned(sign),
+            size: ::ipis::stream::DynStream::Owned(size),
+            sum: ::ipis::stream::DynStream::Owned(sum),
+        })
+    }
+}
@@ -0,0 +1,59 @@
+mod common;
+
+use core::time::Duration;
+use std::sync::Arc;
+
+use ipiis_api::common::{is_kind_resolution_too_deep, Ipiis};
+use ipis::{core::value::hash::Hash, tokio};
+
+use self::common::{test_client, test_server};
+
+#[tokio::test]
+async fn test_kind_resolution_depth() {
+    let kind = Hash::with_str("__ipiis__test__kind_resolution__");
+
+    // a client refusing any forwarded lookup should fail locally
+    let client = test_client("kind-resolution")
+        .await
+        .unwrap()
+        .with_max_resolution_depth(0);
+    let error = client.get_account_primary(Some(&kind)).await.unwrap_err();
+    assert!(is_kind_resolution_too_deep(&error));
+
+    // deploy two servers, being the primaries of each other
+    let (server_a, address_a) = test_server("kind-resolution-a").await.unwrap();
+    let server_a = Arc::new(server_a);
+    let server_a_ref = *server_a.account_ref();
+
+    let (server_b, address_b) = test_server("kind-resolution-b").await.unwrap();
+    let server_b = Arc::new(server_b);
+    let server_b_ref = *server_b.account_ref();
+
+    server_a
+        .book()
+        .set_primary_with_address(None, &server_b_ref, &address_b.to_string())
+        .unwrap();
+    server_b
+        .book()
+        .set_primary_with_address(None, &server_a_ref, &address_a.to_string())
+        .unwrap();
+    tokio::spawn(server_a.clone().run_ipiis());
+    tokio::spawn(server_b.clone().run_ipiis());
+
+    // create a client following the loop
+    let client = test_client("kind-resolution-loop").await.unwrap();
+    client
+        .book()
+        .set_primary_with_address(None, &server_a_ref, &address_a.to_string())
+        .unwrap();
+
+    // the lookup should fail in bounded hops, rather than hang
+    let error = tokio::time::timeout(
+        Duration::from_secs(10),
+        client.get_account_primary(Some(&kind)),
+    )
+    .await
+    .unwrap()
+    .unwrap_err();
+    assert!(is_kind_resolution_too_deep(&error));
+}
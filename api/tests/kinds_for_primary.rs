@@ -0,0 +1,35 @@
+mod common;
+
+use ipiis_api::common::Ipiis;
+use ipis::{
+    core::{account::Account, value::hash::Hash},
+    tokio,
+};
+
+use self::common::test_client;
+
+#[tokio::test]
+async fn test_kinds_for_primary() {
+    // create a client
+    let client = test_client("kinds-for-primary").await.unwrap();
+
+    // set an account as primary for two kinds
+    let account = Account::generate().account_ref();
+    let kinds = [
+        Hash::with_str(&format!("__ipiis__test__kind_a__{account}")),
+        Hash::with_str(&format!("__ipiis__test__kind_b__{account}")),
+    ];
+    for kind in &kinds {
+        client
+            .set_account_primary(Some(kind), &account)
+            .await
+            .unwrap();
+    }
+
+    // list them
+    let listed = client.kinds_for_primary(&account).unwrap();
+    assert_eq!(listed.len(), kinds.len());
+    for kind in kinds {
+        assert!(listed.contains(&Some(kind)));
+    }
+}
@@ -0,0 +1,50 @@
+mod common;
+
+use ipiis_api::common::Ipiis;
+use ipis::{
+    core::{account::Account, value::hash::Hash},
+    tokio,
+};
+
+use self::common::{test_client, TestPeer};
+
+#[tokio::test]
+async fn test_list_accounts() {
+    let server_me = Account::generate();
+    let server_ref = server_me.account_ref();
+
+    // create a client
+    let client = test_client("list-accounts").await.unwrap();
+    let client_ref = *client.account_ref();
+
+    // deploy a root server, trusting the client only
+    let server = TestPeer::deploy_as_with("list-accounts", server_me, None, move |server| {
+        Ok(server.with_authorizer(move |account| account == &client_ref))
+    })
+    .await
+    .unwrap();
+    server.route(&client).unwrap();
+
+    // register several accounts on the server
+    let kind = Hash::with_str(&format!("__ipiis__test__list_accounts__{server_ref}"));
+    let accounts: Vec<_> = (0..3).map(|_| Account::generate().account_ref()).collect();
+    for (port, account) in (9001..).zip(&accounts) {
+        let address = format!("127.0.0.1:{port}").parse().unwrap();
+        server
+            .server
+            .set_address(Some(&kind), account, &address)
+            .await
+            .unwrap();
+    }
+
+    // list the accounts over the wire
+    let listed = client.list_accounts(Some(&kind)).await.unwrap();
+    assert_eq!(listed.len(), accounts.len());
+    for account in &accounts {
+        assert!(listed.contains(account));
+    }
+
+    // the unauthorized accounts should not list them
+    let stranger = server.child("list-accounts-stranger").await.unwrap();
+    assert!(stranger.list_accounts(Some(&kind)).await.is_err());
+}
@@ -0,0 +1,42 @@
+mod common;
+
+use core::time::Duration;
+
+use ipiis_api::common::Ipiis;
+use ipis::{core::account::Account, tokio};
+
+use self::common::TestPeer;
+
+#[tokio::test]
+async fn test_maintenance_evicts_expired_entries() {
+    // deploy a directory server limiting the lifetime of the records
+    let server = TestPeer::deploy_with("maintenance", None, |server| {
+        Ok(server.with_response_ttl(Duration::from_secs(1)))
+    })
+    .await
+    .unwrap();
+
+    // register a target
+    let target = Account::generate().account_ref();
+    server
+        .server
+        .set_address(None, &target, &"127.0.0.1:9821".parse().unwrap())
+        .await
+        .unwrap();
+
+    // cache the record
+    let client = server.child("maintenance").await.unwrap();
+    client.get_address(None, &target).await.unwrap();
+
+    // the record should be kept within the TTL
+    let report = client.maintenance().await.unwrap();
+    assert_eq!(report.evicted_entries, 0);
+    assert!(client.book().get(None, &target).unwrap().is_some());
+
+    // the record should be evicted after the TTL, but not the local ones
+    tokio::time::sleep(Duration::from_millis(1200)).await;
+    let report = client.maintenance().await.unwrap();
+    assert_eq!(report.evicted_entries, 1);
+    assert!(client.book().get(None, &target).unwrap().is_none());
+    assert!(client.book().get(None, &server.account).unwrap().is_some());
+}
@@ -0,0 +1,64 @@
+mod common;
+
+use ipiis_api::common::Ipiis;
+use ipis::{
+    core::{account::Account, value::hash::Hash},
+    tokio,
+};
+
+use self::common::TestPeer;
+
+#[tokio::test]
+async fn test_multi_hop_routing() {
+    // deploy the peers: `end` --> `edge` --> `center`
+    let center = TestPeer::deploy("multi-hop-center", None).await.unwrap();
+    let edge = TestPeer::deploy("multi-hop-edge", Some(&center))
+        .await
+        .unwrap();
+    let end = TestPeer::deploy("multi-hop-end", Some(&edge))
+        .await
+        .unwrap();
+
+    // get the center's address from `end`
+    // route: `end` --> `edge` --> `center`
+    assert_eq!(
+        end.server.get_address(None, &center.account).await.unwrap(),
+        center.address.to_string().parse().unwrap(),
+    );
+
+    // put a dummy primary account in the `center`
+    let kind = Hash::with_str(&format!("__ipiis__test__multi_hop__{}", center.account));
+    let kind_account = Account::generate().account_ref();
+    center
+        .server
+        .set_account_primary(Some(&kind), &kind_account)
+        .await
+        .unwrap();
+
+    // get the `kind`'s account from `end`
+    // route: `end` --> `edge` --> `center`
+    assert_eq!(
+        end.server.get_account_primary(Some(&kind)).await.unwrap(),
+        kind_account,
+    );
+
+    // put another primary account in the `center`
+    let kind = Hash::with_str(&format!("__ipiis__test__multi_hop_2__{}", center.account));
+    let kind_account = Account::generate().account_ref();
+    center
+        .server
+        .set_account_primary(Some(&kind), &kind_account)
+        .await
+        .unwrap();
+
+    // the route is broken while the `edge` is unavailable
+    let edge = edge.shutdown().await.unwrap();
+    assert!(end.server.get_account_primary(Some(&kind)).await.is_err());
+
+    // the route is recovered once the `edge` is back
+    let _edge = edge.deploy().await.unwrap();
+    assert_eq!(
+        end.server.get_account_primary(Some(&kind)).await.unwrap(),
+        kind_account,
+    );
+}
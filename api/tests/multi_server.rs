@@ -0,0 +1,76 @@
+#![cfg(all(feature = "quic", feature = "tcp"))]
+
+mod common;
+
+use std::sync::Arc;
+
+use ipiis_api::{common::Ipiis, multi::MultiServer, quic, tcp};
+use ipis::{core::account::Account, env::Infer, tokio};
+
+use self::common::{test_client, use_router_db};
+
+/// The port shared by both transports
+const PORT: u16 = 5046;
+
+#[tokio::test]
+async fn test_multi_server() {
+    // deploy a node serving both transports
+    use_router_db("multi-server-node");
+    let node = Arc::new(
+        MultiServer::new(Account::generate(), None, PORT)
+            .await
+            .unwrap(),
+    );
+    let node_ref = *node.account_ref().unwrap();
+    assert_eq!(node.quic().unwrap().account_ref(), &node_ref);
+    assert_eq!(node.tcp().unwrap().account_ref(), &node_ref);
+    {
+        let node = node.clone();
+        tokio::spawn(async move { node.run_all().await });
+    }
+
+    // the servers should share the address book
+    let target = Account::generate().account_ref();
+    node.quic()
+        .unwrap()
+        .set_address(None, &target, &"127.0.0.1:9803".to_string())
+        .await
+        .unwrap();
+    assert_eq!(
+        node.tcp().unwrap().book().get(None, &target).unwrap(),
+        Some("127.0.0.1:9803".to_string()),
+    );
+
+    // each client should reach the node over its own transport, at the same address
+    let address = format!("127.0.0.1:{PORT}");
+
+    use_router_db("client-multi-server-quic");
+    let client = quic::client::IpiisClient::genesis(None).await.unwrap();
+    client.set_address(None, &node_ref, &address).await.unwrap();
+    assert!(
+        client
+            .ping(None, &node_ref)
+            .await
+            .unwrap()
+            .identity_confirmed
+    );
+
+    let client = test_client("multi-server-tcp").await.unwrap();
+    client.set_address(None, &node_ref, &address).await.unwrap();
+    assert!(
+        client
+            .ping(None, &node_ref)
+            .await
+            .unwrap()
+            .identity_confirmed
+    );
+
+    // the servers should act as the same account
+    use_router_db("multi-server-other");
+    let other = tcp::server::IpiisServer::genesis(0).await.unwrap();
+    assert!(MultiServer::default()
+        .with_quic(quic::server::IpiisServer::genesis(0).await.unwrap())
+        .unwrap()
+        .with_tcp(other)
+        .is_err());
+}
@@ -0,0 +1,201 @@
+mod common;
+
+use core::time::Duration;
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::Instant,
+};
+
+use ipiis_api::{
+    client::IpiisClient,
+    common::{define_io, external_call, handle_external_call, Ipiis, ServerResult},
+    server::IpiisServer,
+};
+use ipis::{
+    async_trait::async_trait,
+    core::{
+        account::{GuaranteeSigned, GuarantorSigned},
+        anyhow::Result,
+        data::Data,
+    },
+    env::Infer,
+    tokio::{self, sync::Notify},
+};
+
+use self::common::{introduce, test_client, test_server};
+
+/// How long the handler takes
+const WORK: Duration = Duration::from_millis(500);
+
+::ipis::lazy_static::lazy_static! {
+    /// The sum of the values notified so far
+    static ref RECEIVED: AtomicU64 = AtomicU64::new(0);
+
+    /// Notified when a value has been handled
+    static ref HANDLED: Notify = Notify::new();
+}
+
+#[tokio::test]
+async fn test_oneway() {
+    // deploy a server
+    let (server, address) = test_server("oneway").await.unwrap();
+    let server = OnewayServer {
+        client: server.into(),
+    };
+    let server_ref = *server.client.account_ref();
+    tokio::spawn(server.run());
+
+    // create a client
+    let client = test_client("oneway").await.unwrap();
+    introduce(&client, &server_ref, &address).unwrap();
+
+    // the one-way requests should be explicit
+    assert!(io::OpCode::Publish.is_oneway());
+    assert!(!io::OpCode::Ack.is_oneway());
+    let error = async {
+        external_call!(
+            client: client,
+            target: None => &server_ref,
+            request: self::io => Ack,
+            sign: client.sign_owned(server_ref, 0)?,
+            inputs: { },
+            outputs: oneway,
+        );
+        Result::<_, ::ipis::core::anyhow::Error>::Ok(())
+    }
+    .await
+    .unwrap_err();
+    assert!(error.to_string().contains("not one-way"), "{error:#}");
+
+    // the client should return before the server handles the request
+    let instant = Instant::now();
+    async {
+        external_call!(
+            client: client,
+            target: None => &server_ref,
+            request: self::io => Publish,
+            sign: client.sign_owned(server_ref, 0)?,
+            inputs: {
+                value: 42,
+            },
+            outputs: oneway,
+        );
+        Result::<_, ::ipis::core::anyhow::Error>::Ok(())
+    }
+    .await
+    .unwrap();
+    assert!(instant.elapsed() < WORK);
+    assert_eq!(RECEIVED.load(Ordering::SeqCst), 0);
+
+    // the server should still handle it
+    tokio::time::timeout(Duration::from_secs(5), HANDLED.notified())
+        .await
+        .unwrap();
+    assert_eq!(RECEIVED.load(Ordering::SeqCst), 42);
+}
+
+define_io! {
+    Publish = 0 {
+        oneway: true,
+        inputs: {
+            value: u64,
+        },
+        input_sign: Data<GuaranteeSigned, u8>,
+        outputs: { },
+        output_sign: Data<GuarantorSigned, u8>,
+        generics: { },
+    },
+    Ack = 1 {
+        inputs: { },
+        input_sign: Data<GuaranteeSigned, u8>,
+        outputs: { },
+        output_sign: Data<GuarantorSigned, u8>,
+        generics: { },
+    },
+}
+
+pub struct OnewayServer {
+    client: Arc<IpiisServer>,
+}
+
+impl AsRef<IpiisClient> for OnewayServer {
+    fn as_ref(&self) -> &IpiisClient {
+        &self.client
+    }
+}
+
+#[async_trait]
+impl<'a> Infer<'a> for OnewayServer {
+    type GenesisArgs = <IpiisServer as Infer<'a>>::GenesisArgs;
+    type GenesisResult = Self;
+
+    async fn try_infer() -> Result<Self> {
+        Ok(Self {
+            client: IpiisServer::try_infer().await?.into(),
+        })
+    }
+
+    async fn genesis(
+        args: <Self as Infer<'a>>::GenesisArgs,
+    ) -> Result<<Self as Infer<'a>>::GenesisResult> {
+        Ok(Self {
+            client: IpiisServer::genesis(args).await?.into(),
+        })
+    }
+}
+
+handle_external_call!(
+    server: OnewayServer => IpiisServer,
+    name: run,
+    request: self::io => {
+        Publish => handle_publish,
+        Ack => handle_ack,
+    },
+);
+
+impl OnewayServer {
+    async fn handle_publish(
+        client: &IpiisServer,
+        req: self::io::request::Publish<'static>,
+    ) -> Result<self::io::response::Publish<'static>> {
+        // unpack sign
+        let sign_as_guarantee = req.__sign.into_owned().await?;
+
+        // unpack data
+        let value = req.value.into_owned().await?;
+
+        // handle data
+        tokio::time::sleep(WORK).await;
+        RECEIVED.fetch_add(value, Ordering::SeqCst);
+        HANDLED.notify_one();
+
+        // sign data, though not sent
+        let sign = client.sign_as_guarantor(sign_as_guarantee)?;
+
+        // pack data
+        Ok(self::io::response::Publish {
+            __lifetime: Default::default(),
+            __sign: ::ipis::stream::DynStream::Owned(sign),
+        })
+    }
+
+    async fn handle_ack(
+        client: &IpiisServer,
+        req: self::io::request::Ack<'static>,
+    ) -> Result<self::io::response::Ack<'static>> {
+        // unpack sign
+        let sign_as_guarantee = req.__sign.into_owned().await?;
+
+        // sign data
+        let sign = client.sign_as_guarantor(sign_as_guarantee)?;
+
+        // pack data
+        Ok(self::io::response::Ack {
+            __lifetime: Default::default(),
+            __sign: ::ipis::stream::DynStream::Owned(sign),
+        })
+    }
+}
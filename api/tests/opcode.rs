@@ -0,0 +1,177 @@
+mod common;
+
+use core::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use ipiis_api::{
+    client::IpiisClient,
+    common::{define_io, external_call, try_recv_request, Ipiis, IpiisError},
+    server::IpiisServer,
+};
+use ipis::{
+    core::anyhow::{bail, Result},
+    tokio::{self, io::AsyncReadExt},
+};
+
+use self::common::{introduce, test_client, test_server};
+
+static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+mod forward {
+    use ipiis_api::common::{Ipiis, ServerResult};
+    use ipis::core::{
+        account::{GuaranteeSigned, GuarantorSigned},
+        data::Data,
+    };
+
+    super::define_io! {
+        Foo = 1 {
+            idempotent: true,
+            inputs: { },
+            input_sign: Data<GuaranteeSigned, u8>,
+            outputs: { },
+            output_sign: Data<GuarantorSigned, u8>,
+            generics: { },
+        },
+        Bar = 7 {
+            inputs: { },
+            input_sign: Data<GuaranteeSigned, u8>,
+            outputs: { },
+            output_sign: Data<GuarantorSigned, u8>,
+            generics: { },
+        },
+    }
+}
+
+mod reordered {
+    use ipiis_api::common::{Ipiis, ServerResult};
+    use ipis::core::{
+        account::{GuaranteeSigned, GuarantorSigned},
+        data::Data,
+    };
+
+    super::define_io! {
+        Bar = 7 {
+            inputs: { },
+            input_sign: Data<GuaranteeSigned, u8>,
+            outputs: { },
+            output_sign: Data<GuarantorSigned, u8>,
+            generics: { },
+        },
+        Foo = 1 {
+            idempotent: true,
+            inputs: { },
+            input_sign: Data<GuaranteeSigned, u8>,
+            outputs: { },
+            output_sign: Data<GuarantorSigned, u8>,
+            generics: { },
+        },
+    }
+}
+
+#[test]
+fn test_opcode_stable() {
+    // reordering should not change the wire format
+    assert_eq!(
+        forward::io::OpCode::Foo.to_bytes(),
+        reordered::io::OpCode::Foo.to_bytes(),
+    );
+    assert_eq!(
+        forward::io::OpCode::Bar.to_bytes(),
+        reordered::io::OpCode::Bar.to_bytes(),
+    );
+    assert_eq!(forward::io::OpCode::Bar.to_bytes(), 7u16.to_le_bytes());
+
+    // idempotency is declared per opcode
+    assert!(forward::io::OpCode::Foo.is_idempotent());
+    assert!(!forward::io::OpCode::Bar.is_idempotent());
+
+    // unknown opcodes should be rejected
+    assert!(matches!(
+        reordered::io::OpCode::try_from(2),
+        Err(IpiisError::UnknownOpcode(2)),
+    ));
+}
+
+#[tokio::test]
+async fn test_unacknowledged() {
+    // deploy a server dropping the connection after executing the request
+    let (server, address) = test_server("opcode").await.unwrap();
+    let server = Arc::new(DropBeforeAckServer {
+        client: server.into(),
+    });
+    let server_ref = *server.client.account_ref();
+    tokio::spawn(server.clone().run());
+
+    // create a client
+    let client = test_client("opcode").await.unwrap();
+    introduce(&client, &server_ref, &address).unwrap();
+
+    // send a non-idempotent request
+    let error = async {
+        external_call!(
+            client: client,
+            target: None => &server_ref,
+            request: self::forward::io => Bar,
+            sign: client.sign_owned(server_ref, 0)?,
+            inputs: { },
+            outputs: { },
+        );
+        Result::<_, ::ipis::core::anyhow::Error>::Ok(())
+    }
+    .await
+    .unwrap_err();
+
+    // it should be reported as unacknowledged, without being re-sent
+    assert!(
+        matches!(
+            error.downcast_ref::<IpiisError>(),
+            Some(IpiisError::Unacknowledged(_)),
+        ),
+        "{error:#}",
+    );
+    assert_eq!(COUNTER.load(Ordering::SeqCst), 1);
+}
+
+pub struct DropBeforeAckServer {
+    client: Arc<IpiisServer>,
+}
+
+impl AsRef<IpiisClient> for DropBeforeAckServer {
+    fn as_ref(&self) -> &IpiisClient {
+        &self.client
+    }
+}
+
+impl DropBeforeAckServer {
+    async fn run(self: Arc<Self>) {
+        self.client.run(self.clone(), Self::handle).await
+    }
+
+    /// Executes `Bar`, and then drops the connection before acknowledging it.
+    async fn handle(
+        self: Arc<Self>,
+        _send: <IpiisClient as Ipiis>::Writer,
+        mut recv: <IpiisClient as Ipiis>::Reader,
+    ) -> Result<()> {
+        // recv opcode
+        let code = recv.read_u16_le().await?;
+        if code.to_le_bytes() != forward::io::OpCode::Bar.to_bytes() {
+            bail!("unexpected opcode: {code}");
+        }
+
+        // recv request
+        let req = try_recv_request(forward::io::request::Bar::recv(
+            AsRef::<IpiisClient>::as_ref(&*self),
+            &mut recv,
+        ))
+        .await?;
+
+        // unpack sign
+        let _ = req.__sign.into_owned().await?;
+
+        // handle data
+        COUNTER.fetch_add(1, Ordering::SeqCst);
+        Ok(())
+    }
+}
@@ -0,0 +1,190 @@
+mod common;
+
+use core::time::Duration;
+use std::sync::Arc;
+
+use bytecheck::CheckBytes;
+use ipiis_api::{
+    client::IpiisClient,
+    common::{define_io, external_call, handle_external_call, Ipiis, ServerResult},
+    server::IpiisServer,
+};
+use ipis::{
+    async_trait::async_trait,
+    core::{
+        account::{Account, AccountRef, GuaranteeSigned, GuarantorSigned},
+        anyhow::Result,
+        data::Data,
+        signed::IsSigned,
+        value::hash::Hash,
+    },
+    env::Infer,
+    futures::{stream, Stream, StreamExt, TryStreamExt},
+    tokio::{self, sync::Notify},
+};
+use rkyv::{Archive, Deserialize, Serialize};
+
+use self::common::{introduce, test_client, test_server};
+
+::ipis::lazy_static::lazy_static! {
+    /// Notified when the client has received an entry, letting the handler yield the next one
+    static ref RECEIVED: Notify = Notify::new();
+}
+
+#[tokio::test]
+async fn test_output_stream() {
+    // deploy a server
+    let (server, address) = test_server("output-stream").await.unwrap();
+    let server = ListServer {
+        client: server.into(),
+    };
+    let server_ref = *server.client.account_ref();
+
+    // register several accounts on the server
+    let kind = Hash::with_str(&format!("__ipiis__test__output_stream__{server_ref}"));
+    let accounts: Vec<_> = (0..3).map(|_| Account::generate().account_ref()).collect();
+    for (port, account) in (9101..).zip(&accounts) {
+        server
+            .client
+            .set_address(Some(&kind), account, &format!("127.0.0.1:{port}"))
+            .await
+            .unwrap();
+    }
+
+    tokio::spawn(server.run());
+
+    // create a client
+    let client = test_client("output-stream").await.unwrap();
+    introduce(&client, &server_ref, &address).unwrap();
+
+    assert!(io::OpCode::ListAccounts.has_output_stream());
+
+    // recv the entries one at a time, which never ends if the server buffers them all first
+    let listed = tokio::time::timeout(Duration::from_secs(30), async {
+        let ((count,), mut entries) = external_call!(
+            client: client,
+            target: None => &server_ref,
+            request: self::io => ListAccounts,
+            sign: client.sign_owned(server_ref, Some(kind))?,
+            inputs: { },
+            outputs: { count, },
+            output_stream: true,
+        );
+
+        let mut listed = vec![];
+        while let Some(Entry(account)) = entries.try_next().await? {
+            listed.push(account);
+            RECEIVED.notify_one();
+        }
+        assert_eq!(count, listed.len() as u64);
+        Result::<_, ::ipis::core::anyhow::Error>::Ok(listed)
+    })
+    .await
+    .expect("the entries should be streamed one at a time")
+    .unwrap();
+
+    assert_eq!(listed.len(), accounts.len());
+    for account in &accounts {
+        assert!(listed.contains(account));
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Archive, Serialize, Deserialize)]
+#[archive(compare(PartialEq))]
+#[archive_attr(derive(CheckBytes, Debug, PartialEq, Eq))]
+pub struct Entry(AccountRef);
+
+impl IsSigned for Entry {}
+
+define_io! {
+    ListAccounts = 0 {
+        idempotent: true,
+        output_stream: true,
+        inputs: { },
+        input_sign: Data<GuaranteeSigned, Option<Hash>>,
+        outputs: {
+            count: u64,
+        },
+        output_sign: Data<GuarantorSigned, Option<Hash>>,
+        generics: { },
+    },
+}
+
+pub struct ListServer {
+    client: Arc<IpiisServer>,
+}
+
+impl AsRef<IpiisClient> for ListServer {
+    fn as_ref(&self) -> &IpiisClient {
+        &self.client
+    }
+}
+
+#[async_trait]
+impl<'a> Infer<'a> for ListServer {
+    type GenesisArgs = <IpiisServer as Infer<'a>>::GenesisArgs;
+    type GenesisResult = Self;
+
+    async fn try_infer() -> Result<Self> {
+        Ok(Self {
+            client: IpiisServer::try_infer().await?.into(),
+        })
+    }
+
+    async fn genesis(
+        args: <Self as Infer<'a>>::GenesisArgs,
+    ) -> Result<<Self as Infer<'a>>::GenesisResult> {
+        Ok(Self {
+            client: IpiisServer::genesis(args).await?.into(),
+        })
+    }
+}
+
+handle_external_call!(
+    server: ListServer => IpiisServer,
+    name: run,
+    request: self::io => { },
+    response_stream: self::io => {
+        ListAccounts => handle_list_accounts,
+    },
+);
+
+impl ListServer {
+    async fn handle_list_accounts(
+        client: &IpiisServer,
+        req: self::io::request::ListAccounts<'static>,
+    ) -> Result<(
+        self::io::response::ListAccounts<'static>,
+        impl Stream<Item = Entry>,
+    )> {
+        // unpack sign
+        let sign_as_guarantee = req.__sign.into_owned().await?;
+
+        // unpack data
+        let kind = sign_as_guarantee.data;
+
+        // handle data
+        let accounts = client.list_accounts(kind.as_ref()).await?;
+        let count = accounts.len() as u64;
+
+        // yield the next entry only after the client has received the previous one
+        let entries =
+            stream::iter(accounts.into_iter().enumerate()).then(|(index, account)| async move {
+                if index > 0 {
+                    RECEIVED.notified().await;
+                }
+                Entry(account)
+            });
+
+        // sign data
+        let sign = client.sign_as_guarantor(sign_as_guarantee)?;
+
+        // pack data
+        let res = self::io::response::ListAccounts {
+            __lifetime: Default::default(),
+            __sign: ::ipis::stream::DynStream::Owned(sign),
+            count: ::ipis::stream::DynStream::Owned(count),
+        };
+        Ok((res, entries))
+    }
+}
@@ -0,0 +1,24 @@
+mod common;
+
+use core::time::Duration;
+
+use ipiis_api::common::ping_flood;
+use ipis::tokio;
+
+use self::common::TestPeer;
+
+#[tokio::test]
+async fn test_ping_flood() {
+    // deploy a server
+    let server = TestPeer::deploy("ping-flood", None).await.unwrap();
+
+    // create a client
+    let client = server.client("ping-flood").await.unwrap();
+
+    // flood the server
+    let report = ping_flood(&client, None, &server.account, 100, 8).await;
+    assert_eq!(report.succeeded, 100);
+    assert_eq!(report.failed, 0);
+    assert!(report.iops > 0.0);
+    assert!(report.rtt_p99 > Duration::ZERO);
+}
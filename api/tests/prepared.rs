@@ -0,0 +1,48 @@
+mod common;
+
+use ipiis_api::common::{external_call, io, prepare_signed_request, send_prepared, Ipiis};
+use ipis::{core::account::Account, tokio};
+
+use self::common::{test_client, TestPeer};
+
+#[tokio::test]
+async fn test_prepared_request() {
+    // deploy a server
+    let server = TestPeer::deploy("prepared", None).await.unwrap();
+    let server_ref = server.account;
+
+    // register a target in the server
+    let target = Account::generate().account_ref();
+    let address = "127.0.0.1:9803".to_string();
+    server
+        .server
+        .set_address(None, &target, &address)
+        .await
+        .unwrap();
+
+    // pre-sign a request on the key holder, which knows no address at all
+    let signer = test_client("prepared-signer").await.unwrap();
+    let prepared = {
+        let req: io::request::GetAddress<'static, String> = external_call!(
+            client: signer,
+            target: None => &server_ref,
+            request: ::ipiis_api::common::io => GetAddress,
+            sign: signer.sign_owned(server_ref, (None, target)).unwrap(),
+            inputs: { },
+            outputs: none,
+        );
+        prepare_signed_request(server_ref, req).await.unwrap()
+    };
+    assert!(prepared
+        .bytes
+        .starts_with(&io::OpCode::GetAddress.to_bytes()));
+
+    // send it via the other client, without the key of the signer
+    let sender = server.child("prepared-sender").await.unwrap();
+
+    let mut recv = send_prepared(&sender, None, &prepared).await.unwrap();
+    let mut res = io::response::GetAddress::<String>::recv(&server_ref, &mut recv)
+        .await
+        .unwrap();
+    assert_eq!(res.address.to_owned().await.unwrap(), address);
+}
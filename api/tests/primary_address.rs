@@ -0,0 +1,46 @@
+mod common;
+
+use core::time::Duration;
+
+use ipiis_api::{
+    client::IpiisClient,
+    common::{Ipiis, IpiisError},
+};
+use ipis::{core::account::Account, env::Infer, tokio};
+
+use self::common::use_router_db;
+
+#[tokio::test]
+async fn test_primary_address_unknown() {
+    use_router_db("client-primary-address");
+
+    // the primary is known by its account only
+    let primary = Account::generate().account_ref();
+    let client = IpiisClient::genesis(Some(primary)).await.unwrap();
+
+    // the lookups should fail at once, rather than resolving the primary from itself
+    let target = Account::generate().account_ref();
+    for target in [target, primary] {
+        let error = tokio::time::timeout(Duration::from_secs(5), client.get_address(None, &target))
+            .await
+            .expect("the lookup should not recurse")
+            .unwrap_err();
+        assert!(
+            matches!(
+                error.downcast_ref(),
+                Some(IpiisError::PrimaryAddressUnknown(account)) if account == &primary.to_string(),
+            ),
+            "{error:#}",
+        );
+    }
+
+    // so should the upstream calls
+    let error = client.list_accounts(None).await.unwrap_err();
+    assert!(
+        matches!(
+            error.downcast_ref(),
+            Some(IpiisError::PrimaryAddressUnknown(_)),
+        ),
+        "{error:#}",
+    );
+}
@@ -0,0 +1,153 @@
+mod common;
+
+use core::time::Duration;
+use std::sync::{Arc, Mutex};
+
+use ipiis_api::{
+    client::IpiisClient,
+    common::{
+        define_io, external_call, handle_external_call, on_progress, report_progress, Ipiis,
+        Progress, ServerResult,
+    },
+    server::IpiisServer,
+};
+use ipis::{
+    async_trait::async_trait,
+    core::{
+        account::{GuaranteeSigned, GuarantorSigned},
+        anyhow::Result,
+        data::Data,
+    },
+    env::Infer,
+    tokio,
+};
+
+use self::common::{introduce, test_client, test_server};
+
+const TOTAL: u64 = 3;
+
+#[tokio::test]
+async fn test_progress() {
+    // deploy a server
+    let (server, address) = test_server("progress").await.unwrap();
+    let server = ProgressServer {
+        client: server.into(),
+    };
+    let server_ref = *server.client.account_ref();
+    tokio::spawn(server.run());
+
+    // create a client
+    let client = test_client("progress").await.unwrap();
+    introduce(&client, &server_ref, &address).unwrap();
+
+    // observe the progress of a long request
+    let events = Arc::new(Mutex::new(vec![]));
+    let (processed,) = on_progress(
+        {
+            let events = events.clone();
+            move |progress| events.lock().unwrap().push(progress)
+        },
+        async {
+            // external call
+            let (processed,) = external_call!(
+                client: client,
+                target: None => &server_ref,
+                request: self::io => Work,
+                sign: client.sign_owned(server_ref, 0)?,
+                inputs: { },
+                outputs: { processed, },
+            );
+            Result::<_, ::ipis::core::anyhow::Error>::Ok((processed,))
+        },
+    )
+    .await
+    .unwrap();
+
+    // the progress should precede the result
+    assert_eq!(
+        *events.lock().unwrap(),
+        (1..=TOTAL)
+            .map(|processed| Progress {
+                processed,
+                total: TOTAL,
+            })
+            .collect::<Vec<_>>(),
+    );
+    assert_eq!(processed, TOTAL);
+}
+
+define_io! {
+    Work = 0 {
+        inputs: { },
+        input_sign: Data<GuaranteeSigned, u8>,
+        outputs: {
+            processed: u64,
+        },
+        output_sign: Data<GuarantorSigned, u8>,
+        generics: { },
+    },
+}
+
+pub struct ProgressServer {
+    client: Arc<IpiisServer>,
+}
+
+impl AsRef<IpiisClient> for ProgressServer {
+    fn as_ref(&self) -> &IpiisClient {
+        &self.client
+    }
+}
+
+#[async_trait]
+impl<'a> Infer<'a> for ProgressServer {
+    type GenesisArgs = <IpiisServer as Infer<'a>>::GenesisArgs;
+    type GenesisResult = Self;
+
+    async fn try_infer() -> Result<Self> {
+        Ok(Self {
+            client: IpiisServer::try_infer().await?.into(),
+        })
+    }
+
+    async fn genesis(
+        args: <Self as Infer<'a>>::GenesisArgs,
+    ) -> Result<<Self as Infer<'a>>::GenesisResult> {
+        Ok(Self {
+            client: IpiisServer::genesis(args).await?.into(),
+        })
+    }
+}
+
+handle_external_call!(
+    server: ProgressServer => IpiisServer,
+    name: run,
+    request: self::io => {
+        Work => handle_work,
+    },
+);
+
+impl ProgressServer {
+    async fn handle_work(
+        client: &IpiisServer,
+        req: self::io::request::Work<'static>,
+    ) -> Result<self::io::response::Work<'static>> {
+        // unpack sign
+        let sign_as_guarantee = req.__sign.into_owned().await?;
+
+        // handle data
+        for processed in 1..=TOTAL {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            report_progress(processed, TOTAL);
+        }
+
+        // sign data
+        let sign = client.sign_as_guarantor(sign_as_guarantee)?;
+
+        // pack data
+        Ok(self::io::response::Work {
+            __lifetime: Default::default(),
+            __sign: ::ipis::stream::DynStream::Owned(sign),
+            processed: ::ipis::stream::DynStream::Owned(TOTAL),
+        })
+    }
+}
@@ -0,0 +1,12 @@
+use ipiis_api::{client::IpiisClient, common::Ipiis};
+use ipis::{env::Infer, tokio};
+
+#[tokio::test]
+async fn test_protocol() {
+    let client = IpiisClient::genesis(None).await.unwrap();
+
+    #[cfg(feature = "quic")]
+    assert_eq!(client.protocol(), "quic");
+    #[cfg(feature = "tcp")]
+    assert_eq!(client.protocol(), "tcp");
+}
@@ -0,0 +1,83 @@
+mod common;
+
+use std::sync::Arc;
+
+use ipiis_api::{
+    client::IpiisClient,
+    common::{recv_server_result, send_raw_request_header, Ipiis, ServerResult},
+};
+use ipis::{
+    core::account::AccountRef,
+    tokio::{
+        self,
+        io::{AsyncReadExt, AsyncWriteExt},
+    },
+};
+
+use self::common::{introduce, test_client, test_server};
+
+const OPCODE_ECHO: u16 = 0x100;
+const OPCODE_UNKNOWN: u16 = 0x101;
+
+#[tokio::test]
+async fn test_raw_handler() {
+    // deploy a server echoing the raw requests back
+    let (server, address) = test_server("raw-handler").await.unwrap();
+    let server = Arc::new(server);
+    server.on_raw(OPCODE_ECHO, |_client, mut recv| async move {
+        let len = recv.read_u32_le().await?;
+        let mut msg = vec![0; len as usize];
+        recv.read_exact(&mut msg).await?;
+
+        let mut res = vec![ServerResult::ACK_OK.bits()];
+        res.extend_from_slice(&len.to_le_bytes());
+        res.extend_from_slice(&msg);
+        Ok(res)
+    });
+
+    let server_ref = *server.account_ref();
+    tokio::spawn(server.clone().run_ipiis());
+
+    // create a client
+    let client = test_client("raw-handler").await.unwrap();
+    introduce(&client, &server_ref, &address).unwrap();
+
+    // the registered handler should be served along with the directory
+    client.ping(None, &server_ref).await.unwrap();
+    let res = call(&client, &server_ref, OPCODE_ECHO, b"hello")
+        .await
+        .unwrap();
+    assert_eq!(res, b"hello");
+
+    // the opcodes without handlers should be rejected
+    assert!(call(&client, &server_ref, OPCODE_UNKNOWN, b"hello")
+        .await
+        .is_err());
+
+    // the requests without the signed header should be rejected
+    let (mut send, mut recv) = client.call_raw(None, &server_ref).await.unwrap();
+    send.write_all(&OPCODE_ECHO.to_le_bytes()).await.unwrap();
+    send.write_u32_le(5).await.unwrap();
+    send.write_all(b"hello").await.unwrap();
+    send.flush().await.unwrap();
+    assert!(recv_server_result(&mut recv).await.is_err());
+}
+
+async fn call(
+    client: &IpiisClient,
+    target: &AccountRef,
+    opcode: u16,
+    msg: &[u8],
+) -> ::ipis::core::anyhow::Result<Vec<u8>> {
+    let (mut send, mut recv) = client.call_raw(None, target).await?;
+    send_raw_request_header(client, target, opcode, &mut send).await?;
+    send.write_u32_le(msg.len() as u32).await?;
+    send.write_all(msg).await?;
+    send.flush().await?;
+
+    recv_server_result(&mut recv).await?;
+    let len = recv.read_u32_le().await?;
+    let mut res = vec![0; len as usize];
+    recv.read_exact(&mut res).await?;
+    Ok(res)
+}
@@ -0,0 +1,29 @@
+#![cfg(feature = "tcp")]
+
+mod common;
+
+use core::time::Duration;
+
+use ipis::tokio::{self, io::AsyncReadExt, net::TcpStream};
+
+use self::common::TestPeer;
+
+#[tokio::test]
+async fn test_read_timeout() {
+    // deploy a server
+    let server = TestPeer::deploy_with("read-timeout", None, |server| {
+        Ok(server.with_read_timeout(Duration::from_secs(1)))
+    })
+    .await
+    .unwrap();
+
+    // connect, but send nothing
+    let mut stream = TcpStream::connect(server.address).await.unwrap();
+
+    // the server should close the connection after the timeout
+    let len = tokio::time::timeout(Duration::from_secs(5), stream.read(&mut [0; 1]))
+        .await
+        .expect("the idle connection was not reaped")
+        .unwrap_or_default();
+    assert_eq!(len, 0);
+}
@@ -0,0 +1,189 @@
+use std::{collections::HashMap, sync::Mutex};
+
+use ipiis_api::common::{
+    test_util::{CallRecord, RecordingClient},
+    Ipiis,
+};
+use ipis::{
+    async_trait::async_trait,
+    core::{
+        account::{Account, AccountRef},
+        anyhow::{anyhow, bail, Result},
+        value::hash::Hash,
+    },
+    tokio::{self, io::DuplexStream},
+};
+
+#[tokio::test]
+async fn test_recording_client() {
+    let kind = Hash::with_str("__ipiis__test__recording__");
+    let primary = Account::generate().account_ref();
+    let address = "127.0.0.1:5000".to_string();
+
+    // prepare a mock knowing the kind's primary account
+    let mock = MockClient::new(Account::generate());
+    mock.set_account_primary(Some(&kind), &primary)
+        .await
+        .unwrap();
+    mock.set_address(Some(&kind), &primary, &address)
+        .await
+        .unwrap();
+
+    // run the application logic through the recorder
+    let client = RecordingClient::new(mock);
+    assert_eq!(resolve_primary(&client, &kind).await.unwrap(), address);
+
+    // the upstream lookup should be recorded before the address lookup
+    assert_eq!(
+        client.take_records(),
+        vec![
+            CallRecord::GetAccountPrimary { kind: Some(kind) },
+            CallRecord::GetAddress {
+                kind: Some(kind),
+                target: primary,
+            },
+        ],
+    );
+    assert!(client.records().is_empty());
+
+    // the failed calls should be recorded as well
+    assert!(client.call_raw(None, &primary).await.is_err());
+    assert_eq!(
+        client.records(),
+        vec![CallRecord::CallRaw {
+            kind: None,
+            target: primary,
+        }],
+    );
+}
+
+/// The application logic under test.
+async fn resolve_primary<C>(client: &C, kind: &Hash) -> Result<<C as Ipiis>::Address>
+where
+    C: Ipiis + Send + Sync,
+{
+    let primary = client.get_account_primary(Some(kind)).await?;
+    client.get_address(Some(kind), &primary).await
+}
+
+/// A client keeping the address book in memory, without any network.
+struct MockClient {
+    account: Account,
+    account_ref: AccountRef,
+    primaries: Mutex<HashMap<Option<Hash>, AccountRef>>,
+    addresses: Mutex<HashMap<(Option<Hash>, AccountRef), String>>,
+}
+
+impl MockClient {
+    fn new(account: Account) -> Self {
+        Self {
+            account_ref: account.account_ref(),
+            account,
+            primaries: Default::default(),
+            addresses: Default::default(),
+        }
+    }
+}
+
+#[async_trait]
+impl Ipiis for MockClient {
+    type Address = String;
+    type Reader = DuplexStream;
+    type Writer = DuplexStream;
+
+    unsafe fn account_me(&self) -> Result<&Account> {
+        Ok(&self.account)
+    }
+
+    fn account_ref(&self) -> &AccountRef {
+        &self.account_ref
+    }
+
+    async fn get_account_primary(&self, kind: Option<&Hash>) -> Result<AccountRef> {
+        self.primaries
+            .lock()
+            .unwrap()
+            .get(&kind.copied())
+            .copied()
+            .ok_or_else(|| anyhow!("failed to get primary account"))
+    }
+
+    async fn set_account_primary(&self, kind: Option<&Hash>, account: &AccountRef) -> Result<()> {
+        self.primaries
+            .lock()
+            .unwrap()
+            .insert(kind.copied(), *account);
+        Ok(())
+    }
+
+    async fn delete_account_primary(&self, kind: Option<&Hash>) -> Result<()> {
+        self.primaries.lock().unwrap().remove(&kind.copied());
+        Ok(())
+    }
+
+    async fn get_address(&self, kind: Option<&Hash>, target: &AccountRef) -> Result<String> {
+        self.addresses
+            .lock()
+            .unwrap()
+            .get(&(kind.copied(), *target))
+            .cloned()
+            .ok_or_else(|| anyhow!("failed to get address: {target}"))
+    }
+
+    async fn set_address(
+        &self,
+        kind: Option<&Hash>,
+        target: &AccountRef,
+        address: &String,
+    ) -> Result<()> {
+        self.addresses
+            .lock()
+            .unwrap()
+            .insert((kind.copied(), *target), address.clone());
+        Ok(())
+    }
+
+    async fn set_address_verified(
+        &self,
+        kind: Option<&Hash>,
+        target: &AccountRef,
+        address: &String,
+    ) -> Result<()> {
+        self.set_address(kind, target, address).await
+    }
+
+    async fn delete_address(&self, kind: Option<&Hash>, target: &AccountRef) -> Result<()> {
+        self.addresses
+            .lock()
+            .unwrap()
+            .remove(&(kind.copied(), *target));
+        Ok(())
+    }
+
+    async fn list_accounts(&self, kind: Option<&Hash>) -> Result<Vec<AccountRef>> {
+        Ok(self
+            .addresses
+            .lock()
+            .unwrap()
+            .keys()
+            .filter(|(k, _)| k.as_ref() == kind)
+            .map(|(_, account)| *account)
+            .collect())
+    }
+
+    fn protocol(&self) -> &'static str {
+        "mock"
+    }
+
+    async fn call_raw(
+        &self,
+        _kind: Option<&Hash>,
+        target: &AccountRef,
+    ) -> Result<(DuplexStream, DuplexStream)> {
+        bail!("the mock has no network: {target}")
+    }
+
+    async fn disconnect(&self, _target: &AccountRef, _reason: u32) -> Result<()> {
+        Ok(())
+    }
+}
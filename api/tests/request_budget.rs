@@ -0,0 +1,149 @@
+mod common;
+
+use core::time::Duration;
+use std::{sync::Arc, time::Instant};
+
+use bytecheck::CheckBytes;
+use ipiis_api::{
+    client::IpiisClient,
+    common::{define_io, external_call, handle_external_call, Ipiis, ServerResult},
+    server::IpiisServer,
+};
+use ipis::{
+    async_trait::async_trait,
+    core::{
+        account::{GuaranteeSigned, GuarantorSigned},
+        anyhow::Result,
+        data::Data,
+        signed::IsSigned,
+    },
+    env::Infer,
+    tokio,
+};
+use rkyv::{Archive, Deserialize, Serialize};
+
+use self::common::{introduce, test_client, test_server};
+
+/// Larger than a half of the budget, so only one request fits at once
+const BLOB_SIZE: usize = 768 * 1024;
+const BUDGET: u32 = 1024 * 1024;
+const HANDLE_TIME: Duration = Duration::from_secs(1);
+
+#[tokio::test]
+async fn test_request_budget() {
+    // deploy a server
+    let (server, address) = test_server("request-budget").await.unwrap();
+    let server = BudgetServer {
+        client: server.with_request_budget(BUDGET).into(),
+    };
+    let server_ref = *server.client.account_ref();
+    tokio::spawn(server.run());
+
+    // create a client
+    let client = Arc::new(test_client("request-budget").await.unwrap());
+    introduce(&client, &server_ref, &address).unwrap();
+
+    // send large requests concurrently
+    let instant = Instant::now();
+    let tasks: Vec<_> = (0..2)
+        .map(|_| {
+            let client = client.clone();
+            tokio::spawn(async move {
+                let blob = Blob(vec![42; BLOB_SIZE]);
+
+                // external call
+                external_call!(
+                    client: client,
+                    target: None => &server_ref,
+                    request: self::io => Upload,
+                    sign: client.sign_owned(server_ref, blob)?,
+                    inputs: { },
+                );
+                Result::<_, ::ipis::core::anyhow::Error>::Ok(())
+            })
+        })
+        .collect();
+    for task in tasks {
+        task.await.unwrap().unwrap();
+    }
+
+    // the requests should be handled one by one
+    assert!(instant.elapsed() >= HANDLE_TIME * 2);
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Archive, Serialize, Deserialize)]
+#[archive(compare(PartialEq))]
+#[archive_attr(derive(CheckBytes, Debug, PartialEq, Eq))]
+pub struct Blob(Vec<u8>);
+
+impl IsSigned for Blob {}
+
+define_io! {
+    Upload = 0 {
+        inputs: { },
+        input_sign: Data<GuaranteeSigned, Blob>,
+        outputs: { },
+        output_sign: Data<GuarantorSigned, Blob>,
+        generics: { },
+    },
+}
+
+pub struct BudgetServer {
+    client: Arc<IpiisServer>,
+}
+
+impl AsRef<IpiisClient> for BudgetServer {
+    fn as_ref(&self) -> &IpiisClient {
+        &self.client
+    }
+}
+
+#[async_trait]
+impl<'a> Infer<'a> for BudgetServer {
+    type GenesisArgs = <IpiisServer as Infer<'a>>::GenesisArgs;
+    type GenesisResult = Self;
+
+    async fn try_infer() -> Result<Self> {
+        Ok(Self {
+            client: IpiisServer::try_infer().await?.into(),
+        })
+    }
+
+    async fn genesis(
+        args: <Self as Infer<'a>>::GenesisArgs,
+    ) -> Result<<Self as Infer<'a>>::GenesisResult> {
+        Ok(Self {
+            client: IpiisServer::genesis(args).await?.into(),
+        })
+    }
+}
+
+handle_external_call!(
+    server: BudgetServer => IpiisServer,
+    name: run,
+    request: self::io => {
+        Upload => handle_upload,
+    },
+);
+
+impl BudgetServer {
+    async fn handle_upload(
+        client: &IpiisServer,
+        req: self::io::request::Upload<'static>,
+    ) -> Result<self::io::response::Upload<'static>> {
+        // unpack sign
+        let sign_as_guarantee = req.__sign.into_owned().await?;
+
+        // handle data
+        tokio::time::sleep(HANDLE_TIME).await;
+
+        // sign data
+        let sign = client.sign_as_guarantor(sign_as_guarantee)?;
+
+        // pack data
+        Ok(self::io::response::Upload {
+            __lifetime: Default::default(),
+            __sign: ::ipis::stream::DynStream::Owned(sign),
+        })
+    }
+}
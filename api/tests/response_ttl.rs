@@ -0,0 +1,49 @@
+mod common;
+
+use core::time::Duration;
+
+use ipiis_api::common::Ipiis;
+use ipis::{core::account::Account, tokio};
+
+use self::common::TestPeer;
+
+#[tokio::test]
+async fn test_response_ttl() {
+    // deploy a directory server limiting the lifetime of the records
+    let server = TestPeer::deploy_with("response-ttl", None, |server| {
+        Ok(server.with_response_ttl(Duration::from_secs(1)))
+    })
+    .await
+    .unwrap();
+
+    // register a target
+    let target = Account::generate().account_ref();
+    server
+        .server
+        .set_address(None, &target, &"127.0.0.1:9811".parse().unwrap())
+        .await
+        .unwrap();
+
+    // create a client
+    let client = server.child("response-ttl").await.unwrap();
+
+    // cache the record
+    let address = client.get_address(None, &target).await.unwrap();
+    assert_eq!(address.to_string(), "127.0.0.1:9811");
+
+    // move the target
+    server
+        .server
+        .set_address(None, &target, &"127.0.0.1:9812".parse().unwrap())
+        .await
+        .unwrap();
+
+    // the cached record should be served within the TTL
+    let address = client.get_address(None, &target).await.unwrap();
+    assert_eq!(address.to_string(), "127.0.0.1:9811");
+
+    // the client should re-query the directory after the TTL
+    tokio::time::sleep(Duration::from_millis(1200)).await;
+    let address = client.get_address(None, &target).await.unwrap();
+    assert_eq!(address.to_string(), "127.0.0.1:9812");
+}
@@ -0,0 +1,182 @@
+mod common;
+
+use core::time::Duration;
+use std::{sync::Arc, time::Instant};
+
+use ipiis_api::{
+    client::IpiisClient,
+    common::{define_io, external_call, handle_external_call, Ipiis, ServerResult},
+    server::IpiisServer,
+};
+use ipis::{
+    async_trait::async_trait,
+    core::{
+        account::{AccountRef, GuaranteeSigned, GuarantorSigned},
+        anyhow::Result,
+        data::Data,
+    },
+    env::Infer,
+    tokio,
+};
+
+use self::common::{introduce, test_client, test_server};
+
+const HANDLE_TIME: Duration = Duration::from_secs(1);
+const RETRY_AFTER: Duration = Duration::from_millis(1500);
+
+#[tokio::test]
+async fn test_retry_after() {
+    // deploy a server handling a request at once, without queueing the others
+    let (server, address) = test_server("retry-after").await.unwrap();
+    let server = SheddingServer {
+        client: server
+            .with_max_concurrent_requests(1)
+            .with_load_shedding(0, RETRY_AFTER)
+            .unwrap()
+            .into(),
+    };
+    let server_ref = *server.client.account_ref();
+    tokio::spawn(server.run());
+
+    // create a client
+    let client = Arc::new(test_client("retry-after").await.unwrap());
+    introduce(&client, &server_ref, &address).unwrap();
+
+    // saturate the server
+    let task = {
+        let client = client.clone();
+        tokio::spawn(async move { call(&client, server_ref, self::io::OpCode::Slow).await })
+    };
+    tokio::time::sleep(HANDLE_TIME / 4).await;
+
+    // the shed request should be re-sent after the suggested delay
+    let instant = Instant::now();
+    call(&client, server_ref, self::io::OpCode::Ping)
+        .await
+        .unwrap();
+    assert!(instant.elapsed() >= RETRY_AFTER);
+    assert!(instant.elapsed() < RETRY_AFTER * 2);
+
+    task.await.unwrap().unwrap();
+}
+
+async fn call(client: &IpiisClient, target: AccountRef, opcode: self::io::OpCode) -> Result<()> {
+    match opcode {
+        self::io::OpCode::Slow => {
+            // external call
+            external_call!(
+                client: client,
+                target: None => &target,
+                request: self::io => Slow,
+                sign: client.sign_owned(target, 0)?,
+                inputs: { },
+            );
+        }
+        self::io::OpCode::Ping => {
+            // external call
+            external_call!(
+                client: client,
+                target: None => &target,
+                request: self::io => Ping,
+                sign: client.sign_owned(target, 0)?,
+                inputs: { },
+            );
+        }
+    }
+    Ok(())
+}
+
+define_io! {
+    Slow = 0 {
+        inputs: { },
+        input_sign: Data<GuaranteeSigned, u8>,
+        outputs: { },
+        output_sign: Data<GuarantorSigned, u8>,
+        generics: { },
+    },
+    Ping = 1 {
+        inputs: { },
+        input_sign: Data<GuaranteeSigned, u8>,
+        outputs: { },
+        output_sign: Data<GuarantorSigned, u8>,
+        generics: { },
+    },
+}
+
+pub struct SheddingServer {
+    client: Arc<IpiisServer>,
+}
+
+impl AsRef<IpiisClient> for SheddingServer {
+    fn as_ref(&self) -> &IpiisClient {
+        &self.client
+    }
+}
+
+#[async_trait]
+impl<'a> Infer<'a> for SheddingServer {
+    type GenesisArgs = <IpiisServer as Infer<'a>>::GenesisArgs;
+    type GenesisResult = Self;
+
+    async fn try_infer() -> Result<Self> {
+        Ok(Self {
+            client: IpiisServer::try_infer().await?.into(),
+        })
+    }
+
+    async fn genesis(
+        args: <Self as Infer<'a>>::GenesisArgs,
+    ) -> Result<<Self as Infer<'a>>::GenesisResult> {
+        Ok(Self {
+            client: IpiisServer::genesis(args).await?.into(),
+        })
+    }
+}
+
+handle_external_call!(
+    server: SheddingServer => IpiisServer,
+    name: run,
+    request: self::io => {
+        Slow => handle_slow,
+        Ping => handle_ping,
+    },
+);
+
+impl SheddingServer {
+    async fn handle_slow(
+        client: &IpiisServer,
+        req: self::io::request::Slow<'static>,
+    ) -> Result<self::io::response::Slow<'static>> {
+        // unpack sign
+        let sign_as_guarantee = req.__sign.into_owned().await?;
+
+        // handle data
+        tokio::time::sleep(HANDLE_TIME).await;
+
+        // sign data
+        let sign = client.sign_as_guarantor(sign_as_guarantee)?;
+
+        // pack data
+        Ok(self::io::response::Slow {
+            __lifetime: Default::default(),
+            __sign: ::ipis::stream::DynStream::Owned(sign),
+        })
+    }
+
+    async fn handle_ping(
+        client: &IpiisServer,
+        req: self::io::request::Ping<'static>,
+    ) -> Result<self::io::response::Ping<'static>> {
+        // unpack sign
+        let sign_as_guarantee = req.__sign.into_owned().await?;
+
+        // sign data
+        let sign = client.sign_as_guarantor(sign_as_guarantee)?;
+
+        // pack data
+        Ok(self::io::response::Ping {
+            __lifetime: Default::default(),
+            __sign: ::ipis::stream::DynStream::Owned(sign),
+        })
+    }
+}
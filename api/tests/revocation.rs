@@ -0,0 +1,66 @@
+mod common;
+
+use core::time::Duration;
+
+use ipiis_api::common::{is_unauthorized, Ipiis};
+use ipis::tokio;
+
+use self::common::{introduce, TestPeer};
+
+#[tokio::test]
+async fn test_revocation() {
+    // deploy an authority rejecting the revoked accounts
+    let authority = TestPeer::deploy_with("revocation-authority", None, |server| {
+        Ok(server.with_revoked_accounts([]))
+    })
+    .await
+    .unwrap();
+
+    // deploy a server following the authority
+    let authority_ref = authority.account;
+    let server = TestPeer::deploy_with("revocation-server", None, move |server| {
+        server.with_revocation_authority(authority_ref, Duration::from_millis(100))
+    })
+    .await
+    .unwrap();
+    introduce(&server.server, &authority.account, &authority.address).unwrap();
+
+    // create a client
+    let client = authority.client("revocation").await.unwrap();
+    let client_ref = *client.account_ref();
+    introduce(&client, &server.account, &server.address).unwrap();
+
+    // the requests should be accepted before the revocation
+    client.ping(None, &authority.account).await.unwrap();
+    client.ping(None, &server.account).await.unwrap();
+
+    // revoke the client
+    assert!(authority
+        .server
+        .revocation_list()
+        .unwrap()
+        .revoke(client_ref));
+
+    // the authority should reject the requests of the revoked account
+    let error = client.ping(None, &authority.account).await.unwrap_err();
+    assert!(is_unauthorized(&error));
+    assert!(error.to_string().contains("revoked"));
+
+    // the server should reject them as well, after refreshing the list
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    assert!(server
+        .server
+        .revocation_list()
+        .unwrap()
+        .is_revoked(&client_ref));
+    let error = client.ping(None, &server.account).await.unwrap_err();
+    assert!(is_unauthorized(&error));
+
+    // the restored account should be accepted again
+    assert!(authority
+        .server
+        .revocation_list()
+        .unwrap()
+        .restore(&client_ref));
+    client.ping(None, &authority.account).await.unwrap();
+}
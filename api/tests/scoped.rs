@@ -0,0 +1,42 @@
+mod common;
+
+use ipiis_api::common::Ipiis;
+use ipis::{
+    core::{account::Account, value::hash::Hash},
+    tokio,
+};
+
+use self::common::test_client;
+
+#[tokio::test]
+async fn test_scoped() {
+    // create a client
+    let client = test_client("scoped").await.unwrap();
+
+    // register an address of the kind
+    let target = Account::generate().account_ref();
+    let kind = Hash::with_str(&format!("__ipiis__test__kind__{target}"));
+    let address = "127.0.0.1:9801".to_string();
+    client
+        .set_address(Some(&kind), &target, &address)
+        .await
+        .unwrap();
+
+    // the scoped client should resolve the same
+    let scoped = client.scoped(kind);
+    assert_eq!(
+        scoped.get_address(&target).await.unwrap(),
+        client.get_address(Some(&kind), &target).await.unwrap(),
+    );
+
+    // as `Ipiis`, the calls without a kind should be scoped as well
+    assert_eq!(
+        Ipiis::get_address(&scoped, None, &target).await.unwrap(),
+        address,
+    );
+    assert!(client.get_address(None, &target).await.is_err());
+
+    // the scoped client should share the address book
+    scoped.delete_address(&target).await.unwrap();
+    assert!(client.get_address(Some(&kind), &target).await.is_err());
+}
@@ -0,0 +1,23 @@
+mod common;
+
+use ipiis_api::common::{Ipiis, IpiisError};
+use ipis::tokio;
+
+use self::common::{introduce, test_server};
+
+#[tokio::test]
+async fn test_self_connection() {
+    // create a server
+    let (server, address) = test_server("self-connection").await.unwrap();
+    let account = *server.account_ref();
+
+    // (mis)map its own account to its own address
+    introduce(&server, &account, &address).unwrap();
+
+    // dialing itself should fail rather than hang
+    let error = server.call_raw(None, &account).await.err().unwrap();
+    assert!(matches!(
+        error.downcast_ref::<IpiisError>(),
+        Some(IpiisError::SelfConnection(_)),
+    ));
+}
@@ -0,0 +1,45 @@
+mod common;
+
+use ipiis_api::{client::IpiisClient, common::Ipiis};
+use ipis::{env::Infer, tokio};
+
+use self::common::TestPeer;
+
+#[tokio::test]
+async fn test_server_time() {
+    // create a client before the server takes over the address book env
+    let client = IpiisClient::genesis(None).await.unwrap();
+
+    // deploy a server reporting its processing time
+    let server =
+        TestPeer::deploy_with(
+            "server-time",
+            None,
+            |server| Ok(server.with_server_timing()),
+        )
+        .await
+        .unwrap();
+    client
+        .set_address(None, &server.account, &server.address.to_string())
+        .await
+        .unwrap();
+
+    // the server should report its processing time
+    let report = client.ping(None, &server.account).await.unwrap();
+    assert!(report.identity_confirmed);
+
+    let server_time = report.server_time.expect("server time is not reported");
+    assert!(!server_time.is_zero());
+    assert!(server_time < report.rtt);
+
+    // the other servers should not report it
+    let server = TestPeer::deploy("server-time-off", None).await.unwrap();
+    client
+        .set_address(None, &server.account, &server.address.to_string())
+        .await
+        .unwrap();
+
+    let report = client.ping(None, &server.account).await.unwrap();
+    assert!(report.identity_confirmed);
+    assert_eq!(report.server_time, None);
+}
@@ -0,0 +1,204 @@
+mod common;
+
+use std::sync::Arc;
+
+use ipiis_api::{
+    client::IpiisClient,
+    common::{handle_external_call, is_not_found, Ipiis, IpiisError, ServerResult},
+    server::IpiisServer,
+};
+use ipis::{
+    async_trait::async_trait,
+    core::{account::Account, anyhow::Result},
+    env::Infer,
+    tokio,
+};
+
+use self::common::{test_client, test_server_as, TestPeer};
+
+/// The address a faulty root stores instead of the requested one
+const FAULTY_ADDRESS: &str = "127.0.0.1:1";
+
+#[tokio::test]
+async fn test_set_address_verified() {
+    let address = "127.0.0.1:5000".to_string();
+
+    // deploy a root server
+    let root = TestPeer::deploy("set-address-verified", None)
+        .await
+        .unwrap();
+    let target = *test_client("set-address-verified-target")
+        .await
+        .unwrap()
+        .account_ref();
+
+    // the root client should verify the stored address
+    let client = root
+        .child("set-address-verified-root")
+        .await
+        .unwrap()
+        .with_account(root.account_me().unwrap())
+        .unwrap();
+    client
+        .set_address_verified(None, &target, &address)
+        .await
+        .unwrap();
+    assert_eq!(
+        root.server.book().get(None, &target).unwrap(),
+        Some(address.clone())
+    );
+
+    // the other clients should fail without setting it
+    let child = root.child("set-address-verified-child").await.unwrap();
+    assert!(child
+        .set_address_verified(None, &target, &"127.0.0.1:5001".to_string())
+        .await
+        .is_err());
+    assert_eq!(child.book().get(None, &target).unwrap(), None);
+
+    // so should the clients without any root
+    let orphan = test_client("set-address-verified-orphan").await.unwrap();
+    let error = orphan
+        .set_address_verified(None, &target, &address)
+        .await
+        .unwrap_err();
+    assert!(is_not_found(&error), "{error:#}");
+}
+
+#[tokio::test]
+async fn test_set_address_verified_faulty_root() {
+    let address = "127.0.0.1:5000".to_string();
+
+    // deploy a faulty root server
+    let account_me = Account::generate();
+    let account_me_str = account_me.to_string();
+    let (server, server_address) = test_server_as("set-address-verified-faulty", account_me)
+        .await
+        .unwrap();
+    let server = FaultyServer {
+        client: server.into(),
+    };
+    let server_ref = *server.client.account_ref();
+    let book = server.client.book().clone();
+    tokio::spawn(server.run());
+
+    // create a root client
+    let client = test_client("set-address-verified-faulty")
+        .await
+        .unwrap()
+        .with_account(account_me_str.parse().unwrap())
+        .unwrap();
+    client
+        .book()
+        .set_primary_with_address(None, &server_ref, &server_address.to_string())
+        .unwrap();
+    let target = *test_client("set-address-verified-faulty-target")
+        .await
+        .unwrap()
+        .account_ref();
+
+    // the divergence should be detected
+    let error = client
+        .set_address_verified(None, &target, &address)
+        .await
+        .unwrap_err();
+    assert!(error.to_string().contains("diverged"), "{error:#}");
+    assert_eq!(
+        book.get(None, &target).unwrap(),
+        Some(FAULTY_ADDRESS.to_string()),
+    );
+}
+
+pub struct FaultyServer {
+    client: Arc<IpiisServer>,
+}
+
+impl AsRef<IpiisClient> for FaultyServer {
+    fn as_ref(&self) -> &IpiisClient {
+        &self.client
+    }
+}
+
+#[async_trait]
+impl<'a> Infer<'a> for FaultyServer {
+    type GenesisArgs = <IpiisServer as Infer<'a>>::GenesisArgs;
+    type GenesisResult = Self;
+
+    async fn try_infer() -> Result<Self> {
+        Ok(Self {
+            client: IpiisServer::try_infer().await?.into(),
+        })
+    }
+
+    async fn genesis(
+        args: <Self as Infer<'a>>::GenesisArgs,
+    ) -> Result<<Self as Infer<'a>>::GenesisResult> {
+        Ok(Self {
+            client: IpiisServer::genesis(args).await?.into(),
+        })
+    }
+}
+
+handle_external_call!(
+    server: FaultyServer => IpiisServer,
+    name: run,
+    request: ::ipiis_api::common::io => {
+        GetAddress => handle_get_address,
+        SetAddress => handle_set_address,
+    },
+);
+
+impl FaultyServer {
+    async fn handle_get_address(
+        client: &IpiisServer,
+        req: ::ipiis_api::common::io::request::GetAddress<'static, String>,
+    ) -> Result<::ipiis_api::common::io::response::GetAddress<'static, String>> {
+        // unpack sign
+        let sign_as_guarantee = req.__sign.into_owned().await?;
+
+        // unpack data
+        let (kind, account) = sign_as_guarantee.data;
+
+        // handle data
+        let address = client
+            .book()
+            .get(kind.as_ref(), &account)?
+            .ok_or_else(|| IpiisError::NotFound(account.to_string()))?;
+
+        // sign data
+        let sign = client.sign_as_guarantor(sign_as_guarantee)?;
+
+        // pack data
+        Ok(::ipiis_api::common::io::response::GetAddress {
+            __lifetime: Default::default(),
+            __sign: ::ipis::stream::DynStream::Owned(sign),
+            address: ::ipis::stream::DynStream::Owned(address),
+            ttl_ms: ::ipis::stream::DynStream::Owned(None),
+        })
+    }
+
+    async fn handle_set_address(
+        client: &IpiisServer,
+        req: ::ipiis_api::common::io::request::SetAddress<'static, String>,
+    ) -> Result<::ipiis_api::common::io::response::SetAddress<'static, String>> {
+        // unpack sign
+        let sign_as_guarantee = req.__sign.into_owned().await?;
+
+        // unpack data
+        let (kind, account, _) = &sign_as_guarantee.data;
+
+        // handle data, storing another address
+        client
+            .book()
+            .set(kind.as_ref(), account, &FAULTY_ADDRESS.to_string())?;
+
+        // sign data
+        let sign = client.sign_as_guarantor(sign_as_guarantee)?;
+
+        // pack data
+        Ok(::ipiis_api::common::io::response::SetAddress {
+            __lifetime: Default::default(),
+            __sign: ::ipis::stream::DynStream::Owned(sign),
+        })
+    }
+}
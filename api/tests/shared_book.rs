@@ -0,0 +1,60 @@
+mod common;
+
+use std::{net::SocketAddr, sync::Arc};
+
+use ipiis_api::{common::Ipiis, server::IpiisServer};
+use ipis::{core::account::Account, tokio};
+
+use self::common::{introduce, test_client, test_server};
+
+#[tokio::test]
+async fn test_shared_book() {
+    // deploy a server
+    let (server_a, address_a) = test_server("shared-book").await.unwrap();
+    let server_a = Arc::new(server_a);
+    let server_a_ref = *server_a.account_ref();
+    tokio::spawn(server_a.clone().run_ipiis());
+
+    // deploy another server sharing the address book
+    let server_b = Arc::new(
+        IpiisServer::with_book(
+            None,
+            0,
+            server_a.book().clone().with_account(Account::generate()),
+        )
+        .await
+        .unwrap(),
+    );
+    let server_b_ref = *server_b.account_ref();
+    let address_b = SocketAddr::from(([127, 0, 0, 1], server_b.local_addr().unwrap().port()));
+    assert_ne!(server_a_ref, server_b_ref);
+    tokio::spawn(server_b.clone().run_ipiis());
+
+    // a write via one server should be visible to the other
+    let address = address_b.to_string();
+    server_a
+        .set_address(None, &server_b_ref, &address)
+        .await
+        .unwrap();
+    assert_eq!(
+        server_b.get_address(None, &server_b_ref).await.unwrap(),
+        address,
+    );
+
+    let address = address_a.to_string();
+    server_b
+        .set_address(None, &server_a_ref, &address)
+        .await
+        .unwrap();
+    assert_eq!(
+        server_a.get_address(None, &server_a_ref).await.unwrap(),
+        address,
+    );
+
+    // both servers should be serving as their own accounts
+    let client = test_client("shared-book").await.unwrap();
+    for (target, address) in [(&server_a_ref, &address_a), (&server_b_ref, &address_b)] {
+        introduce(&client, target, address).unwrap();
+        client.ping(None, target).await.unwrap();
+    }
+}
@@ -0,0 +1,140 @@
+mod common;
+
+use core::{
+    sync::atomic::{AtomicUsize, Ordering},
+    time::Duration,
+};
+use std::sync::Arc;
+
+use ipiis_api::{
+    client::IpiisClient,
+    common::{define_io, handle_external_call, Ipiis, ServerResult},
+    server::IpiisServer,
+};
+use ipis::{
+    async_trait::async_trait,
+    core::{
+        account::{Account, AccountRef, GuaranteeSigned, GuarantorSigned},
+        anyhow::Result,
+        data::Data,
+        value::hash::Hash,
+    },
+    env::Infer,
+    futures, tokio,
+};
+
+use self::common::{test_client, test_server};
+
+/// Number of the `GetAddress` requests received by the mock primary
+static NUM_REQUESTS: AtomicUsize = AtomicUsize::new(0);
+
+const ADDRESS: &str = "127.0.0.1:5024";
+
+#[tokio::test]
+async fn test_single_flight() {
+    // deploy a mock primary
+    let (primary, address) = test_server("single-flight").await.unwrap();
+    let primary = MockPrimary {
+        client: primary.into(),
+    };
+    let primary_ref = *primary.client.account_ref();
+    tokio::spawn(primary.run());
+
+    // create a client
+    let client = Arc::new(test_client("single-flight").await.unwrap());
+    client
+        .book()
+        .set_primary_with_address(None, &primary_ref, &address.to_string())
+        .unwrap();
+
+    // look up a cold target concurrently
+    let target = Account::generate().account_ref();
+    let addresses = futures::future::try_join_all((0..50).map(|_| {
+        let client = client.clone();
+        async move { client.get_address(None, &target).await }
+    }))
+    .await
+    .unwrap();
+
+    // all lookups should share a single upstream request
+    assert!(addresses.iter().all(|address| address == ADDRESS));
+    assert_eq!(NUM_REQUESTS.load(Ordering::SeqCst), 1);
+}
+
+pub struct MockPrimary {
+    client: Arc<IpiisServer>,
+}
+
+impl AsRef<IpiisClient> for MockPrimary {
+    fn as_ref(&self) -> &IpiisClient {
+        &self.client
+    }
+}
+
+#[async_trait]
+impl<'a> Infer<'a> for MockPrimary {
+    type GenesisArgs = <IpiisServer as Infer<'a>>::GenesisArgs;
+    type GenesisResult = Self;
+
+    async fn try_infer() -> Result<Self> {
+        Ok(Self {
+            client: IpiisServer::try_infer().await?.into(),
+        })
+    }
+
+    async fn genesis(
+        args: <Self as Infer<'a>>::GenesisArgs,
+    ) -> Result<<Self as Infer<'a>>::GenesisResult> {
+        Ok(Self {
+            client: IpiisServer::genesis(args).await?.into(),
+        })
+    }
+}
+
+// the same wire format as the built-in `GetAddress`
+define_io! {
+    GetAddress = 3 {
+        idempotent: true,
+        inputs: { },
+        input_sign: Data<GuaranteeSigned, (Option<Hash>, AccountRef)>,
+        outputs: {
+            address: Address,
+            ttl_ms: Option<u64>,
+        },
+        output_sign: Data<GuarantorSigned, (Option<Hash>, AccountRef)>,
+        generics: { Address, },
+    },
+}
+
+handle_external_call!(
+    server: MockPrimary => IpiisServer,
+    name: run,
+    request: self::io => {
+        GetAddress => handle_get_address,
+    },
+);
+
+impl MockPrimary {
+    async fn handle_get_address(
+        client: &IpiisServer,
+        req: self::io::request::GetAddress<'static, String>,
+    ) -> Result<self::io::response::GetAddress<'static, String>> {
+        // unpack sign
+        let sign_as_guarantee = req.__sign.into_owned().await?;
+
+        // handle data
+        NUM_REQUESTS.fetch_add(1, Ordering::SeqCst);
+        tokio::time::sleep(Duration::from_millis(200)).await;
+
+        // sign data
+        let sign = client.sign_as_guarantor(sign_as_guarantee)?;
+
+        // pack data
+        Ok(self::io::response::GetAddress {
+            __lifetime: Default::default(),
+            __sign: ::ipis::stream::DynStream::Owned(sign),
+            address: ::ipis::stream::DynStream::Owned(ADDRESS.to_string()),
+            ttl_ms: ::ipis::stream::DynStream::Owned(None),
+        })
+    }
+}
@@ -0,0 +1,61 @@
+mod common;
+
+use ipiis_api::common::Ipiis;
+use ipis::{
+    core::{account::Account, value::hash::Hash},
+    tokio,
+};
+
+use self::common::{test_client, TestPeer};
+
+#[tokio::test]
+async fn test_snapshot_primaries() {
+    // create a follower
+    let follower = test_client("snapshot-primaries-follower").await.unwrap();
+    let follower_ref = *follower.account_ref();
+
+    // deploy a leader, trusting the follower only
+    let leader = TestPeer::deploy_with("snapshot-primaries-leader", None, move |server| {
+        Ok(server.with_authorizer(move |account| account == &follower_ref))
+    })
+    .await
+    .unwrap();
+    leader.route(&follower).unwrap();
+
+    // set some entries in the leader
+    let kind_a = Hash::with_str("__ipiis__test__snapshot_primaries__a__");
+    let kind_b = Hash::with_str("__ipiis__test__snapshot_primaries__b__");
+    let account_a = Account::generate().account_ref();
+    let account_b = Account::generate().account_ref();
+    let address_a = "127.0.0.1:5022".to_string();
+    leader
+        .server
+        .set_account_primary(Some(&kind_a), &account_a)
+        .await
+        .unwrap();
+    leader
+        .server
+        .set_address(Some(&kind_a), &account_a, &address_a)
+        .await
+        .unwrap();
+    leader
+        .server
+        .set_account_primary(Some(&kind_b), &account_b)
+        .await
+        .unwrap();
+
+    // take a snapshot from the follower
+    let mut snapshot = follower.snapshot_primaries().await.unwrap();
+    snapshot.sort_by_key(|(_, account, _)| account.to_string());
+
+    let mut expected = vec![
+        (Some(kind_a), account_a, Some(address_a)),
+        (Some(kind_b), account_b, None),
+    ];
+    expected.sort_by_key(|(_, account, _)| account.to_string());
+    assert_eq!(snapshot, expected);
+
+    // the others should be rejected
+    let stranger = leader.child("snapshot-primaries-stranger").await.unwrap();
+    assert!(stranger.snapshot_primaries().await.is_err());
+}
@@ -0,0 +1,60 @@
+mod common;
+
+use core::time::Duration;
+use std::time::Instant;
+
+use ipiis_api::{
+    client::RouteBook,
+    common::{is_not_found, Ipiis},
+};
+use ipis::{
+    core::{account::Account, value::hash::Hash},
+    tokio,
+};
+
+use self::common::test_client;
+
+#[tokio::test]
+async fn test_static_routes() {
+    // no one answers on the address of the primary, which should never be asked
+    let primary = Account::generate().account_ref();
+    let kind = Hash::with_str("__ipiis__test__static_routes__");
+    let seeded = Account::generate().account_ref();
+    let book = RouteBook {
+        primaries: vec![(None, primary), (Some(kind), seeded)],
+        addresses: vec![
+            (None, primary, "127.0.0.1:5070".to_string()),
+            (Some(kind), seeded, "127.0.0.1:9301".to_string()),
+        ],
+    };
+    let client = test_client("static-routes")
+        .await
+        .unwrap()
+        .with_static_routes(&book)
+        .unwrap();
+    assert!(client.config_summary().static_routing);
+
+    // the seeded accounts should be resolved locally
+    assert_eq!(
+        client.get_address(Some(&kind), &seeded).await.unwrap(),
+        "127.0.0.1:9301",
+    );
+    assert_eq!(
+        client.get_account_primary(Some(&kind)).await.unwrap(),
+        seeded
+    );
+
+    // the unknown ones should fail at once, without asking the primary
+    let instant = Instant::now();
+    let unknown = Account::generate().account_ref();
+    let error = client.get_address(Some(&kind), &unknown).await.unwrap_err();
+    assert!(is_not_found(&error), "{error:#}");
+
+    let other_kind = Hash::with_str("__ipiis__test__static_routes__other__");
+    let error = client
+        .get_account_primary(Some(&other_kind))
+        .await
+        .unwrap_err();
+    assert!(is_not_found(&error), "{error:#}");
+    assert!(instant.elapsed() < Duration::from_secs(1));
+}
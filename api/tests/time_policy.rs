@@ -0,0 +1,78 @@
+mod common;
+
+use core::time::Duration;
+
+use ipiis_api::common::{
+    external_call, io, is_stale_request, prepare_signed_request, send_prepared, Ipiis, TimePolicy,
+};
+use ipis::{core::account::Account, tokio};
+
+use self::common::TestPeer;
+
+#[tokio::test]
+async fn test_time_policy() {
+    // deploy a server tolerating a tight clock skew
+    let server = TestPeer::deploy_with("time-policy", None, |server| {
+        Ok(server.with_time_policy(TimePolicy {
+            clock_skew_tolerance: Duration::from_millis(100),
+            default_request_ttl: Duration::from_millis(200),
+            ..Default::default()
+        }))
+    })
+    .await
+    .unwrap();
+    let server_ref = server.account;
+
+    // register a target
+    let target = Account::generate().account_ref();
+    server
+        .server
+        .set_address(None, &target, &"127.0.0.1:9813".parse().unwrap())
+        .await
+        .unwrap();
+
+    // create a client limiting the lifetime of the cached records by itself
+    let client = server
+        .child("time-policy")
+        .await
+        .unwrap()
+        .with_time_policy(TimePolicy {
+            cache_record_ttl: Some(Duration::from_millis(300)),
+            ..Default::default()
+        });
+
+    // the fresh requests should be accepted
+    let address = client.get_address(None, &target).await.unwrap();
+    assert_eq!(address.to_string(), "127.0.0.1:9813");
+
+    // move the target
+    server
+        .server
+        .set_address(None, &target, &"127.0.0.1:9814".parse().unwrap())
+        .await
+        .unwrap();
+
+    // the cached record should be served within the TTL of the client
+    let address = client.get_address(None, &target).await.unwrap();
+    assert_eq!(address.to_string(), "127.0.0.1:9813");
+
+    // the client should re-query the directory after the TTL, though the server has none
+    tokio::time::sleep(Duration::from_millis(400)).await;
+    let address = client.get_address(None, &target).await.unwrap();
+    assert_eq!(address.to_string(), "127.0.0.1:9814");
+
+    // the requests older than the TTL and the skew should be rejected
+    let req: io::request::GetAddress<'static, String> = external_call!(
+        client: client,
+        target: None => &server_ref,
+        request: ::ipiis_api::common::io => GetAddress,
+        sign: client.sign_owned(server_ref, (None, target)).unwrap(),
+        inputs: { },
+        outputs: none,
+    );
+    let prepared = prepare_signed_request(server_ref, req).await.unwrap();
+
+    tokio::time::sleep(Duration::from_millis(400)).await;
+    let error = send_prepared(&client, None, &prepared).await.unwrap_err();
+    assert!(is_stale_request(&error), "{error:#}");
+}
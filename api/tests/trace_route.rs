@@ -0,0 +1,42 @@
+mod common;
+
+use ipis::tokio;
+
+use self::common::TestPeer;
+
+#[tokio::test]
+async fn test_trace_route() {
+    // deploy the peers: `end` --> `edge` --> `center`
+    let center = TestPeer::deploy("trace-route-center", None).await.unwrap();
+    let edge = TestPeer::deploy("trace-route-edge", Some(&center))
+        .await
+        .unwrap();
+    let end = TestPeer::deploy("trace-route-end", Some(&edge))
+        .await
+        .unwrap();
+
+    // trace the center's address from `end`
+    let hops = end
+        .server
+        .trace_address(None, &center.account)
+        .await
+        .unwrap();
+    assert_eq!(
+        hops.iter().map(|hop| hop.account).collect::<Vec<_>>(),
+        [edge.account, center.account],
+    );
+    assert!(hops.iter().all(|hop| hop.answered && hop.error.is_none()));
+
+    // the trace should point out the broken hop
+    let edge_account = edge.account;
+    let _edge = edge.shutdown().await.unwrap();
+    let hops = end
+        .server
+        .trace_address(None, &center.account)
+        .await
+        .unwrap();
+    assert_eq!(hops.len(), 1);
+    assert_eq!(hops[0].account, edge_account);
+    assert!(!hops[0].answered);
+    assert!(hops[0].error.is_some());
+}
@@ -0,0 +1,68 @@
+mod common;
+
+use core::time::Duration;
+use std::sync::{Arc, Mutex};
+
+use ipiis_api::common::{
+    external_call, is_invalid_signature, Ipiis, Nonce, VerificationAudit, VerificationFailure,
+};
+use ipis::{core::account::Account, tokio};
+
+use self::common::TestPeer;
+
+#[tokio::test]
+async fn test_verification_audit() {
+    // deploy a server auditing the failed verifications
+    let failures: Arc<Mutex<Vec<VerificationFailure>>> = Default::default();
+    let audit = {
+        let failures = failures.clone();
+        VerificationAudit::new(10, Duration::from_secs(60))
+            .with_observer(move |failure| failures.lock().unwrap().push(failure.clone()))
+    };
+    let server = {
+        let audit = audit.clone();
+        TestPeer::deploy_with("verification-audit", None, move |server| {
+            Ok(server.with_verification_audit(audit.clone()))
+        })
+        .await
+        .unwrap()
+    };
+    let server_ref = server.account;
+
+    // create a client
+    let client = server.client("verification-audit").await.unwrap();
+
+    // a valid request should not be audited
+    assert!(
+        client
+            .ping(None, &server_ref)
+            .await
+            .unwrap()
+            .identity_confirmed
+    );
+    assert_eq!(audit.failures(), 0);
+
+    // forge a request signed for another account
+    let other = Account::generate().account_ref();
+    let result = async {
+        external_call!(
+            client: &client,
+            target: None => &server_ref,
+            request: ::ipiis_api::common::io => Ping,
+            sign: client.sign_owned(other, Nonce::generate())?,
+            inputs: { },
+        );
+        ::ipis::core::anyhow::Result::<()>::Ok(())
+    }
+    .await;
+    let error = result.unwrap_err();
+    assert!(is_invalid_signature(&error), "{error:#}");
+
+    // the failure should be logged exactly once
+    assert_eq!(audit.failures(), 1);
+    let failures = failures.lock().unwrap();
+    assert_eq!(failures.len(), 1);
+    assert_eq!(failures[0].account, client.account_me_ref());
+    assert_eq!(failures[0].opcode, "Ping");
+    assert!(!failures[0].reason.is_empty());
+}
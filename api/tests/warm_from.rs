@@ -0,0 +1,43 @@
+mod common;
+
+use ipiis_api::common::Ipiis;
+use ipis::{
+    core::{account::Account, value::hash::Hash},
+    tokio,
+};
+
+use self::common::TestPeer;
+
+#[tokio::test]
+async fn test_warm_from() {
+    // deploy a directory server
+    let server = TestPeer::deploy("warm-from", None).await.unwrap();
+
+    // populate the directory
+    let kind = Hash::with_str(&format!("__ipiis__test__warm_from__{}", server.account));
+    let accounts: Vec<_> = (0..2).map(|_| Account::generate().account_ref()).collect();
+    for (port, account) in (9001..).zip(&accounts) {
+        let address = format!("127.0.0.1:{port}").parse().unwrap();
+        server
+            .server
+            .set_address(Some(&kind), account, &address)
+            .await
+            .unwrap();
+    }
+
+    // create a fresh client without any primary account
+    let client = server.client("warm-from").await.unwrap();
+
+    // warm the address book
+    let count = client
+        .warm_from(&server.account, &[Some(kind)])
+        .await
+        .unwrap();
+    assert_eq!(count, accounts.len());
+
+    // the addresses should be served from the local cache
+    for (port, account) in (9001..).zip(&accounts) {
+        let address = client.get_address(Some(&kind), account).await.unwrap();
+        assert_eq!(address.to_string(), format!("127.0.0.1:{port}"));
+    }
+}
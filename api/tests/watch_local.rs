@@ -0,0 +1,52 @@
+mod common;
+
+use core::time::Duration;
+
+use ipiis_api::{client::BookChange, common::Ipiis};
+use ipis::{
+    core::account::Account,
+    futures::{pin_mut, StreamExt},
+    tokio,
+};
+
+use self::common::test_client;
+
+const TIMEOUT: Duration = Duration::from_secs(1);
+
+#[tokio::test]
+async fn test_watch_local() {
+    // create a client
+    let client = test_client("watch-local").await.unwrap();
+
+    // subscribe to the local changes
+    let changes = client.watch_local();
+    pin_mut!(changes);
+
+    // mutate the address book
+    let target = Account::generate().account_ref();
+    let address = "127.0.0.1:9801".to_string();
+    client.set_address(None, &target, &address).await.unwrap();
+    client.delete_address(None, &target).await.unwrap();
+
+    // the changes should be received in order
+    assert_eq!(
+        tokio::time::timeout(TIMEOUT, changes.next()).await.unwrap(),
+        Some(BookChange::SetAddress {
+            kind: None,
+            account: target,
+            address,
+        }),
+    );
+    assert_eq!(
+        tokio::time::timeout(TIMEOUT, changes.next()).await.unwrap(),
+        Some(BookChange::DeleteAddress {
+            kind: None,
+            account: target,
+        }),
+    );
+
+    // the changes made before subscribing should not be received
+    let changes = client.watch_local();
+    pin_mut!(changes);
+    assert!(tokio::time::timeout(TIMEOUT, changes.next()).await.is_err());
+}
@@ -0,0 +1,51 @@
+mod common;
+
+use std::sync::{Arc, Mutex};
+
+use ipiis_api::common::{io, replay_bytes, Ipiis};
+use ipis::{core::account::Account, tokio};
+
+use self::common::{test_client, TestPeer};
+
+#[tokio::test]
+async fn test_wire_capture() {
+    // deploy a server
+    let server = TestPeer::deploy("wire-capture", None).await.unwrap();
+    let server_ref = server.account;
+
+    // register a target in the server
+    let target = Account::generate().account_ref();
+    let address = "127.0.0.1:9802".to_string();
+    server
+        .server
+        .set_address(None, &target, &address)
+        .await
+        .unwrap();
+
+    // create a client capturing the sent requests
+    let captured: Arc<Mutex<Vec<Vec<u8>>>> = Default::default();
+    let client = {
+        let captured = captured.clone();
+        test_client("wire-capture")
+            .await
+            .unwrap()
+            .with_wire_capture(move |bytes| captured.lock().unwrap().push(bytes.to_vec()))
+    };
+    server.route(&client).unwrap();
+
+    // resolve the target from the server
+    assert_eq!(client.get_address(None, &target).await.unwrap(), address);
+
+    // the request should be captured as it was sent
+    let bytes = captured.lock().unwrap().pop().unwrap();
+    assert!(bytes.starts_with(&io::OpCode::GetAddress.to_bytes()));
+
+    // the captured bytes should be replayed as they are
+    let mut recv = replay_bytes(&client, None, &server_ref, &bytes)
+        .await
+        .unwrap();
+    let mut res = io::response::GetAddress::<String>::recv(&server_ref, &mut recv)
+        .await
+        .unwrap();
+    assert_eq!(res.address.to_owned().await.unwrap(), address);
+}
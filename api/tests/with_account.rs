@@ -0,0 +1,41 @@
+mod common;
+
+use ipiis_api::{
+    client::IpiisClient,
+    common::{external_call, Ipiis},
+};
+use ipis::{core::value::hash::Hash, tokio};
+
+use self::common::TestPeer;
+
+#[tokio::test]
+async fn test_with_account() {
+    // deploy a root server
+    let server = TestPeer::deploy("with-account", None).await.unwrap();
+    let server_ref = server.account;
+
+    // create a client
+    let client = server.child("with-account").await.unwrap();
+
+    let set_account_primary = |client: &IpiisClient| {
+        let client = client.clone();
+        async move {
+            external_call!(
+                client: &client,
+                target: None => &server_ref,
+                request: ::ipiis_api::common::io => SetAccountPrimary,
+                sign: client.sign_owned(server_ref, (None::<Hash>, server_ref))?,
+                inputs: { },
+            );
+            ::ipis::core::anyhow::Result::<()>::Ok(())
+        }
+    };
+
+    // only the root can update the primary account
+    assert!(set_account_primary(&client).await.is_err());
+
+    // rebind the client as the root
+    let client = client.with_account(server.account_me().unwrap()).unwrap();
+    assert_eq!(client.account_ref(), &server_ref);
+    set_account_primary(&client).await.unwrap();
+}
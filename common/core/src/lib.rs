@@ -0,0 +1,65 @@
+//! The wire definitions of IPIIS, without `std`.
+//!
+//! The signed payloads depend on `ipis`, so they are left to `ipiis-common`;
+//! this crate only describes how a message is framed on the wire,
+//! so that the constrained clients can offload the transport.
+
+#![no_std]
+
+pub const CLIENT_DUMMY: u8 = 42;
+
+::bitflags::bitflags! {
+    pub struct ServerResult: u8 {
+        const ACK = 0b10000000;
+        const OK = 0b01000000;
+        const ERR = 0b00100000;
+        /// The flag is followed by the server-side processing time (`u64` LE, in microseconds).
+        const TIMED = 0b00010000;
+        /// An intermediate frame followed by the progress (`processed`, `total`: `u64` LE),
+        /// which precedes the flag of the final result.
+        const PROGRESS = 0b00001000;
+        /// The request has been shed without being handled, and the flag is followed by
+        /// the suggested delay before re-sending it (`u64` LE, in milliseconds).
+        const RETRY_AFTER = 0b00000100;
+
+        const ACK_OK = Self::ACK.bits | Self::OK.bits;
+        const ACK_OK_TIMED = Self::ACK_OK.bits | Self::TIMED.bits;
+        const ACK_ERR = Self::ACK.bits | Self::ERR.bits;
+        const ACK_RETRY_AFTER = Self::ACK.bits | Self::RETRY_AFTER.bits;
+    }
+}
+
+/// The opcodes of the built-in requests, sent as a `u16` (little-endian).
+pub mod opcode {
+    pub const GET_ACCOUNT_PRIMARY: u16 = 0;
+    pub const SET_ACCOUNT_PRIMARY: u16 = 1;
+    pub const DELETE_ACCOUNT_PRIMARY: u16 = 2;
+    pub const GET_ADDRESS: u16 = 3;
+    pub const SET_ADDRESS: u16 = 4;
+    pub const DELETE_ADDRESS: u16 = 5;
+    pub const PING: u16 = 6;
+    pub const LIST_ACCOUNTS: u16 = 7;
+    pub const SNAPSHOT_PRIMARIES: u16 = 8;
+    pub const LIST_REVOKED_ACCOUNTS: u16 = 9;
+    pub const HELLO: u16 = 10;
+    pub const CAPABILITIES: u16 = 11;
+    pub const SINK: u16 = 12;
+
+    pub const fn to_bytes(opcode: u16) -> [u8; 2] {
+        opcode.to_le_bytes()
+    }
+
+    pub const fn from_bytes(bytes: [u8; 2]) -> u16 {
+        u16::from_le_bytes(bytes)
+    }
+}
+
+/// The application codes closing a connection, e.g. as a QUIC `VarInt`.
+///
+/// The other codes are left to the callers of `disconnect`.
+pub mod close_code {
+    pub const NORMAL: u32 = 0;
+    pub const SHUTTING_DOWN: u32 = 1;
+    pub const OVERLOADED: u32 = 2;
+    pub const PROTOCOL_ERROR: u32 = 3;
+}
@@ -0,0 +1,9 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+// Note that libFuzzer aborts on any panic, even if it is caught by the decoder,
+// so that the panicking inputs are reported as crashes.
+fuzz_target!(|data: &[u8]| {
+    let _ = ::ipiis_common::try_decode_request(data);
+});
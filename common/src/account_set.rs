@@ -0,0 +1,71 @@
+use bytecheck::CheckBytes;
+use ipis::core::account::AccountRef;
+use rkyv::{Archive, Deserialize, Serialize};
+
+/// A set of accounts, deduplicated and kept in the insertion order.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Archive, Serialize, Deserialize)]
+#[archive_attr(derive(CheckBytes, Debug))]
+pub struct AccountSet(Vec<AccountRef>);
+
+impl AccountSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds an account, returning whether it was newly inserted.
+    pub fn insert(&mut self, account: AccountRef) -> bool {
+        if self.0.contains(&account) {
+            false
+        } else {
+            self.0.push(account);
+            true
+        }
+    }
+
+    pub fn contains(&self, account: &AccountRef) -> bool {
+        self.0.contains(account)
+    }
+
+    pub fn into_vec(self) -> Vec<AccountRef> {
+        self.0
+    }
+}
+
+impl ::core::ops::Deref for AccountSet {
+    type Target = [AccountRef];
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl FromIterator<AccountRef> for AccountSet {
+    fn from_iter<T: IntoIterator<Item = AccountRef>>(iter: T) -> Self {
+        let mut set = Self::new();
+        for account in iter {
+            set.insert(account);
+        }
+        set
+    }
+}
+
+impl From<Vec<AccountRef>> for AccountSet {
+    fn from(accounts: Vec<AccountRef>) -> Self {
+        accounts.into_iter().collect()
+    }
+}
+
+impl From<AccountSet> for Vec<AccountRef> {
+    fn from(set: AccountSet) -> Self {
+        set.0
+    }
+}
+
+impl IntoIterator for AccountSet {
+    type Item = AccountRef;
+    type IntoIter = ::std::vec::IntoIter<AccountRef>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
@@ -0,0 +1,123 @@
+use core::{fmt, time::Duration};
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    time::Instant,
+};
+
+use ipis::{core::account::AccountRef, log::warn};
+
+/// A request which has failed the signature verification.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VerificationFailure {
+    /// The account claimed by the request, not verified
+    pub account: AccountRef,
+    pub opcode: &'static str,
+    /// Why the verification has failed, e.g. a bad signature, a wrong target or an expired one
+    pub reason: String,
+}
+
+/// A server-wide audit trail of the requests failing the signature verification,
+/// e.g. to detect the probing peers.
+///
+/// Every failure is counted, but at most `max_logs` of them are logged per `window`,
+/// so that a flood of the forged requests does not flood the logs.
+#[derive(Clone)]
+pub struct VerificationAudit {
+    state: Arc<Mutex<AuditState>>,
+    failures: Arc<AtomicU64>,
+    max_logs: u32,
+    window: Duration,
+    observer: Option<Arc<dyn Fn(&VerificationFailure) + Send + Sync>>,
+}
+
+impl fmt::Debug for VerificationAudit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("VerificationAudit")
+            .field("failures", &self.failures())
+            .field("max_logs", &self.max_logs)
+            .field("window", &self.window)
+            .finish()
+    }
+}
+
+#[derive(Debug)]
+struct AuditState {
+    /// The beginning of the current window
+    since: Instant,
+    /// The failures logged in the current window
+    logged: u32,
+    /// The failures not logged in the current window
+    suppressed: u64,
+}
+
+impl VerificationAudit {
+    pub fn new(max_logs: u32, window: Duration) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(AuditState {
+                since: Instant::now(),
+                logged: 0,
+                suppressed: 0,
+            })),
+            failures: Default::default(),
+            max_logs,
+            window,
+            observer: None,
+        }
+    }
+
+    /// Passes each logged failure to the callback as well, e.g. to forward it to a SIEM.
+    pub fn with_observer<F>(mut self, observer: F) -> Self
+    where
+        F: Fn(&VerificationFailure) + Send + Sync + 'static,
+    {
+        self.observer = Some(Arc::new(observer));
+        self
+    }
+
+    /// Returns the number of all the failures, including the ones not logged.
+    pub fn failures(&self) -> u64 {
+        self.failures.load(Ordering::Relaxed)
+    }
+
+    /// Counts the failure, logging it unless the limit of the window is reached.
+    pub fn report(&self, failure: VerificationFailure) {
+        self.failures.fetch_add(1, Ordering::Relaxed);
+
+        {
+            let mut state = self.state.lock().unwrap();
+
+            // begin a new window
+            if state.since.elapsed() >= self.window {
+                if state.suppressed > 0 {
+                    warn!(
+                        "suppressed {} verification failures in the last {:?}",
+                        state.suppressed, self.window,
+                    );
+                }
+                state.since = Instant::now();
+                state.logged = 0;
+                state.suppressed = 0;
+            }
+
+            if state.logged >= self.max_logs {
+                state.suppressed += 1;
+                return;
+            }
+            state.logged += 1;
+        }
+
+        let VerificationFailure {
+            account,
+            opcode,
+            reason,
+        } = &failure;
+        warn!("verification failed: account={account}, opcode={opcode}, reason={reason}");
+
+        if let Some(observer) = &self.observer {
+            observer(&failure)
+        }
+    }
+}
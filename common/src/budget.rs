@@ -0,0 +1,184 @@
+use core::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+use std::{io, sync::Arc};
+
+use ipis::tokio::{
+    io::{AsyncRead, ReadBuf},
+    sync::{AcquireError, OwnedSemaphorePermit, Semaphore},
+};
+
+/// The maximum number of bytes reserved by a single read.
+const CHUNK_SIZE: u32 = 64 * 1024;
+
+type Acquire = Pin<Box<dyn Future<Output = Result<OwnedSemaphorePermit, AcquireError>> + Send>>;
+
+/// A server-wide budget of the in-flight request bytes.
+///
+/// The bytes of a request are reserved before being read,
+/// and released when the request has been handled.
+#[derive(Clone, Debug)]
+pub struct RequestBudget {
+    semaphore: Arc<Semaphore>,
+    capacity: u32,
+}
+
+impl RequestBudget {
+    pub fn new(capacity: u32) -> Self {
+        Self {
+            semaphore: Arc::new(Semaphore::new(capacity as usize)),
+            capacity,
+        }
+    }
+
+    /// Returns the total number of bytes in the budget.
+    pub fn capacity(&self) -> u32 {
+        self.capacity
+    }
+
+    /// Returns the number of bytes not reserved by any request.
+    pub fn available(&self) -> usize {
+        self.semaphore.available_permits()
+    }
+}
+
+/// A reader reserving the [`RequestBudget`] for the bytes it reads.
+///
+/// The reserved bytes are released on drop.
+pub struct BudgetedReader<R> {
+    inner: R,
+    budget: Option<RequestBudget>,
+    /// Number of bytes reserved for the bytes read so far
+    held: u32,
+    /// Number of bytes reserved for the pending read
+    reserved: u32,
+    /// A pending reservation of the bytes, as `(held, want, acquire)`
+    acquiring: Option<(u32, u32, Acquire)>,
+}
+
+impl<R> BudgetedReader<R> {
+    /// Wraps the reader, passing it through if there is no budget.
+    pub fn new(inner: R, budget: Option<&RequestBudget>) -> Self {
+        Self {
+            inner,
+            budget: budget.cloned(),
+            held: 0,
+            reserved: 0,
+            acquiring: None,
+        }
+    }
+
+    fn poll_reserve(&mut self, cx: &mut Context<'_>, want: u32) -> Poll<io::Result<()>> {
+        let budget = match &self.budget {
+            Some(budget) => budget,
+            None => return Poll::Ready(Ok(())),
+        };
+
+        if self.acquiring.is_none() {
+            match budget.semaphore.clone().try_acquire_many_owned(want) {
+                Ok(permit) => {
+                    permit.forget();
+                    self.reserved = want;
+                    return Poll::Ready(Ok(()));
+                }
+                Err(_) => {
+                    // wait without holding any bytes,
+                    // so that the partially read requests cannot starve each other
+                    let held = ::core::mem::take(&mut self.held);
+                    budget.semaphore.add_permits(held as usize);
+
+                    let acquire = budget.semaphore.clone().acquire_many_owned(held + want);
+                    self.acquiring = Some((held, want, Box::pin(acquire)));
+                }
+            }
+        }
+
+        let (held, want, acquire) = self.acquiring.as_mut().unwrap();
+        match acquire.as_mut().poll(cx) {
+            Poll::Ready(Ok(permit)) => {
+                permit.forget();
+                self.held = *held;
+                self.reserved = *want;
+                self.acquiring = None;
+                Poll::Ready(Ok(()))
+            }
+            Poll::Ready(Err(e)) => {
+                self.acquiring = None;
+                Poll::Ready(Err(io::Error::new(io::ErrorKind::Other, e)))
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+
+    fn release(&mut self, count: u32) {
+        if let Some(budget) = &self.budget {
+            budget.semaphore.add_permits(count as usize);
+        }
+    }
+}
+
+impl<R> AsyncRead for BudgetedReader<R>
+where
+    R: AsyncRead + Unpin,
+{
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        if this.budget.is_none() || buf.remaining() == 0 {
+            return Pin::new(&mut this.inner).poll_read(cx, buf);
+        }
+
+        // reserve the bytes before reading
+        if this.reserved == 0 {
+            let capacity = this.budget.as_ref().map(|e| e.capacity).unwrap_or_default();
+            let room = capacity.saturating_sub(this.held);
+            if room == 0 {
+                return Poll::Ready(Err(io::Error::new(
+                    io::ErrorKind::Other,
+                    format!("request exceeds the in-flight budget: {capacity} bytes"),
+                )));
+            }
+
+            let want = buf.remaining().min(CHUNK_SIZE.min(room) as usize) as u32;
+            match this.poll_reserve(cx, want) {
+                Poll::Ready(Ok(())) => (),
+                Poll::Ready(Err(e)) => return Poll::Ready(Err(e)),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+
+        // read at most the reserved bytes
+        let limit = buf.remaining().min(this.reserved as usize);
+        let mut sub = buf.take(limit);
+        let result = Pin::new(&mut this.inner).poll_read(cx, &mut sub);
+        let len = sub.filled().len();
+
+        match result {
+            Poll::Ready(result) => {
+                // SAFETY: the bytes are initialized by the inner reader
+                unsafe { buf.assume_init(len) };
+                buf.advance(len);
+
+                // keep the bytes read, and release the rest
+                let reserved = ::core::mem::take(&mut this.reserved);
+                this.held += len as u32;
+                this.release(reserved - len as u32);
+
+                Poll::Ready(result)
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+impl<R> Drop for BudgetedReader<R> {
+    fn drop(&mut self) {
+        let count = self.held + self.reserved;
+        self.release(count);
+    }
+}
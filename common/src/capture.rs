@@ -0,0 +1,59 @@
+use core::fmt;
+use std::sync::Arc;
+
+use ipis::{
+    core::{account::AccountRef, anyhow::Result, value::hash::Hash},
+    tokio::io::AsyncWriteExt,
+};
+
+use crate::Ipiis;
+
+/// A callback observing the exact bytes of each request sent, e.g. to dump them for replay.
+///
+/// The captured requests are buffered before being sent, including their streamed inputs.
+#[derive(Clone)]
+pub struct WireCapture(Arc<dyn Fn(&[u8]) + Send + Sync>);
+
+impl fmt::Debug for WireCapture {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("WireCapture").finish()
+    }
+}
+
+impl WireCapture {
+    pub fn new<F>(callback: F) -> Self
+    where
+        F: Fn(&[u8]) + Send + Sync + 'static,
+    {
+        Self(Arc::new(callback))
+    }
+
+    /// Passes the bytes of a request to the callback.
+    pub fn capture(&self, bytes: &[u8]) {
+        (self.0)(bytes)
+    }
+}
+
+/// Re-sends the captured bytes of a request to the target as they are,
+/// returning the stream of its response, right after the result flag.
+///
+/// The request is still verified by the target, so it should have been signed for it.
+pub async fn replay_bytes<C>(
+    client: &C,
+    kind: Option<&Hash>,
+    target: &AccountRef,
+    bytes: &[u8],
+) -> Result<<C as Ipiis>::Reader>
+where
+    C: Ipiis,
+{
+    // make a connection
+    let (mut send, mut recv) = client.call_raw(kind, target).await?;
+
+    // send data
+    send.write_all(bytes).await?;
+
+    // recv flag
+    crate::recv_server_result(&mut recv).await?;
+    Ok(recv)
+}
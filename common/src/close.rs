@@ -0,0 +1,53 @@
+use core::fmt;
+
+use ipiis_common_core::close_code;
+
+/// Why the peer has closed the connection.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum CloseCode {
+    Normal,
+    /// The peer is shutting down, and may come back later
+    ShuttingDown,
+    /// The peer cannot afford the connection now
+    Overloaded,
+    /// The peer has received a malformed message
+    ProtocolError,
+}
+
+impl fmt::Display for CloseCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Normal => write!(f, "normal"),
+            Self::ShuttingDown => write!(f, "shutting down"),
+            Self::Overloaded => write!(f, "overloaded"),
+            Self::ProtocolError => write!(f, "protocol error"),
+        }
+    }
+}
+
+impl CloseCode {
+    pub const fn code(self) -> u32 {
+        match self {
+            Self::Normal => close_code::NORMAL,
+            Self::ShuttingDown => close_code::SHUTTING_DOWN,
+            Self::Overloaded => close_code::OVERLOADED,
+            Self::ProtocolError => close_code::PROTOCOL_ERROR,
+        }
+    }
+
+    /// Parses the received code, returning `None` for the codes given by the applications.
+    pub fn from_code(code: u64) -> Option<Self> {
+        match u32::try_from(code).ok()? {
+            close_code::NORMAL => Some(Self::Normal),
+            close_code::SHUTTING_DOWN => Some(Self::ShuttingDown),
+            close_code::OVERLOADED => Some(Self::Overloaded),
+            close_code::PROTOCOL_ERROR => Some(Self::ProtocolError),
+            _ => None,
+        }
+    }
+
+    /// Whether the peer may accept a new connection later.
+    pub fn is_retriable(self) -> bool {
+        matches!(self, Self::ShuttingDown | Self::Overloaded)
+    }
+}
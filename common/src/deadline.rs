@@ -0,0 +1,23 @@
+use core::{fmt, future::Future, time::Duration};
+
+use ipis::{core::anyhow::Result, tokio};
+
+use crate::IpiisError;
+
+/// Runs the handler of a request, failing with [`IpiisError::DeadlineExceeded`]
+/// if it takes longer than the timeout of its opcode.
+pub async fn with_deadline<F, T>(
+    opcode: impl fmt::Debug,
+    timeout: Option<Duration>,
+    handler: F,
+) -> Result<T>
+where
+    F: Future<Output = Result<T>>,
+{
+    match timeout {
+        Some(timeout) => tokio::time::timeout(timeout, handler)
+            .await
+            .map_err(|_| IpiisError::DeadlineExceeded(format!("{opcode:?} after {timeout:?}")))?,
+        None => handler.await,
+    }
+}
@@ -0,0 +1,92 @@
+use core::future::Future;
+use std::{io, panic::AssertUnwindSafe};
+
+use ipis::{
+    core::{
+        account::AccountRef,
+        anyhow::{bail, Error, Result},
+    },
+    futures::{executor::block_on, FutureExt},
+};
+
+use crate::{
+    io::{request, OpCode},
+    IpiisError,
+};
+
+/// A request of the directory, decoded from the untrusted bytes.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct DecodedRequest {
+    pub opcode: OpCode,
+    /// The account which has signed the request, not verified yet
+    pub account: AccountRef,
+}
+
+/// Decodes a request frame of the directory, validating all of its fields before use.
+///
+/// Any malformed input, even the one panicking while being validated,
+/// is rejected as [`IpiisError::BadRequest`].
+pub fn try_decode_request(bytes: &[u8]) -> Result<DecodedRequest> {
+    match ::std::panic::catch_unwind(|| block_on(decode_request(bytes))) {
+        Ok(Ok(request)) => Ok(request),
+        Ok(Err(e)) => Err(IpiisError::BadRequest(e.to_string()).into()),
+        Err(_) => Err(IpiisError::BadRequest("panicked while decoding".to_string()).into()),
+    }
+}
+
+async fn decode_request(mut bytes: &[u8]) -> Result<DecodedRequest> {
+    macro_rules! decode {
+        ( $request:ty ) => {{
+            let mut request = <$request>::decode(&mut bytes).await?;
+            request.__sign.as_ref().await?.metadata.guarantee
+        }};
+    }
+
+    let opcode = OpCode::recv(&mut bytes).await?;
+    let account = match opcode {
+        OpCode::GetAccountPrimary => decode!(request::GetAccountPrimary<'static, String>),
+        OpCode::SetAccountPrimary => decode!(request::SetAccountPrimary<'static>),
+        OpCode::DeleteAccountPrimary => decode!(request::DeleteAccountPrimary<'static>),
+        OpCode::GetAddress => decode!(request::GetAddress<'static, String>),
+        OpCode::SetAddress => decode!(request::SetAddress<'static, String>),
+        OpCode::DeleteAddress => decode!(request::DeleteAddress<'static>),
+        OpCode::Ping => decode!(request::Ping<'static>),
+        OpCode::ListAccounts => decode!(request::ListAccounts<'static>),
+        OpCode::SnapshotPrimaries => decode!(request::SnapshotPrimaries<'static, String>),
+        OpCode::ListRevokedAccounts => decode!(request::ListRevokedAccounts<'static>),
+        OpCode::Hello => decode!(request::Hello<'static, String>),
+        OpCode::Capabilities => decode!(request::Capabilities<'static>),
+        OpCode::Sink => decode!(request::Sink<'static>),
+    };
+
+    if !bytes.is_empty() {
+        bail!("trailing bytes after the request: {} bytes", bytes.len());
+    }
+    Ok(DecodedRequest { opcode, account })
+}
+
+/// Receives a request from the untrusted stream, rejecting the malformed one,
+/// even if it panics while being validated, as [`IpiisError::BadRequest`].
+///
+/// The network errors and the failed verifications, e.g. [`IpiisError::InvalidSignature`],
+/// are passed through.
+pub async fn try_recv_request<F, T>(recv: F) -> Result<T>
+where
+    F: Future<Output = Result<T>>,
+{
+    match AssertUnwindSafe(recv).catch_unwind().await {
+        Ok(Ok(request)) => Ok(request),
+        Ok(Err(e)) if is_malformed(&e) => Err(IpiisError::BadRequest(e.to_string()).into()),
+        Ok(Err(e)) => Err(e),
+        Err(_) => Err(IpiisError::BadRequest("panicked while decoding".to_string()).into()),
+    }
+}
+
+fn is_malformed(error: &Error) -> bool {
+    error.chain().all(|cause| {
+        cause.downcast_ref::<IpiisError>().is_none()
+            && cause
+                .downcast_ref::<io::Error>()
+                .map_or(true, |e| e.kind() == io::ErrorKind::InvalidData)
+    })
+}
@@ -0,0 +1,215 @@
+use core::{
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+use std::{
+    collections::{HashMap, VecDeque},
+    io,
+    sync::{Arc, Mutex},
+    time::Instant,
+};
+
+use ipis::{
+    core::account::AccountRef,
+    tokio::{
+        io::{AsyncRead, ReadBuf},
+        sync::oneshot,
+    },
+};
+use sha2::{Digest, Sha256};
+
+/// The id of a request supplied by the client, i.e. the SHA-256 digest of its signed bytes.
+///
+/// The signed bytes carry the nonce of the client,
+/// so a new request gets a new id, while a re-sent one keeps it.
+pub type RequestId = [u8; 32];
+
+type RequestKey = (AccountRef, u16, RequestId);
+
+/// A server-wide window of the recent responses, replayed to the duplicated requests.
+///
+/// A request is identified by its account, its opcode and its [`RequestId`],
+/// so that a client re-sending the same signed request after a lost response
+/// gets the original response, instead of executing the request twice.
+#[derive(Clone, Debug)]
+pub struct ResponseCache {
+    state: Arc<Mutex<CacheState>>,
+    window: Duration,
+    capacity: usize,
+}
+
+#[derive(Debug, Default)]
+struct CacheState {
+    entries: HashMap<RequestKey, CacheEntry>,
+    /// The completed requests, in the completion order
+    completed: VecDeque<(RequestKey, Instant)>,
+}
+
+#[derive(Debug)]
+enum CacheEntry {
+    /// The request is being handled, with the duplicated ones waiting for it
+    Pending(Vec<oneshot::Sender<()>>),
+    /// The response of the handled request
+    Completed(Arc<[u8]>),
+}
+
+impl ResponseCache {
+    pub fn new(window: Duration, capacity: usize) -> Self {
+        Self {
+            state: Default::default(),
+            window,
+            capacity: capacity.max(1),
+        }
+    }
+
+    /// Returns how long the responses are kept.
+    pub fn window(&self) -> Duration {
+        self.window
+    }
+
+    /// Returns the maximum number of the kept responses.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Looks up the response of the request,
+    /// waiting for the same request being handled.
+    pub async fn begin(
+        &self,
+        account: AccountRef,
+        opcode: u16,
+        request_id: RequestId,
+    ) -> CacheLookup {
+        let key = (account, opcode, request_id);
+        loop {
+            let pending = {
+                let mut state = self.state.lock().unwrap();
+                state.purge(self.window, self.capacity);
+
+                match state.entries.get_mut(&key) {
+                    Some(CacheEntry::Completed(response)) => {
+                        return CacheLookup::Replay(response.clone())
+                    }
+                    Some(CacheEntry::Pending(waiters)) => {
+                        let (tx, rx) = oneshot::channel();
+                        waiters.push(tx);
+                        rx
+                    }
+                    None => {
+                        state.entries.insert(key, CacheEntry::Pending(vec![]));
+                        return CacheLookup::Execute(ResponseSlot {
+                            cache: self.clone(),
+                            key,
+                            completed: false,
+                        });
+                    }
+                }
+            };
+
+            // the waiters are dropped when the request is handled or aborted
+            let _ = pending.await;
+        }
+    }
+}
+
+impl CacheState {
+    fn purge(&mut self, window: Duration, capacity: usize) {
+        while let Some((key, completed_at)) = self.completed.front() {
+            if self.completed.len() <= capacity && completed_at.elapsed() < window {
+                break;
+            }
+
+            self.entries.remove(key);
+            self.completed.pop_front();
+        }
+    }
+}
+
+/// The result of [`ResponseCache::begin`].
+#[derive(Debug)]
+pub enum CacheLookup {
+    /// The request has been already handled, so its response should be sent again
+    Replay(Arc<[u8]>),
+    /// The request should be handled, filling the slot with its response
+    Execute(ResponseSlot),
+}
+
+/// A reserved entry of the [`ResponseCache`], released on drop if not completed.
+#[derive(Debug)]
+pub struct ResponseSlot {
+    cache: ResponseCache,
+    key: RequestKey,
+    completed: bool,
+}
+
+impl ResponseSlot {
+    /// Stores the response, and wakes the duplicated requests to replay it.
+    pub fn complete(mut self, response: Arc<[u8]>) {
+        let ResponseCache {
+            state,
+            window,
+            capacity,
+        } = &self.cache;
+
+        let mut state = state.lock().unwrap();
+        state
+            .entries
+            .insert(self.key, CacheEntry::Completed(response));
+        state.completed.push_back((self.key, Instant::now()));
+        state.purge(*window, *capacity);
+        self.completed = true;
+    }
+}
+
+impl Drop for ResponseSlot {
+    fn drop(&mut self) {
+        // let the duplicated requests be handled again
+        if !self.completed {
+            self.cache.state.lock().unwrap().entries.remove(&self.key);
+        }
+    }
+}
+
+/// A reader digesting the bytes it reads, to identify a request by its [`RequestId`].
+pub struct FingerprintReader<R> {
+    inner: R,
+    hasher: Option<Sha256>,
+}
+
+impl<R> FingerprintReader<R> {
+    /// Wraps the reader, passing it through if not enabled.
+    pub fn new(inner: R, enabled: bool) -> Self {
+        Self {
+            inner,
+            hasher: enabled.then(Sha256::new),
+        }
+    }
+
+    /// Returns the id of the request read so far, if enabled.
+    pub fn fingerprint(&self) -> Option<RequestId> {
+        self.hasher
+            .as_ref()
+            .map(|hasher| hasher.clone().finalize().into())
+    }
+}
+
+impl<R> AsyncRead for FingerprintReader<R>
+where
+    R: AsyncRead + Unpin,
+{
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+
+        let offset = buf.filled().len();
+        let result = Pin::new(&mut this.inner).poll_read(cx, buf);
+        if let (Poll::Ready(Ok(())), Some(hasher)) = (&result, &mut this.hasher) {
+            hasher.update(&buf.filled()[offset..]);
+        }
+        result
+    }
+}
@@ -0,0 +1,88 @@
+use core::{fmt, time::Duration};
+
+use ipis::core::{account::AccountRef, value::hash::Hash};
+
+use crate::{ByteStats, Ipiis};
+
+/// A snapshot of the state of a client, e.g. to debug a node in production.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Diagnostics {
+    /// The name of the transport, e.g. `quic` or `tcp`
+    pub protocol: &'static str,
+    pub account: AccountRef,
+    /// The connections kept open to the peers, if the client pools them
+    pub connections: Vec<ConnectionDiagnostics>,
+    /// Number of the entries in the address book, if the client has one
+    pub book_entries: Option<usize>,
+    /// Number of the outgoing requests not completed yet
+    pub in_flight_requests: usize,
+    /// The bytes transferred by all the accounts, if the server meters them
+    pub bytes: ByteStats,
+}
+
+/// A connection kept open to a peer.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConnectionDiagnostics {
+    pub kind: Option<Hash>,
+    pub account: AccountRef,
+    pub address: String,
+    /// The round-trip time estimated by the transport
+    pub rtt: Duration,
+}
+
+impl Diagnostics {
+    /// Collects the state shared by all the transports,
+    /// leaving the connections and the address book to be filled by the client.
+    pub fn new<C>(client: &C) -> Self
+    where
+        C: Ipiis + ?Sized,
+    {
+        Self {
+            protocol: client.protocol(),
+            account: *client.account_ref(),
+            connections: Default::default(),
+            book_entries: None,
+            in_flight_requests: client
+                .request_registry()
+                .map(|registry| registry.in_flight().len())
+                .unwrap_or_default(),
+            bytes: client
+                .byte_meter()
+                .map(|meter| meter.total())
+                .unwrap_or_default(),
+        }
+    }
+}
+
+impl fmt::Display for Diagnostics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "protocol: {}", self.protocol)?;
+        writeln!(f, "account: {}", self.account)?;
+        match self.book_entries {
+            Some(entries) => writeln!(f, "book entries: {entries}")?,
+            None => writeln!(f, "book entries: -")?,
+        }
+        writeln!(f, "in-flight requests: {}", self.in_flight_requests)?;
+        writeln!(
+            f,
+            "bytes: in={}, out={}",
+            self.bytes.bytes_in, self.bytes.bytes_out,
+        )?;
+
+        writeln!(f, "connections: {}", self.connections.len())?;
+        for ConnectionDiagnostics {
+            kind,
+            account,
+            address,
+            rtt,
+        } in &self.connections
+        {
+            match kind {
+                Some(kind) => write!(f, "  {kind}/")?,
+                None => write!(f, "  ")?,
+            }
+            writeln!(f, "{account} @ {address}: rtt={rtt:?}")?;
+        }
+        Ok(())
+    }
+}
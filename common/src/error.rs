@@ -0,0 +1,160 @@
+use core::time::Duration;
+use std::io;
+
+use ipis::{core::anyhow::Error, tokio::time::error::Elapsed};
+use thiserror::Error;
+
+use crate::CloseCode;
+
+#[derive(Debug, Error)]
+pub enum IpiisError {
+    #[error("internal error: {0}")]
+    Remote(String),
+    #[error("the request was sent, but not acknowledged: {0}")]
+    Unacknowledged(String),
+    #[error("unknown opcode: {0}")]
+    UnknownOpcode(u16),
+    #[error("refused to connect to itself: {0}")]
+    SelfConnection(String),
+    #[error("not authorized: {0}")]
+    Unauthorized(String),
+    #[error("request cancelled: {0}")]
+    Cancelled(u64),
+    #[error("not found: {0}")]
+    NotFound(String),
+    #[error("revoked account: {0}")]
+    Revoked(String),
+    #[error("quota exceeded: {0}")]
+    QuotaExceeded(String),
+    #[error("bad request: {0}")]
+    BadRequest(String),
+    #[error("overloaded: retry after {0:?}")]
+    RetryAfter(Duration),
+    #[error("kind resolution too deep: {0}")]
+    KindResolutionTooDeep(String),
+    #[error("connection closed by the peer: {0}")]
+    ConnectionClosed(CloseCode),
+    #[error("timed out connecting to {0}")]
+    ConnectTimeout(String),
+    #[error("deadline exceeded: {0}")]
+    DeadlineExceeded(String),
+    #[error("the address of the primary account is unknown: {0}")]
+    PrimaryAddressUnknown(String),
+    #[error("stale request: {0}")]
+    StaleRequest(String),
+    #[error("invalid signature: {0}")]
+    InvalidSignature(String),
+}
+
+/// Whether the error is caused by an elapsed deadline.
+pub fn is_timeout(error: &Error) -> bool {
+    matches!(
+        find(error),
+        Some(IpiisError::ConnectTimeout(_) | IpiisError::DeadlineExceeded(_)),
+    ) || is_remote(error, IpiisError::DeadlineExceeded(Default::default()))
+        || error.chain().any(|cause| {
+            cause.is::<Elapsed>()
+                || matches!(
+                    cause.downcast_ref::<io::Error>().map(io::Error::kind),
+                    Some(io::ErrorKind::TimedOut),
+                )
+        })
+}
+
+/// Whether the account is not allowed to issue the request, locally or by the remote.
+///
+/// The revoked accounts are not allowed as well.
+pub fn is_unauthorized(error: &Error) -> bool {
+    matches!(
+        find(error),
+        Some(IpiisError::Unauthorized(_) | IpiisError::Revoked(_)),
+    ) || is_remote(error, IpiisError::Unauthorized(Default::default()))
+        || is_remote(error, IpiisError::Revoked(Default::default()))
+}
+
+/// Whether the request has been rejected as signed too long ago or in the future,
+/// locally or by the remote.
+pub fn is_stale_request(error: &Error) -> bool {
+    matches!(find(error), Some(IpiisError::StaleRequest(_)))
+        || is_remote(error, IpiisError::StaleRequest(Default::default()))
+}
+
+/// Whether the request has been rejected as its signature is not valid for the receiver,
+/// locally or by the remote, e.g. forged or signed for another account.
+pub fn is_invalid_signature(error: &Error) -> bool {
+    matches!(find(error), Some(IpiisError::InvalidSignature(_)))
+        || is_remote(error, IpiisError::InvalidSignature(Default::default()))
+}
+
+/// Whether the account has transferred its quota, locally or in the remote.
+pub fn is_quota_exceeded(error: &Error) -> bool {
+    matches!(find(error), Some(IpiisError::QuotaExceeded(_)))
+        || is_remote(error, IpiisError::QuotaExceeded(Default::default()))
+}
+
+/// Whether the requested entry does not exist, locally or in the remote.
+pub fn is_not_found(error: &Error) -> bool {
+    matches!(find(error), Some(IpiisError::NotFound(_)))
+        || is_remote(error, IpiisError::NotFound(Default::default()))
+}
+
+/// Whether the lookup of the kind's primary account has been forwarded too deeply,
+/// locally or in the remote.
+pub fn is_kind_resolution_too_deep(error: &Error) -> bool {
+    matches!(find(error), Some(IpiisError::KindResolutionTooDeep(_)))
+        || is_remote(error, IpiisError::KindResolutionTooDeep(Default::default()))
+}
+
+/// Whether the connection to the target has failed or been lost.
+///
+/// Note that the timeouts are classified by [`is_timeout`] instead.
+pub fn is_connection_error(error: &Error) -> bool {
+    matches!(
+        find(error),
+        Some(IpiisError::Unacknowledged(_) | IpiisError::ConnectionClosed(_)),
+    ) || error.chain().any(|cause| {
+        matches!(
+            cause.downcast_ref::<io::Error>().map(io::Error::kind),
+            Some(
+                io::ErrorKind::ConnectionRefused
+                    | io::ErrorKind::ConnectionReset
+                    | io::ErrorKind::ConnectionAborted
+                    | io::ErrorKind::NotConnected
+                    | io::ErrorKind::AddrNotAvailable
+                    | io::ErrorKind::BrokenPipe
+                    | io::ErrorKind::UnexpectedEof
+            ),
+        )
+    })
+}
+
+/// Returns why the peer has closed the connection, if it has told so.
+pub fn close_code_of(error: &Error) -> Option<CloseCode> {
+    match find(error) {
+        Some(IpiisError::ConnectionClosed(code)) => Some(*code),
+        _ => None,
+    }
+}
+
+fn find(error: &Error) -> Option<&IpiisError> {
+    error.chain().find_map(|cause| cause.downcast_ref())
+}
+
+/// Whether the error is sent by the remote as the given kind.
+///
+/// The remote errors are sent as messages, so they are matched by the prefix of the kind,
+/// even if forwarded by the other remotes.
+fn is_remote(error: &Error, kind: IpiisError) -> bool {
+    match find(error) {
+        Some(IpiisError::Remote(message)) => {
+            let prefix = IpiisError::Remote(Default::default()).to_string();
+
+            let mut message = message.as_str();
+            while let Some(inner) = message.strip_prefix(&prefix) {
+                message = inner;
+            }
+            message.starts_with(&kind.to_string())
+        }
+        _ => false,
+    }
+}
@@ -0,0 +1,13 @@
+use ipis::{async_trait::async_trait, core::anyhow::Result};
+
+/// A request or a response of an opcode defined by [`define_io!`](crate::define_io),
+/// which can be written to the bytes on the wire and read back.
+#[async_trait]
+pub trait IoFrame: Sized + Send {
+    /// Writes the frame as it is sent on the wire, including its opcode or result flag.
+    async fn to_frame(&mut self) -> Result<Vec<u8>>;
+
+    /// Reads the frame from the untrusted bytes, validating all of its fields
+    /// but without verifying its sign.
+    async fn from_frame(bytes: &[u8]) -> Result<Self>;
+}
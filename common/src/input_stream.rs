@@ -0,0 +1,139 @@
+use core::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+use std::io;
+
+use ipis::{
+    core::anyhow::Result,
+    tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, ReadBuf},
+};
+
+/// The default maximum number of bytes in a chunk of the streamed inputs.
+pub const DEFAULT_CHUNK_SIZE: u32 = 64 * 1024;
+
+/// The streamed input of a request, consumed incrementally by the handler
+/// rather than being buffered before it runs, e.g. a large file.
+///
+/// On the wire, it follows the other inputs as the chunks prefixed by their lengths
+/// (`u32`, little-endian), terminated by an empty chunk.
+pub struct InputStream<R> {
+    inner: R,
+    /// The length of the chunk being read
+    header: [u8; 4],
+    /// Number of bytes of the length read so far
+    header_read: usize,
+    /// Number of bytes left in the current chunk
+    remaining: u32,
+    /// Whether the terminating chunk has been read
+    finished: bool,
+}
+
+impl<R> InputStream<R> {
+    pub fn new(inner: R) -> Self {
+        Self {
+            inner,
+            header: Default::default(),
+            header_read: 0,
+            remaining: 0,
+            finished: false,
+        }
+    }
+
+    /// Whether the whole input has been consumed.
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+}
+
+impl<R> InputStream<R>
+where
+    R: AsyncRead + Unpin,
+{
+    /// Discards the rest of the input, returning the number of bytes discarded.
+    pub async fn drain(&mut self) -> Result<u64> {
+        ::ipis::tokio::io::copy(self, &mut ::ipis::tokio::io::sink())
+            .await
+            .map_err(Into::into)
+    }
+}
+
+impl<R> AsyncRead for InputStream<R>
+where
+    R: AsyncRead + Unpin,
+{
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+
+        loop {
+            if this.finished || buf.remaining() == 0 {
+                return Poll::Ready(Ok(()));
+            }
+
+            // read the body of the current chunk
+            if this.remaining > 0 {
+                let max = buf.remaining().min(this.remaining as usize);
+                let mut chunk = ReadBuf::new(&mut buf.initialize_unfilled_to(max)[..max]);
+                match Pin::new(&mut this.inner).poll_read(cx, &mut chunk) {
+                    Poll::Ready(Ok(())) => {}
+                    Poll::Ready(Err(e)) => return Poll::Ready(Err(e)),
+                    Poll::Pending => return Poll::Pending,
+                }
+
+                let len = chunk.filled().len();
+                if len == 0 {
+                    return Poll::Ready(Err(io::ErrorKind::UnexpectedEof.into()));
+                }
+                buf.advance(len);
+                this.remaining -= len as u32;
+                return Poll::Ready(Ok(()));
+            }
+
+            // read the length of the next chunk
+            let mut header = ReadBuf::new(&mut this.header[this.header_read..]);
+            match Pin::new(&mut this.inner).poll_read(cx, &mut header) {
+                Poll::Ready(Ok(())) => {}
+                Poll::Ready(Err(e)) => return Poll::Ready(Err(e)),
+                Poll::Pending => return Poll::Pending,
+            }
+
+            let len = header.filled().len();
+            if len == 0 {
+                return Poll::Ready(Err(io::ErrorKind::UnexpectedEof.into()));
+            }
+            this.header_read += len;
+            if this.header_read == this.header.len() {
+                this.header_read = 0;
+                this.remaining = u32::from_le_bytes(this.header);
+                this.finished = this.remaining == 0;
+            }
+        }
+    }
+}
+
+/// Sends the reader as a streamed input, in the chunks of at most `chunk_size` bytes,
+/// returning the number of bytes sent.
+pub async fn copy_input_stream<R, W>(mut reader: R, send: &mut W, chunk_size: u32) -> Result<u64>
+where
+    R: AsyncRead + Unpin,
+    W: AsyncWrite + Unpin,
+{
+    let mut buf = vec![0; chunk_size.max(1) as usize];
+    let mut total = 0;
+
+    loop {
+        let len = reader.read(&mut buf).await?;
+
+        // send the chunk, or the terminating one
+        send.write_u32_le(len as u32).await?;
+        if len == 0 {
+            break Ok(total);
+        }
+        send.write_all(&buf[..len]).await?;
+        total += len as u64;
+    }
+}
@@ -1,17 +1,90 @@
+use core::time::Duration;
+
 use ipis::{
     async_trait::async_trait,
     core::{
         account::{Account, AccountRef, GuaranteeSigned, GuarantorSigned},
-        anyhow::Result,
+        anyhow::{bail, Error, Result},
         data::Data,
         signature::SignatureSerializer,
         signed::IsSigned,
         value::hash::Hash,
     },
-    tokio::io::{AsyncRead, AsyncWrite},
+    stream::DynStream,
+    tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite},
 };
 use rkyv::{Archive, Serialize};
 
+mod account_set;
+mod audit;
+mod budget;
+mod capture;
+mod close;
+mod deadline;
+mod decode;
+mod dedup;
+mod diagnostics;
+mod error;
+mod frame;
+mod input_stream;
+mod output_stream;
+mod ping;
+mod prepared;
+mod progress;
+mod raw;
+mod registry;
+mod resolution;
+mod retry_budget;
+mod revocation;
+pub mod ring;
+mod scheduler;
+mod scoped;
+mod sealed;
+mod time_policy;
+mod transport_identity;
+mod usage;
+
+#[cfg(feature = "test-util")]
+pub mod test_util;
+
+pub use ipiis_common_core::{close_code, opcode, ServerResult, CLIENT_DUMMY};
+
+pub use self::account_set::AccountSet;
+pub use self::audit::{VerificationAudit, VerificationFailure};
+pub use self::budget::{BudgetedReader, RequestBudget};
+pub use self::capture::{replay_bytes, WireCapture};
+pub use self::close::CloseCode;
+pub use self::deadline::with_deadline;
+pub use self::decode::{try_decode_request, try_recv_request, DecodedRequest};
+pub use self::dedup::{CacheLookup, FingerprintReader, RequestId, ResponseCache, ResponseSlot};
+pub use self::diagnostics::{ConnectionDiagnostics, Diagnostics};
+pub use self::error::{
+    close_code_of, is_connection_error, is_invalid_signature, is_kind_resolution_too_deep,
+    is_not_found, is_quota_exceeded, is_stale_request, is_timeout, is_unauthorized, IpiisError,
+};
+pub use self::frame::IoFrame;
+pub use self::input_stream::{copy_input_stream, InputStream, DEFAULT_CHUNK_SIZE};
+pub use self::output_stream::{copy_output_stream, recv_output_stream};
+pub use self::ping::{
+    bench_resolve, ping_flood, BandwidthReport, HopInfo, Nonce, PingFloodReport, PingReport,
+    ResolveReport, MAX_HOPS,
+};
+pub use self::prepared::{prepare_signed_request, send_prepared, SignedRequestBytes};
+pub use self::progress::{forward_progress, on_progress, report_progress, Progress};
+pub use self::raw::{recv_raw_request_header, send_raw_request_header, RawHandlers, RawReader};
+pub use self::registry::{RequestHandle, RequestRegistration, RequestRegistry};
+pub use self::resolution::{
+    next_resolution_depth, with_resolution_depth, DEFAULT_MAX_RESOLUTION_DEPTH,
+};
+pub use self::retry_budget::RetryBudget;
+pub use self::revocation::RevocationList;
+pub use self::scheduler::{RequestPermit, RequestScheduler};
+pub use self::scoped::ScopedClient;
+pub use self::sealed::Sealed;
+pub use self::time_policy::TimePolicy;
+pub use self::transport_identity::TransportIdentity;
+pub use self::usage::{ByteMeter, ByteStats, MeteredRequest, MeteredStream};
+
 #[async_trait]
 pub trait Ipiis {
     type Address: IsSigned + Send + Sync;
@@ -28,6 +101,12 @@ pub trait Ipiis {
 
     fn account_ref(&self) -> &AccountRef;
 
+    /// Returns the public account of the client,
+    /// e.g. for logging or comparison, without touching the secret key.
+    fn account_me_ref(&self) -> AccountRef {
+        *self.account_ref()
+    }
+
     async fn get_account_primary(&self, kind: Option<&Hash>) -> Result<AccountRef>;
 
     async fn set_account_primary(&self, kind: Option<&Hash>, account: &AccountRef) -> Result<()>;
@@ -47,8 +126,33 @@ pub trait Ipiis {
         address: &<Self as Ipiis>::Address,
     ) -> Result<()>;
 
+    /// Sets the address and then re-reads it from the root,
+    /// failing if the root has stored a different one.
+    ///
+    /// Only the root clients can verify it; the others fail without setting it.
+    async fn set_address_verified(
+        &self,
+        kind: Option<&Hash>,
+        target: &AccountRef,
+        address: &<Self as Ipiis>::Address,
+    ) -> Result<()>;
+
     async fn delete_address(&self, kind: Option<&Hash>, target: &AccountRef) -> Result<()>;
 
+    /// Lists the accounts having an address of the given kind.
+    ///
+    /// The root only answers the accounts approved by its authorizer.
+    async fn list_accounts(&self, kind: Option<&Hash>) -> Result<Vec<AccountRef>>;
+
+    /// Wraps the client to operate within the given kind,
+    /// sharing the connections and the address book.
+    fn scoped(&self, kind: Hash) -> ScopedClient<Self>
+    where
+        Self: Clone + Sized,
+    {
+        ScopedClient::new(self.clone(), kind)
+    }
+
     fn sign<'a, T>(&self, target: AccountRef, msg: &'a T) -> Result<Data<GuaranteeSigned, &'a T>>
     where
         T: Archive + Serialize<SignatureSerializer> + IsSigned,
@@ -65,6 +169,34 @@ pub trait Ipiis {
         Data::builder().build_owned(unsafe { self.account_me() }?, target, msg)
     }
 
+    /// Signs the message as the given account instead of `account_me()`.
+    fn sign_as<'a, T>(
+        &self,
+        me: &Account,
+        target: AccountRef,
+        msg: &'a T,
+    ) -> Result<Data<GuaranteeSigned, &'a T>>
+    where
+        T: Archive + Serialize<SignatureSerializer> + IsSigned,
+        <T as Archive>::Archived: ::core::fmt::Debug + PartialEq,
+    {
+        Data::builder().build(me, target, msg)
+    }
+
+    /// Signs the message as the given account instead of `account_me()`.
+    fn sign_owned_as<T>(
+        &self,
+        me: &Account,
+        target: AccountRef,
+        msg: T,
+    ) -> Result<Data<GuaranteeSigned, T>>
+    where
+        T: Archive + Serialize<SignatureSerializer> + IsSigned,
+        <T as Archive>::Archived: ::core::fmt::Debug + PartialEq,
+    {
+        Data::builder().build_owned(me, target, msg)
+    }
+
     fn sign_as_guarantor<T>(
         &self,
         msg: Data<GuaranteeSigned, T>,
@@ -75,13 +207,166 @@ pub trait Ipiis {
         msg.sign(unsafe { self.account_me() }?)
     }
 
-    fn protocol(&self) -> Result<String>;
+    /// Opens the payload sealed to this account by [`Sealed::seal`],
+    /// e.g. relayed by the other peers.
+    fn unseal(&self, sealed: &Sealed) -> Result<Vec<u8>> {
+        sealed.open(unsafe { self.account_me() }?)
+    }
+
+    /// Returns the name of the transport, e.g. `quic` or `tcp`.
+    fn protocol(&self) -> &'static str;
+
+    /// Returns the budget of the in-flight request bytes, if the server has one.
+    fn request_budget(&self) -> Option<&RequestBudget> {
+        None
+    }
+
+    /// Returns the scheduler of the concurrent requests, if the server has one.
+    fn request_scheduler(&self) -> Option<&RequestScheduler> {
+        None
+    }
+
+    /// Returns the registry of the outgoing requests, if the client has one.
+    fn request_registry(&self) -> Option<&RequestRegistry> {
+        None
+    }
+
+    /// Returns the budget of the retries shared by all the retry sites, if the client has one.
+    fn retry_budget(&self) -> Option<&RetryBudget> {
+        None
+    }
+
+    /// Returns the window of the recent responses, if the server has one.
+    fn response_cache(&self) -> Option<&ResponseCache> {
+        None
+    }
+
+    /// Returns the list of the revoked accounts, if the server has one.
+    fn revocation_list(&self) -> Option<&RevocationList> {
+        None
+    }
+
+    /// Returns the accounting of the transferred bytes, if the server has one.
+    fn byte_meter(&self) -> Option<&ByteMeter> {
+        None
+    }
+
+    /// Whether the server reports its processing time in the responses.
+    fn reports_server_time(&self) -> bool {
+        false
+    }
+
+    /// Returns the capture of the sent requests, if the client has one.
+    fn wire_capture(&self) -> Option<&WireCapture> {
+        None
+    }
+
+    /// Returns the audit trail of the failed verifications, if the server has one.
+    fn verification_audit(&self) -> Option<&VerificationAudit> {
+        None
+    }
+
+    /// Returns the handlers of the raw requests registered at runtime, if the server has them.
+    fn raw_handlers(&self) -> Option<&RawHandlers> {
+        None
+    }
+
+    /// Returns the time-sensitive behaviors, e.g. the tolerance of the clock skew.
+    fn time_policy(&self) -> &TimePolicy {
+        &TimePolicy::DEFAULT
+    }
+
+    /// Returns a snapshot of the state of the client,
+    /// e.g. the pooled connections and the size of the address book.
+    async fn diagnostics(&self) -> Result<Diagnostics> {
+        Ok(Diagnostics::new(self))
+    }
+
+    /// Sends a signed nonce to the target, and checks whether the target
+    /// has signed the same nonce back with its own key.
+    async fn ping(&self, kind: Option<&Hash>, target: &AccountRef) -> Result<PingReport>
+    where
+        Self: Sized,
+    {
+        use ipis::core::account::Verifier;
+
+        let nonce = Nonce::generate();
+        let instant = ::std::time::Instant::now();
+
+        // external call
+        let mut req = external_call!(
+            client: self,
+            target: kind => target,
+            request: crate::io => Ping,
+            sign: self.sign_owned(*target, nonce)?,
+            inputs: { },
+            outputs: none,
+        );
+        let (mut recv, server_time) = req.send_timed(self, kind, target).await?;
+
+        // recv sign
+        let sign: Data<GuarantorSigned, Nonce> =
+            DynStream::recv(&mut recv).await?.into_owned().await?;
+        let rtt = instant.elapsed();
+
+        // verify data
+        let identity_confirmed = sign.verify(Some(target)).is_ok() && sign.data == nonce;
+
+        Ok(PingReport {
+            rtt,
+            server_time,
+            identity_confirmed,
+        })
+    }
+
+    /// Measures the raw throughput of the connection to the target,
+    /// sending the given number of bytes to be discarded by the target.
+    ///
+    /// The bytes are streamed without the application framing,
+    /// so it checks the health of the transport rather than the handlers.
+    async fn benchmark_connection(
+        &self,
+        kind: Option<&Hash>,
+        target: &AccountRef,
+        bytes: usize,
+    ) -> Result<BandwidthReport>
+    where
+        Self: Sized,
+    {
+        // measure the round-trip time first
+        let PingReport { rtt, .. } = self.ping(kind, target).await?;
+
+        // blast the bytes
+        let bytes = bytes as u64;
+        let instant = ::std::time::Instant::now();
+        let (received,) = external_call!(
+            client: self,
+            target: kind => target,
+            request: crate::io => Sink,
+            sign: self.sign_owned(*target, bytes)?,
+            inputs: { },
+            input_stream: ::ipis::tokio::io::repeat(0).take(bytes),
+            outputs: { received, },
+        );
+        let elapsed = instant.elapsed();
+
+        if received != bytes {
+            bail!("the target has received {received} of {bytes} bytes: {target}");
+        }
+        Ok(BandwidthReport::new(bytes, elapsed, rtt))
+    }
 
     async fn call_raw(
         &self,
         kind: Option<&Hash>,
         target: &AccountRef,
     ) -> Result<(<Self as Ipiis>::Writer, <Self as Ipiis>::Reader)>;
+
+    /// Closes the connections to the target, notifying it of the application-defined reason.
+    ///
+    /// The reasons of [`CloseCode`] are understood by the peers,
+    /// e.g. `CloseCode::Normal.code()`.
+    async fn disconnect(&self, target: &AccountRef, reason: u32) -> Result<()>;
 }
 
 #[async_trait]
@@ -132,10 +417,23 @@ where
         (**self).set_address(kind, target, address).await
     }
 
+    async fn set_address_verified(
+        &self,
+        kind: Option<&Hash>,
+        target: &AccountRef,
+        address: &<Self as Ipiis>::Address,
+    ) -> Result<()> {
+        (**self).set_address_verified(kind, target, address).await
+    }
+
     async fn delete_address(&self, kind: Option<&Hash>, target: &AccountRef) -> Result<()> {
         (**self).delete_address(kind, target).await
     }
 
+    async fn list_accounts(&self, kind: Option<&Hash>) -> Result<Vec<AccountRef>> {
+        (**self).list_accounts(kind).await
+    }
+
     fn sign<'a, T>(&self, target: AccountRef, msg: &'a T) -> Result<Data<GuaranteeSigned, &'a T>>
     where
         T: Archive + Serialize<SignatureSerializer> + IsSigned,
@@ -152,6 +450,32 @@ where
         (**self).sign_owned(target, msg)
     }
 
+    fn sign_as<'a, T>(
+        &self,
+        me: &Account,
+        target: AccountRef,
+        msg: &'a T,
+    ) -> Result<Data<GuaranteeSigned, &'a T>>
+    where
+        T: Archive + Serialize<SignatureSerializer> + IsSigned,
+        <T as Archive>::Archived: ::core::fmt::Debug + PartialEq,
+    {
+        (**self).sign_as(me, target, msg)
+    }
+
+    fn sign_owned_as<T>(
+        &self,
+        me: &Account,
+        target: AccountRef,
+        msg: T,
+    ) -> Result<Data<GuaranteeSigned, T>>
+    where
+        T: Archive + Serialize<SignatureSerializer> + IsSigned,
+        <T as Archive>::Archived: ::core::fmt::Debug + PartialEq,
+    {
+        (**self).sign_owned_as(me, target, msg)
+    }
+
     fn sign_as_guarantor<T>(
         &self,
         msg: Data<GuaranteeSigned, T>,
@@ -162,10 +486,75 @@ where
         (**self).sign_as_guarantor(msg)
     }
 
-    fn protocol(&self) -> Result<String> {
+    fn protocol(&self) -> &'static str {
         (**self).protocol()
     }
 
+    fn request_budget(&self) -> Option<&RequestBudget> {
+        (**self).request_budget()
+    }
+
+    fn request_scheduler(&self) -> Option<&RequestScheduler> {
+        (**self).request_scheduler()
+    }
+
+    fn request_registry(&self) -> Option<&RequestRegistry> {
+        (**self).request_registry()
+    }
+
+    fn retry_budget(&self) -> Option<&RetryBudget> {
+        (**self).retry_budget()
+    }
+
+    fn response_cache(&self) -> Option<&ResponseCache> {
+        (**self).response_cache()
+    }
+
+    fn revocation_list(&self) -> Option<&RevocationList> {
+        (**self).revocation_list()
+    }
+
+    fn byte_meter(&self) -> Option<&ByteMeter> {
+        (**self).byte_meter()
+    }
+
+    fn reports_server_time(&self) -> bool {
+        (**self).reports_server_time()
+    }
+
+    fn wire_capture(&self) -> Option<&WireCapture> {
+        (**self).wire_capture()
+    }
+
+    fn verification_audit(&self) -> Option<&VerificationAudit> {
+        (**self).verification_audit()
+    }
+
+    fn raw_handlers(&self) -> Option<&RawHandlers> {
+        (**self).raw_handlers()
+    }
+
+    fn time_policy(&self) -> &TimePolicy {
+        (**self).time_policy()
+    }
+
+    async fn diagnostics(&self) -> Result<Diagnostics> {
+        (**self).diagnostics().await
+    }
+
+    async fn ping(&self, kind: Option<&Hash>, target: &AccountRef) -> Result<PingReport> {
+        (**self).ping(kind, target).await
+    }
+
+    async fn benchmark_connection(
+        &self,
+        kind: Option<&Hash>,
+        target: &AccountRef,
+        bytes: usize,
+    ) -> Result<BandwidthReport> {
+        (**self).benchmark_connection(kind, target, bytes).await
+    }
+
     async fn call_raw(
         &self,
         kind: Option<&Hash>,
@@ -173,75 +562,236 @@ where
     ) -> Result<(<Self as Ipiis>::Writer, <Self as Ipiis>::Reader)> {
         (**self).call_raw(kind, target).await
     }
+
+    async fn disconnect(&self, target: &AccountRef, reason: u32) -> Result<()> {
+        (**self).disconnect(target, reason).await
+    }
+}
+
+/// The maximum number of re-sending a request shed by the server,
+/// waiting for the delay suggested by [`ServerResult::RETRY_AFTER`].
+pub const MAX_SHED_RETRIES: u32 = 3;
+
+/// Receives the result flag of a response,
+/// converting the server-side error into `Err`.
+pub async fn recv_server_result<R>(recv: R) -> Result<()>
+where
+    R: AsyncRead + Unpin,
+{
+    recv_server_result_timed(recv).await.map(|_| ())
+}
+
+/// Receives the result flag of a response like [`recv_server_result`],
+/// returning the server-side processing time if the server has reported it.
+pub async fn recv_server_result_timed<R>(mut recv: R) -> Result<Option<Duration>>
+where
+    R: AsyncRead + Unpin,
+{
+    loop {
+        match recv.read_u8().await.map(ServerResult::from_bits) {
+            // parse the progress
+            Ok(Some(ServerResult::PROGRESS)) => {
+                let processed = recv.read_u64_le().await.map_err(network_error)?;
+                let total = recv.read_u64_le().await.map_err(network_error)?;
+                self::progress::notify_progress(Progress { processed, total });
+            }
+            flag => break recv_server_result_flag(recv, flag).await,
+        }
+    }
 }
 
-pub const CLIENT_DUMMY: u8 = 42;
-::ipis::bitflags::bitflags! {
+async fn recv_server_result_flag<R>(
+    mut recv: R,
+    flag: ::std::io::Result<Option<ServerResult>>,
+) -> Result<Option<Duration>>
+where
+    R: AsyncRead + Unpin,
+{
+    match flag {
+        // parse the data
+        Ok(Some(ServerResult::ACK_OK)) => Ok(None),
+        Ok(Some(ServerResult::ACK_OK_TIMED)) => match recv.read_u64_le().await {
+            Ok(server_time_us) => Ok(Some(Duration::from_micros(server_time_us))),
+            Err(e) => Err(network_error(e)),
+        },
+        // parse the suggested delay of the shed request
+        Ok(Some(ServerResult::ACK_RETRY_AFTER)) => match recv.read_u64_le().await {
+            Ok(delay_ms) => Err(IpiisError::RetryAfter(Duration::from_millis(delay_ms)).into()),
+            Err(e) => Err(network_error(e)),
+        },
+        // parse the error
+        Ok(Some(ServerResult::ACK_ERR)) => {
+            // recv data
+            let res: String = DynStream::recv(&mut recv).await?.to_owned().await?;
 
-    pub struct ServerResult: u8 {
-        const ACK = 0b10000000;
-        const OK = 0b01000000;
-        const ERR = 0b00100000;
+            // TODO: verify data
 
-        const ACK_OK = Self::ACK.bits | Self::OK.bits;
-        const ACK_ERR = Self::ACK.bits | Self::ERR.bits;
+            Err(IpiisError::Remote(res).into())
+        }
+        Ok(Some(flag)) if flag.contains(ServerResult::ACK) => {
+            bail!("unknown ACK flag: {flag:?}")
+        }
+        Ok(Some(_) | None) => bail!("cannot parse the result of response"),
+        Err(e) => Err(network_error(e)),
     }
 }
 
+/// Keeps the I/O error as the cause, so that it can be classified, e.g. by [`is_timeout`].
+fn network_error(error: ::std::io::Error) -> Error {
+    let message = format!("network error: {error}");
+    Error::new(error).context(message)
+}
+
 define_io! {
-    GetAccountPrimary {
+    // with the depth of the lookup, bounding the primaries forwarding it,
+    // and the lifetime of the record in milliseconds, if the server limits it
+    GetAccountPrimary = 0 {
+        idempotent: true,
+        priority: 1,
         inputs: { },
-        input_sign: Data<GuaranteeSigned, Option<Hash>>,
+        input_sign: Data<GuaranteeSigned, (Option<Hash>, u8)>,
         outputs: {
             account: AccountRef,
             address: Option<Address>,
+            ttl_ms: Option<u64>,
         },
-        output_sign: Data<GuarantorSigned, Option<Hash>>,
+        output_sign: Data<GuarantorSigned, (Option<Hash>, u8)>,
         generics: { Address, },
     },
-    SetAccountPrimary {
+    SetAccountPrimary = 1 {
+        priority: 1,
         inputs: { },
         input_sign: Data<GuaranteeSigned, (Option<Hash>, AccountRef)>,
         outputs: { },
         output_sign: Data<GuarantorSigned, (Option<Hash>, AccountRef)>,
         generics: { },
     },
-    DeleteAccountPrimary {
+    DeleteAccountPrimary = 2 {
+        priority: 1,
         inputs: { },
         input_sign: Data<GuaranteeSigned, Option<Hash>>,
         outputs: { },
         output_sign: Data<GuarantorSigned, Option<Hash>>,
         generics: { },
     },
-    GetAddress {
+    // with the lifetime of the record in milliseconds, if the server limits it
+    GetAddress = 3 {
+        idempotent: true,
+        priority: 1,
         inputs: { },
         input_sign: Data<GuaranteeSigned, (Option<Hash>, AccountRef)>,
         outputs: {
             address: Address,
+            ttl_ms: Option<u64>,
         },
         output_sign: Data<GuarantorSigned, (Option<Hash>, AccountRef)>,
         generics: { Address, },
     },
-    SetAddress {
+    SetAddress = 4 {
+        priority: 1,
         inputs: { },
         input_sign: Data<GuaranteeSigned, (Option<Hash>, AccountRef, Address)>,
         outputs: { },
         output_sign: Data<GuarantorSigned, (Option<Hash>, AccountRef, Address)>,
         generics: { Address, },
     },
-    DeleteAddress {
+    DeleteAddress = 5 {
+        priority: 1,
         inputs: { },
         input_sign: Data<GuaranteeSigned, (Option<Hash>, AccountRef)>,
         outputs: { },
         output_sign: Data<GuarantorSigned, (Option<Hash>, AccountRef)>,
         generics: { },
     },
+    Ping = 6 {
+        idempotent: true,
+        priority: 1,
+        inputs: { },
+        input_sign: Data<GuaranteeSigned, Nonce>,
+        outputs: { },
+        output_sign: Data<GuarantorSigned, Nonce>,
+        generics: { },
+    },
+    ListAccounts = 7 {
+        idempotent: true,
+        priority: 1,
+        inputs: { },
+        input_sign: Data<GuaranteeSigned, Option<Hash>>,
+        outputs: {
+            accounts: AccountSet,
+        },
+        output_sign: Data<GuarantorSigned, Option<Hash>>,
+        generics: { },
+    },
+    SnapshotPrimaries = 8 {
+        idempotent: true,
+        priority: 1,
+        inputs: { },
+        input_sign: Data<GuaranteeSigned, u8>,
+        outputs: {
+            primaries: Vec<(Option<Hash>, AccountRef, Option<Address>)>,
+        },
+        output_sign: Data<GuarantorSigned, u8>,
+        generics: { Address, },
+    },
+    ListRevokedAccounts = 9 {
+        idempotent: true,
+        priority: 1,
+        inputs: { },
+        input_sign: Data<GuaranteeSigned, u8>,
+        outputs: {
+            accounts: AccountSet,
+        },
+        output_sign: Data<GuarantorSigned, u8>,
+        generics: { },
+    },
+    // sent before the account of the peer is known,
+    // so each record of the advertised address is signed by its own account
+    Hello = 10 {
+        idempotent: true,
+        priority: 1,
+        inputs: { },
+        input_sign: Data<GuaranteeSigned, Option<Address>>,
+        outputs: {
+            record: Data<GuaranteeSigned, Option<Address>>,
+        },
+        output_sign: Data<GuarantorSigned, Option<Address>>,
+        generics: { Address, },
+    },
+    Capabilities = 11 {
+        idempotent: true,
+        priority: 1,
+        inputs: { },
+        input_sign: Data<GuaranteeSigned, u8>,
+        outputs: {
+            opcodes: Vec<String>,
+        },
+        output_sign: Data<GuarantorSigned, u8>,
+        generics: { },
+    },
+    // discards the streamed input, to measure the raw throughput of the connection
+    Sink = 12 {
+        input_stream: true,
+        inputs: { },
+        input_sign: Data<GuaranteeSigned, u64>,
+        outputs: {
+            received: u64,
+        },
+        output_sign: Data<GuarantorSigned, u64>,
+        generics: { },
+    },
 }
 
 #[macro_export]
 macro_rules! define_io {
     (
-        $($case:ident {
+        $($case:ident $( = $code:literal )? {
+            $( idempotent: $idempotent:literal, )?
+            $( priority: $priority:literal, )?
+            $( timeout_ms: $timeout_ms:literal, )?
+            $( input_stream: $input_stream:literal, )?
+            $( output_stream: $output_stream:literal, )?
+            $( oneway: $oneway:literal, )?
             inputs: { $( $input_field:ident : $input_ty:ty ,)* },
             input_sign: $input_sign:ty,
             outputs: { $( $output_field:ident : $output_ty:ty ,)* },
@@ -250,17 +800,95 @@ macro_rules! define_io {
         },)*
     ) => {::ipis::paste::paste! {
         pub mod io {
-            use bytecheck::CheckBytes;
-            use rkyv::{Archive, Deserialize, Serialize};
-
-            #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Archive, Serialize, Deserialize)]
-            #[archive(compare(PartialEq))]
-            #[archive_attr(derive(CheckBytes, Copy, Clone, Debug, PartialEq, Eq, Hash))]
+            /// The opcode is sent as a fixed `u16` (little-endian) on the wire,
+            /// so give an explicit discriminant (`Case = 3 { .. }`) to keep it stable.
+            #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+            #[repr(u16)]
             pub enum OpCode {$(
-                $case,
+                $case $( = $code )?,
             )*}
 
-            impl ::ipis::core::signed::IsSigned for OpCode {}
+            impl OpCode {
+                pub const fn to_bytes(self) -> [u8; 2] {
+                    (self as u16).to_le_bytes()
+                }
+
+                /// Whether the request can be safely re-sent after a response-phase failure.
+                #[allow(clippy::nonminimal_bool)]
+                pub const fn is_idempotent(self) -> bool {
+                    match self {$(
+                        Self::$case => false $( || $idempotent )?,
+                    )*}
+                }
+
+                /// The priority class of the request, where the higher classes
+                /// are dispatched first when the server is saturated.
+                pub const fn priority(self) -> u8 {
+                    match self {$(
+                        Self::$case => 0 $( + $priority )?,
+                    )*}
+                }
+
+                /// Whether the request is followed by a streamed input,
+                /// consumed incrementally by the handler.
+                #[allow(clippy::nonminimal_bool)]
+                pub const fn has_input_stream(self) -> bool {
+                    match self {$(
+                        Self::$case => false $( || $input_stream )?,
+                    )*}
+                }
+
+                /// Whether the response is followed by the streamed outputs,
+                /// yielded one at a time by the handler until the end marker.
+                #[allow(clippy::nonminimal_bool)]
+                pub const fn has_output_stream(self) -> bool {
+                    match self {$(
+                        Self::$case => false $( || $output_stream )?,
+                    )*}
+                }
+
+                /// Whether the request is sent without waiting for any response,
+                /// so the server neither sends one nor reports its errors.
+                #[allow(clippy::nonminimal_bool)]
+                pub const fn is_oneway(self) -> bool {
+                    match self {$(
+                        Self::$case => false $( || $oneway )?,
+                    )*}
+                }
+
+                /// The maximum duration of handling the request, if limited.
+                pub fn timeout(self) -> Option<::core::time::Duration> {
+                    match self {$(
+                        Self::$case => {
+                            let timeout_ms: Option<u64> = None $( .or(Some($timeout_ms)) )?;
+                            timeout_ms.map(::core::time::Duration::from_millis)
+                        }
+                    )*}
+                }
+
+                pub async fn recv(
+                    mut recv: impl ::ipis::tokio::io::AsyncRead + Unpin,
+                ) -> ::ipis::core::anyhow::Result<Self> {
+                    use ipis::tokio::io::AsyncReadExt;
+
+                    let mut buf = [0; 2];
+                    recv.read_exact(&mut buf).await?;
+                    Self::try_from(u16::from_le_bytes(buf)).map_err(Into::into)
+                }
+            }
+
+            impl ::core::convert::TryFrom<u16> for OpCode {
+                type Error = $crate::IpiisError;
+
+                fn try_from(code: u16) -> ::core::result::Result<Self, Self::Error> {
+                    $(
+                        if code == Self::$case as u16 {
+                            return Ok(Self::$case);
+                        }
+                    )*
+                    Err($crate::IpiisError::UnknownOpcode(code))
+                }
+            }
 
             pub mod request {
                 use super::super::*;
@@ -356,11 +984,26 @@ macro_rules! define_io {
                                     + PartialEq,
                             )*
                         {
-                            // send data
-                            let recv = self.send(client, kind, target).await?;
+                            let request = async move {
+                                // send data
+                                let recv = self.send(client, kind, target).await?;
+
+                                // recv data
+                                super::response::$case::recv(target, recv)
+                                    .await
+                                    .map_err(Self::__map_unacknowledged)
+                            };
 
-                            // recv data
-                            super::response::$case::recv(target, recv).await
+                            // register the request to be listed and cancelled
+                            match client.request_registry() {
+                                Some(registry) => {
+                                    registry
+                                        .register(*target, super::OpCode::$case as u16)
+                                        .run(request)
+                                        .await
+                                }
+                                None => request.await,
+                            }
                         }
 
                         pub async fn send<__IpiisClient>(
@@ -412,25 +1055,218 @@ macro_rules! define_io {
                                     + PartialEq,
                             )*
                         {
-                            use ipis::tokio::io::AsyncReadExt;
-
-                            // make a opcode
-                            let mut opcode = ::ipis::stream::DynStream::Owned(super::OpCode::$case);
+                            self.send_timed(client, kind, target)
+                                .await
+                                .map(|(recv, _)| recv)
+                        }
 
-                            // pack data
-                            opcode.serialize_inner().await?;
-                            self.__sign.serialize_inner().await?;
+                        /// Sends the request like `send`,
+                        /// also returning the server-side processing time if the server has reported it.
+                        pub async fn send_timed<__IpiisClient>(
+                            &'__io mut self,
+                            client: &__IpiisClient,
+                            kind: Option<&::ipis::core::value::hash::Hash>,
+                            target: &::ipis::core::account::AccountRef,
+                        ) -> ::ipis::core::anyhow::Result<(
+                            <__IpiisClient as super::super::Ipiis>::Reader,
+                            Option<::core::time::Duration>,
+                        )>
+                        where
+                            __IpiisClient: super::super::Ipiis,
+                            <::ipis::core::data::Data<::ipis::core::account::GuaranteeSigned, String> as ::ipis::rkyv::Archive>::Archived: ::ipis::rkyv::Deserialize<
+                                    ::ipis::core::data::Data<::ipis::core::account::GuaranteeSigned, String>,
+                                    ::ipis::rkyv::de::deserializers::SharedDeserializeMap,
+                                >,
                             $(
-                                {
-                                    self.$input_field.serialize_inner().await?;
-                                }
-                            )*
-
-                            // make a connection
-                            let (mut send, mut recv) = client.call_raw(kind, target).await?;
+                                $input_ty: ::ipis::core::signed::IsSigned
+                                    + ::ipis::rkyv::Archive
+                                    + ::ipis::rkyv::Serialize<::ipis::core::signature::SignatureSerializer>
+                                    + ::ipis::rkyv::Serialize<::ipis::core::signed::Serializer>
+                                    + Send
+                                    + Sync
+                                    + 'static,
+                                <$input_ty as ::ipis::rkyv::Archive>::Archived: for<'__bytecheck> ::ipis::bytecheck::CheckBytes<
+                                        ::ipis::rkyv::validation::validators::DefaultValidator<'__bytecheck>,
+                                    > + ::ipis::rkyv::Deserialize<
+                                        $input_ty,
+                                        ::ipis::rkyv::de::deserializers::SharedDeserializeMap,
+                                    >
+                                    + ::core::fmt::Debug
+                                    + PartialEq,
+                                )*
+                            $(
+                                $generic: ::ipis::core::signed::IsSigned
+                                    + ::ipis::rkyv::Archive
+                                    + ::ipis::rkyv::Serialize<::ipis::core::signature::SignatureSerializer>
+                                    + ::ipis::rkyv::Serialize<::ipis::core::signed::Serializer>
+                                    + ::core::fmt::Debug
+                                    + PartialEq
+                                    + Send
+                                    + Sync
+                                    + 'static,
+                                <$generic as ::ipis::rkyv::Archive>::Archived: for<'__bytecheck> ::ipis::bytecheck::CheckBytes<
+                                        ::ipis::rkyv::validation::validators::DefaultValidator<'__bytecheck>,
+                                    > + ::ipis::rkyv::Deserialize<
+                                        $generic,
+                                        ::ipis::rkyv::de::deserializers::SharedDeserializeMap,
+                                    >
+                                    + ::core::fmt::Debug
+                                    + PartialEq,
+                            )*
+                        {
+                            use ipis::tokio::io::AsyncWriteExt;
+
+                            // make a opcode
+                            let opcode = super::OpCode::$case.to_bytes();
+
+                            // pack data
+                            self.__sign.serialize_inner().await?;
+                            $(
+                                {
+                                    self.$input_field.serialize_inner().await?;
+                                }
+                            )*
+
+                            let mut retries = 0;
+                            loop {
+                                // make a connection
+                                let (mut send, mut recv) = client.call_raw(kind, target).await?;
+
+                                match client.wire_capture() {
+                                    // send the request at once, exposing its bytes to the capture
+                                    Some(capture) => {
+                                        let mut bytes = opcode.to_vec();
+                                        self.__sign.copy_to(&mut bytes).await?;
+                                        $(
+                                            {
+                                                self.$input_field.copy_to(&mut bytes).await?;
+                                            }
+                                        )*
+
+                                        capture.capture(&bytes);
+                                        send.write_all(&bytes).await?;
+                                    }
+                                    None => {
+                                        // send opcode
+                                        send.write_all(&opcode).await?;
+
+                                        // send sign
+                                        self.__sign.copy_to(&mut send).await?;
+
+                                        // send data
+                                        $(
+                                            {
+                                                self.$input_field.copy_to(&mut send).await?;
+                                            }
+                                        )*
+                                    }
+                                }
+
+                                // recv flag
+                                match $crate::recv_server_result_timed(&mut recv).await {
+                                    Ok(server_time) => break Ok((recv, server_time)),
+                                    Err(e) => match e.downcast_ref() {
+                                        // re-send the shed request after the suggested delay
+                                        Some($crate::IpiisError::RetryAfter(delay))
+                                            if retries < $crate::MAX_SHED_RETRIES
+                                                && client
+                                                    .retry_budget()
+                                                    .map_or(true, $crate::RetryBudget::try_acquire) =>
+                                        {
+                                            ::ipis::tokio::time::sleep(*delay).await;
+                                            retries += 1;
+                                        }
+                                        _ => break Err(Self::__map_unacknowledged(e)),
+                                    },
+                                }
+                            }
+                        }
+                    }
+
+                    impl<'__io, $( $generic, )* > $case<'__io, $( $generic, )* >
+                    where
+                        $(
+                            $generic: ::rkyv::Archive + Clone + ::core::fmt::Debug + PartialEq + ::ipis::core::signed::IsSigned,
+                            <$generic as ::rkyv::Archive>::Archived: ::core::fmt::Debug + PartialEq,
+                        )*
+                    {
+                        /// Sends the one-way request, returning right after it is written
+                        /// without waiting for the server to handle it.
+                        pub async fn send_oneway<__IpiisClient>(
+                            &'__io mut self,
+                            client: &__IpiisClient,
+                            kind: Option<&::ipis::core::value::hash::Hash>,
+                            target: &::ipis::core::account::AccountRef,
+                        ) -> ::ipis::core::anyhow::Result<()>
+                        where
+                            __IpiisClient: super::super::Ipiis,
+                            <::ipis::core::data::Data<::ipis::core::account::GuaranteeSigned, String> as ::ipis::rkyv::Archive>::Archived: ::ipis::rkyv::Deserialize<
+                                    ::ipis::core::data::Data<::ipis::core::account::GuaranteeSigned, String>,
+                                    ::ipis::rkyv::de::deserializers::SharedDeserializeMap,
+                                >,
+                            $(
+                                $input_ty: ::ipis::core::signed::IsSigned
+                                    + ::ipis::rkyv::Archive
+                                    + ::ipis::rkyv::Serialize<::ipis::core::signature::SignatureSerializer>
+                                    + ::ipis::rkyv::Serialize<::ipis::core::signed::Serializer>
+                                    + Send
+                                    + Sync
+                                    + 'static,
+                                <$input_ty as ::ipis::rkyv::Archive>::Archived: for<'__bytecheck> ::ipis::bytecheck::CheckBytes<
+                                        ::ipis::rkyv::validation::validators::DefaultValidator<'__bytecheck>,
+                                    > + ::ipis::rkyv::Deserialize<
+                                        $input_ty,
+                                        ::ipis::rkyv::de::deserializers::SharedDeserializeMap,
+                                    >
+                                    + ::core::fmt::Debug
+                                    + PartialEq,
+                                )*
+                            $(
+                                $generic: ::ipis::core::signed::IsSigned
+                                    + ::ipis::rkyv::Archive
+                                    + ::ipis::rkyv::Serialize<::ipis::core::signature::SignatureSerializer>
+                                    + ::ipis::rkyv::Serialize<::ipis::core::signed::Serializer>
+                                    + ::core::fmt::Debug
+                                    + PartialEq
+                                    + Send
+                                    + Sync
+                                    + 'static,
+                                <$generic as ::ipis::rkyv::Archive>::Archived: for<'__bytecheck> ::ipis::bytecheck::CheckBytes<
+                                        ::ipis::rkyv::validation::validators::DefaultValidator<'__bytecheck>,
+                                    > + ::ipis::rkyv::Deserialize<
+                                        $generic,
+                                        ::ipis::rkyv::de::deserializers::SharedDeserializeMap,
+                                    >
+                                    + ::core::fmt::Debug
+                                    + PartialEq,
+                            )*
+                        {
+                            use ipis::tokio::io::AsyncWriteExt;
+
+                            // reject waiting for nothing, which would fail silently
+                            if !super::OpCode::$case.is_oneway() {
+                                ::ipis::core::anyhow::bail!(
+                                    "the request is not one-way: {:?}",
+                                    super::OpCode::$case,
+                                );
+                            }
+
+                            // make a opcode
+                            let opcode = super::OpCode::$case.to_bytes();
+
+                            // pack data
+                            self.__sign.serialize_inner().await?;
+                            $(
+                                {
+                                    self.$input_field.serialize_inner().await?;
+                                }
+                            )*
+
+                            // make a connection
+                            let (mut send, _) = client.call_raw(kind, target).await?;
 
                             // send opcode
-                            opcode.copy_to(&mut send).await?;
+                            send.write_all(&opcode).await?;
 
                             // send sign
                             self.__sign.copy_to(&mut send).await?;
@@ -441,31 +1277,296 @@ macro_rules! define_io {
                                     self.$input_field.copy_to(&mut send).await?;
                                 }
                             )*
+                            send.flush().await.map_err(Into::into)
+                        }
 
-                            // recv flag
-                            match recv.read_u8().await.map(super::super::ServerResult::from_bits) {
-                                // parse the data
-                                Ok(Some(super::super::ServerResult::ACK_OK)) => Ok(recv),
-                                // parse the error
-                                Ok(Some(super::super::ServerResult::ACK_ERR)) => {
-                                    // recv data
-                                    let res: String = ::ipis::stream::DynStream::recv(&mut recv)
-                                        .await?
-                                        .to_owned().await?;
-
-                                    // TODO: verify data
-
-                                    ::ipis::core::anyhow::bail!("internal error: {res}")
-                                }
-                                Ok(Some(flag)) if flag.contains(super::super::ServerResult::ACK) => {
-                                    ::ipis::core::anyhow::bail!("unknown ACK flag: {flag:?}")
-                                }
-                                Ok(Some(_) | None) => {
-                                    ::ipis::core::anyhow::bail!("cannot parse the result of response")
+                        /// Sends the request like `call`, followed by the streamed input,
+                        /// e.g. a large file consumed incrementally by the handler.
+                        pub async fn call_streamed<__IpiisClient, __Stream>(
+                            &'__io mut self,
+                            client: &__IpiisClient,
+                            kind: Option<&::ipis::core::value::hash::Hash>,
+                            target: &::ipis::core::account::AccountRef,
+                            stream: __Stream,
+                        ) -> ::ipis::core::anyhow::Result<super::response::$case<'static, $( $generic, )* >>
+                        where
+                            __IpiisClient: super::super::Ipiis,
+                            <::ipis::core::data::Data<::ipis::core::account::GuaranteeSigned, String> as ::ipis::rkyv::Archive>::Archived: ::ipis::rkyv::Deserialize<
+                                    ::ipis::core::data::Data<::ipis::core::account::GuaranteeSigned, String>,
+                                    ::ipis::rkyv::de::deserializers::SharedDeserializeMap,
+                                >,
+                            $(
+                                $input_ty: ::ipis::core::signed::IsSigned
+                                    + ::ipis::rkyv::Archive
+                                    + ::ipis::rkyv::Serialize<::ipis::core::signature::SignatureSerializer>
+                                    + ::ipis::rkyv::Serialize<::ipis::core::signed::Serializer>
+                                    + Send
+                                    + Sync
+                                    + 'static,
+                                <$input_ty as ::ipis::rkyv::Archive>::Archived: for<'__bytecheck> ::ipis::bytecheck::CheckBytes<
+                                        ::ipis::rkyv::validation::validators::DefaultValidator<'__bytecheck>,
+                                    > + ::ipis::rkyv::Deserialize<
+                                        $input_ty,
+                                        ::ipis::rkyv::de::deserializers::SharedDeserializeMap,
+                                    >
+                                    + ::core::fmt::Debug
+                                    + PartialEq,
+                                )*
+                            $(
+                                $output_ty: ::ipis::rkyv::Archive + ::core::fmt::Debug + PartialEq + 'static,
+                                <$output_ty as ::ipis::rkyv::Archive>::Archived: for<'__bytecheck> ::ipis::bytecheck::CheckBytes<
+                                        ::ipis::rkyv::validation::validators::DefaultValidator<'__bytecheck>,
+                                    > + ::ipis::rkyv::Deserialize<
+                                        $output_ty,
+                                        ::ipis::rkyv::de::deserializers::SharedDeserializeMap,
+                                    >
+                                    + ::core::fmt::Debug
+                                    + PartialEq,
+                            )*
+                            $(
+                                $generic: ::ipis::core::signed::IsSigned
+                                    + ::ipis::rkyv::Archive
+                                    + ::ipis::rkyv::Serialize<::ipis::core::signature::SignatureSerializer>
+                                    + ::ipis::rkyv::Serialize<::ipis::core::signed::Serializer>
+                                    + ::core::fmt::Debug
+                                    + PartialEq
+                                    + Send
+                                    + Sync
+                                    + 'static,
+                                <$generic as ::ipis::rkyv::Archive>::Archived: for<'__bytecheck> ::ipis::bytecheck::CheckBytes<
+                                        ::ipis::rkyv::validation::validators::DefaultValidator<'__bytecheck>,
+                                    > + ::ipis::rkyv::Deserialize<
+                                        $generic,
+                                        ::ipis::rkyv::de::deserializers::SharedDeserializeMap,
+                                    >
+                                    + ::core::fmt::Debug
+                                    + PartialEq,
+                            )*
+                            __Stream: ::ipis::tokio::io::AsyncRead + Unpin,
+                        {
+                            // send data
+                            let recv = self.send_streamed(client, kind, target, stream).await?;
+
+                            // recv data
+                            super::response::$case::recv(target, recv)
+                                .await
+                                .map_err(Self::__map_unacknowledged)
+                        }
+
+                        /// Sends the request like `call`, receiving the streamed outputs after the response,
+                        /// e.g. the entries of a large listing yielded one at a time by the handler.
+                        ///
+                        /// The stream ends at the end marker sent by the handler,
+                        /// unlike a subscription which is pushed until either side leaves.
+                        #[allow(clippy::type_complexity)]
+                        pub async fn call_output_stream<__IpiisClient, __Item>(
+                            &'__io mut self,
+                            client: &__IpiisClient,
+                            kind: Option<&::ipis::core::value::hash::Hash>,
+                            target: &::ipis::core::account::AccountRef,
+                        ) -> ::ipis::core::anyhow::Result<(
+                            super::response::$case<'static, $( $generic, )* >,
+                            ::ipis::futures::stream::BoxStream<'static, ::ipis::core::anyhow::Result<__Item>>,
+                        )>
+                        where
+                            __IpiisClient: super::super::Ipiis,
+                            <::ipis::core::data::Data<::ipis::core::account::GuaranteeSigned, String> as ::ipis::rkyv::Archive>::Archived: ::ipis::rkyv::Deserialize<
+                                    ::ipis::core::data::Data<::ipis::core::account::GuaranteeSigned, String>,
+                                    ::ipis::rkyv::de::deserializers::SharedDeserializeMap,
+                                >,
+                            $(
+                                $input_ty: ::ipis::core::signed::IsSigned
+                                    + ::ipis::rkyv::Archive
+                                    + ::ipis::rkyv::Serialize<::ipis::core::signature::SignatureSerializer>
+                                    + ::ipis::rkyv::Serialize<::ipis::core::signed::Serializer>
+                                    + Send
+                                    + Sync
+                                    + 'static,
+                                <$input_ty as ::ipis::rkyv::Archive>::Archived: for<'__bytecheck> ::ipis::bytecheck::CheckBytes<
+                                        ::ipis::rkyv::validation::validators::DefaultValidator<'__bytecheck>,
+                                    > + ::ipis::rkyv::Deserialize<
+                                        $input_ty,
+                                        ::ipis::rkyv::de::deserializers::SharedDeserializeMap,
+                                    >
+                                    + ::core::fmt::Debug
+                                    + PartialEq,
+                                )*
+                            $(
+                                $output_ty: ::ipis::rkyv::Archive + ::core::fmt::Debug + PartialEq + 'static,
+                                <$output_ty as ::ipis::rkyv::Archive>::Archived: for<'__bytecheck> ::ipis::bytecheck::CheckBytes<
+                                        ::ipis::rkyv::validation::validators::DefaultValidator<'__bytecheck>,
+                                    > + ::ipis::rkyv::Deserialize<
+                                        $output_ty,
+                                        ::ipis::rkyv::de::deserializers::SharedDeserializeMap,
+                                    >
+                                    + ::core::fmt::Debug
+                                    + PartialEq,
+                            )*
+                            $(
+                                $generic: ::ipis::core::signed::IsSigned
+                                    + ::ipis::rkyv::Archive
+                                    + ::ipis::rkyv::Serialize<::ipis::core::signature::SignatureSerializer>
+                                    + ::ipis::rkyv::Serialize<::ipis::core::signed::Serializer>
+                                    + ::core::fmt::Debug
+                                    + PartialEq
+                                    + Send
+                                    + Sync
+                                    + 'static,
+                                <$generic as ::ipis::rkyv::Archive>::Archived: for<'__bytecheck> ::ipis::bytecheck::CheckBytes<
+                                        ::ipis::rkyv::validation::validators::DefaultValidator<'__bytecheck>,
+                                    > + ::ipis::rkyv::Deserialize<
+                                        $generic,
+                                        ::ipis::rkyv::de::deserializers::SharedDeserializeMap,
+                                    >
+                                    + ::core::fmt::Debug
+                                    + PartialEq,
+                            )*
+                            __Item: ::ipis::core::signed::IsSigned
+                                + ::ipis::rkyv::Archive
+                                + ::ipis::rkyv::Serialize<::ipis::core::signature::SignatureSerializer>
+                                + ::ipis::rkyv::Serialize<::ipis::core::signed::Serializer>
+                                + ::core::fmt::Debug
+                                + PartialEq
+                                + Send
+                                + Sync
+                                + 'static,
+                            <__Item as ::ipis::rkyv::Archive>::Archived: for<'__bytecheck> ::ipis::bytecheck::CheckBytes<
+                                    ::ipis::rkyv::validation::validators::DefaultValidator<'__bytecheck>,
+                                > + ::ipis::rkyv::Deserialize<
+                                    __Item,
+                                    ::ipis::rkyv::de::deserializers::SharedDeserializeMap,
+                                >
+                                + ::core::fmt::Debug
+                                + PartialEq,
+                        {
+                            // reject waiting for the outputs never sent
+                            if !super::OpCode::$case.has_output_stream() {
+                                ::ipis::core::anyhow::bail!(
+                                    "the response has no streamed outputs: {:?}",
+                                    super::OpCode::$case,
+                                );
+                            }
+
+                            // send data
+                            let mut recv = self.send(client, kind, target).await?;
+
+                            // recv data
+                            let res = super::response::$case::recv(target, &mut recv)
+                                .await
+                                .map_err(Self::__map_unacknowledged)?;
+
+                            // recv the streamed outputs
+                            Ok((res, $crate::recv_output_stream(recv)))
+                        }
+
+                        /// Sends the request like `send`, followed by the streamed input.
+                        ///
+                        /// The request is not re-sent even if shed, as the stream cannot be rewound.
+                        pub async fn send_streamed<__IpiisClient, __Stream>(
+                            &'__io mut self,
+                            client: &__IpiisClient,
+                            kind: Option<&::ipis::core::value::hash::Hash>,
+                            target: &::ipis::core::account::AccountRef,
+                            stream: __Stream,
+                        ) -> ::ipis::core::anyhow::Result<<__IpiisClient as super::super::Ipiis>::Reader>
+                        where
+                            __IpiisClient: super::super::Ipiis,
+                            <::ipis::core::data::Data<::ipis::core::account::GuaranteeSigned, String> as ::ipis::rkyv::Archive>::Archived: ::ipis::rkyv::Deserialize<
+                                    ::ipis::core::data::Data<::ipis::core::account::GuaranteeSigned, String>,
+                                    ::ipis::rkyv::de::deserializers::SharedDeserializeMap,
+                                >,
+                            $(
+                                $input_ty: ::ipis::core::signed::IsSigned
+                                    + ::ipis::rkyv::Archive
+                                    + ::ipis::rkyv::Serialize<::ipis::core::signature::SignatureSerializer>
+                                    + ::ipis::rkyv::Serialize<::ipis::core::signed::Serializer>
+                                    + Send
+                                    + Sync
+                                    + 'static,
+                                <$input_ty as ::ipis::rkyv::Archive>::Archived: for<'__bytecheck> ::ipis::bytecheck::CheckBytes<
+                                        ::ipis::rkyv::validation::validators::DefaultValidator<'__bytecheck>,
+                                    > + ::ipis::rkyv::Deserialize<
+                                        $input_ty,
+                                        ::ipis::rkyv::de::deserializers::SharedDeserializeMap,
+                                    >
+                                    + ::core::fmt::Debug
+                                    + PartialEq,
+                                )*
+                            $(
+                                $generic: ::ipis::core::signed::IsSigned
+                                    + ::ipis::rkyv::Archive
+                                    + ::ipis::rkyv::Serialize<::ipis::core::signature::SignatureSerializer>
+                                    + ::ipis::rkyv::Serialize<::ipis::core::signed::Serializer>
+                                    + ::core::fmt::Debug
+                                    + PartialEq
+                                    + Send
+                                    + Sync
+                                    + 'static,
+                                <$generic as ::ipis::rkyv::Archive>::Archived: for<'__bytecheck> ::ipis::bytecheck::CheckBytes<
+                                        ::ipis::rkyv::validation::validators::DefaultValidator<'__bytecheck>,
+                                    > + ::ipis::rkyv::Deserialize<
+                                        $generic,
+                                        ::ipis::rkyv::de::deserializers::SharedDeserializeMap,
+                                    >
+                                    + ::core::fmt::Debug
+                                    + PartialEq,
+                            )*
+                            __Stream: ::ipis::tokio::io::AsyncRead + Unpin,
+                        {
+                            use ipis::tokio::io::AsyncWriteExt;
+
+                            if !super::OpCode::$case.has_input_stream() {
+                                ::ipis::core::anyhow::bail!(
+                                    "the request does not take a streamed input: {:?}",
+                                    super::OpCode::$case,
+                                );
+                            }
+
+                            // make a opcode
+                            let opcode = super::OpCode::$case.to_bytes();
+
+                            // pack data
+                            self.__sign.serialize_inner().await?;
+                            $(
+                                {
+                                    self.$input_field.serialize_inner().await?;
                                 }
-                                Err(e) => {
-                                    ::ipis::core::anyhow::bail!("network error: {e}")
+                            )*
+
+                            // make a connection
+                            let (mut send, mut recv) = client.call_raw(kind, target).await?;
+
+                            // send opcode
+                            send.write_all(&opcode).await?;
+
+                            // send sign
+                            self.__sign.copy_to(&mut send).await?;
+
+                            // send data
+                            $(
+                                {
+                                    self.$input_field.copy_to(&mut send).await?;
                                 }
+                            )*
+                            $crate::copy_input_stream(stream, &mut send, $crate::DEFAULT_CHUNK_SIZE).await?;
+
+                            // recv flag
+                            $crate::recv_server_result(&mut recv)
+                                .await
+                                .map_err(Self::__map_unacknowledged)?;
+                            Ok(recv)
+                        }
+
+                        fn __map_unacknowledged(
+                            error: ::ipis::core::anyhow::Error,
+                        ) -> ::ipis::core::anyhow::Error {
+                            // the request may have been executed, so it is not safe to re-send it
+                            if super::OpCode::$case.is_idempotent()
+                                || error.downcast_ref::<$crate::IpiisError>().is_some()
+                            {
+                                error
+                            } else {
+                                $crate::IpiisError::Unacknowledged(error.to_string()).into()
                             }
                         }
                     }
@@ -477,12 +1578,12 @@ macro_rules! define_io {
                             <$generic as ::rkyv::Archive>::Archived: ::core::fmt::Debug + PartialEq,
                         )*
                     {
-                        pub async fn recv<__IpiisClient>(
-                            client: &__IpiisClient,
+                        /// Receives the request from the untrusted bytes, validating all of its fields
+                        /// before use, but without verifying its sign.
+                        pub async fn decode(
                             mut recv: impl ::ipis::tokio::io::AsyncRead + Unpin,
                         ) -> ::ipis::core::anyhow::Result<Self>
                         where
-                            __IpiisClient: super::super::Ipiis,
                             <::ipis::core::data::Data<::ipis::core::account::GuaranteeSigned, String> as ::ipis::rkyv::Archive>::Archived: ::ipis::rkyv::Deserialize<
                                     ::ipis::core::data::Data<::ipis::core::account::GuaranteeSigned, String>,
                                     ::ipis::rkyv::de::deserializers::SharedDeserializeMap,
@@ -518,8 +1619,6 @@ macro_rules! define_io {
                                     + PartialEq,
                             )*
                         {
-                            use ipis::core::account::Verifier;
-
                             // recv data
                             let mut res = Self {
                                 __lifetime: Default::default(),
@@ -529,15 +1628,179 @@ macro_rules! define_io {
                                 )*
                             };
 
+                            // validate data
+                            res.__sign.as_ref().await?;
+                            $(
+                                res.$input_field.as_ref().await?;
+                            )*
+
+                            Ok(res)
+                        }
+
+                        pub async fn recv<__IpiisClient>(
+                            client: &__IpiisClient,
+                            recv: impl ::ipis::tokio::io::AsyncRead + Unpin,
+                        ) -> ::ipis::core::anyhow::Result<Self>
+                        where
+                            __IpiisClient: super::super::Ipiis,
+                            <::ipis::core::data::Data<::ipis::core::account::GuaranteeSigned, String> as ::ipis::rkyv::Archive>::Archived: ::ipis::rkyv::Deserialize<
+                                    ::ipis::core::data::Data<::ipis::core::account::GuaranteeSigned, String>,
+                                    ::ipis::rkyv::de::deserializers::SharedDeserializeMap,
+                                >,
+                            $(
+                                $input_ty: ::ipis::rkyv::Archive + ::core::fmt::Debug + PartialEq + 'static,
+                                <$input_ty as ::ipis::rkyv::Archive>::Archived: for<'__bytecheck> ::ipis::bytecheck::CheckBytes<
+                                        ::ipis::rkyv::validation::validators::DefaultValidator<'__bytecheck>,
+                                    > + ::ipis::rkyv::Deserialize<
+                                        $input_ty,
+                                        ::ipis::rkyv::de::deserializers::SharedDeserializeMap,
+                                    >
+                                    + ::core::fmt::Debug
+                                    + PartialEq,
+                            )*
+                            $(
+                                $generic: ::ipis::core::signed::IsSigned
+                                    + ::ipis::rkyv::Archive
+                                    + ::ipis::rkyv::Serialize<::ipis::core::signature::SignatureSerializer>
+                                    + ::ipis::rkyv::Serialize<::ipis::core::signed::Serializer>
+                                    + ::core::fmt::Debug
+                                    + PartialEq
+                                    + Send
+                                    + Sync
+                                    + 'static,
+                                <$generic as ::ipis::rkyv::Archive>::Archived: for<'__bytecheck> ::ipis::bytecheck::CheckBytes<
+                                        ::ipis::rkyv::validation::validators::DefaultValidator<'__bytecheck>,
+                                    > + ::ipis::rkyv::Deserialize<
+                                        $generic,
+                                        ::ipis::rkyv::de::deserializers::SharedDeserializeMap,
+                                    >
+                                    + ::core::fmt::Debug
+                                    + PartialEq,
+                            )*
+                        {
+                            use ipis::core::account::Verifier;
+
+                            // recv data
+                            let mut res = Self::decode(recv).await?;
+
                             // verify data
                             {
                                 // select the sign data
                                 let data = res.__sign.as_ref().await?;
 
-                                // verify it
-                                data.verify(Some(client.account_ref()))?
-                            };
+                                // verify it, its age and its transport, auditing the failure
+                                let verified = data
+                                    .verify(Some(client.account_ref()))
+                                    .map_err(|e| {
+                                        ::ipis::core::anyhow::Error::from(
+                                            $crate::IpiisError::InvalidSignature(e.to_string()),
+                                        )
+                                    })
+                                    .and_then(|_| {
+                                        client
+                                            .time_policy()
+                                            .verify_signed_at(&data.metadata.created_date)
+                                    })
+                                    .and_then(|_| {
+                                        $crate::TransportIdentity::check(&data.metadata.guarantee)
+                                    });
+                                if let Err(e) = verified {
+                                    if let Some(audit) = client.verification_audit() {
+                                        audit.report($crate::VerificationFailure {
+                                            account: data.metadata.guarantee,
+                                            opcode: stringify!($case),
+                                            reason: e.to_string(),
+                                        });
+                                    }
+                                    return Err(e);
+                                }
+
+                                // account the request to the verified account
+                                $crate::MeteredRequest::identify(&data.metadata.guarantee)?
+                            };
+
+                            Ok(res)
+                        }
+                    }
+                    #[::ipis::async_trait::async_trait]
+                    impl<$( $generic, )* > $crate::IoFrame for $case<'static, $( $generic, )* >
+                    where
+                            <::ipis::core::data::Data<::ipis::core::account::GuaranteeSigned, String> as ::ipis::rkyv::Archive>::Archived: ::ipis::rkyv::Deserialize<
+                                    ::ipis::core::data::Data<::ipis::core::account::GuaranteeSigned, String>,
+                                    ::ipis::rkyv::de::deserializers::SharedDeserializeMap,
+                                >,
+                            $(
+                                $input_ty: ::ipis::core::signed::IsSigned
+                                    + ::ipis::rkyv::Archive
+                                    + ::ipis::rkyv::Serialize<::ipis::core::signature::SignatureSerializer>
+                                    + ::ipis::rkyv::Serialize<::ipis::core::signed::Serializer>
+                                    + ::core::fmt::Debug
+                                    + PartialEq
+                                    + Send
+                                    + Sync
+                                    + 'static,
+                                <$input_ty as ::ipis::rkyv::Archive>::Archived: for<'__bytecheck> ::ipis::bytecheck::CheckBytes<
+                                        ::ipis::rkyv::validation::validators::DefaultValidator<'__bytecheck>,
+                                    > + ::ipis::rkyv::Deserialize<
+                                        $input_ty,
+                                        ::ipis::rkyv::de::deserializers::SharedDeserializeMap,
+                                    >
+                                    + ::core::fmt::Debug
+                                    + PartialEq,
+                            )*
+                            $(
+                                $generic: ::ipis::core::signed::IsSigned
+                                    + ::ipis::rkyv::Archive
+                                    + ::ipis::rkyv::Serialize<::ipis::core::signature::SignatureSerializer>
+                                    + ::ipis::rkyv::Serialize<::ipis::core::signed::Serializer>
+                                    + Clone
+                                    + ::core::fmt::Debug
+                                    + PartialEq
+                                    + Send
+                                    + Sync
+                                    + 'static,
+                                <$generic as ::ipis::rkyv::Archive>::Archived: for<'__bytecheck> ::ipis::bytecheck::CheckBytes<
+                                        ::ipis::rkyv::validation::validators::DefaultValidator<'__bytecheck>,
+                                    > + ::ipis::rkyv::Deserialize<
+                                        $generic,
+                                        ::ipis::rkyv::de::deserializers::SharedDeserializeMap,
+                                    >
+                                    + ::core::fmt::Debug
+                                    + PartialEq,
+                            )*
+                    {
+                        async fn to_frame(&mut self) -> ::ipis::core::anyhow::Result<Vec<u8>> {
+                            // pack data
+                            self.__sign.serialize_inner().await?;
+                            $(
+                                {
+                                    self.$input_field.serialize_inner().await?;
+                                }
+                            )*
+
+                            let mut bytes = super::OpCode::$case.to_bytes().to_vec();
+                            self.__sign.copy_to(&mut bytes).await?;
+                            $(
+                                {
+                                    self.$input_field.copy_to(&mut bytes).await?;
+                                }
+                            )*
+                            Ok(bytes)
+                        }
+
+                        async fn from_frame(mut bytes: &[u8]) -> ::ipis::core::anyhow::Result<Self> {
+                            // recv opcode
+                            let opcode = super::OpCode::recv(&mut bytes).await?;
+                            if opcode != super::OpCode::$case {
+                                ::ipis::core::anyhow::bail!("unexpected opcode: {opcode:?}");
+                            }
+
+                            // recv data
+                            let res = Self::decode(&mut bytes).await?;
 
+                            if !bytes.is_empty() {
+                                ::ipis::core::anyhow::bail!("trailing bytes after the request: {} bytes", bytes.len());
+                            }
                             Ok(res)
                         }
                     }
@@ -579,9 +1842,56 @@ macro_rules! define_io {
                         )*
                     {
                         pub async fn send<__IpiisClient>(
+                            &'__io mut self,
+                            client: &__IpiisClient,
+                            send: &mut <__IpiisClient as super::super::Ipiis>::Writer,
+                        ) -> ::ipis::core::anyhow::Result<()>
+                        where
+                            __IpiisClient: super::super::Ipiis,
+                            <::ipis::core::data::Data<::ipis::core::account::GuaranteeSigned, String> as ::ipis::rkyv::Archive>::Archived: ::ipis::rkyv::Deserialize<
+                                    ::ipis::core::data::Data<::ipis::core::account::GuaranteeSigned, String>,
+                                    ::ipis::rkyv::de::deserializers::SharedDeserializeMap,
+                                >,
+                            $(
+                                $output_ty: ::ipis::rkyv::Archive + ::core::fmt::Debug + PartialEq + 'static,
+                                <$output_ty as ::ipis::rkyv::Archive>::Archived: for<'__bytecheck> ::ipis::bytecheck::CheckBytes<
+                                        ::ipis::rkyv::validation::validators::DefaultValidator<'__bytecheck>,
+                                    > + ::ipis::rkyv::Deserialize<
+                                        $output_ty,
+                                        ::ipis::rkyv::de::deserializers::SharedDeserializeMap,
+                                    >
+                                    + ::core::fmt::Debug
+                                    + PartialEq,
+                            )*
+                            $(
+                                $generic: ::ipis::core::signed::IsSigned
+                                    + ::ipis::rkyv::Archive
+                                    + ::ipis::rkyv::Serialize<::ipis::core::signature::SignatureSerializer>
+                                    + ::ipis::rkyv::Serialize<::ipis::core::signed::Serializer>
+                                    + ::core::fmt::Debug
+                                    + PartialEq
+                                    + Send
+                                    + Sync
+                                    + 'static,
+                                <$generic as ::ipis::rkyv::Archive>::Archived: for<'__bytecheck> ::ipis::bytecheck::CheckBytes<
+                                        ::ipis::rkyv::validation::validators::DefaultValidator<'__bytecheck>,
+                                    > + ::ipis::rkyv::Deserialize<
+                                        $generic,
+                                        ::ipis::rkyv::de::deserializers::SharedDeserializeMap,
+                                    >
+                                    + ::core::fmt::Debug
+                                    + PartialEq,
+                            )*
+                        {
+                            self.send_timed(client, send, None).await
+                        }
+
+                        /// Sends the response, reporting the server-side processing time if given.
+                        pub async fn send_timed<__IpiisClient>(
                             &'__io mut self,
                             _client: &__IpiisClient,
-                            mut send: &mut <__IpiisClient as super::super::Ipiis>::Writer,
+                            send: &mut <__IpiisClient as super::super::Ipiis>::Writer,
+                            server_time: Option<::core::time::Duration>,
                         ) -> ::ipis::core::anyhow::Result<()>
                         where
                             __IpiisClient: super::super::Ipiis,
@@ -619,14 +1929,66 @@ macro_rules! define_io {
                                     + ::core::fmt::Debug
                                     + PartialEq,
                             )*
+                        {
+                            self.send_to(send, server_time).await
+                        }
+
+                        /// Sends the response to any writer, e.g. a buffer to be replayed later.
+                        pub async fn send_to(
+                            &'__io mut self,
+                            mut send: impl ::ipis::tokio::io::AsyncWrite + Unpin,
+                            server_time: Option<::core::time::Duration>,
+                        ) -> ::ipis::core::anyhow::Result<()>
+                        where
+                            <::ipis::core::data::Data<::ipis::core::account::GuaranteeSigned, String> as ::ipis::rkyv::Archive>::Archived: ::ipis::rkyv::Deserialize<
+                                    ::ipis::core::data::Data<::ipis::core::account::GuaranteeSigned, String>,
+                                    ::ipis::rkyv::de::deserializers::SharedDeserializeMap,
+                                >,
+                            $(
+                                $output_ty: ::ipis::rkyv::Archive + ::core::fmt::Debug + PartialEq + 'static,
+                                <$output_ty as ::ipis::rkyv::Archive>::Archived: for<'__bytecheck> ::ipis::bytecheck::CheckBytes<
+                                        ::ipis::rkyv::validation::validators::DefaultValidator<'__bytecheck>,
+                                    > + ::ipis::rkyv::Deserialize<
+                                        $output_ty,
+                                        ::ipis::rkyv::de::deserializers::SharedDeserializeMap,
+                                    >
+                                    + ::core::fmt::Debug
+                                    + PartialEq,
+                            )*
+                            $(
+                                $generic: ::ipis::core::signed::IsSigned
+                                    + ::ipis::rkyv::Archive
+                                    + ::ipis::rkyv::Serialize<::ipis::core::signature::SignatureSerializer>
+                                    + ::ipis::rkyv::Serialize<::ipis::core::signed::Serializer>
+                                    + ::core::fmt::Debug
+                                    + PartialEq
+                                    + Send
+                                    + Sync
+                                    + 'static,
+                                <$generic as ::ipis::rkyv::Archive>::Archived: for<'__bytecheck> ::ipis::bytecheck::CheckBytes<
+                                        ::ipis::rkyv::validation::validators::DefaultValidator<'__bytecheck>,
+                                    > + ::ipis::rkyv::Deserialize<
+                                        $generic,
+                                        ::ipis::rkyv::de::deserializers::SharedDeserializeMap,
+                                    >
+                                    + ::core::fmt::Debug
+                                    + PartialEq,
+                            )*
                         {
                             use ipis::tokio::io::AsyncWriteExt;
 
                             // make a flag
-                            let flag = super::super::ServerResult::ACK_OK;
+                            let flag = match server_time {
+                                Some(_) => super::super::ServerResult::ACK_OK_TIMED,
+                                None => super::super::ServerResult::ACK_OK,
+                            };
 
                             // send flag
                             send.write_u8(flag.bits()).await?;
+                            if let Some(server_time) = server_time {
+                                send.write_u64_le(server_time.as_micros().try_into().unwrap_or(u64::MAX))
+                                    .await?;
+                            }
 
                             // send sign
                             self.__sign.copy_to(&mut send).await?;
@@ -711,6 +2073,76 @@ macro_rules! define_io {
                             Ok(res)
                         }
                     }
+                    #[::ipis::async_trait::async_trait]
+                    impl<$( $generic, )* > $crate::IoFrame for $case<'static, $( $generic, )* >
+                    where
+                            <::ipis::core::data::Data<::ipis::core::account::GuaranteeSigned, String> as ::ipis::rkyv::Archive>::Archived: ::ipis::rkyv::Deserialize<
+                                    ::ipis::core::data::Data<::ipis::core::account::GuaranteeSigned, String>,
+                                    ::ipis::rkyv::de::deserializers::SharedDeserializeMap,
+                                >,
+                            $(
+                                $output_ty: ::ipis::rkyv::Archive + ::core::fmt::Debug + PartialEq + 'static,
+                                <$output_ty as ::ipis::rkyv::Archive>::Archived: for<'__bytecheck> ::ipis::bytecheck::CheckBytes<
+                                        ::ipis::rkyv::validation::validators::DefaultValidator<'__bytecheck>,
+                                    > + ::ipis::rkyv::Deserialize<
+                                        $output_ty,
+                                        ::ipis::rkyv::de::deserializers::SharedDeserializeMap,
+                                    >
+                                    + ::core::fmt::Debug
+                                    + PartialEq,
+                            )*
+                            $(
+                                $generic: ::ipis::core::signed::IsSigned
+                                    + ::ipis::rkyv::Archive
+                                    + ::ipis::rkyv::Serialize<::ipis::core::signature::SignatureSerializer>
+                                    + ::ipis::rkyv::Serialize<::ipis::core::signed::Serializer>
+                                    + Clone
+                                    + ::core::fmt::Debug
+                                    + PartialEq
+                                    + Send
+                                    + Sync
+                                    + 'static,
+                                <$generic as ::ipis::rkyv::Archive>::Archived: for<'__bytecheck> ::ipis::bytecheck::CheckBytes<
+                                        ::ipis::rkyv::validation::validators::DefaultValidator<'__bytecheck>,
+                                    > + ::ipis::rkyv::Deserialize<
+                                        $generic,
+                                        ::ipis::rkyv::de::deserializers::SharedDeserializeMap,
+                                    >
+                                    + ::core::fmt::Debug
+                                    + PartialEq,
+                            )*
+                    {
+                        async fn to_frame(&mut self) -> ::ipis::core::anyhow::Result<Vec<u8>> {
+                            let mut bytes = vec![];
+                            self.send_to(&mut bytes, None).await?;
+                            Ok(bytes)
+                        }
+
+                        async fn from_frame(mut bytes: &[u8]) -> ::ipis::core::anyhow::Result<Self> {
+                            // recv flag
+                            $crate::recv_server_result(&mut bytes).await?;
+
+                            // recv data
+                            let mut res = Self {
+                                __lifetime: Default::default(),
+                                __sign: ::ipis::stream::DynStream::recv(&mut bytes).await?,
+                                $(
+                                    $output_field: ::ipis::stream::DynStream::recv(&mut bytes).await?,
+                                )*
+                            };
+
+                            // validate data
+                            res.__sign.as_ref().await?;
+                            $(
+                                res.$output_field.as_ref().await?;
+                            )*
+
+                            if !bytes.is_empty() {
+                                ::ipis::core::anyhow::bail!("trailing bytes after the response: {} bytes", bytes.len());
+                            }
+                            Ok(res)
+                        }
+                    }
                 )*
             }
         }
@@ -727,13 +2159,26 @@ macro_rules! define_io {
 ///     client: self,
 ///     target: None => &primary,
 ///     request: ::ipiis_common::io => GetAccountPrimary,
-///     sign: self.sign(primary, Some(*kind))?,
+///     sign: self.sign(primary, (Some(*kind), 1))?,
+///     signer: &account, // optional (default: `account_me()`)
 ///     inputs: {
 ///         sign: self.sign(primary, Some(*kind))?,
 ///         kind: Some(*kind),
 ///     },
 ///     outputs: { account, address, },
 /// );
+///
+/// // external call, receiving the streamed outputs one at a time
+/// let ((count,), mut entries) = external_call!(
+///     client: self,
+///     target: None => &target,
+///     request: crate::io => ListAccounts,
+///     sign: self.sign(target, ())?,
+///     inputs: { },
+///     outputs: { count, },
+///     output_stream: true,
+/// );
+/// while let Some(entry) = entries.try_next().await? { /* ... */ }
 /// ```
 ///
 #[macro_export]
@@ -743,6 +2188,73 @@ macro_rules! external_call {
         target: $kind:expr => $target:expr,
         request: $io:path => $req:ident,
         sign: $input_sign:expr,
+        $( signer: $signer:expr ,)?
+        inputs: { $( $input_field:ident : $input_value:expr ,)* },
+        $( inputs_mode: $mode:ident ,)?
+        outputs: { $( $output:ident ,)* },
+        output_stream: true,
+    ) => {{
+        // pack request
+        #[allow(clippy::redundant_field_names)]
+        let mut req = external_call!(
+            client: $client,
+            target: $kind => $target,
+            request: $io => $req,
+            sign: $input_sign,
+            $( signer: $signer ,)?
+            inputs: { $( $input_field : $input_value ,)* },
+            $( inputs_mode: $mode ,)?
+            outputs: none,
+        );
+
+        // recv response, followed by the streamed outputs
+        #[allow(unused_mut)]
+        let (mut res, stream) = req.call_output_stream($client, $kind, $target).await?;
+
+        // unpack response
+        #[allow(clippy::unused_unit)]
+        ({( $( res.$output.to_owned().await?, )* )}, stream)
+    }};
+    (
+        client: $client:expr,
+        target: $kind:expr => $target:expr,
+        request: $io:path => $req:ident,
+        sign: $input_sign:expr,
+        $( signer: $signer:expr ,)?
+        inputs: { $( $input_field:ident : $input_value:expr ,)* },
+        $( inputs_mode: $mode:ident ,)?
+        input_stream: $input_stream:expr,
+        outputs: { $( $output:ident ,)* },
+    ) => {{
+        // pack request
+        #[allow(clippy::redundant_field_names)]
+        let mut req = external_call!(
+            client: $client,
+            target: $kind => $target,
+            request: $io => $req,
+            sign: $input_sign,
+            $( signer: $signer ,)?
+            inputs: { $( $input_field : $input_value ,)* },
+            $( inputs_mode: $mode ,)?
+            outputs: none,
+        );
+
+        // send the streamed input, and recv response
+        #[allow(unused_mut)]
+        let mut res = req
+            .call_streamed($client, $kind, $target, $input_stream)
+            .await?;
+
+        // unpack response
+        #[allow(clippy::unused_unit)]
+        {( $( res.$output.to_owned().await?, )* )}
+    }};
+    (
+        client: $client:expr,
+        target: $kind:expr => $target:expr,
+        request: $io:path => $req:ident,
+        sign: $input_sign:expr,
+        $( signer: $signer:expr ,)?
         inputs: { $( $input_field:ident : $input_value:expr ,)* },
         $( inputs_mode: $mode:ident ,)?
     ) => {
@@ -751,6 +2263,7 @@ macro_rules! external_call {
             target: $kind => $target,
             request: $io => $req,
             sign: $input_sign,
+            $( signer: $signer ,)?
             inputs: { $( $input_field : $input_value ,)* },
             $( inputs_mode: $mode ,)?
             outputs: { },
@@ -761,6 +2274,7 @@ macro_rules! external_call {
         target: $kind:expr => $target:expr,
         request: $io:path => $req:ident,
         sign: $input_sign:expr,
+        $( signer: $signer:expr ,)?
         inputs: { $( $input_field:ident : $input_value:expr ,)* },
         $( inputs_mode: $mode:ident ,)?
         outputs: { $( $output:ident ,)* },
@@ -774,6 +2288,7 @@ macro_rules! external_call {
             target: $kind => $target,
             request: $io => $req,
             sign: $input_sign,
+            $( signer: $signer ,)?
             inputs: { $( $input_field : $input_value ,)* },
             $( inputs_mode: $mode ,)?
             outputs: call,
@@ -788,6 +2303,7 @@ macro_rules! external_call {
         target: $kind:expr => $target:expr,
         request: $io:path => $req:ident,
         sign: $input_sign:expr,
+        $( signer: $signer:expr ,)?
         inputs: { $( $input_field:ident : $input_value:expr ,)* },
         $( inputs_mode: $mode:ident ,)?
         outputs: call,
@@ -799,6 +2315,7 @@ macro_rules! external_call {
             target: $kind => $target,
             request: $io => $req,
             sign: $input_sign,
+            $( signer: $signer ,)?
             inputs: { $( $input_field : $input_value ,)* },
             $( inputs_mode: $mode ,)?
             outputs: none,
@@ -812,6 +2329,7 @@ macro_rules! external_call {
         target: $kind:expr => $target:expr,
         request: $io:path => $req:ident,
         sign: $input_sign:expr,
+        $( signer: $signer:expr ,)?
         inputs: { $( $input_field:ident : $input_value:expr ,)* },
         $( inputs_mode: $mode:ident ,)?
         outputs: send,
@@ -823,6 +2341,7 @@ macro_rules! external_call {
             target: $kind => $target,
             request: $io => $req,
             sign: $input_sign,
+            $( signer: $signer ,)?
             inputs: { $( $input_field : $input_value ,)* },
             $( inputs_mode: $mode ,)?
             outputs: none,
@@ -836,6 +2355,33 @@ macro_rules! external_call {
         target: $kind:expr => $target:expr,
         request: $io:path => $req:ident,
         sign: $input_sign:expr,
+        $( signer: $signer:expr ,)?
+        inputs: { $( $input_field:ident : $input_value:expr ,)* },
+        $( inputs_mode: $mode:ident ,)?
+        outputs: oneway,
+    ) => {{
+        // pack request
+        #[allow(clippy::redundant_field_names)]
+        let mut req = external_call!(
+            client: $client,
+            target: $kind => $target,
+            request: $io => $req,
+            sign: $input_sign,
+            $( signer: $signer ,)?
+            inputs: { $( $input_field : $input_value ,)* },
+            $( inputs_mode: $mode ,)?
+            outputs: none,
+        );
+
+        // send request, without waiting for any response
+        req.send_oneway($client, $kind, $target).await?
+    }};
+    (
+        client: $client:expr,
+        target: $kind:expr => $target:expr,
+        request: $io:path => $req:ident,
+        sign: $input_sign:expr,
+        $( signer: $signer:expr ,)?
         inputs: { $( $input_field:ident : $input_value:expr ,)* },
         outputs: none,
     ) => {{
@@ -844,6 +2390,7 @@ macro_rules! external_call {
             target: $kind => $target,
             request: $io => $req,
             sign: $input_sign,
+            $( signer: $signer ,)?
             inputs: { $( $input_field : $input_value ,)* },
             inputs_mode: owned,
             outputs: none,
@@ -854,6 +2401,7 @@ macro_rules! external_call {
         target: $kind:expr => $target:expr,
         request: $io:path => $req:ident,
         sign: $input_sign:expr,
+        $( signer: $signer:expr ,)?
         inputs: { $( $input_field:ident : $input_value:expr ,)* },
         inputs_mode: owned,
         outputs: none,
@@ -863,6 +2411,7 @@ macro_rules! external_call {
             target: $kind => $target,
             request: $io => $req,
             sign: $input_sign,
+            $( signer: $signer ,)?
             inputs: { $( $input_field : ::ipis::stream::DynStream::Owned($input_value) ,)* },
             inputs_mode: none,
             outputs: none,
@@ -873,6 +2422,7 @@ macro_rules! external_call {
         target: $kind:expr => $target:expr,
         request: $io:path => $req:ident,
         sign: $input_sign:expr,
+        $( signer: $signer:expr ,)?
         inputs: { $( $input_field:ident : $input_value:expr ,)* },
         inputs_mode: none,
         outputs: none,
@@ -886,11 +2436,18 @@ macro_rules! external_call {
             // select the sign data
             let data = $input_sign;
 
+            // select the signer (default: `account_me()`)
+            let signer: Option<&::ipis::core::account::Account> = None $( .or(Some($signer)) )?;
+
             // sign it
             if data.is_signed_dyn() {
                 ::ipis::stream::DynStream::Owned(data)
             } else {
-                ::ipis::stream::DynStream::OwnedAlignedVec($client.sign_owned(*$target, data)?.to_bytes()?)
+                let data = match signer {
+                    Some(signer) => $client.sign_owned_as(signer, *$target, data)?,
+                    None => $client.sign_owned(*$target, data)?,
+                };
+                ::ipis::stream::DynStream::OwnedAlignedVec(data.to_bytes()?)
             }
         };
 
@@ -928,6 +2485,8 @@ macro_rules! handle_external_call {
         name: $name:ident,
         request: $io:path => { $( $opcode:ident => $handler:ident ,)* },
         $( request_raw: $io_raw:path => { $( $opcode_raw:ident => $handler_raw:ident ,)* },)?
+        $( request_stream: $io_stream:path => { $( $opcode_stream:ident => $handler_stream:ident ,)* },)?
+        $( response_stream: $io_output:path => { $( $opcode_output:ident => $handler_output:ident ,)* },)?
     ) => {
         impl $server {
             pub async fn $name(self) {
@@ -942,14 +2501,30 @@ macro_rules! handle_external_call {
             server: $server => $client,
             request: $io => { $( $opcode => $handler ,)* },
             $( request_raw: $io_raw => { $( $opcode_raw => $handler_raw ,)* },)?
+            $( request_stream: $io_stream => { $( $opcode_stream => $handler_stream ,)* },)?
+            $( response_stream: $io_output => { $( $opcode_output => $handler_output ,)* },)?
         );
     };
     (
         server: $server:ty => $client:ty,
         request: $io:path => { $( $opcode:ident => $handler:ident ,)* },
         $( request_raw: $io_raw:path => { $( $opcode_raw:ident => $handler_raw:ident ,)* },)?
+        $( request_stream: $io_stream:path => { $( $opcode_stream:ident => $handler_stream:ident ,)* },)?
+        $( response_stream: $io_output:path => { $( $opcode_output:ident => $handler_output:ident ,)* },)?
     ) => {
         impl $server {
+            /// Returns the names of the opcodes handled by the server, as registered.
+            ///
+            /// The raw handlers registered at runtime are not included.
+            pub fn supported_opcodes() -> &'static [&'static str] {
+                &[
+                    $( stringify!($opcode), )*
+                    $($( stringify!($opcode_raw), )*)?
+                    $($( stringify!($opcode_stream), )*)?
+                    $($( stringify!($opcode_output), )*)?
+                ]
+            }
+
             async fn __handle<__IpiisClient>(
                 client: Arc<$client>,
                 mut send: <__IpiisClient as Ipiis>::Writer,
@@ -964,6 +2539,15 @@ macro_rules! handle_external_call {
                 match Self::__try_handle(&client, &mut send, recv).await {
                     Ok(()) => Ok(()),
                     Err(e) => {
+                        // suggest the client to re-send the shed request later
+                        if let Some($crate::IpiisError::RetryAfter(delay)) = e.downcast_ref() {
+                            let delay_ms = delay.as_millis().try_into().unwrap_or(u64::MAX);
+
+                            send.write_u8(ServerResult::ACK_RETRY_AFTER.bits()).await?;
+                            send.write_u64_le(delay_ms).await?;
+                            return Ok(());
+                        }
+
                         // collect data
                         let mut data = ::ipis::stream::DynStream::Owned(e.to_string());
 
@@ -993,32 +2577,253 @@ macro_rules! handle_external_call {
                 use $io::{OpCode, request};
 
                 // recv opcode
-                let opcode: OpCode = ::ipis::stream::DynStream::recv(&mut recv)
-                    .await?
-                    .to_owned()
-                    .await?;
+                let code = {
+                    use ipis::tokio::io::AsyncReadExt;
+
+                    recv.read_u16_le().await?
+                };
+                let opcode = match OpCode::try_from(code) {
+                    Ok(opcode) => opcode,
+                    // pass the others to the handlers registered at runtime
+                    Err(e) => {
+                        use ipis::tokio::io::AsyncWriteExt;
+
+                        let handlers = AsRef::<__IpiisClient>::as_ref(client)
+                            .raw_handlers()
+                            .filter(|handlers| handlers.contains(code));
+                        let handlers = match handlers {
+                            Some(handlers) => handlers,
+                            None => return Err(e.into()),
+                        };
+
+                        // account the transferred bytes to the verified account
+                        let meter = $crate::MeteredRequest::new(
+                            AsRef::<__IpiisClient>::as_ref(client).byte_meter(),
+                        );
+                        let mut send = meter.stream(&mut *send);
+                        let mut recv = meter.stream(recv);
+
+                        // verify the request as the typed ones
+                        meter
+                            .scope($crate::recv_raw_request_header(
+                                AsRef::<__IpiisClient>::as_ref(client),
+                                code,
+                                &mut recv,
+                            ))
+                            .await?;
+
+                        return match handlers.handle(code, Box::pin(recv)) {
+                            Some(handler) => {
+                                let response = meter.scope(handler).await?;
+                                send.write_all(&response).await.map_err(Into::into)
+                            }
+                            None => Err(e.into()),
+                        };
+                    }
+                };
+                let instant = ::std::time::Instant::now();
+                let server_time = || {
+                    AsRef::<__IpiisClient>::as_ref(client)
+                        .reports_server_time()
+                        .then(|| instant.elapsed())
+                };
+
+                // wait for the turn of the request
+                let _permit = match AsRef::<__IpiisClient>::as_ref(client).request_scheduler() {
+                    Some(scheduler) => Some(scheduler.acquire(opcode.priority()).await?),
+                    None => None,
+                };
 
                 // select command
                 match opcode {
                     $(
                         OpCode::$opcode => {
+                            // account the transferred bytes to the verified account
+                            let meter = $crate::MeteredRequest::new(
+                                AsRef::<__IpiisClient>::as_ref(client).byte_meter(),
+                            );
+                            let mut send = meter.stream(&mut *send);
+
+                            // reserve the in-flight bytes until the request is handled
+                            let recv = $crate::BudgetedReader::new(
+                                meter.stream(recv),
+                                AsRef::<__IpiisClient>::as_ref(client).request_budget(),
+                            );
+
+                            // fingerprint the mutations to be deduplicated
+                            let cache = AsRef::<__IpiisClient>::as_ref(client)
+                                .response_cache()
+                                .filter(|_| !opcode.is_idempotent() && !opcode.is_oneway());
+                            let mut recv = $crate::FingerprintReader::new(recv, cache.is_some());
+
                             // recv request
-                            let mut req = request::$opcode::recv(client.as_ref(), recv).await?;
+                            let mut req = meter
+                                .scope($crate::try_recv_request(request::$opcode::recv(
+                                    client.as_ref(),
+                                    &mut recv,
+                                )))
+                                .await?;
+
+                            // reject the revoked accounts
+                            if let Some(list) = AsRef::<__IpiisClient>::as_ref(client).revocation_list() {
+                                let account = req.__sign.as_ref().await?.metadata.guarantee;
+                                if list.is_revoked(&account) {
+                                    return Err($crate::IpiisError::Revoked(account.to_string()).into());
+                                }
+                            }
+
+                            // replay the response of the duplicated request
+                            let slot = match (cache, recv.fingerprint()) {
+                                (Some(cache), Some(request_id)) => {
+                                    let account = req.__sign.as_ref().await?.metadata.guarantee;
+                                    match cache.begin(account, opcode as u16, request_id).await {
+                                        $crate::CacheLookup::Replay(response) => {
+                                            use ipis::tokio::io::AsyncWriteExt;
+                                            return send.write_all(&response).await.map_err(Into::into);
+                                        }
+                                        $crate::CacheLookup::Execute(slot) => Some(slot),
+                                    }
+                                }
+                                _ => None,
+                            };
+
+                            // handle the one-way request, which no one waits for
+                            if opcode.is_oneway() {
+                                let handler = $crate::with_deadline(
+                                    opcode,
+                                    opcode.timeout(),
+                                    Self::$handler(client, req),
+                                );
+                                if let Err(e) = handler.await {
+                                    ::ipis::log::warn!("failed to handle the one-way request {opcode:?}: {e}");
+                                }
+                                return Ok(());
+                            }
 
-                            // handle request
-                            let mut res = Self::$handler(client, req).await?;
+                            // handle request, sending its progress
+                            let handler =
+                                $crate::with_deadline(opcode, opcode.timeout(), Self::$handler(client, req));
+                            let mut res = $crate::forward_progress(&mut send, handler).await?;
 
                             // send response
-                            res.send(client.as_ref(), &mut *send).await
+                            match slot {
+                                Some(slot) => {
+                                    use ipis::tokio::io::AsyncWriteExt;
+
+                                    let mut response = vec![];
+                                    res.send_to(&mut response, server_time()).await?;
+
+                                    let response: ::std::sync::Arc<[u8]> = response.into();
+                                    slot.complete(response.clone());
+                                    send.write_all(&response).await.map_err(Into::into)
+                                }
+                                None => res.send_to(&mut send, server_time()).await,
+                            }
                         }
                     )*
                     $($(
                         OpCode::$opcode_raw => {
-                            // handle raw request
-                            let mut res = Self::$handler_raw(client, recv).await?;
+                            // account the transferred bytes to the verified account
+                            let meter = $crate::MeteredRequest::new(
+                                AsRef::<__IpiisClient>::as_ref(client).byte_meter(),
+                            );
+                            let mut send = meter.stream(&mut *send);
+
+                            // handle raw request, sending its progress
+                            let handler = $crate::with_deadline(
+                                opcode,
+                                opcode.timeout(),
+                                Self::$handler_raw(client, meter.stream(recv)),
+                            );
+                            let mut res = meter
+                                .scope($crate::forward_progress(&mut send, handler))
+                                .await?;
 
                             // send response
-                            res.send(client.as_ref(), &mut *send).await
+                            res.send_to(&mut send, server_time()).await
+                        },
+                    )*)?
+                    $($(
+                        OpCode::$opcode_stream => {
+                            // account the transferred bytes to the verified account
+                            let meter = $crate::MeteredRequest::new(
+                                AsRef::<__IpiisClient>::as_ref(client).byte_meter(),
+                            );
+                            let mut send = meter.stream(&mut *send);
+
+                            // the streamed input is not buffered, so neither budgeted nor deduplicated
+                            let mut recv = meter.stream(recv);
+
+                            // recv request, except its streamed input
+                            let mut req = meter
+                                .scope($crate::try_recv_request(request::$opcode_stream::recv(
+                                    client.as_ref(),
+                                    &mut recv,
+                                )))
+                                .await?;
+
+                            // reject the revoked accounts
+                            if let Some(list) = AsRef::<__IpiisClient>::as_ref(client).revocation_list() {
+                                let account = req.__sign.as_ref().await?.metadata.guarantee;
+                                if list.is_revoked(&account) {
+                                    return Err($crate::IpiisError::Revoked(account.to_string()).into());
+                                }
+                            }
+
+                            // handle request, consuming its streamed input and sending its progress
+                            let stream = $crate::InputStream::new(&mut recv);
+                            let handler = $crate::with_deadline(
+                                opcode,
+                                opcode.timeout(),
+                                Self::$handler_stream(client, req, stream),
+                            );
+                            let mut res = $crate::forward_progress(&mut send, handler).await?;
+
+                            // send response
+                            res.send_to(&mut send, server_time()).await
+                        },
+                    )*)?
+                    $($(
+                        OpCode::$opcode_output => {
+                            // account the transferred bytes to the verified account
+                            let meter = $crate::MeteredRequest::new(
+                                AsRef::<__IpiisClient>::as_ref(client).byte_meter(),
+                            );
+                            let mut send = meter.stream(&mut *send);
+
+                            // the streamed outputs are not buffered, so not deduplicated
+                            let mut recv = $crate::BudgetedReader::new(
+                                meter.stream(recv),
+                                AsRef::<__IpiisClient>::as_ref(client).request_budget(),
+                            );
+
+                            // recv request
+                            let mut req = meter
+                                .scope($crate::try_recv_request(request::$opcode_output::recv(
+                                    client.as_ref(),
+                                    &mut recv,
+                                )))
+                                .await?;
+
+                            // reject the revoked accounts
+                            if let Some(list) = AsRef::<__IpiisClient>::as_ref(client).revocation_list() {
+                                let account = req.__sign.as_ref().await?.metadata.guarantee;
+                                if list.is_revoked(&account) {
+                                    return Err($crate::IpiisError::Revoked(account.to_string()).into());
+                                }
+                            }
+
+                            // handle request, sending its progress
+                            let handler = $crate::with_deadline(
+                                opcode,
+                                opcode.timeout(),
+                                Self::$handler_output(client, req),
+                            );
+                            let (mut res, stream) = $crate::forward_progress(&mut send, handler).await?;
+
+                            // send response, followed by the streamed outputs
+                            res.send_to(&mut send, server_time()).await?;
+                            $crate::copy_output_stream(stream, &mut send).await.map(|_| ())
                         },
                     )*)?
                 }
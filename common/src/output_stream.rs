@@ -0,0 +1,114 @@
+use bytecheck::CheckBytes;
+use ipis::{
+    core::{
+        anyhow::{bail, Result},
+        signature::SignatureSerializer,
+        signed::IsSigned,
+    },
+    futures::{
+        stream::{self, BoxStream},
+        Stream, StreamExt,
+    },
+    stream::DynStream,
+    tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt},
+};
+use rkyv::{
+    de::deserializers::SharedDeserializeMap, validation::validators::DefaultValidator, Archive,
+    Deserialize, Serialize,
+};
+
+/// Marks an item of the streamed outputs.
+const MARKER_ITEM: u8 = 1;
+
+/// Marks the end of the streamed outputs.
+const MARKER_END: u8 = 0;
+
+/// Sends the items as the streamed outputs of a response, returning the number of the items sent.
+///
+/// On the wire, they follow the other outputs, each prefixed by a marker,
+/// and terminated by the end marker.
+pub async fn copy_output_stream<S, T, W>(stream: S, send: &mut W) -> Result<u64>
+where
+    S: Stream<Item = T>,
+    T: IsSigned
+        + Archive
+        + Serialize<SignatureSerializer>
+        + Serialize<::ipis::core::signed::Serializer>
+        + ::core::fmt::Debug
+        + PartialEq
+        + Send
+        + Sync
+        + 'static,
+    <T as Archive>::Archived: for<'a> CheckBytes<DefaultValidator<'a>>
+        + Deserialize<T, SharedDeserializeMap>
+        + ::core::fmt::Debug
+        + PartialEq,
+    W: AsyncWrite + Unpin,
+{
+    let mut stream = Box::pin(stream);
+    let mut count = 0;
+
+    while let Some(item) = stream.next().await {
+        send.write_u8(MARKER_ITEM).await?;
+        DynStream::Owned(item).copy_to(&mut *send).await?;
+        count += 1;
+    }
+
+    send.write_u8(MARKER_END).await?;
+    send.flush().await?;
+    Ok(count)
+}
+
+/// Receives the streamed outputs of a response, one item at a time.
+pub fn recv_output_stream<T, R>(recv: R) -> BoxStream<'static, Result<T>>
+where
+    T: IsSigned
+        + Archive
+        + Serialize<SignatureSerializer>
+        + Serialize<::ipis::core::signed::Serializer>
+        + ::core::fmt::Debug
+        + PartialEq
+        + Send
+        + Sync
+        + 'static,
+    <T as Archive>::Archived: for<'a> CheckBytes<DefaultValidator<'a>>
+        + Deserialize<T, SharedDeserializeMap>
+        + ::core::fmt::Debug
+        + PartialEq,
+    R: AsyncRead + Send + Unpin + 'static,
+{
+    stream::unfold(Some(recv), |recv| async move {
+        let mut recv = recv?;
+        match recv_item(&mut recv).await {
+            Ok(Some(item)) => Some((Ok(item), Some(recv))),
+            Ok(None) => None,
+            // stop on the first error
+            Err(e) => Some((Err(e), None)),
+        }
+    })
+    .boxed()
+}
+
+async fn recv_item<T, R>(recv: &mut R) -> Result<Option<T>>
+where
+    T: IsSigned
+        + Archive
+        + Serialize<SignatureSerializer>
+        + Serialize<::ipis::core::signed::Serializer>
+        + ::core::fmt::Debug
+        + PartialEq
+        + Send
+        + Sync
+        + 'static,
+    <T as Archive>::Archived: for<'a> CheckBytes<DefaultValidator<'a>>
+        + Deserialize<T, SharedDeserializeMap>
+        + ::core::fmt::Debug
+        + PartialEq,
+    R: AsyncRead + Unpin,
+{
+    match recv.read_u8().await? {
+        MARKER_ITEM => DynStream::recv(recv).await?.into_owned().await.map(Some),
+        MARKER_END => Ok(None),
+        marker => bail!("unexpected marker of the streamed outputs: {marker}"),
+    }
+}
@@ -0,0 +1,187 @@
+use core::{future::Future, time::Duration};
+use std::time::Instant;
+
+use bytecheck::CheckBytes;
+use ipis::{
+    core::{account::AccountRef, anyhow::Result, signed::IsSigned, value::hash::Hash},
+    futures::{stream, StreamExt},
+};
+use rkyv::{Archive, Deserialize, Serialize};
+
+use crate::Ipiis;
+
+/// The maximum number of the hops to trace, as the TTL of `traceroute`.
+pub const MAX_HOPS: usize = 16;
+
+/// A random challenge echoed back by the `Ping` target.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Archive, Serialize, Deserialize)]
+#[archive(compare(PartialEq))]
+#[archive_attr(derive(CheckBytes, Copy, Clone, Debug, PartialEq, Eq))]
+pub struct Nonce(pub [u8; 32]);
+
+impl IsSigned for Nonce {}
+
+impl Nonce {
+    pub fn generate() -> Self {
+        Self(::rand::random())
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct PingReport {
+    /// Measured round-trip time
+    pub rtt: Duration,
+
+    /// Processing time reported by the target, if any
+    pub server_time: Option<Duration>,
+
+    /// Whether the target has signed the nonce back with the expected key
+    pub identity_confirmed: bool,
+}
+
+/// The raw throughput of the connection to a target.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct BandwidthReport {
+    /// Number of the bytes sent and discarded by the target
+    pub bytes: u64,
+
+    /// Elapsed time of sending the bytes, until the target has acknowledged them
+    pub elapsed: Duration,
+
+    /// Measured round-trip time, before sending the bytes
+    pub rtt: Duration,
+
+    /// Achieved throughput, in bits per second
+    pub bps: f64,
+}
+
+impl BandwidthReport {
+    pub fn new(bytes: u64, elapsed: Duration, rtt: Duration) -> Self {
+        Self {
+            bytes,
+            elapsed,
+            rtt,
+            bps: bytes as f64 * 8.0 / elapsed.as_secs_f64(),
+        }
+    }
+}
+
+/// The aggregate of the pings flooded to a target.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct PingFloodReport {
+    /// Number of the pings confirming the identity of the target
+    pub succeeded: usize,
+
+    /// Number of the pings failed or not confirming the identity of the target
+    pub failed: usize,
+
+    /// Elapsed time of the whole flood
+    pub elapsed: Duration,
+
+    /// Succeeded pings per second
+    pub iops: f64,
+
+    /// 99th percentile round-trip time of the succeeded pings
+    pub rtt_p99: Duration,
+}
+
+/// Fires `count` pings to the target, at most `concurrency` at once,
+/// e.g. for an ad-hoc load test without the bench harness.
+pub async fn ping_flood<C>(
+    client: &C,
+    kind: Option<&Hash>,
+    target: &AccountRef,
+    count: usize,
+    concurrency: usize,
+) -> PingFloodReport
+where
+    C: Ipiis + Sync,
+{
+    let instant = Instant::now();
+    let results: Vec<_> = stream::iter(0..count)
+        .map(|_| client.ping(kind, target))
+        .buffer_unordered(concurrency.max(1))
+        .collect()
+        .await;
+    let elapsed = instant.elapsed();
+
+    let mut rtts: Vec<_> = results
+        .into_iter()
+        .filter_map(Result::ok)
+        .filter(|report| report.identity_confirmed)
+        .map(|report| report.rtt)
+        .collect();
+    rtts.sort_unstable();
+
+    PingFloodReport {
+        succeeded: rtts.len(),
+        failed: count - rtts.len(),
+        elapsed,
+        iops: rtts.len() as f64 / elapsed.as_secs_f64(),
+        rtt_p99: percentile(&rtts, 0.99),
+    }
+}
+
+/// The latencies of the address lookups repeated by [`bench_resolve`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct ResolveReport {
+    /// Number of the lookups resolved
+    pub succeeded: usize,
+
+    /// Number of the lookups failed
+    pub failed: usize,
+
+    /// Median latency of the resolved lookups
+    pub p50: Duration,
+
+    /// 99th percentile latency of the resolved lookups
+    pub p99: Duration,
+}
+
+/// Repeats `count` lookups one by one, measuring the latency of each,
+/// e.g. with the clients' `get_address_uncached` to bypass the local address book.
+pub async fn bench_resolve<F, Fut, T>(count: usize, mut resolve: F) -> ResolveReport
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T>>,
+{
+    let mut latencies = Vec::with_capacity(count);
+    for _ in 0..count {
+        let instant = Instant::now();
+        if resolve().await.is_ok() {
+            latencies.push(instant.elapsed());
+        }
+    }
+    latencies.sort_unstable();
+
+    ResolveReport {
+        succeeded: latencies.len(),
+        failed: count - latencies.len(),
+        p50: percentile(&latencies, 0.50),
+        p99: percentile(&latencies, 0.99),
+    }
+}
+
+/// Returns the nearest-rank percentile of the sorted durations.
+fn percentile(sorted: &[Duration], q: f64) -> Duration {
+    match sorted.len() {
+        0 => Duration::ZERO,
+        len => sorted[((len as f64 * q).ceil() as usize).clamp(1, len) - 1],
+    }
+}
+
+/// A primary consulted while tracing the resolution of an address.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HopInfo {
+    /// Account of the primary
+    pub account: AccountRef,
+
+    /// Measured round-trip time of the lookup
+    pub rtt: Duration,
+
+    /// Whether the primary has resolved the target
+    pub answered: bool,
+
+    /// Why the primary has not resolved the target, if so
+    pub error: Option<String>,
+}
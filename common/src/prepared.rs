@@ -0,0 +1,51 @@
+use bytecheck::CheckBytes;
+use ipis::core::{account::AccountRef, anyhow::Result, value::hash::Hash};
+use rkyv::{Archive, Deserialize, Serialize};
+
+use crate::{IoFrame, Ipiis};
+
+/// A request signed in advance, e.g. on an offline machine holding the key,
+/// to be sent later by the other client as it is.
+///
+/// The sender does not need the key of the signer, but the sign still binds
+/// the request to the target, so it cannot be redirected to the others.
+#[derive(Clone, Debug, PartialEq, Eq, Archive, Serialize, Deserialize)]
+#[archive(compare(PartialEq))]
+#[archive_attr(derive(CheckBytes, Debug, PartialEq, Eq))]
+pub struct SignedRequestBytes {
+    pub target: AccountRef,
+    /// The request as it is sent on the wire, including its opcode
+    pub bytes: Vec<u8>,
+}
+
+/// Writes the signed request to the bytes for the target, without sending it.
+///
+/// The request is usually built with the `outputs: none` mode of
+/// [`external_call!`](crate::external_call), signed by the key holder.
+pub async fn prepare_signed_request<Req>(
+    target: AccountRef,
+    mut request: Req,
+) -> Result<SignedRequestBytes>
+where
+    Req: IoFrame,
+{
+    Ok(SignedRequestBytes {
+        target,
+        bytes: request.to_frame().await?,
+    })
+}
+
+/// Sends the pre-signed request to its target, returning the stream of its response,
+/// right after the result flag.
+///
+/// The response is signed for the signer of the request, not for the sender.
+pub async fn send_prepared<C>(
+    client: &C,
+    kind: Option<&Hash>,
+    prepared: &SignedRequestBytes,
+) -> Result<<C as Ipiis>::Reader>
+where
+    C: Ipiis,
+{
+    crate::replay_bytes(client, kind, &prepared.target, &prepared.bytes).await
+}
@@ -0,0 +1,86 @@
+use core::future::Future;
+use std::sync::Arc;
+
+use ipis::{
+    core::anyhow::Result,
+    tokio::{
+        self,
+        io::{AsyncWrite, AsyncWriteExt},
+        sync::mpsc,
+    },
+};
+
+use crate::ServerResult;
+
+/// The progress of a long request, sent ahead of its response.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Progress {
+    pub processed: u64,
+    pub total: u64,
+}
+
+type ProgressCallback = Arc<dyn Fn(Progress) + Send + Sync>;
+
+tokio::task_local! {
+    /// The progress sink of the request being handled
+    static PROGRESS_SENDER: mpsc::UnboundedSender<Progress>;
+
+    /// The progress callback of the request being sent
+    static PROGRESS_CALLBACK: ProgressCallback;
+}
+
+/// Reports the progress of the request being handled to its client.
+///
+/// It does nothing if called outside of a handler.
+pub fn report_progress(processed: u64, total: u64) {
+    let _ = PROGRESS_SENDER.try_with(|sender| sender.send(Progress { processed, total }));
+}
+
+/// Runs the requests, observing their progress reported by the servers.
+pub async fn on_progress<F, Fut>(callback: F, requests: Fut) -> Fut::Output
+where
+    F: Fn(Progress) + Send + Sync + 'static,
+    Fut: Future,
+{
+    PROGRESS_CALLBACK.scope(Arc::new(callback), requests).await
+}
+
+/// Passes the received progress to the callback, if any.
+pub(crate) fn notify_progress(progress: Progress) {
+    let _ = PROGRESS_CALLBACK.try_with(|callback| callback(progress));
+}
+
+/// Runs the handler, sending its progress as the intermediate frames.
+pub async fn forward_progress<W, F, T>(mut send: W, handler: F) -> Result<T>
+where
+    W: AsyncWrite + Unpin,
+    F: Future<Output = Result<T>>,
+{
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let handler = PROGRESS_SENDER.scope(tx, handler);
+    tokio::pin!(handler);
+
+    loop {
+        tokio::select! {
+            biased;
+            Some(progress) = rx.recv() => send_progress(&mut send, progress).await?,
+            result = &mut handler => {
+                // flush the progress reported just before the completion
+                while let Ok(progress) = rx.try_recv() {
+                    send_progress(&mut send, progress).await?;
+                }
+                break result;
+            }
+        }
+    }
+}
+
+async fn send_progress<W>(mut send: W, progress: Progress) -> Result<()>
+where
+    W: AsyncWrite + Unpin,
+{
+    send.write_u8(ServerResult::PROGRESS.bits()).await?;
+    send.write_u64_le(progress.processed).await?;
+    send.write_u64_le(progress.total).await?;
+    Ok(())
+}
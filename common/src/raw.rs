@@ -0,0 +1,153 @@
+use core::{fmt, pin::Pin};
+use std::{
+    collections::HashMap,
+    sync::{Arc, RwLock},
+};
+
+use ipis::{
+    core::{
+        account::{AccountRef, GuaranteeSigned, Verifier},
+        anyhow::{bail, Error, Result},
+        data::Data,
+    },
+    futures::{
+        future::{BoxFuture, FutureExt},
+        Future,
+    },
+    stream::DynStream,
+    tokio::io::{AsyncRead, AsyncWrite, AsyncWriteExt},
+};
+
+use crate::{Ipiis, IpiisError, MeteredRequest, TransportIdentity, VerificationFailure};
+
+/// The stream of a request handled by [`RawHandlers`], right after its opcode.
+pub type RawReader = Pin<Box<dyn AsyncRead + Send + Sync>>;
+
+type RawHandler = Arc<dyn Fn(RawReader) -> BoxFuture<'static, Result<Vec<u8>>> + Send + Sync>;
+
+/// The handlers of the raw requests registered at runtime, by their opcodes,
+/// so that the simple services do not need a server of their own.
+///
+/// Each handler returns the bytes of the response, starting with its result flag,
+/// e.g. made by [`IoFrame::to_frame`](crate::IoFrame::to_frame).
+///
+/// The requests are guarded as the typed ones before reaching the handlers,
+/// so they should start with the header sent by [`send_raw_request_header`].
+#[derive(Clone, Default)]
+pub struct RawHandlers(Arc<RwLock<HashMap<u16, RawHandler>>>);
+
+impl fmt::Debug for RawHandlers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut opcodes: Vec<_> = self.0.read().unwrap().keys().copied().collect();
+        opcodes.sort_unstable();
+
+        f.debug_tuple("RawHandlers").field(&opcodes).finish()
+    }
+}
+
+impl RawHandlers {
+    /// Registers the handler of the opcode, replacing the old one if any.
+    pub fn insert<F, Fut>(&self, opcode: u16, handler: F)
+    where
+        F: Fn(RawReader) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<Vec<u8>>> + Send + 'static,
+    {
+        let handler: RawHandler = Arc::new(move |recv| handler(recv).boxed());
+        self.0.write().unwrap().insert(opcode, handler);
+    }
+
+    /// Whether the opcode has a handler.
+    pub fn contains(&self, opcode: u16) -> bool {
+        self.0.read().unwrap().contains_key(&opcode)
+    }
+
+    /// Unregisters the handler of the opcode, returning whether it has existed.
+    pub fn remove(&self, opcode: u16) -> bool {
+        self.0.write().unwrap().remove(&opcode).is_some()
+    }
+
+    /// Passes the request to the handler of the opcode, if any.
+    pub fn handle(
+        &self,
+        opcode: u16,
+        recv: RawReader,
+    ) -> Option<BoxFuture<'static, Result<Vec<u8>>>> {
+        let handler = self.0.read().unwrap().get(&opcode).cloned()?;
+        Some(handler(recv))
+    }
+}
+
+/// Sends the header of a raw request handled by [`RawHandlers`]:
+/// its opcode, followed by the opcode signed for the target.
+pub async fn send_raw_request_header<C, W>(
+    client: &C,
+    target: &AccountRef,
+    opcode: u16,
+    send: &mut W,
+) -> Result<()>
+where
+    C: Ipiis + ?Sized,
+    W: AsyncWrite + Send + Unpin,
+{
+    let sign = client.sign_owned(*target, opcode)?;
+
+    send.write_u16_le(opcode).await?;
+    DynStream::Owned(sign).copy_to(send).await
+}
+
+/// Receives the signed opcode of a raw request, right after its opcode,
+/// verifying it as the typed requests are, and returns the verified account.
+///
+/// The failed verifications are audited, the request is accounted to the verified account,
+/// and the revoked accounts are rejected.
+pub async fn recv_raw_request_header<C, R>(
+    client: &C,
+    opcode: u16,
+    recv: &mut R,
+) -> Result<AccountRef>
+where
+    C: Ipiis + ?Sized,
+    R: AsyncRead + Send + Unpin,
+{
+    let data: Data<GuaranteeSigned, u16> = DynStream::recv(recv).await?.into_owned().await?;
+    let account = data.metadata.guarantee;
+
+    // verify it, its age, its transport and its opcode, auditing the failure
+    let verified = data
+        .verify(Some(client.account_ref()))
+        .map_err(|e| Error::from(IpiisError::InvalidSignature(e.to_string())))
+        .and_then(|_| {
+            client
+                .time_policy()
+                .verify_signed_at(&data.metadata.created_date)
+        })
+        .and_then(|_| TransportIdentity::check(&account))
+        .and_then(|_| {
+            if data.data == opcode {
+                Ok(())
+            } else {
+                bail!("the opcode is signed for another one: {}", data.data)
+            }
+        });
+    if let Err(e) = verified {
+        if let Some(audit) = client.verification_audit() {
+            audit.report(VerificationFailure {
+                account,
+                opcode: "Raw",
+                reason: e.to_string(),
+            });
+        }
+        return Err(e);
+    }
+
+    // account the request to the verified account
+    MeteredRequest::identify(&account)?;
+
+    // reject the revoked accounts
+    if let Some(list) = client.revocation_list() {
+        if list.is_revoked(&account) {
+            return Err(IpiisError::Revoked(account.to_string()).into());
+        }
+    }
+    Ok(account)
+}
@@ -0,0 +1,131 @@
+use core::{
+    future::Future,
+    sync::atomic::{AtomicU64, Ordering},
+    time::Duration,
+};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::Instant,
+};
+
+use ipis::{
+    core::{account::AccountRef, anyhow::Result},
+    futures::future::{self, Either},
+    tokio::sync::oneshot,
+};
+
+use crate::IpiisError;
+
+/// A registry of the outgoing requests, to list and cancel them.
+#[derive(Clone, Debug, Default)]
+pub struct RequestRegistry {
+    next_id: Arc<AtomicU64>,
+    requests: Arc<Mutex<HashMap<u64, RequestEntry>>>,
+}
+
+#[derive(Debug)]
+struct RequestEntry {
+    target: AccountRef,
+    opcode: u16,
+    started: Instant,
+    cancel: Option<oneshot::Sender<()>>,
+}
+
+/// A snapshot of an in-flight request.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct RequestHandle {
+    pub id: u64,
+    pub target: AccountRef,
+    pub opcode: u16,
+    pub elapsed: Duration,
+}
+
+impl RequestRegistry {
+    /// Registers an outgoing request, which is deregistered when the registration is dropped.
+    pub fn register(&self, target: AccountRef, opcode: u16) -> RequestRegistration {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let (tx, rx) = oneshot::channel();
+
+        self.requests.lock().unwrap().insert(
+            id,
+            RequestEntry {
+                target,
+                opcode,
+                started: Instant::now(),
+                cancel: Some(tx),
+            },
+        );
+
+        RequestRegistration {
+            id,
+            registry: self.clone(),
+            cancelled: rx,
+        }
+    }
+
+    /// Returns the in-flight requests, in the order of issue.
+    pub fn in_flight(&self) -> Vec<RequestHandle> {
+        let mut requests: Vec<_> = self
+            .requests
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(&id, entry)| RequestHandle {
+                id,
+                target: entry.target,
+                opcode: entry.opcode,
+                elapsed: entry.started.elapsed(),
+            })
+            .collect();
+        requests.sort_by_key(|request| request.id);
+        requests
+    }
+
+    /// Aborts the in-flight request, dropping its stream.
+    ///
+    /// Returns `false` if there is no such request.
+    pub fn cancel(&self, id: u64) -> bool {
+        match self
+            .requests
+            .lock()
+            .unwrap()
+            .get_mut(&id)
+            .and_then(|entry| entry.cancel.take())
+        {
+            Some(cancel) => cancel.send(()).is_ok(),
+            None => false,
+        }
+    }
+}
+
+/// A registered outgoing request.
+#[derive(Debug)]
+pub struct RequestRegistration {
+    id: u64,
+    registry: RequestRegistry,
+    cancelled: oneshot::Receiver<()>,
+}
+
+impl RequestRegistration {
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    /// Runs the request until it completes or is cancelled.
+    pub async fn run<T>(mut self, request: impl Future<Output = Result<T>>) -> Result<T> {
+        let request = Box::pin(request);
+        match future::select(request, &mut self.cancelled).await {
+            Either::Left((result, _)) => result,
+            Either::Right((Ok(()), _)) => Err(IpiisError::Cancelled(self.id).into()),
+            // the cancellation has been abandoned
+            Either::Right((Err(_), request)) => request.await,
+        }
+    }
+}
+
+impl Drop for RequestRegistration {
+    fn drop(&mut self) {
+        self.registry.requests.lock().unwrap().remove(&self.id);
+    }
+}
@@ -0,0 +1,40 @@
+use core::future::Future;
+
+use ipis::{
+    core::{anyhow::Result, value::hash::Hash},
+    tokio,
+};
+
+use crate::IpiisError;
+
+/// The default maximum number of the primaries forwarding a lookup of the kind's primary account.
+pub const DEFAULT_MAX_RESOLUTION_DEPTH: u8 = 8;
+
+tokio::task_local! {
+    /// The depth of the lookup being handled
+    static RESOLUTION_DEPTH: u8;
+}
+
+/// Runs the handler of a lookup forwarded at the given depth,
+/// so that the lookups issued by the handler are one level deeper.
+pub async fn with_resolution_depth<F>(depth: u8, handler: F) -> F::Output
+where
+    F: Future,
+{
+    RESOLUTION_DEPTH.scope(depth, handler).await
+}
+
+/// Returns the depth of the next lookup of the kind's primary account,
+/// failing if it would exceed the limit.
+///
+/// The lookups issued outside of a handler start at `1`.
+pub fn next_resolution_depth(kind: &Hash, max_depth: u8) -> Result<u8> {
+    let depth = RESOLUTION_DEPTH
+        .try_with(|depth| *depth)
+        .unwrap_or_default();
+    if depth >= max_depth {
+        Err(IpiisError::KindResolutionTooDeep(format!("{kind} at depth {depth}")).into())
+    } else {
+        Ok(depth + 1)
+    }
+}
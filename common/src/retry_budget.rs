@@ -0,0 +1,93 @@
+use core::time::Duration;
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    time::Instant,
+};
+
+/// A client-wide budget of the retries, shared by all the retry sites,
+/// e.g. the address resolution, the shed requests and the reconnection.
+///
+/// It is a token bucket holding at most `retries` tokens, refilled evenly over `window`,
+/// so that the total volume of the retries is bounded even when many calls fail at once.
+/// A retry without a token fails fast, returning the last error instead.
+#[derive(Clone, Debug)]
+pub struct RetryBudget {
+    state: Arc<Mutex<BudgetState>>,
+    denied: Arc<AtomicU64>,
+    retries: u32,
+    window: Duration,
+}
+
+#[derive(Debug)]
+struct BudgetState {
+    /// The tokens left, including the fraction refilled so far
+    tokens: f64,
+    /// The last time the tokens have been refilled
+    refilled: Instant,
+}
+
+impl RetryBudget {
+    pub fn new(retries: u32, window: Duration) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(BudgetState {
+                tokens: retries as f64,
+                refilled: Instant::now(),
+            })),
+            denied: Default::default(),
+            retries,
+            window,
+        }
+    }
+
+    /// Returns the maximum number of the retries per window.
+    pub fn retries(&self) -> u32 {
+        self.retries
+    }
+
+    /// Returns the duration in which the whole budget is refilled.
+    pub fn window(&self) -> Duration {
+        self.window
+    }
+
+    /// Returns the number of the retries which can be made right now.
+    pub fn available(&self) -> u32 {
+        let mut state = self.state.lock().unwrap();
+        self.refill(&mut state);
+        state.tokens as u32
+    }
+
+    /// Returns the number of the retries given up as the budget was exhausted.
+    pub fn denied(&self) -> u64 {
+        self.denied.load(Ordering::Relaxed)
+    }
+
+    /// Takes a token for a retry, returning `false` if the budget is exhausted.
+    pub fn try_acquire(&self) -> bool {
+        let mut state = self.state.lock().unwrap();
+        self.refill(&mut state);
+
+        if state.tokens >= 1.0 {
+            state.tokens -= 1.0;
+            true
+        } else {
+            self.denied.fetch_add(1, Ordering::Relaxed);
+            false
+        }
+    }
+
+    fn refill(&self, state: &mut BudgetState) {
+        let now = Instant::now();
+        let elapsed = now.duration_since(state.refilled);
+        state.refilled = now;
+
+        let refilled = if self.window.is_zero() {
+            self.retries as f64
+        } else {
+            self.retries as f64 * elapsed.as_secs_f64() / self.window.as_secs_f64()
+        };
+        state.tokens = (state.tokens + refilled).min(self.retries as f64);
+    }
+}
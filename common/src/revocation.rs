@@ -0,0 +1,114 @@
+use core::{future::Future, time::Duration};
+use std::{
+    collections::HashSet,
+    sync::{Arc, RwLock},
+};
+
+use ipis::{
+    core::{
+        account::AccountRef,
+        anyhow::{anyhow, Result},
+    },
+    log::warn,
+    tokio::{self, task::JoinHandle},
+};
+
+/// A server-wide list of the revoked accounts, whose requests are rejected.
+///
+/// The accounts are either revoked locally, or fetched from an authority periodically.
+#[derive(Clone, Debug, Default)]
+pub struct RevocationList {
+    state: Arc<RwLock<RevocationState>>,
+    refresher: Option<Arc<Refresher>>,
+}
+
+#[derive(Debug, Default)]
+struct RevocationState {
+    /// The accounts revoked locally, e.g. by the config
+    local: HashSet<AccountRef>,
+    /// The accounts revoked by the authority, replaced on each refresh
+    fetched: HashSet<AccountRef>,
+}
+
+/// A background task refreshing the list, cancelled on drop.
+#[derive(Debug)]
+struct Refresher(JoinHandle<()>);
+
+impl Drop for Refresher {
+    fn drop(&mut self) {
+        self.0.abort()
+    }
+}
+
+impl RevocationList {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether the account has been revoked, locally or by the authority.
+    pub fn is_revoked(&self, account: &AccountRef) -> bool {
+        let state = self.state.read().unwrap();
+        state.local.contains(account) || state.fetched.contains(account)
+    }
+
+    /// Revokes the account locally, returning whether it was newly revoked.
+    pub fn revoke(&self, account: AccountRef) -> bool {
+        self.state.write().unwrap().local.insert(account)
+    }
+
+    /// Restores the account revoked locally, returning whether it was revoked.
+    pub fn restore(&self, account: &AccountRef) -> bool {
+        self.state.write().unwrap().local.remove(account)
+    }
+
+    /// Returns all the revoked accounts.
+    pub fn to_vec(&self) -> Vec<AccountRef> {
+        let state = self.state.read().unwrap();
+        state.local.union(&state.fetched).copied().collect()
+    }
+
+    /// Refreshes the accounts revoked by the authority periodically in background.
+    ///
+    /// The task is cancelled when the last clone of this list is dropped.
+    pub fn with_refresh<F, Fut>(self, interval: Duration, fetch: F) -> Result<Self>
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<Vec<AccountRef>>> + Send,
+    {
+        let runtime = tokio::runtime::Handle::try_current()
+            .map_err(|e| anyhow!("failed to spawn the refresh task: {e}"))?;
+
+        let state = self.state.clone();
+        let task = runtime.spawn(async move {
+            let mut interval = tokio::time::interval(interval);
+            loop {
+                interval.tick().await;
+                match fetch().await {
+                    Ok(accounts) => {
+                        state.write().unwrap().fetched = accounts.into_iter().collect();
+                    }
+                    Err(e) => warn!("failed to refresh the revoked accounts: {e}"),
+                }
+            }
+        });
+
+        Ok(Self {
+            refresher: Some(Arc::new(Refresher(task))),
+            ..self
+        })
+    }
+}
+
+impl Extend<AccountRef> for RevocationList {
+    fn extend<T: IntoIterator<Item = AccountRef>>(&mut self, iter: T) {
+        self.state.write().unwrap().local.extend(iter)
+    }
+}
+
+impl FromIterator<AccountRef> for RevocationList {
+    fn from_iter<T: IntoIterator<Item = AccountRef>>(iter: T) -> Self {
+        let mut list = Self::new();
+        list.extend(iter);
+        list
+    }
+}
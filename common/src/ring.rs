@@ -0,0 +1,122 @@
+use std::collections::BTreeMap;
+
+use ipis::core::account::AccountRef;
+
+use crate::AccountSet;
+
+/// The default number of the virtual nodes per directory node.
+pub const DEFAULT_REPLICAS: u32 = 64;
+
+/// A consistent hash ring sharding the accounts across the directory nodes,
+/// e.g. to pick the node to be asked for the address of an account.
+///
+/// Each node is placed on the ring as several virtual nodes, so that the accounts
+/// are spread evenly, and adding or removing a node remaps its own accounts only.
+/// The positions are derived from the accounts only, so every client agrees on the owners.
+#[derive(Clone, Debug)]
+pub struct HashRing {
+    replicas: u32,
+    nodes: AccountSet,
+    ring: BTreeMap<u64, AccountRef>,
+}
+
+impl Default for HashRing {
+    fn default() -> Self {
+        Self::new(DEFAULT_REPLICAS)
+    }
+}
+
+impl HashRing {
+    pub fn new(replicas: u32) -> Self {
+        Self {
+            replicas: replicas.max(1),
+            nodes: Default::default(),
+            ring: Default::default(),
+        }
+    }
+
+    /// Adds a directory node, returning whether it was newly inserted.
+    pub fn insert(&mut self, node: AccountRef) -> bool {
+        if !self.nodes.insert(node) {
+            return false;
+        }
+
+        for replica in 0..self.replicas {
+            self.ring.entry(position(&node, replica)).or_insert(node);
+        }
+        true
+    }
+
+    /// Removes a directory node, returning whether it was present.
+    ///
+    /// Only the accounts owned by the node are remapped, to the next nodes on the ring.
+    pub fn remove(&mut self, node: &AccountRef) -> bool {
+        if !self.nodes.contains(node) {
+            return false;
+        }
+
+        self.nodes = self.nodes.iter().filter(|e| *e != node).copied().collect();
+        self.ring.retain(|_, owner| owner != node);
+
+        // refill the positions shadowed by the removed node, if any
+        for owner in self.nodes.iter() {
+            for replica in 0..self.replicas {
+                self.ring.entry(position(owner, replica)).or_insert(*owner);
+            }
+        }
+        true
+    }
+
+    /// Returns the directory node owning the account, if any node is added.
+    pub fn get(&self, account: &AccountRef) -> Option<&AccountRef> {
+        let key = position(account, 0);
+        self.ring
+            .range(key..)
+            .next()
+            .or_else(|| self.ring.iter().next())
+            .map(|(_, owner)| owner)
+    }
+
+    pub fn nodes(&self) -> &AccountSet {
+        &self.nodes
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+}
+
+impl FromIterator<AccountRef> for HashRing {
+    fn from_iter<T: IntoIterator<Item = AccountRef>>(iter: T) -> Self {
+        let mut ring = Self::default();
+        for node in iter {
+            ring.insert(node);
+        }
+        ring
+    }
+}
+
+/// Places the account on the ring, with FNV-1a and a SplitMix64 finalizer,
+/// so that the positions are stable across the processes and the platforms.
+fn position(account: &AccountRef, replica: u32) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0100_0000_01b3;
+
+    let mut hash = OFFSET_BASIS;
+    for byte in account
+        .as_bytes()
+        .iter()
+        .chain(replica.to_le_bytes().iter())
+    {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(PRIME);
+    }
+
+    hash = (hash ^ (hash >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    hash = (hash ^ (hash >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    hash ^ (hash >> 31)
+}
@@ -0,0 +1,124 @@
+use core::time::Duration;
+use std::{
+    collections::{BTreeMap, VecDeque},
+    sync::{Arc, Mutex},
+};
+
+use ipis::{core::anyhow::Result, tokio::sync::oneshot};
+
+use crate::IpiisError;
+
+/// A server-wide limit of the concurrently handled requests.
+///
+/// When the limit is saturated, the queued requests are dispatched
+/// by the priority class of their opcode, and FIFO within a class.
+#[derive(Clone, Debug)]
+pub struct RequestScheduler {
+    state: Arc<Mutex<SchedulerState>>,
+    capacity: usize,
+    /// The maximum number of the queued requests, and the delay suggested to the shed ones
+    shedding: Option<(usize, Duration)>,
+}
+
+#[derive(Debug, Default)]
+struct SchedulerState {
+    /// Number of the requests being handled
+    running: usize,
+    /// The queued requests by the priority class
+    waiters: BTreeMap<u8, VecDeque<oneshot::Sender<RequestPermit>>>,
+}
+
+impl RequestScheduler {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            state: Default::default(),
+            capacity: capacity.max(1),
+            shedding: None,
+        }
+    }
+
+    /// Sheds the requests beyond `max_queued` waiters,
+    /// suggesting their clients to re-send them after the delay.
+    pub fn with_shedding(self, max_queued: usize, retry_after: Duration) -> Self {
+        Self {
+            shedding: Some((max_queued, retry_after)),
+            ..self
+        }
+    }
+
+    /// Returns the maximum number of the concurrently handled requests.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Waits for the turn of the request, which ends when the permit is dropped.
+    ///
+    /// When the queue is full, the request is shed with [`IpiisError::RetryAfter`].
+    pub async fn acquire(&self, priority: u8) -> Result<RequestPermit> {
+        let permit = {
+            let mut state = self.state.lock().unwrap();
+            if state.running < self.capacity && state.waiters.is_empty() {
+                state.running += 1;
+                return Ok(RequestPermit {
+                    scheduler: Some(self.clone()),
+                });
+            }
+
+            if let Some((max_queued, retry_after)) = self.shedding {
+                let queued: usize = state.waiters.values().map(VecDeque::len).sum();
+                if queued >= max_queued {
+                    return Err(IpiisError::RetryAfter(retry_after).into());
+                }
+            }
+
+            let (tx, rx) = oneshot::channel();
+            state.waiters.entry(priority).or_default().push_back(tx);
+            rx
+        };
+
+        // the permit is handed over by the finished request
+        Ok(permit
+            .await
+            .expect("the scheduler should outlive the waiters"))
+    }
+
+    fn release(&self) {
+        let mut state = self.state.lock().unwrap();
+
+        // hand over to the first waiter of the highest class
+        while let Some(mut entry) = state.waiters.last_entry() {
+            let tx = entry.get_mut().pop_front();
+            if entry.get().is_empty() {
+                entry.remove();
+            }
+
+            if let Some(tx) = tx {
+                let permit = RequestPermit {
+                    scheduler: Some(self.clone()),
+                };
+                match tx.send(permit) {
+                    Ok(()) => return,
+                    // the waiter has been cancelled
+                    Err(mut permit) => {
+                        permit.scheduler.take();
+                    }
+                }
+            }
+        }
+        state.running -= 1;
+    }
+}
+
+/// A turn of a request given by the [`RequestScheduler`].
+#[derive(Debug)]
+pub struct RequestPermit {
+    scheduler: Option<RequestScheduler>,
+}
+
+impl Drop for RequestPermit {
+    fn drop(&mut self) {
+        if let Some(scheduler) = self.scheduler.take() {
+            scheduler.release();
+        }
+    }
+}
@@ -0,0 +1,273 @@
+use ipis::{
+    async_trait::async_trait,
+    core::{
+        account::{Account, AccountRef, GuaranteeSigned, GuarantorSigned},
+        anyhow::Result,
+        data::Data,
+        signature::SignatureSerializer,
+        signed::IsSigned,
+        value::hash::Hash,
+    },
+};
+use rkyv::{Archive, Serialize};
+
+use crate::{
+    ByteMeter, Diagnostics, Ipiis, PingReport, RawHandlers, RequestBudget, RequestRegistry,
+    RequestScheduler, ResponseCache, RetryBudget, RevocationList, TimePolicy, VerificationAudit,
+    WireCapture,
+};
+
+/// A client operating within a single `kind`.
+///
+/// As [`Ipiis`], the calls without a `kind` (`None`) fall back to the scoped one.
+/// It shares the connections and the address book with the inner client.
+#[derive(Clone, Debug)]
+pub struct ScopedClient<C> {
+    inner: C,
+    kind: Hash,
+}
+
+impl<C> ScopedClient<C> {
+    pub fn new(inner: C, kind: Hash) -> Self {
+        Self { inner, kind }
+    }
+
+    pub fn inner(&self) -> &C {
+        &self.inner
+    }
+
+    pub fn kind(&self) -> &Hash {
+        &self.kind
+    }
+
+    fn scope<'a>(&'a self, kind: Option<&'a Hash>) -> Option<&'a Hash> {
+        kind.or(Some(&self.kind))
+    }
+}
+
+impl<C> ScopedClient<C>
+where
+    C: Ipiis + Send + Sync,
+{
+    pub async fn get_account_primary(&self) -> Result<AccountRef> {
+        self.inner.get_account_primary(Some(&self.kind)).await
+    }
+
+    pub async fn set_account_primary(&self, account: &AccountRef) -> Result<()> {
+        self.inner
+            .set_account_primary(Some(&self.kind), account)
+            .await
+    }
+
+    pub async fn delete_account_primary(&self) -> Result<()> {
+        self.inner.delete_account_primary(Some(&self.kind)).await
+    }
+
+    pub async fn get_address(&self, target: &AccountRef) -> Result<<C as Ipiis>::Address> {
+        self.inner.get_address(Some(&self.kind), target).await
+    }
+
+    pub async fn set_address(
+        &self,
+        target: &AccountRef,
+        address: &<C as Ipiis>::Address,
+    ) -> Result<()> {
+        self.inner
+            .set_address(Some(&self.kind), target, address)
+            .await
+    }
+
+    pub async fn set_address_verified(
+        &self,
+        target: &AccountRef,
+        address: &<C as Ipiis>::Address,
+    ) -> Result<()> {
+        self.inner
+            .set_address_verified(Some(&self.kind), target, address)
+            .await
+    }
+
+    pub async fn delete_address(&self, target: &AccountRef) -> Result<()> {
+        self.inner.delete_address(Some(&self.kind), target).await
+    }
+
+    pub async fn list_accounts(&self) -> Result<Vec<AccountRef>> {
+        self.inner.list_accounts(Some(&self.kind)).await
+    }
+
+    pub async fn ping(&self, target: &AccountRef) -> Result<PingReport> {
+        self.inner.ping(Some(&self.kind), target).await
+    }
+
+    pub async fn call_raw(
+        &self,
+        target: &AccountRef,
+    ) -> Result<(<C as Ipiis>::Writer, <C as Ipiis>::Reader)> {
+        self.inner.call_raw(Some(&self.kind), target).await
+    }
+}
+
+#[async_trait]
+impl<C> Ipiis for ScopedClient<C>
+where
+    C: Ipiis + Send + Sync,
+    <C as Ipiis>::Address: 'static,
+{
+    type Address = <C as Ipiis>::Address;
+    type Reader = <C as Ipiis>::Reader;
+    type Writer = <C as Ipiis>::Writer;
+
+    unsafe fn account_me(&self) -> Result<&Account> {
+        self.inner.account_me()
+    }
+
+    fn account_ref(&self) -> &AccountRef {
+        self.inner.account_ref()
+    }
+
+    async fn get_account_primary(&self, kind: Option<&Hash>) -> Result<AccountRef> {
+        self.inner.get_account_primary(self.scope(kind)).await
+    }
+
+    async fn set_account_primary(&self, kind: Option<&Hash>, account: &AccountRef) -> Result<()> {
+        self.inner
+            .set_account_primary(self.scope(kind), account)
+            .await
+    }
+
+    async fn delete_account_primary(&self, kind: Option<&Hash>) -> Result<()> {
+        self.inner.delete_account_primary(self.scope(kind)).await
+    }
+
+    async fn get_address(
+        &self,
+        kind: Option<&Hash>,
+        target: &AccountRef,
+    ) -> Result<<Self as Ipiis>::Address> {
+        self.inner.get_address(self.scope(kind), target).await
+    }
+
+    async fn set_address(
+        &self,
+        kind: Option<&Hash>,
+        target: &AccountRef,
+        address: &<Self as Ipiis>::Address,
+    ) -> Result<()> {
+        self.inner
+            .set_address(self.scope(kind), target, address)
+            .await
+    }
+
+    async fn set_address_verified(
+        &self,
+        kind: Option<&Hash>,
+        target: &AccountRef,
+        address: &<Self as Ipiis>::Address,
+    ) -> Result<()> {
+        self.inner
+            .set_address_verified(self.scope(kind), target, address)
+            .await
+    }
+
+    async fn delete_address(&self, kind: Option<&Hash>, target: &AccountRef) -> Result<()> {
+        self.inner.delete_address(self.scope(kind), target).await
+    }
+
+    async fn list_accounts(&self, kind: Option<&Hash>) -> Result<Vec<AccountRef>> {
+        self.inner.list_accounts(self.scope(kind)).await
+    }
+
+    fn sign<'a, T>(&self, target: AccountRef, msg: &'a T) -> Result<Data<GuaranteeSigned, &'a T>>
+    where
+        T: Archive + Serialize<SignatureSerializer> + IsSigned,
+        <T as Archive>::Archived: ::core::fmt::Debug + PartialEq,
+    {
+        self.inner.sign(target, msg)
+    }
+
+    fn sign_owned<T>(&self, target: AccountRef, msg: T) -> Result<Data<GuaranteeSigned, T>>
+    where
+        T: Archive + Serialize<SignatureSerializer> + IsSigned,
+        <T as Archive>::Archived: ::core::fmt::Debug + PartialEq,
+    {
+        self.inner.sign_owned(target, msg)
+    }
+
+    fn sign_as_guarantor<T>(
+        &self,
+        msg: Data<GuaranteeSigned, T>,
+    ) -> Result<Data<GuarantorSigned, T>>
+    where
+        T: IsSigned,
+    {
+        self.inner.sign_as_guarantor(msg)
+    }
+
+    fn protocol(&self) -> &'static str {
+        self.inner.protocol()
+    }
+
+    fn request_budget(&self) -> Option<&RequestBudget> {
+        self.inner.request_budget()
+    }
+
+    fn request_scheduler(&self) -> Option<&RequestScheduler> {
+        self.inner.request_scheduler()
+    }
+
+    fn request_registry(&self) -> Option<&RequestRegistry> {
+        self.inner.request_registry()
+    }
+
+    fn retry_budget(&self) -> Option<&RetryBudget> {
+        self.inner.retry_budget()
+    }
+
+    fn response_cache(&self) -> Option<&ResponseCache> {
+        self.inner.response_cache()
+    }
+
+    fn revocation_list(&self) -> Option<&RevocationList> {
+        self.inner.revocation_list()
+    }
+
+    fn byte_meter(&self) -> Option<&ByteMeter> {
+        self.inner.byte_meter()
+    }
+
+    fn reports_server_time(&self) -> bool {
+        self.inner.reports_server_time()
+    }
+
+    fn wire_capture(&self) -> Option<&WireCapture> {
+        self.inner.wire_capture()
+    }
+
+    fn verification_audit(&self) -> Option<&VerificationAudit> {
+        self.inner.verification_audit()
+    }
+
+    fn raw_handlers(&self) -> Option<&RawHandlers> {
+        self.inner.raw_handlers()
+    }
+
+    fn time_policy(&self) -> &TimePolicy {
+        self.inner.time_policy()
+    }
+
+    async fn diagnostics(&self) -> Result<Diagnostics> {
+        self.inner.diagnostics().await
+    }
+
+    async fn call_raw(
+        &self,
+        kind: Option<&Hash>,
+        target: &AccountRef,
+    ) -> Result<(<Self as Ipiis>::Writer, <Self as Ipiis>::Reader)> {
+        self.inner.call_raw(self.scope(kind), target).await
+    }
+
+    async fn disconnect(&self, target: &AccountRef, reason: u32) -> Result<()> {
+        self.inner.disconnect(target, reason).await
+    }
+}
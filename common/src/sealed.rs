@@ -0,0 +1,101 @@
+use bytecheck::CheckBytes;
+use chacha20poly1305::{
+    aead::{Aead, NewAead},
+    ChaCha20Poly1305, Key, Nonce,
+};
+use curve25519_dalek::{
+    constants::X25519_BASEPOINT, edwards::CompressedEdwardsY, montgomery::MontgomeryPoint,
+    scalar::Scalar,
+};
+use ipis::core::{
+    account::{Account, AccountRef},
+    anyhow::{anyhow, Result},
+    signed::IsSigned,
+};
+use rkyv::{Archive, Deserialize, Serialize};
+use sha2::{Digest, Sha256, Sha512};
+
+/// A payload encrypted end-to-end to the target account,
+/// so that the peers relaying it can route it but not read it.
+///
+/// The payload is encrypted with ChaCha20-Poly1305, by the key agreed (X25519)
+/// between a one-time key and the target's own key, converted from Ed25519.
+/// So only the target can open it, but the sender is not authenticated by itself:
+/// sign it as the other inputs to prove the sender.
+#[derive(Clone, Debug, PartialEq, Eq, Archive, Serialize, Deserialize)]
+#[archive(compare(PartialEq))]
+#[archive_attr(derive(CheckBytes, Debug, PartialEq, Eq))]
+pub struct Sealed {
+    /// The one-time public key of the sender
+    ephemeral: [u8; 32],
+    ciphertext: Vec<u8>,
+}
+
+impl IsSigned for Sealed {}
+
+impl Sealed {
+    /// Encrypts the payload to the target account.
+    pub fn seal(target: &AccountRef, payload: &[u8]) -> Result<Self> {
+        let target = to_montgomery(target)?;
+
+        // agree a one-time key
+        let secret = clamp(::rand::random());
+        let ephemeral = X25519_BASEPOINT * secret;
+        let key = derive_key(&(target * secret), &ephemeral, &target);
+
+        let ciphertext = ChaCha20Poly1305::new(&key)
+            .encrypt(&Nonce::default(), payload)
+            .map_err(|_| anyhow!("failed to seal the payload"))?;
+
+        Ok(Self {
+            ephemeral: ephemeral.to_bytes(),
+            ciphertext,
+        })
+    }
+
+    /// Decrypts the payload with the key of the target account,
+    /// failing if it is sealed to the other account or tampered.
+    pub fn open(&self, account: &Account) -> Result<Vec<u8>> {
+        let ephemeral = MontgomeryPoint(self.ephemeral);
+        let target = to_montgomery(&account.account_ref())?;
+
+        // the Ed25519 secret scalar, as `SHA-512(seed)[..32]`
+        let seed = &account.to_bytes()[..32];
+        let mut secret = [0; 32];
+        secret.copy_from_slice(&Sha512::digest(seed)[..32]);
+        let key = derive_key(&(ephemeral * clamp(secret)), &ephemeral, &target);
+
+        ChaCha20Poly1305::new(&key)
+            .decrypt(&Nonce::default(), self.ciphertext.as_ref())
+            .map_err(|_| anyhow!("failed to open the sealed payload"))
+    }
+}
+
+fn to_montgomery(account: &AccountRef) -> Result<MontgomeryPoint> {
+    CompressedEdwardsY(*account.as_bytes())
+        .decompress()
+        .map(|point| point.to_montgomery())
+        .ok_or_else(|| anyhow!("malformed account: {account}"))
+}
+
+fn clamp(mut bytes: [u8; 32]) -> Scalar {
+    bytes[0] &= 248;
+    bytes[31] &= 127;
+    bytes[31] |= 64;
+    Scalar::from_bits(bytes)
+}
+
+/// Derives the key from the agreed secret, bound to both of the public keys.
+///
+/// As the one-time key is never reused, neither is the key, so the nonce can be fixed.
+fn derive_key(
+    shared: &MontgomeryPoint,
+    ephemeral: &MontgomeryPoint,
+    target: &MontgomeryPoint,
+) -> Key {
+    let mut hasher = Sha256::new();
+    hasher.update(shared.as_bytes());
+    hasher.update(ephemeral.as_bytes());
+    hasher.update(target.as_bytes());
+    hasher.finalize()
+}
@@ -0,0 +1,368 @@
+//! Helpers to unit-test the code built on ipiis without standing up a server,
+//! e.g. the opcodes defined by [`define_io!`](crate::define_io).
+
+use std::sync::{Arc, Mutex};
+
+use ipis::{
+    async_trait::async_trait,
+    core::{
+        account::{Account, AccountRef, GuaranteeSigned, GuarantorSigned},
+        anyhow::{ensure, Result},
+        data::Data,
+        signature::SignatureSerializer,
+        signed::IsSigned,
+        value::hash::Hash,
+    },
+};
+use rkyv::{Archive, Serialize};
+
+use crate::{
+    ByteMeter, Diagnostics, IoFrame, Ipiis, PingReport, RawHandlers, RequestBudget,
+    RequestRegistry, RequestScheduler, ResponseCache, RetryBudget, RevocationList, TimePolicy,
+    VerificationAudit, WireCapture,
+};
+
+/// Writes the request to the bytes and reads it back,
+/// failing unless it is written to the same bytes again.
+pub async fn roundtrip_request<Req>(req: Req) -> Result<Req>
+where
+    Req: IoFrame,
+{
+    roundtrip(req).await
+}
+
+/// Writes the response to the bytes and reads it back,
+/// failing unless it is written to the same bytes again.
+pub async fn roundtrip_response<Res>(res: Res) -> Result<Res>
+where
+    Res: IoFrame,
+{
+    roundtrip(res).await
+}
+
+async fn roundtrip<T>(mut frame: T) -> Result<T>
+where
+    T: IoFrame,
+{
+    let bytes = frame.to_frame().await?;
+    let mut decoded = T::from_frame(&bytes).await?;
+
+    let bytes_decoded = decoded.to_frame().await?;
+    ensure!(
+        bytes == bytes_decoded,
+        "the frame has changed after a round-trip: {} bytes -> {} bytes",
+        bytes.len(),
+        bytes_decoded.len(),
+    );
+    Ok(decoded)
+}
+
+/// A call made through [`RecordingClient`], with its arguments.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CallRecord<Address> {
+    GetAccountPrimary {
+        kind: Option<Hash>,
+    },
+    SetAccountPrimary {
+        kind: Option<Hash>,
+        account: AccountRef,
+    },
+    DeleteAccountPrimary {
+        kind: Option<Hash>,
+    },
+    GetAddress {
+        kind: Option<Hash>,
+        target: AccountRef,
+    },
+    SetAddress {
+        kind: Option<Hash>,
+        target: AccountRef,
+        address: Address,
+    },
+    SetAddressVerified {
+        kind: Option<Hash>,
+        target: AccountRef,
+        address: Address,
+    },
+    DeleteAddress {
+        kind: Option<Hash>,
+        target: AccountRef,
+    },
+    ListAccounts {
+        kind: Option<Hash>,
+    },
+    Ping {
+        kind: Option<Hash>,
+        target: AccountRef,
+    },
+    CallRaw {
+        kind: Option<Hash>,
+        target: AccountRef,
+    },
+    Disconnect {
+        target: AccountRef,
+        reason: u32,
+    },
+}
+
+/// A client recording the calls made through it, in order,
+/// while delegating them to the inner client, e.g. a mock.
+///
+/// The calls made by the inner client on its own, e.g. its upstream lookups, are not recorded.
+/// The clones share the records.
+pub struct RecordingClient<C>
+where
+    C: Ipiis,
+{
+    inner: C,
+    records: Arc<Mutex<Vec<CallRecord<<C as Ipiis>::Address>>>>,
+}
+
+impl<C> Clone for RecordingClient<C>
+where
+    C: Ipiis + Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            records: self.records.clone(),
+        }
+    }
+}
+
+impl<C> RecordingClient<C>
+where
+    C: Ipiis,
+    <C as Ipiis>::Address: Clone,
+{
+    pub fn new(inner: C) -> Self {
+        Self {
+            inner,
+            records: Default::default(),
+        }
+    }
+
+    pub fn inner(&self) -> &C {
+        &self.inner
+    }
+
+    /// Returns the calls recorded so far.
+    pub fn records(&self) -> Vec<CallRecord<<C as Ipiis>::Address>> {
+        self.records.lock().unwrap().clone()
+    }
+
+    /// Returns the calls recorded so far, forgetting them.
+    pub fn take_records(&self) -> Vec<CallRecord<<C as Ipiis>::Address>> {
+        ::core::mem::take(&mut *self.records.lock().unwrap())
+    }
+
+    fn record(&self, record: CallRecord<<C as Ipiis>::Address>) {
+        self.records.lock().unwrap().push(record)
+    }
+}
+
+#[async_trait]
+impl<C> Ipiis for RecordingClient<C>
+where
+    C: Ipiis + Send + Sync,
+    <C as Ipiis>::Address: Clone + 'static,
+{
+    type Address = <C as Ipiis>::Address;
+    type Reader = <C as Ipiis>::Reader;
+    type Writer = <C as Ipiis>::Writer;
+
+    unsafe fn account_me(&self) -> Result<&Account> {
+        self.inner.account_me()
+    }
+
+    fn account_ref(&self) -> &AccountRef {
+        self.inner.account_ref()
+    }
+
+    async fn get_account_primary(&self, kind: Option<&Hash>) -> Result<AccountRef> {
+        self.record(CallRecord::GetAccountPrimary {
+            kind: kind.copied(),
+        });
+        self.inner.get_account_primary(kind).await
+    }
+
+    async fn set_account_primary(&self, kind: Option<&Hash>, account: &AccountRef) -> Result<()> {
+        self.record(CallRecord::SetAccountPrimary {
+            kind: kind.copied(),
+            account: *account,
+        });
+        self.inner.set_account_primary(kind, account).await
+    }
+
+    async fn delete_account_primary(&self, kind: Option<&Hash>) -> Result<()> {
+        self.record(CallRecord::DeleteAccountPrimary {
+            kind: kind.copied(),
+        });
+        self.inner.delete_account_primary(kind).await
+    }
+
+    async fn get_address(
+        &self,
+        kind: Option<&Hash>,
+        target: &AccountRef,
+    ) -> Result<<Self as Ipiis>::Address> {
+        self.record(CallRecord::GetAddress {
+            kind: kind.copied(),
+            target: *target,
+        });
+        self.inner.get_address(kind, target).await
+    }
+
+    async fn set_address(
+        &self,
+        kind: Option<&Hash>,
+        target: &AccountRef,
+        address: &<Self as Ipiis>::Address,
+    ) -> Result<()> {
+        self.record(CallRecord::SetAddress {
+            kind: kind.copied(),
+            target: *target,
+            address: address.clone(),
+        });
+        self.inner.set_address(kind, target, address).await
+    }
+
+    async fn set_address_verified(
+        &self,
+        kind: Option<&Hash>,
+        target: &AccountRef,
+        address: &<Self as Ipiis>::Address,
+    ) -> Result<()> {
+        self.record(CallRecord::SetAddressVerified {
+            kind: kind.copied(),
+            target: *target,
+            address: address.clone(),
+        });
+        self.inner.set_address_verified(kind, target, address).await
+    }
+
+    async fn delete_address(&self, kind: Option<&Hash>, target: &AccountRef) -> Result<()> {
+        self.record(CallRecord::DeleteAddress {
+            kind: kind.copied(),
+            target: *target,
+        });
+        self.inner.delete_address(kind, target).await
+    }
+
+    async fn list_accounts(&self, kind: Option<&Hash>) -> Result<Vec<AccountRef>> {
+        self.record(CallRecord::ListAccounts {
+            kind: kind.copied(),
+        });
+        self.inner.list_accounts(kind).await
+    }
+
+    fn sign<'a, T>(&self, target: AccountRef, msg: &'a T) -> Result<Data<GuaranteeSigned, &'a T>>
+    where
+        T: Archive + Serialize<SignatureSerializer> + IsSigned,
+        <T as Archive>::Archived: ::core::fmt::Debug + PartialEq,
+    {
+        self.inner.sign(target, msg)
+    }
+
+    fn sign_owned<T>(&self, target: AccountRef, msg: T) -> Result<Data<GuaranteeSigned, T>>
+    where
+        T: Archive + Serialize<SignatureSerializer> + IsSigned,
+        <T as Archive>::Archived: ::core::fmt::Debug + PartialEq,
+    {
+        self.inner.sign_owned(target, msg)
+    }
+
+    fn sign_as_guarantor<T>(
+        &self,
+        msg: Data<GuaranteeSigned, T>,
+    ) -> Result<Data<GuarantorSigned, T>>
+    where
+        T: IsSigned,
+    {
+        self.inner.sign_as_guarantor(msg)
+    }
+
+    fn protocol(&self) -> &'static str {
+        self.inner.protocol()
+    }
+
+    fn request_budget(&self) -> Option<&RequestBudget> {
+        self.inner.request_budget()
+    }
+
+    fn request_scheduler(&self) -> Option<&RequestScheduler> {
+        self.inner.request_scheduler()
+    }
+
+    fn request_registry(&self) -> Option<&RequestRegistry> {
+        self.inner.request_registry()
+    }
+
+    fn retry_budget(&self) -> Option<&RetryBudget> {
+        self.inner.retry_budget()
+    }
+
+    fn response_cache(&self) -> Option<&ResponseCache> {
+        self.inner.response_cache()
+    }
+
+    fn revocation_list(&self) -> Option<&RevocationList> {
+        self.inner.revocation_list()
+    }
+
+    fn byte_meter(&self) -> Option<&ByteMeter> {
+        self.inner.byte_meter()
+    }
+
+    fn reports_server_time(&self) -> bool {
+        self.inner.reports_server_time()
+    }
+
+    fn wire_capture(&self) -> Option<&WireCapture> {
+        self.inner.wire_capture()
+    }
+
+    fn verification_audit(&self) -> Option<&VerificationAudit> {
+        self.inner.verification_audit()
+    }
+
+    fn raw_handlers(&self) -> Option<&RawHandlers> {
+        self.inner.raw_handlers()
+    }
+
+    fn time_policy(&self) -> &TimePolicy {
+        self.inner.time_policy()
+    }
+
+    async fn diagnostics(&self) -> Result<Diagnostics> {
+        self.inner.diagnostics().await
+    }
+
+    async fn ping(&self, kind: Option<&Hash>, target: &AccountRef) -> Result<PingReport> {
+        self.record(CallRecord::Ping {
+            kind: kind.copied(),
+            target: *target,
+        });
+        self.inner.ping(kind, target).await
+    }
+
+    async fn call_raw(
+        &self,
+        kind: Option<&Hash>,
+        target: &AccountRef,
+    ) -> Result<(<Self as Ipiis>::Writer, <Self as Ipiis>::Reader)> {
+        self.record(CallRecord::CallRaw {
+            kind: kind.copied(),
+            target: *target,
+        });
+        self.inner.call_raw(kind, target).await
+    }
+
+    async fn disconnect(&self, target: &AccountRef, reason: u32) -> Result<()> {
+        self.record(CallRecord::Disconnect {
+            target: *target,
+            reason,
+        });
+        self.inner.disconnect(target, reason).await
+    }
+}
@@ -0,0 +1,63 @@
+use core::time::Duration;
+
+use ipis::core::{
+    anyhow::Result,
+    chrono::{self, DateTime, Utc},
+};
+
+use crate::IpiisError;
+
+/// The time-sensitive behaviors of a client or a server, configured in one place.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct TimePolicy {
+    /// How far the clocks of the peers may differ
+    pub clock_skew_tolerance: Duration,
+    /// How long the signed requests are accepted after being signed, e.g. pre-signed ones
+    pub default_request_ttl: Duration,
+    /// How long the records of the directory are cached, if limited:
+    /// advertised to the clients when serving, and bounding the ones cached otherwise
+    pub cache_record_ttl: Option<Duration>,
+}
+
+impl Default for TimePolicy {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+impl TimePolicy {
+    pub const DEFAULT: Self = Self {
+        clock_skew_tolerance: Duration::from_secs(30),
+        default_request_ttl: Duration::from_secs(60 * 60),
+        cache_record_ttl: None,
+    };
+
+    /// Checks the request signed at the date, rejecting the ones expired or signed in the future,
+    /// beyond the tolerance of the clock skew.
+    pub fn verify_signed_at(&self, signed_at: &DateTime<Utc>) -> Result<()> {
+        let now = Utc::now();
+        let skew = chrono::Duration::from_std(self.clock_skew_tolerance)?;
+        let ttl = chrono::Duration::from_std(self.default_request_ttl)?;
+
+        if *signed_at > now + skew {
+            Err(IpiisError::StaleRequest(format!("signed in the future: {signed_at}")).into())
+        } else if *signed_at + ttl + skew < now {
+            Err(IpiisError::StaleRequest(format!("expired: signed at {signed_at}")).into())
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Bounds the lifetime of a record fetched from the directory, given in milliseconds.
+    pub fn bound_ttl_ms(&self, ttl_ms: Option<u64>) -> Option<u64> {
+        match (ttl_ms, self.cache_record_ttl_ms()) {
+            (Some(ttl_ms), Some(limit)) => Some(ttl_ms.min(limit)),
+            (ttl_ms, limit) => ttl_ms.or(limit),
+        }
+    }
+
+    /// Returns the lifetime of the records served to the clients, in milliseconds.
+    pub fn cache_record_ttl_ms(&self) -> Option<u64> {
+        self.cache_record_ttl.map(|ttl| ttl.as_millis() as u64)
+    }
+}
@@ -0,0 +1,50 @@
+use core::future::Future;
+
+use ipis::{
+    core::{account::AccountRef, anyhow::Result},
+    tokio,
+};
+
+use crate::IpiisError;
+
+tokio::task_local! {
+    /// The account authenticated by the transport of the request being received
+    static TRANSPORT_ACCOUNT: AccountRef;
+}
+
+/// The account authenticated by the transport of the requests, e.g. the client certificate of QUIC,
+/// which the accounts signing them should match.
+///
+/// Otherwise, a valid certificate could be mixed with the sign of another account.
+pub struct TransportIdentity;
+
+impl TransportIdentity {
+    /// Runs the future, e.g. handling a request, bound to the account of the transport if any.
+    pub async fn scope<F>(account: Option<AccountRef>, fut: F) -> F::Output
+    where
+        F: Future,
+    {
+        match account {
+            Some(account) => TRANSPORT_ACCOUNT.scope(account, fut).await,
+            None => fut.await,
+        }
+    }
+
+    /// Checks whether the request is signed by the account of the transport.
+    ///
+    /// It does nothing if called outside of [`TransportIdentity::scope`].
+    pub fn check(account: &AccountRef) -> Result<()> {
+        TRANSPORT_ACCOUNT
+            .try_with(|bound| {
+                if bound == account {
+                    Ok(())
+                } else {
+                    Err(IpiisError::Unauthorized(format!(
+                        "signed by {account}, but the certificate is bound to {bound}"
+                    ))
+                    .into())
+                }
+            })
+            .unwrap_or(Ok(()))
+    }
+}
@@ -0,0 +1,284 @@
+use core::{
+    future::Future,
+    pin::Pin,
+    sync::atomic::{AtomicU64, Ordering},
+    task::{Context, Poll},
+    time::Duration,
+};
+use std::{
+    collections::HashMap,
+    io,
+    sync::{Arc, Mutex},
+    time::Instant,
+};
+
+use ipis::{
+    core::{account::AccountRef, anyhow::Result},
+    tokio::{
+        self,
+        io::{AsyncRead, AsyncWrite, ReadBuf},
+    },
+};
+
+use crate::IpiisError;
+
+/// The bytes transferred by an account.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct ByteStats {
+    /// The bytes of the requests received from the account
+    pub bytes_in: u64,
+    /// The bytes of the responses sent to the account
+    pub bytes_out: u64,
+}
+
+impl ByteStats {
+    pub fn total(&self) -> u64 {
+        self.bytes_in + self.bytes_out
+    }
+}
+
+/// A server-wide accounting of the bytes transferred per verified account.
+///
+/// With a quota, the requests of an account are rejected
+/// once it has transferred the quota within the current window.
+#[derive(Clone, Debug, Default)]
+pub struct ByteMeter {
+    state: Arc<Mutex<HashMap<AccountRef, MeterEntry>>>,
+    quota: Option<(u64, Duration)>,
+}
+
+#[derive(Debug)]
+struct MeterEntry {
+    /// The bytes transferred so far
+    total: ByteStats,
+    /// When the current window has started
+    window_started: Instant,
+    /// The bytes transferred within the current window
+    window_bytes: u64,
+}
+
+impl ByteMeter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Limits the bytes transferred by each account within the window.
+    pub fn with_quota(self, bytes: u64, window: Duration) -> Self {
+        Self {
+            quota: Some((bytes, window)),
+            ..self
+        }
+    }
+
+    /// Returns the bytes transferred by the account so far.
+    pub fn usage(&self, account: &AccountRef) -> ByteStats {
+        self.state
+            .lock()
+            .unwrap()
+            .get(account)
+            .map(|entry| entry.total)
+            .unwrap_or_default()
+    }
+
+    /// Returns the bytes transferred by all the accounts so far.
+    pub fn total(&self) -> ByteStats {
+        self.state
+            .lock()
+            .unwrap()
+            .values()
+            .fold(ByteStats::default(), |total, entry| ByteStats {
+                bytes_in: total.bytes_in + entry.total.bytes_in,
+                bytes_out: total.bytes_out + entry.total.bytes_out,
+            })
+    }
+
+    /// Checks whether the account may issue another request.
+    pub fn check(&self, account: &AccountRef) -> Result<()> {
+        let (bytes, window) = match self.quota {
+            Some(quota) => quota,
+            None => return Ok(()),
+        };
+
+        let mut state = self.state.lock().unwrap();
+        match state.get_mut(account) {
+            Some(entry) => {
+                entry.roll(window);
+                if entry.window_bytes >= bytes {
+                    Err(IpiisError::QuotaExceeded(account.to_string()).into())
+                } else {
+                    Ok(())
+                }
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Adds the bytes transferred by the account.
+    pub fn record(&self, account: AccountRef, stats: ByteStats) {
+        let window = self.quota.map(|(_, window)| window);
+
+        let mut state = self.state.lock().unwrap();
+        let entry = state.entry(account).or_insert_with(|| MeterEntry {
+            total: Default::default(),
+            window_started: Instant::now(),
+            window_bytes: 0,
+        });
+        if let Some(window) = window {
+            entry.roll(window);
+        }
+
+        entry.total.bytes_in += stats.bytes_in;
+        entry.total.bytes_out += stats.bytes_out;
+        entry.window_bytes += stats.total();
+    }
+}
+
+impl MeterEntry {
+    fn roll(&mut self, window: Duration) {
+        if self.window_started.elapsed() >= window {
+            self.window_started = Instant::now();
+            self.window_bytes = 0;
+        }
+    }
+}
+
+tokio::task_local! {
+    /// The metering of the request being received
+    static METERED_REQUEST: Arc<MeteredState>;
+}
+
+/// The metering of a single request, recorded to the [`ByteMeter`] on drop.
+///
+/// The bytes are accounted to the first account verified within [`MeteredRequest::scope`].
+pub struct MeteredRequest {
+    state: Option<Arc<MeteredState>>,
+}
+
+struct MeteredState {
+    meter: ByteMeter,
+    account: Mutex<Option<AccountRef>>,
+    bytes_in: AtomicU64,
+    bytes_out: AtomicU64,
+}
+
+impl MeteredRequest {
+    /// Starts metering a request, doing nothing if there is no meter.
+    pub fn new(meter: Option<&ByteMeter>) -> Self {
+        Self {
+            state: meter.map(|meter| {
+                Arc::new(MeteredState {
+                    meter: meter.clone(),
+                    account: Default::default(),
+                    bytes_in: Default::default(),
+                    bytes_out: Default::default(),
+                })
+            }),
+        }
+    }
+
+    /// Wraps the stream, counting the bytes read and written.
+    pub fn stream<S>(&self, inner: S) -> MeteredStream<S> {
+        MeteredStream {
+            inner,
+            state: self.state.clone(),
+        }
+    }
+
+    /// Runs the future, e.g. receiving the request, within the metering.
+    pub async fn scope<F>(&self, fut: F) -> F::Output
+    where
+        F: Future,
+    {
+        match &self.state {
+            Some(state) => METERED_REQUEST.scope(state.clone(), fut).await,
+            None => fut.await,
+        }
+    }
+
+    /// Accounts the request being received to the verified account,
+    /// failing if its quota has been exceeded.
+    ///
+    /// It does nothing if called outside of [`MeteredRequest::scope`].
+    pub fn identify(account: &AccountRef) -> Result<()> {
+        METERED_REQUEST
+            .try_with(|state| {
+                let mut current = state.account.lock().unwrap();
+                if current.is_some() {
+                    return Ok(());
+                }
+
+                state.meter.check(account)?;
+                *current = Some(*account);
+                Ok(())
+            })
+            .unwrap_or(Ok(()))
+    }
+}
+
+impl Drop for MeteredRequest {
+    fn drop(&mut self) {
+        if let Some(state) = &self.state {
+            if let Some(account) = *state.account.lock().unwrap() {
+                let stats = ByteStats {
+                    bytes_in: state.bytes_in.load(Ordering::SeqCst),
+                    bytes_out: state.bytes_out.load(Ordering::SeqCst),
+                };
+                state.meter.record(account, stats);
+            }
+        }
+    }
+}
+
+/// A stream counting the bytes of a [`MeteredRequest`].
+pub struct MeteredStream<S> {
+    inner: S,
+    state: Option<Arc<MeteredState>>,
+}
+
+impl<S> AsyncRead for MeteredStream<S>
+where
+    S: AsyncRead + Unpin,
+{
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+
+        let offset = buf.filled().len();
+        let result = Pin::new(&mut this.inner).poll_read(cx, buf);
+        if let (Poll::Ready(Ok(())), Some(state)) = (&result, &this.state) {
+            let len = buf.filled().len() - offset;
+            state.bytes_in.fetch_add(len as u64, Ordering::SeqCst);
+        }
+        result
+    }
+}
+
+impl<S> AsyncWrite for MeteredStream<S>
+where
+    S: AsyncWrite + Unpin,
+{
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+
+        let result = Pin::new(&mut this.inner).poll_write(cx, buf);
+        if let (Poll::Ready(Ok(len)), Some(state)) = (&result, &this.state) {
+            state.bytes_out.fetch_add(*len as u64, Ordering::SeqCst);
+        }
+        result
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().inner).poll_flush(cx)
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().inner).poll_shutdown(cx)
+    }
+}
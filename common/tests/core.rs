@@ -0,0 +1,43 @@
+use ipiis_common::{close_code, io::OpCode, opcode, CloseCode};
+
+#[test]
+fn test_core_opcodes() {
+    // the no_std core should describe the same wire format
+    for (code, expected) in [
+        (OpCode::GetAccountPrimary, opcode::GET_ACCOUNT_PRIMARY),
+        (OpCode::SetAccountPrimary, opcode::SET_ACCOUNT_PRIMARY),
+        (OpCode::DeleteAccountPrimary, opcode::DELETE_ACCOUNT_PRIMARY),
+        (OpCode::GetAddress, opcode::GET_ADDRESS),
+        (OpCode::SetAddress, opcode::SET_ADDRESS),
+        (OpCode::DeleteAddress, opcode::DELETE_ADDRESS),
+        (OpCode::Ping, opcode::PING),
+        (OpCode::ListAccounts, opcode::LIST_ACCOUNTS),
+        (OpCode::SnapshotPrimaries, opcode::SNAPSHOT_PRIMARIES),
+        (OpCode::ListRevokedAccounts, opcode::LIST_REVOKED_ACCOUNTS),
+        (OpCode::Hello, opcode::HELLO),
+        (OpCode::Capabilities, opcode::CAPABILITIES),
+        (OpCode::Sink, opcode::SINK),
+    ] {
+        assert_eq!(code.to_bytes(), opcode::to_bytes(expected));
+        assert_eq!(opcode::from_bytes(code.to_bytes()), expected);
+    }
+}
+
+#[test]
+fn test_core_close_codes() {
+    for code in [
+        CloseCode::Normal,
+        CloseCode::ShuttingDown,
+        CloseCode::Overloaded,
+        CloseCode::ProtocolError,
+    ] {
+        assert_eq!(CloseCode::from_code(code.code().into()), Some(code));
+    }
+    assert_eq!(
+        CloseCode::from_code(close_code::SHUTTING_DOWN.into()),
+        Some(CloseCode::ShuttingDown)
+    );
+
+    // the other codes are left to the applications
+    assert_eq!(CloseCode::from_code(42), None);
+}
@@ -0,0 +1,63 @@
+use ipiis_common::{io::OpCode, try_decode_request, DecodedRequest, IpiisError, Nonce};
+use ipis::{
+    core::{account::Account, data::Data},
+    stream::DynStream,
+    tokio,
+};
+
+#[tokio::test]
+async fn test_decode_request() {
+    let account = Account::generate();
+    let target = Account::generate().account_ref();
+
+    // encode a request
+    let mut sign = DynStream::Owned(
+        Data::builder()
+            .build_owned(&account, target, Nonce::generate())
+            .unwrap(),
+    );
+    sign.serialize_inner().await.unwrap();
+
+    let mut bytes = OpCode::Ping.to_bytes().to_vec();
+    sign.copy_to(&mut bytes).await.unwrap();
+
+    // the valid request should be decoded
+    assert_eq!(
+        try_decode_request(&bytes).unwrap(),
+        DecodedRequest {
+            opcode: OpCode::Ping,
+            account: account.account_ref(),
+        },
+    );
+
+    // the truncated requests should be rejected
+    for len in 0..bytes.len() {
+        assert_bad_request(&bytes[..len]);
+    }
+
+    // the corrupted requests should be rejected
+    for index in 2..bytes.len() {
+        let mut corrupted = bytes.clone();
+        corrupted[index] ^= 0xff;
+        if let Ok(request) = try_decode_request(&corrupted) {
+            // the signer may be changed, but it should never panic
+            assert_eq!(request.opcode, OpCode::Ping);
+        }
+    }
+
+    // the random garbage should be rejected
+    for len in [0, 1, 2, 7, 64, 1024, 65536] {
+        for _ in 0..16 {
+            let garbage: Vec<u8> = (0..len).map(|_| ::rand::random()).collect();
+            assert_bad_request(&garbage);
+        }
+    }
+}
+
+fn assert_bad_request(bytes: &[u8]) {
+    let error = try_decode_request(bytes).unwrap_err();
+    assert!(
+        matches!(error.downcast_ref(), Some(IpiisError::BadRequest(_))),
+        "{error:#}",
+    );
+}
@@ -0,0 +1,99 @@
+use core::time::Duration;
+use std::io;
+
+use ipiis_common::{
+    is_connection_error, is_not_found, is_quota_exceeded, is_timeout, is_unauthorized, IpiisError,
+};
+use ipis::{
+    core::anyhow::{anyhow, Error},
+    tokio,
+};
+
+type Predicate = fn(&Error) -> bool;
+
+const PREDICATES: &[(&str, Predicate)] = &[
+    ("timeout", is_timeout),
+    ("unauthorized", is_unauthorized),
+    ("not_found", is_not_found),
+    ("quota_exceeded", is_quota_exceeded),
+    ("connection_error", is_connection_error),
+];
+
+fn assert_classified(error: Error, expected: Option<&str>) {
+    for (name, predicate) in PREDICATES {
+        assert_eq!(
+            predicate(&error),
+            Some(*name) == expected,
+            "{name} on {error:#}",
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_classify_errors() {
+    // timeouts
+    let elapsed = tokio::time::timeout(Duration::ZERO, ::ipis::futures::future::pending::<()>())
+        .await
+        .unwrap_err();
+    assert_classified(elapsed.into(), Some("timeout"));
+    assert_classified(
+        Error::new(io::Error::from(io::ErrorKind::TimedOut)).context("network error"),
+        Some("timeout"),
+    );
+
+    // unauthorized
+    assert_classified(
+        IpiisError::Unauthorized("account".to_string()).into(),
+        Some("unauthorized"),
+    );
+    assert_classified(
+        IpiisError::Remote(IpiisError::Unauthorized("account".to_string()).to_string()).into(),
+        Some("unauthorized"),
+    );
+    assert_classified(
+        IpiisError::Remote(IpiisError::Revoked("account".to_string()).to_string()).into(),
+        Some("unauthorized"),
+    );
+
+    // not found
+    assert_classified(
+        IpiisError::NotFound("address".to_string()).into(),
+        Some("not_found"),
+    );
+    assert_classified(
+        IpiisError::Remote(IpiisError::NotFound("address".to_string()).to_string()).into(),
+        Some("not_found"),
+    );
+
+    // quota exceeded
+    assert_classified(
+        IpiisError::QuotaExceeded("account".to_string()).into(),
+        Some("quota_exceeded"),
+    );
+    assert_classified(
+        IpiisError::Remote(IpiisError::QuotaExceeded("account".to_string()).to_string()).into(),
+        Some("quota_exceeded"),
+    );
+
+    // connection errors
+    for kind in [
+        io::ErrorKind::ConnectionRefused,
+        io::ErrorKind::ConnectionReset,
+        io::ErrorKind::UnexpectedEof,
+    ] {
+        assert_classified(
+            Error::new(io::Error::from(kind)).context("failed to connect"),
+            Some("connection_error"),
+        );
+    }
+    assert_classified(
+        IpiisError::Unacknowledged("reset by peer".to_string()).into(),
+        Some("connection_error"),
+    );
+
+    // the others
+    assert_classified(anyhow!("not authorized: a plain message"), None);
+    assert_classified(IpiisError::Remote("internal".to_string()).into(), None);
+    assert_classified(IpiisError::UnknownOpcode(42).into(), None);
+    assert_classified(IpiisError::Cancelled(0).into(), None);
+}
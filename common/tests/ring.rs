@@ -0,0 +1,53 @@
+use ipiis_common::ring::HashRing;
+use ipis::core::account::{Account, AccountRef};
+
+fn generate(n: usize) -> Vec<AccountRef> {
+    (0..n).map(|_| Account::generate().account_ref()).collect()
+}
+
+#[test]
+fn test_ring_stable_ownership() {
+    let nodes = generate(5);
+    let accounts = generate(256);
+
+    // the owners should not depend on the insertion order
+    let ring: HashRing = nodes.iter().copied().collect();
+    let reversed: HashRing = nodes.iter().rev().copied().collect();
+    for account in &accounts {
+        let owner = ring.get(account).unwrap();
+        assert!(nodes.contains(owner));
+        assert_eq!(ring.get(account), reversed.get(account));
+    }
+
+    // an empty ring owns nothing
+    assert!(HashRing::default().get(&accounts[0]).is_none());
+}
+
+#[test]
+fn test_ring_minimal_remapping() {
+    let nodes = generate(5);
+    let accounts = generate(256);
+
+    let mut ring: HashRing = nodes.iter().copied().collect();
+    let owners: Vec<_> = accounts.iter().map(|e| *ring.get(e).unwrap()).collect();
+
+    // removing a node should remap its own accounts only
+    let removed = nodes[2];
+    assert!(ring.remove(&removed));
+    assert!(!ring.remove(&removed));
+    assert_eq!(ring.len(), nodes.len() - 1);
+    for (account, owner) in accounts.iter().zip(&owners) {
+        let new_owner = ring.get(account).unwrap();
+        if owner == &removed {
+            assert_ne!(new_owner, &removed);
+        } else {
+            assert_eq!(new_owner, owner);
+        }
+    }
+
+    // adding it back should restore the owners
+    assert!(ring.insert(removed));
+    for (account, owner) in accounts.iter().zip(&owners) {
+        assert_eq!(ring.get(account), Some(owner));
+    }
+}
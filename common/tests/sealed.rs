@@ -0,0 +1,29 @@
+use ipiis_common::Sealed;
+use ipis::core::account::Account;
+
+#[test]
+fn test_sealed_relay() {
+    let client = Account::generate();
+    let relay = Account::generate();
+    let target = Account::generate();
+
+    // the client seals the request to the final target
+    let payload = b"confidential".to_vec();
+    let request = Sealed::seal(&target.account_ref(), &payload).unwrap();
+
+    // the relaying peer routes it, but cannot read it
+    assert!(request.open(&relay).is_err());
+    assert!(request.open(&client).is_err());
+
+    // the target can read it, and seals the response back
+    assert_eq!(request.open(&target).unwrap(), payload);
+    let response = Sealed::seal(&client.account_ref(), b"for your eyes only").unwrap();
+    assert!(response.open(&relay).is_err());
+    assert_eq!(response.open(&client).unwrap(), b"for your eyes only");
+
+    // each sealing should use a one-time key
+    assert_ne!(
+        Sealed::seal(&target.account_ref(), &payload).unwrap(),
+        request,
+    );
+}
@@ -0,0 +1,23 @@
+use ipiis_modules_bench_common::{args, clap::Parser, compare::Comparison};
+use ipis::{core::anyhow::Result, log::info};
+
+fn main() -> Result<()> {
+    // init logger
+    ::ipis::logger::init_once();
+
+    // parse the command-line arguments
+    let args = args::ArgsCompare::parse();
+
+    // load the results
+    let comparison = Comparison::load_dir(&args.dir)?;
+
+    // print the comparison
+    println!("{comparison}");
+
+    // save the comparison to a file
+    if let Some(filepath) = args.csv {
+        info!("- Saving comparison to {filepath:?} ...");
+        ::std::fs::write(filepath, comparison.to_csv())?;
+    }
+    Ok(())
+}
@@ -5,14 +5,19 @@ use std::{
     time::{Duration, Instant},
 };
 
-use ipiis_modules_bench_common::{args, byte_unit::Byte, clap::Parser, simulation::Simulator};
+use ipiis_modules_bench_common::{
+    args,
+    byte_unit::Byte,
+    clap::Parser,
+    ramp::{Ramp, RampLevel},
+    simulation::Simulator,
+};
 use ipis::{
     core::{anyhow::Result, chrono::Utc},
     futures,
     log::info,
     tokio,
 };
-use rand::{distributions::Uniform, Rng};
 
 #[tokio::main]
 async fn main() -> Result<()> {
@@ -36,6 +41,7 @@ async fn main() -> Result<()> {
     info!("- Data Size: {}", args.inputs.size);
     info!("- Number of Iteration: {}", args.inputs.iter);
     info!("- Number of Threads: {}", args.inputs.num_threads);
+    info!("- Payload: {}", &args.inputs.payload);
     info!("- Protocol: {protocol_name}");
 
     // compose simulation environment
@@ -50,17 +56,15 @@ async fn main() -> Result<()> {
 
     let size_bytes: usize = args.inputs.size.get_bytes().try_into()?;
     let num_iteration: usize = args.inputs.iter.get_bytes().try_into()?;
-    let num_threads: usize = args.inputs.num_threads.try_into()?;
 
     let simulation = args.simulation;
 
     // init data
     info!("- Initializing...");
-    let range = Uniform::from(0..=255);
-    let data: Arc<[_]> = ::rand::thread_rng()
-        .sample_iter(&range)
-        .take(size_bytes + num_iteration)
-        .collect::<Vec<u8>>()
+    let data: Arc<[_]> = args
+        .inputs
+        .payload
+        .generate(size_bytes + num_iteration)?
         .into();
 
     // construct dataset
@@ -69,41 +73,89 @@ async fn main() -> Result<()> {
         .map(|iter| (iter..iter + size_bytes))
         .collect();
 
-    // begin benchmaring
-    let duration = {
-        info!("- Benchmarking ...");
-
-        let instant = Instant::now();
-        futures::future::try_join_all(
-            (0..args.inputs.num_threads)
-                .map(|offset| crate::protocol::BenchmarkCtx {
-                    num_threads,
-                    size_bytes,
-                    simulation,
-
-                    offset,
-                    dataset: dataset.clone(),
-                    data: data.clone(),
-                })
-                .map(|ctx| protocol.ping(ctx)),
-        )
-        .await?;
-        instant.elapsed()
+    let benchmark = |num_threads: u32| {
+        let protocol = &protocol;
+        let dataset = dataset.clone();
+        let data = data.clone();
+        async move {
+            info!("- Benchmarking with {num_threads} threads ...");
+            let num_threads_usize: usize = num_threads.try_into()?;
+
+            let instant = Instant::now();
+            let latencies = futures::future::try_join_all(
+                (0..num_threads)
+                    .map(|offset| crate::protocol::BenchmarkCtx {
+                        num_threads: num_threads_usize,
+                        size_bytes,
+                        simulation,
+
+                        offset,
+                        dataset: dataset.clone(),
+                        data: data.clone(),
+                    })
+                    .map(|ctx| protocol.ping(ctx)),
+            )
+            .await?;
+            let duration = instant.elapsed();
+
+            let mut latencies: Vec<_> = latencies.into_iter().flatten().collect();
+            Result::<_, ::ipis::core::anyhow::Error>::Ok(RampLevel::new(
+                num_threads,
+                size_bytes,
+                duration,
+                &mut latencies,
+            ))
+        }
     };
 
+    // sweep the number of threads
+    if !args.inputs.ramp.is_empty() {
+        info!("- Ramp: {:?}", &args.inputs.ramp);
+        let ramp = Ramp::sweep(protocol_name.clone(), &args.inputs.ramp, benchmark).await?;
+
+        // save results to files
+        if let Some(save_dir) = args.inputs.save_dir.as_ref() {
+            let filename = format!(
+                "{prefix}{protocol_name}-{timestamp}",
+                prefix = ::ipiis_modules_bench_common::ramp::RAMP_PREFIX,
+                timestamp = timestamp.to_rfc3339(),
+            );
+
+            let filepath = save_dir.join(format!("{filename}.json"));
+            info!("- Saving results to {filepath:?} ...");
+            ramp.save(filepath)?;
+
+            let filepath = save_dir.join(format!("{filename}.csv"));
+            info!("- Saving results to {filepath:?} ...");
+            ::std::fs::write(filepath, ramp.to_csv())?;
+        }
+
+        // print the output
+        info!("- Finished!");
+        println!("{ramp}");
+        return Ok(());
+    }
+
+    // begin benchmaring
+    let level = benchmark(args.inputs.num_threads).await?;
+
     // collect results
     info!("- Collecting results ...");
     let outputs = args::ResultsOutputsMetric {
         protocol: protocol_name.to_string(),
-        elapsed_time_s: duration.as_secs_f64(),
-        iops: num_iteration as f64 / duration.as_secs_f64(),
-        speed_bps: (8 * size_bytes * num_iteration) as f64 / duration.as_secs_f64(),
+        elapsed_time_s: level.elapsed_time_s,
+        iops: level.iops,
+        speed_bps: level.speed_bps,
     };
 
     // save results to a file
     if let Some(mut save_dir) = args.inputs.save_dir.clone() {
         let timestamp = timestamp.to_rfc3339();
-        let filename = format!("benchmark-ipiis-{protocol_name}-{timestamp}.json");
+        let filename = format!(
+            "{prefix}{protocol_name}-{timestamp}.{ext}",
+            prefix = ::ipiis_modules_bench_common::compare::RESULTS_PREFIX,
+            ext = args.inputs.save_format.extension(),
+        );
         let filepath = {
             save_dir.push(filename);
             save_dir
@@ -119,14 +171,14 @@ async fn main() -> Result<()> {
             outputs: outputs.clone(),
             simulation,
         };
-        let file = ::std::fs::File::create(filepath)?;
-        ::serde_json::to_writer(file, &results)?;
+        results.save(filepath)?;
     }
 
     // print the output
     info!("- Finished!");
     info!("- Elapsed Time: {:?}", outputs.elapsed_time_s);
     info!("- IOPS: {}", outputs.iops);
+    info!("- Latency (p99): {}s", level.latency_p99_s);
     info!("- Speed: {}bps", {
         let mut speed = Byte::from_bytes(outputs.speed_bps as u128)
             .get_appropriate_unit(false)
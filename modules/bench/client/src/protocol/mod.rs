@@ -1,4 +1,8 @@
-use std::{ops::Range, sync::Arc};
+use std::{
+    ops::Range,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 use ipiis_common::Ipiis;
 use ipiis_modules_bench_common::{args, IpiisBench};
@@ -11,7 +15,8 @@ mod tcp;
 pub trait Protocol {
     async fn to_string(&self) -> Result<String>;
 
-    async fn ping(&self, ctx: self::BenchmarkCtx) -> Result<()>;
+    /// Sends the requests of the thread, returning the latency of each one.
+    async fn ping(&self, ctx: self::BenchmarkCtx) -> Result<Vec<Duration>>;
 }
 
 pub async fn select(args: &args::ArgsClient) -> Result<Box<dyn Protocol>> {
@@ -25,10 +30,11 @@ pub async fn select(args: &args::ArgsClient) -> Result<Box<dyn Protocol>> {
     }
 }
 
-pub(super) async fn ping<T>(client: &T, ctx: self::BenchmarkCtx) -> Result<()>
+pub(super) async fn ping<T>(client: &T, ctx: self::BenchmarkCtx) -> Result<Vec<Duration>>
 where
     T: Ipiis + IpiisBench,
 {
+    let mut latencies = vec![];
     for range in ctx
         .dataset
         .iter()
@@ -38,9 +44,12 @@ where
         let data = unsafe {
             ::core::slice::from_raw_parts(ctx.data.as_ptr().add(range.start), ctx.size_bytes)
         };
-        client.ping(DynStream::BorrowedSlice(data)).await?;
+
+        let instant = Instant::now();
+        IpiisBench::ping(client, DynStream::BorrowedSlice(data)).await?;
+        latencies.push(instant.elapsed());
     }
-    Ok(())
+    Ok(latencies)
 }
 
 pub struct BenchmarkCtx {
@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use ipiis_api_quic::client::IpiisClient;
 use ipiis_common::Ipiis;
 use ipiis_modules_bench_common::{args, KIND};
@@ -31,10 +33,10 @@ impl ProtocolImpl {
 #[async_trait]
 impl super::Protocol for ProtocolImpl {
     async fn to_string(&self) -> Result<String> {
-        Ok("quic".into())
+        Ok(self.client.protocol().into())
     }
 
-    async fn ping(&self, ctx: super::BenchmarkCtx) -> Result<()> {
+    async fn ping(&self, ctx: super::BenchmarkCtx) -> Result<Vec<Duration>> {
         super::ping(&self.client, ctx).await
     }
 }
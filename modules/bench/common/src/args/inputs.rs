@@ -1,8 +1,13 @@
-use std::path::PathBuf;
+use core::{fmt, str::FromStr};
+use std::path::{Path, PathBuf};
 
 use byte_unit::Byte;
 use clap::{Parser, ValueEnum};
-use ipis::core::account::AccountRef;
+use ipis::core::{
+    account::AccountRef,
+    anyhow::{anyhow, bail, Error, Result},
+};
+use rand::{distributions::Uniform, Rng};
 use serde::{Deserialize, Serialize};
 use simulation::ipnet::IpNet;
 
@@ -26,6 +31,18 @@ pub struct ArgsServer {
     pub inputs: ArgsServerInputs,
 }
 
+#[derive(Debug, Parser)]
+#[clap(author, version, about, long_about = None)]
+pub struct ArgsCompare {
+    /// Directory of the saved results
+    #[clap(env = "SAVE_DIR")]
+    pub dir: PathBuf,
+
+    /// File to export the comparison as CSV
+    #[clap(long, env = "CSV")]
+    pub csv: Option<PathBuf>,
+}
+
 #[derive(Debug, Parser)]
 pub struct ArgsIpiis {
     /// Account of the target server
@@ -56,9 +73,25 @@ pub struct ArgsClientInputs {
     #[clap(long, env = "NUM_THREADS", default_value_t = 1)]
     pub num_threads: u32,
 
+    /// Numbers of threads to sweep in order, e.g. `1,2,4,8,16` (overrides `--num-threads`)
+    #[clap(long, env = "RAMP", value_delimiter = ',')]
+    #[serde(default)]
+    pub ramp: Vec<u32>,
+
+    /// Pattern of benchmarking stream: random, zeros, text or file:PATH
+    #[clap(long, env = "PAYLOAD", default_value_t = ArgsPayload::Random)]
+    #[serde(default)]
+    pub payload: ArgsPayload,
+
     /// Directory to save the results (filename is hashed by protocol and starting time)
     #[clap(long, env = "SAVE_DIR")]
     pub save_dir: Option<PathBuf>,
+
+    /// Format of the saved results
+    #[clap(value_enum)]
+    #[clap(long, env = "SAVE_FORMAT", default_value_t = ArgsSaveFormat::Json)]
+    #[serde(default)]
+    pub save_format: ArgsSaveFormat,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Parser)]
@@ -76,6 +109,125 @@ pub enum ArgsProtocol {
     Tcp,
 }
 
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum ArgsSaveFormat {
+    #[default]
+    Json,
+    JsonGz,
+    JsonZst,
+}
+
+impl ArgsSaveFormat {
+    /// Returns the file extension, without the leading dot.
+    pub const fn extension(self) -> &'static str {
+        match self {
+            Self::Json => "json",
+            Self::JsonGz => "json.gz",
+            Self::JsonZst => "json.zst",
+        }
+    }
+
+    /// Infers the format by the file extension.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let name = path.file_name()?.to_str()?;
+
+        [Self::JsonGz, Self::JsonZst, Self::Json]
+            .into_iter()
+            .find(|format| name.ends_with(&format!(".{}", format.extension())))
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ArgsPayload {
+    /// Uniformly random bytes, which are incompressible
+    #[default]
+    Random,
+    /// All-zero bytes, which are the most compressible
+    Zeros,
+    /// Random words of ASCII text
+    Text,
+    /// The contents of the file, repeated to fill the stream
+    File(PathBuf),
+}
+
+impl ArgsPayload {
+    /// Generates the payload of the given length.
+    pub fn generate(&self, len: usize) -> Result<Vec<u8>> {
+        match self {
+            Self::Random => {
+                let range = Uniform::from(0..=255);
+                Ok(::rand::thread_rng().sample_iter(&range).take(len).collect())
+            }
+            Self::Zeros => Ok(vec![0; len]),
+            Self::Text => {
+                const WORDS: &[&str] = &[
+                    "interplanetary",
+                    "interface",
+                    "interconnection",
+                    "service",
+                    "account",
+                    "address",
+                    "primary",
+                    "request",
+                    "response",
+                    "stream",
+                    "the",
+                    "of",
+                    "to",
+                    "a",
+                ];
+
+                let mut rng = ::rand::thread_rng();
+                let mut data = Vec::with_capacity(len + 16);
+                while data.len() < len {
+                    let word = WORDS[rng.gen_range(0..WORDS.len())];
+                    data.extend_from_slice(word.as_bytes());
+                    data.push(b' ');
+                }
+                data.truncate(len);
+                Ok(data)
+            }
+            Self::File(path) => {
+                let file = ::std::fs::read(path)
+                    .map_err(|e| anyhow!("failed to read the payload file: {path:?}: {e}"))?;
+                if file.is_empty() && len > 0 {
+                    bail!("empty payload file: {path:?}");
+                }
+                Ok(file.into_iter().cycle().take(len).collect())
+            }
+        }
+    }
+}
+
+impl fmt::Display for ArgsPayload {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Random => write!(f, "random"),
+            Self::Zeros => write!(f, "zeros"),
+            Self::Text => write!(f, "text"),
+            Self::File(path) => write!(f, "file:{}", path.display()),
+        }
+    }
+}
+
+impl FromStr for ArgsPayload {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "random" => Ok(Self::Random),
+            "zeros" => Ok(Self::Zeros),
+            "text" => Ok(Self::Text),
+            _ => match s.strip_prefix("file:") {
+                Some(path) if !path.is_empty() => Ok(Self::File(path.into())),
+                _ => bail!("unknown payload: {s:?} (expected random, zeros, text or file:PATH)"),
+            },
+        }
+    }
+}
+
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Parser)]
 pub struct ArgsSimulation {
     /// Manual network delay in milliseconds
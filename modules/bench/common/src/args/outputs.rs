@@ -1,6 +1,13 @@
+use std::{
+    fs::File,
+    io::{BufReader, BufWriter, Write},
+    path::Path,
+};
+
+use ipis::core::anyhow::{anyhow, Result};
 use serde::{Deserialize, Serialize};
 
-use super::inputs::{ArgsClientInputs, ArgsSimulation};
+use super::inputs::{ArgsClientInputs, ArgsSaveFormat, ArgsSimulation};
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct Results {
@@ -10,6 +17,53 @@ pub struct Results {
     pub simulation: ArgsSimulation,
 }
 
+impl Results {
+    /// Loads the results, decompressing them by the file extension.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let format = ArgsSaveFormat::from_path(path)
+            .ok_or_else(|| anyhow!("unknown format of the results: {path:?}"))?;
+
+        let file = BufReader::new(File::open(path)?);
+        match format {
+            ArgsSaveFormat::Json => ::serde_json::from_reader(file),
+            ArgsSaveFormat::JsonGz => {
+                ::serde_json::from_reader(::flate2::read::GzDecoder::new(file))
+            }
+            ArgsSaveFormat::JsonZst => ::serde_json::from_reader(::zstd::Decoder::new(file)?),
+        }
+        .map_err(|e| anyhow!("failed to parse the results: {path:?}: {e}"))
+    }
+
+    /// Saves the results, compressing them by the file extension.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        let format = ArgsSaveFormat::from_path(path)
+            .ok_or_else(|| anyhow!("unknown format of the results: {path:?}"))?;
+
+        let file = BufWriter::new(File::create(path)?);
+        match format {
+            ArgsSaveFormat::Json => {
+                let mut file = file;
+                ::serde_json::to_writer(&mut file, self)?;
+                file.flush()?;
+            }
+            ArgsSaveFormat::JsonGz => {
+                let mut encoder =
+                    ::flate2::write::GzEncoder::new(file, ::flate2::Compression::default());
+                ::serde_json::to_writer(&mut encoder, self)?;
+                encoder.finish()?.flush()?;
+            }
+            ArgsSaveFormat::JsonZst => {
+                let mut encoder = ::zstd::Encoder::new(file, 0)?;
+                ::serde_json::to_writer(&mut encoder, self)?;
+                encoder.finish()?.flush()?;
+            }
+        }
+        Ok(())
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ArgsIpiisPublic {
     /// Public Account of the target server
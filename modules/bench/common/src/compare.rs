@@ -0,0 +1,217 @@
+use core::fmt;
+use std::path::Path;
+
+use byte_unit::Byte;
+use ipis::core::anyhow::{anyhow, Result};
+
+use crate::args::{ArgsPayload, ArgsSaveFormat, ArgsSimulation, Results};
+
+/// The file name prefix of the saved benchmark results.
+pub const RESULTS_PREFIX: &str = "benchmark-ipiis-";
+
+/// Summary of the saved benchmark results, grouped by the inputs.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Comparison {
+    pub groups: Vec<ComparisonGroup>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ComparisonGroup {
+    /// Size of benchmarking stream
+    pub size: Byte,
+
+    /// Number of threads
+    pub num_threads: u32,
+
+    /// Pattern of benchmarking stream
+    pub payload: ArgsPayload,
+
+    /// Simulated environment
+    pub simulation: ArgsSimulation,
+
+    /// Averaged metrics per protocol
+    pub rows: Vec<ComparisonRow>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ComparisonRow {
+    /// Protocol of queried benchmarking stream
+    pub protocol: String,
+
+    /// Number of the merged runs
+    pub runs: usize,
+
+    /// Averaged I/O per seconds
+    pub iops: f64,
+
+    /// Averaged speed as bps
+    pub speed_bps: f64,
+}
+
+impl Comparison {
+    /// Loads all `benchmark-ipiis-*.json` files in the directory,
+    /// including the compressed ones (`.json.gz`, `.json.zst`).
+    pub fn load_dir(dir: impl AsRef<Path>) -> Result<Self> {
+        let dir = dir.as_ref();
+
+        let mut results = vec![];
+        for entry in ::std::fs::read_dir(dir)
+            .map_err(|e| anyhow!("failed to read the directory: {dir:?}: {e}"))?
+        {
+            let path = entry?.path();
+            let is_results = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .map(|name| name.starts_with(RESULTS_PREFIX))
+                .unwrap_or_default()
+                && ArgsSaveFormat::from_path(&path).is_some();
+            if !is_results {
+                continue;
+            }
+
+            results.push(Results::load(&path)?);
+        }
+        Ok(Self::new(results))
+    }
+
+    pub fn new(results: impl IntoIterator<Item = Results>) -> Self {
+        let mut groups: Vec<ComparisonGroup> = vec![];
+
+        for results in results {
+            let group = match groups.iter_mut().find(|group| {
+                group.size == results.inputs.size
+                    && group.num_threads == results.inputs.num_threads
+                    && group.payload == results.inputs.payload
+                    && group.simulation == results.simulation
+            }) {
+                Some(group) => group,
+                None => {
+                    groups.push(ComparisonGroup {
+                        size: results.inputs.size,
+                        num_threads: results.inputs.num_threads,
+                        payload: results.inputs.payload,
+                        simulation: results.simulation,
+                        rows: vec![],
+                    });
+                    groups.last_mut().unwrap()
+                }
+            };
+
+            let outputs = results.outputs;
+            match group
+                .rows
+                .iter_mut()
+                .find(|row| row.protocol == outputs.protocol)
+            {
+                // merge into the running average
+                Some(row) => {
+                    let runs = row.runs as f64;
+                    row.iops = (row.iops * runs + outputs.iops) / (runs + 1.0);
+                    row.speed_bps = (row.speed_bps * runs + outputs.speed_bps) / (runs + 1.0);
+                    row.runs += 1;
+                }
+                None => group.rows.push(ComparisonRow {
+                    protocol: outputs.protocol,
+                    runs: 1,
+                    iops: outputs.iops,
+                    speed_bps: outputs.speed_bps,
+                }),
+            }
+        }
+
+        // sort by the fastest
+        for group in &mut groups {
+            group.rows.sort_by(|a, b| {
+                b.iops
+                    .partial_cmp(&a.iops)
+                    .unwrap_or(::core::cmp::Ordering::Equal)
+            });
+        }
+        Self { groups }
+    }
+
+    /// Exports the comparison as CSV.
+    pub fn to_csv(&self) -> String {
+        let mut csv =
+            "size_bytes,num_threads,payload,network_delay_ms,protocol,runs,iops,speed_bps,delta_percent\n"
+                .to_string();
+        for group in &self.groups {
+            for row in &group.rows {
+                csv.push_str(&format!(
+                    "{},{},{},{},{},{},{},{},{}\n",
+                    group.size.get_bytes(),
+                    group.num_threads,
+                    group.payload,
+                    group
+                        .simulation
+                        .network_delay_ms
+                        .map(|delay| delay.to_string())
+                        .unwrap_or_default(),
+                    row.protocol,
+                    row.runs,
+                    row.iops,
+                    row.speed_bps,
+                    group.delta_percent(row),
+                ));
+            }
+        }
+        csv
+    }
+}
+
+impl ComparisonGroup {
+    /// Returns the row with the highest IOPS.
+    pub fn fastest(&self) -> Option<&ComparisonRow> {
+        self.rows.first()
+    }
+
+    /// Returns the IOPS difference from the fastest one, as percent.
+    pub fn delta_percent(&self, row: &ComparisonRow) -> f64 {
+        match self.fastest() {
+            Some(fastest) if fastest.iops > 0.0 => (row.iops / fastest.iops - 1.0) * 100.0,
+            _ => 0.0,
+        }
+    }
+}
+
+impl fmt::Display for Comparison {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for group in &self.groups {
+            writeln!(
+                f,
+                "# Data Size: {}, Threads: {}, Payload: {}, Network Delay: {}",
+                group.size.get_appropriate_unit(false),
+                group.num_threads,
+                group.payload,
+                group
+                    .simulation
+                    .network_delay_ms
+                    .map(|delay| format!("{delay}ms"))
+                    .unwrap_or_else(|| "none".to_string()),
+            )?;
+            writeln!(
+                f,
+                "{:<12} {:>6} {:>14} {:>14} {:>10}",
+                "protocol", "runs", "iops", "speed", "delta",
+            )?;
+            for row in &group.rows {
+                writeln!(
+                    f,
+                    "{:<12} {:>6} {:>14.3} {:>14} {:>9.1}%",
+                    row.protocol,
+                    row.runs,
+                    row.iops,
+                    {
+                        let mut speed = Byte::from_bytes(row.speed_bps as u128)
+                            .get_appropriate_unit(false)
+                            .to_string();
+                        speed.pop();
+                        format!("{speed}bps")
+                    },
+                    group.delta_percent(row),
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -3,6 +3,8 @@ pub extern crate clap;
 pub extern crate ipiis_modules_bench_simulation as simulation;
 
 pub mod args;
+pub mod compare;
+pub mod ramp;
 
 use ipiis_common::{define_io, external_call, Ipiis, ServerResult};
 use ipis::{
@@ -48,7 +50,7 @@ where
 }
 
 define_io! {
-    Ping {
+    Ping = 0 {
         inputs: {
             data: Vec<u8>,
         },
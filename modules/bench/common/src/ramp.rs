@@ -0,0 +1,163 @@
+use core::{fmt, future::Future, time::Duration};
+use std::path::Path;
+
+use ipis::core::anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+
+/// The file name prefix of the saved concurrency sweeps.
+pub const RAMP_PREFIX: &str = "ramp-ipiis-";
+
+/// The relative IOPS gain under which adding threads is considered saturated.
+pub const SATURATION_GAIN: f64 = 0.05;
+
+/// Results of a benchmark sweeping the number of threads.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Ramp {
+    /// Protocol of queried benchmarking stream
+    pub protocol: String,
+
+    /// Metrics per number of threads, in the swept order
+    pub levels: Vec<RampLevel>,
+
+    /// Number of threads from which adding more stops improving the IOPS
+    pub saturation: Option<u32>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RampLevel {
+    /// Number of threads
+    pub num_threads: u32,
+
+    /// Elapsed time as seconds
+    pub elapsed_time_s: f64,
+
+    /// I/O per seconds
+    pub iops: f64,
+
+    /// Estimated speed as bps
+    pub speed_bps: f64,
+
+    /// 99th percentile latency of a request as seconds
+    pub latency_p99_s: f64,
+}
+
+impl Ramp {
+    /// Runs the benchmark once per number of threads, in order.
+    pub async fn sweep<F, Fut>(protocol: String, num_threads: &[u32], mut f: F) -> Result<Self>
+    where
+        F: FnMut(u32) -> Fut,
+        Fut: Future<Output = Result<RampLevel>>,
+    {
+        if num_threads.is_empty() {
+            bail!("no levels to sweep");
+        }
+
+        let mut levels = Vec::with_capacity(num_threads.len());
+        for &num_threads in num_threads {
+            levels.push(f(num_threads).await?);
+        }
+        Ok(Self::new(protocol, levels))
+    }
+
+    pub fn new(protocol: String, levels: Vec<RampLevel>) -> Self {
+        // find the first level which the next one barely improves
+        let saturation = levels
+            .windows(2)
+            .find(|pair| pair[1].iops < pair[0].iops * (1.0 + SATURATION_GAIN))
+            .map(|pair| pair[0].num_threads);
+
+        Self {
+            protocol,
+            levels,
+            saturation,
+        }
+    }
+
+    /// Saves the sweep as JSON.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let file = ::std::fs::File::create(path)?;
+        ::serde_json::to_writer(file, self).map_err(Into::into)
+    }
+
+    /// Exports the sweep as CSV.
+    pub fn to_csv(&self) -> String {
+        let mut csv =
+            "protocol,num_threads,elapsed_time_s,iops,speed_bps,latency_p99_s,saturated\n"
+                .to_string();
+        for level in &self.levels {
+            csv.push_str(&format!(
+                "{},{},{},{},{},{},{}\n",
+                self.protocol,
+                level.num_threads,
+                level.elapsed_time_s,
+                level.iops,
+                level.speed_bps,
+                level.latency_p99_s,
+                self.saturation == Some(level.num_threads),
+            ));
+        }
+        csv
+    }
+}
+
+impl RampLevel {
+    pub fn new(
+        num_threads: u32,
+        size_bytes: usize,
+        elapsed: Duration,
+        latencies: &mut [Duration],
+    ) -> Self {
+        let num_iteration = latencies.len();
+        Self {
+            num_threads,
+            elapsed_time_s: elapsed.as_secs_f64(),
+            iops: num_iteration as f64 / elapsed.as_secs_f64(),
+            speed_bps: (8 * size_bytes * num_iteration) as f64 / elapsed.as_secs_f64(),
+            latency_p99_s: percentile(latencies, 0.99).as_secs_f64(),
+        }
+    }
+}
+
+/// Returns the nearest-rank percentile of the latencies, sorting them in place.
+pub fn percentile(latencies: &mut [Duration], p: f64) -> Duration {
+    if latencies.is_empty() {
+        return Duration::ZERO;
+    }
+
+    latencies.sort_unstable();
+    let rank = (p * latencies.len() as f64).ceil() as usize;
+    latencies[rank.clamp(1, latencies.len()) - 1]
+}
+
+impl fmt::Display for Ramp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "# Protocol: {}", self.protocol)?;
+        writeln!(
+            f,
+            "{:>8} {:>14} {:>14} {:>12}",
+            "threads", "iops", "p99", "gain",
+        )?;
+
+        let mut last_iops = None;
+        for level in &self.levels {
+            writeln!(
+                f,
+                "{:>8} {:>14.3} {:>12.3}ms {:>11.1}%{}",
+                level.num_threads,
+                level.iops,
+                level.latency_p99_s * 1e3,
+                last_iops
+                    .filter(|&iops: &f64| iops > 0.0)
+                    .map(|iops| (level.iops / iops - 1.0) * 100.0)
+                    .unwrap_or_default(),
+                if self.saturation == Some(level.num_threads) {
+                    " <- saturated"
+                } else {
+                    ""
+                },
+            )?;
+            last_iops = Some(level.iops);
+        }
+        Ok(())
+    }
+}
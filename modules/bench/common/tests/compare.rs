@@ -0,0 +1,91 @@
+use ipiis_modules_bench_common::{
+    args::{
+        ArgsClientInputs, ArgsIpiisPublic, ArgsProtocol, ArgsSaveFormat, ArgsSimulation, Results,
+        ResultsOutputsMetric,
+    },
+    byte_unit::Byte,
+    compare::Comparison,
+};
+
+fn results(protocol: ArgsProtocol, name: &str, iops: f64) -> Results {
+    Results {
+        ipiis: ArgsIpiisPublic {
+            account: Default::default(),
+            address: "127.0.0.1:9801".to_string(),
+        },
+        inputs: ArgsClientInputs {
+            protocol,
+            size: Byte::from_bytes(1_000),
+            iter: Byte::from_bytes(30),
+            num_threads: 1,
+            ramp: vec![],
+            payload: Default::default(),
+            save_dir: None,
+            save_format: Default::default(),
+        },
+        outputs: ResultsOutputsMetric {
+            protocol: name.to_string(),
+            elapsed_time_s: 30.0 / iops,
+            iops,
+            speed_bps: 8_000.0 * iops,
+        },
+        simulation: ArgsSimulation {
+            network_delay_ms: None,
+            network_delay_subnet: None,
+        },
+    }
+}
+
+#[test]
+fn test_compare() {
+    // write synthetic result files
+    let dir = ::std::env::temp_dir().join("ipiis-test-bench-compare");
+    ::std::fs::create_dir_all(&dir).unwrap();
+    for (filename, results) in [
+        (
+            "benchmark-ipiis-tcp-0.json",
+            results(ArgsProtocol::Tcp, "tcp", 100.0),
+        ),
+        (
+            "benchmark-ipiis-quic-0.json",
+            results(ArgsProtocol::Quic, "quic", 150.0),
+        ),
+    ] {
+        let file = ::std::fs::File::create(dir.join(filename)).unwrap();
+        ::serde_json::to_writer(file, &results).unwrap();
+    }
+
+    // compare the results
+    let comparison = Comparison::load_dir(&dir).unwrap();
+    assert_eq!(comparison.groups.len(), 1);
+
+    let group = &comparison.groups[0];
+    assert_eq!(group.rows.len(), 2);
+    assert_eq!(group.fastest().unwrap().protocol, "quic");
+    assert!((group.delta_percent(&group.rows[1]) + 100.0 / 3.0).abs() < 1e-9);
+}
+
+#[test]
+fn test_compressed_results() {
+    let dir = ::std::env::temp_dir().join("ipiis-test-bench-compressed");
+    let _ = ::std::fs::remove_dir_all(&dir);
+    ::std::fs::create_dir_all(&dir).unwrap();
+
+    let expected = results(ArgsProtocol::Tcp, "tcp", 100.0);
+    for format in [
+        ArgsSaveFormat::Json,
+        ArgsSaveFormat::JsonGz,
+        ArgsSaveFormat::JsonZst,
+    ] {
+        // save and reload the results
+        let path = dir.join(format!("benchmark-ipiis-tcp-0.{}", format.extension()));
+        expected.save(&path).unwrap();
+        assert_eq!(Results::load(&path).unwrap(), expected);
+    }
+
+    // the comparison should decompress them transparently
+    let comparison = Comparison::load_dir(&dir).unwrap();
+    assert_eq!(comparison.groups.len(), 1);
+    assert_eq!(comparison.groups[0].rows.len(), 1);
+    assert_eq!(comparison.groups[0].rows[0].runs, 3);
+}
@@ -0,0 +1,42 @@
+use ipiis_common::test_util::{roundtrip_request, roundtrip_response};
+use ipiis_modules_bench_common::io::{request, response};
+use ipis::{
+    core::{account::Account, data::Data},
+    stream::DynStream,
+    tokio,
+};
+
+#[tokio::test]
+async fn test_ping_roundtrip() {
+    let account = Account::generate();
+    let target = Account::generate();
+    let data: Vec<u8> = (0..4096).map(|i| (i % 251) as u8).collect();
+
+    let sign = Data::builder()
+        .build_owned(&account, target.account_ref(), 42u8)
+        .unwrap();
+
+    // the request should be read back as it is
+    let req = request::Ping {
+        __lifetime: Default::default(),
+        __sign: DynStream::Owned(sign.clone()),
+        data: DynStream::Owned(data.clone()),
+    };
+    let req = roundtrip_request(req).await.unwrap();
+
+    let sign_decoded = req.__sign.into_owned().await.unwrap();
+    assert_eq!(sign_decoded.metadata.guarantee, account.account_ref());
+    assert_eq!(sign_decoded.data, 42);
+    assert_eq!(req.data.into_owned().await.unwrap(), data);
+
+    // the response should be read back as it is
+    let res = response::Ping {
+        __lifetime: Default::default(),
+        __sign: DynStream::Owned(sign.sign(&target).unwrap()),
+    };
+    let res = roundtrip_response(res).await.unwrap();
+
+    let sign_decoded = res.__sign.into_owned().await.unwrap();
+    assert_eq!(sign_decoded.metadata.guarantee, account.account_ref());
+    assert_eq!(sign_decoded.data, 42);
+}
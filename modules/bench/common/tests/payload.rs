@@ -0,0 +1,34 @@
+use ipiis_modules_bench_common::args::ArgsPayload;
+
+const SIZE: usize = 1_000_000;
+
+#[test]
+fn test_payload_compressibility() {
+    let compressed_len = |payload: ArgsPayload| {
+        let data = payload.generate(SIZE).unwrap();
+        assert_eq!(data.len(), SIZE);
+        ::zstd::encode_all(data.as_slice(), 0).unwrap().len()
+    };
+
+    // zeros should be transferred in far fewer bytes than its logical size
+    assert!(compressed_len(ArgsPayload::Zeros) < SIZE / 100);
+    assert!(compressed_len(ArgsPayload::Text) < SIZE / 2);
+
+    // random bytes should be incompressible
+    assert!(compressed_len(ArgsPayload::Random) > SIZE * 99 / 100);
+}
+
+#[test]
+fn test_payload_file() {
+    let path = ::std::env::temp_dir().join("ipiis-test-bench-payload.txt");
+    ::std::fs::write(&path, b"abc").unwrap();
+
+    // the file should be repeated to fill the stream
+    let payload: ArgsPayload = format!("file:{}", path.display()).parse().unwrap();
+    assert_eq!(payload, ArgsPayload::File(path));
+    assert_eq!(payload.generate(8).unwrap(), b"abcabcab");
+
+    assert_eq!("zeros".parse::<ArgsPayload>().unwrap(), ArgsPayload::Zeros);
+    assert!("file:".parse::<ArgsPayload>().is_err());
+    assert!("unknown".parse::<ArgsPayload>().is_err());
+}
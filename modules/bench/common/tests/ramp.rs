@@ -0,0 +1,50 @@
+use core::time::Duration;
+
+use ipiis_modules_bench_common::ramp::{percentile, Ramp, RampLevel};
+use ipis::tokio;
+
+const LEVELS: &[u32] = &[1, 2, 4, 8, 16];
+
+#[tokio::test]
+async fn test_ramp() {
+    // simulate a server saturating at 4 threads
+    let ramp = Ramp::sweep("tcp".to_string(), LEVELS, |num_threads| async move {
+        let iops = 100.0 * num_threads.min(4) as f64;
+        let mut latencies = vec![Duration::from_secs_f64(num_threads as f64 / iops); 100];
+        Ok(RampLevel::new(
+            num_threads,
+            1_000,
+            Duration::from_secs_f64(100.0 / iops),
+            &mut latencies,
+        ))
+    })
+    .await
+    .unwrap();
+
+    // one entry per level
+    assert_eq!(
+        ramp.levels
+            .iter()
+            .map(|level| level.num_threads)
+            .collect::<Vec<_>>(),
+        LEVELS,
+    );
+    assert!((ramp.levels[2].iops - 400.0).abs() < 1e-9);
+    assert_eq!(ramp.saturation, Some(4));
+
+    // export
+    let csv = ramp.to_csv();
+    assert_eq!(csv.lines().count(), 1 + LEVELS.len());
+    assert!(csv.lines().nth(3).unwrap().ends_with(",true"));
+
+    let json: Ramp = ::serde_json::from_str(&::serde_json::to_string(&ramp).unwrap()).unwrap();
+    assert_eq!(json, ramp);
+}
+
+#[test]
+fn test_percentile() {
+    let mut latencies: Vec<_> = (1..=100).rev().map(Duration::from_millis).collect();
+    assert_eq!(percentile(&mut latencies, 0.99), Duration::from_millis(99));
+    assert_eq!(percentile(&mut latencies, 1.0), Duration::from_millis(100));
+    assert_eq!(percentile(&mut [], 0.99), Duration::ZERO);
+}
@@ -7,6 +7,10 @@ use ipis::core::account::AccountRef;
 pub struct Args {
     #[clap(subcommand)]
     pub command: Command,
+
+    /// Operate purely on the local address book, bypassing the directory
+    #[clap(long, global = true, env = "ipiis_client_local")]
+    pub local: bool,
 }
 
 #[allow(clippy::enum_variant_names)]
@@ -37,6 +41,10 @@ pub enum Command {
         /// Whether the target server is primary
         #[clap(long, env = "ipiis_client_is_primary")]
         primary: bool,
+
+        /// Whether to re-read the address from the root after setting
+        #[clap(long, env = "ipiis_client_verify")]
+        verify: bool,
     },
     DeleteAccount {
         /// Kind of the target server
@@ -47,4 +55,63 @@ pub enum Command {
         #[clap(long, env = "ipiis_client_account")]
         account: Option<AccountRef>,
     },
+    /// Traces the resolution of the address along the primaries
+    Traceroute {
+        /// Kind of the target server
+        #[clap(long, env = "ipiis_client_kind")]
+        kind: Option<String>,
+
+        /// Account of the target server
+        #[clap(long, env = "ipiis_client_account")]
+        account: AccountRef,
+    },
+    /// Sets the primary account of a kind and its address in the local address book
+    Bootstrap {
+        /// Kind of the target server
+        #[clap(long, env = "ipiis_client_kind")]
+        kind: Option<String>,
+
+        /// Account of the primary server
+        #[clap(long, env = "ipiis_client_account")]
+        account: AccountRef,
+
+        /// Address of the primary server
+        #[clap(long, env = "ipiis_client_address")]
+        address: <IpiisClient as Ipiis>::Address,
+    },
+    /// Prints the effective configuration of the client
+    Config,
+    /// Fires the pings to the target repeatedly, printing the IOPS and the p99 latency
+    BenchPing {
+        /// Kind of the target server
+        #[clap(long, env = "ipiis_client_kind")]
+        kind: Option<String>,
+
+        /// Account of the target server
+        #[clap(long, env = "ipiis_client_account")]
+        target: AccountRef,
+
+        /// Number of the pings
+        #[clap(long, default_value_t = 1000)]
+        count: usize,
+
+        /// Number of the concurrent pings
+        #[clap(long, default_value_t = 16)]
+        concurrency: usize,
+    },
+    /// Resolves the address of the target repeatedly, bypassing the local address book,
+    /// printing the p50 and the p99 latency
+    BenchResolve {
+        /// Kind of the target server
+        #[clap(long, env = "ipiis_client_kind")]
+        kind: Option<String>,
+
+        /// Account of the target server
+        #[clap(long, env = "ipiis_client_account")]
+        account: AccountRef,
+
+        /// Number of the lookups
+        #[clap(long, default_value_t = 100)]
+        count: usize,
+    },
 }
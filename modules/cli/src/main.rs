@@ -1,9 +1,15 @@
 mod args;
 
 use clap::Parser;
-use ipiis_api::{client::IpiisClient, common::Ipiis};
+use ipiis_api::{
+    client::IpiisClient,
+    common::{bench_resolve, ping_flood, Ipiis},
+};
 use ipis::{
-    core::{anyhow::Result, value::hash::Hash},
+    core::{
+        anyhow::{anyhow, bail, Result},
+        value::hash::Hash,
+    },
     env::Infer,
     tokio,
 };
@@ -20,16 +26,28 @@ async fn main() -> Result<()> {
     let client = IpiisClient::try_infer().await?;
 
     // execute a command
+    let local = args.local;
     match args.command {
         args::Command::GetAccount { kind, account } => {
             let kind = kind.as_ref().map(|kind| Hash::with_str(kind));
             let target = match account {
                 Some(account) => account,
+                None if local => client
+                    .book()
+                    .get_primary(kind.as_ref())?
+                    .ok_or_else(|| anyhow!("failed to get primary address"))?,
                 None => client.get_account_primary(kind.as_ref()).await?,
             };
 
             let account = target.to_string();
-            let address = client.get_address(kind.as_ref(), &target).await?;
+            let address = if local {
+                client
+                    .book()
+                    .get(kind.as_ref(), &target)?
+                    .ok_or_else(|| anyhow!("failed to get address: {account}"))?
+            } else {
+                client.get_address(kind.as_ref(), &target).await?
+            };
             println!("Account = {account}");
             println!("Address = {address}");
             Ok(())
@@ -39,12 +57,31 @@ async fn main() -> Result<()> {
             account,
             address,
             primary,
+            verify,
         } => {
             let kind = kind.as_ref().map(|kind| Hash::with_str(kind));
 
-            client
-                .set_address(kind.as_ref(), &account, &address)
-                .await?;
+            if local {
+                if verify {
+                    bail!("cannot verify the address against the root in local mode");
+                }
+
+                client.book().set(kind.as_ref(), &account, &address)?;
+                if primary {
+                    client.book().set_primary(kind.as_ref(), &account)?;
+                }
+                return Ok(());
+            }
+
+            if verify {
+                client
+                    .set_address_verified(kind.as_ref(), &account, &address)
+                    .await?;
+            } else {
+                client
+                    .set_address(kind.as_ref(), &account, &address)
+                    .await?;
+            }
             if primary {
                 client.set_account_primary(kind.as_ref(), &account).await?;
             }
@@ -54,17 +91,115 @@ async fn main() -> Result<()> {
             let kind = kind.as_ref().map(|kind| Hash::with_str(kind));
             let target = match account {
                 Some(account) => account,
+                None if local => client
+                    .book()
+                    .get_primary(kind.as_ref())?
+                    .ok_or_else(|| anyhow!("failed to get primary address"))?,
                 None => client.get_account_primary(kind.as_ref()).await?,
             };
 
-            if account.is_none() {
-                client.delete_account_primary(kind.as_ref()).await?;
+            if local {
+                if account.is_none() {
+                    client.book().delete_primary(kind.as_ref())?;
+                }
+                client.book().delete(kind.as_ref(), &target)?;
+            } else {
+                if account.is_none() {
+                    client.delete_account_primary(kind.as_ref()).await?;
+                }
+                client.delete_address(kind.as_ref(), &target).await?;
             }
 
             let account = target.to_string();
-            client.delete_address(kind.as_ref(), &target).await?;
             println!("Account = {account}");
             Ok(())
         }
+        args::Command::Traceroute { kind, account } => {
+            if local {
+                bail!("cannot trace the address in local mode");
+            }
+
+            let kind = kind.as_ref().map(|kind| Hash::with_str(kind));
+            let hops = client.trace_address(kind.as_ref(), &account).await?;
+
+            let account = account.to_string();
+            println!("Account = {account}");
+            for (index, hop) in hops.iter().enumerate() {
+                let index = index + 1;
+                let account = hop.account.to_string();
+                let rtt = hop.rtt;
+                match &hop.error {
+                    None => println!("{index:>2}  {account}  {rtt:?}"),
+                    Some(error) => println!("{index:>2}  {account}  {rtt:?}  * {error}"),
+                }
+            }
+            Ok(())
+        }
+        args::Command::Bootstrap {
+            kind,
+            account,
+            address,
+        } => {
+            let kind = kind.as_ref().map(|kind| Hash::with_str(kind));
+
+            client
+                .book()
+                .set_primary_with_address(kind.as_ref(), &account, &address)?;
+
+            let account = account.to_string();
+            println!("Account = {account}");
+            println!("Address = {address}");
+            Ok(())
+        }
+        args::Command::Config => {
+            let summary = client.config_summary();
+            println!("{}", ::serde_json::to_string_pretty(&summary)?);
+            Ok(())
+        }
+        args::Command::BenchPing {
+            kind,
+            target,
+            count,
+            concurrency,
+        } => {
+            if local {
+                bail!("cannot ping the target in local mode");
+            }
+
+            let kind = kind.as_ref().map(|kind| Hash::with_str(kind));
+            let report = ping_flood(&client, kind.as_ref(), &target, count, concurrency).await;
+
+            let account = target.to_string();
+            println!("Account = {account}");
+            println!("Succeeded = {}", report.succeeded);
+            println!("Failed = {}", report.failed);
+            println!("Elapsed = {:?}", report.elapsed);
+            println!("IOPS = {:.1}", report.iops);
+            println!("p99 = {:?}", report.rtt_p99);
+            Ok(())
+        }
+        args::Command::BenchResolve {
+            kind,
+            account,
+            count,
+        } => {
+            if local {
+                bail!("cannot resolve the address in local mode");
+            }
+
+            let kind = kind.as_ref().map(|kind| Hash::with_str(kind));
+            let report = bench_resolve(count, || {
+                client.get_address_uncached(kind.as_ref(), &account)
+            })
+            .await;
+
+            let account = account.to_string();
+            println!("Account = {account}");
+            println!("Succeeded = {}", report.succeeded);
+            println!("Failed = {}", report.failed);
+            println!("p50 = {:?}", report.p50);
+            println!("p99 = {:?}", report.p99);
+            Ok(())
+        }
     }
 }
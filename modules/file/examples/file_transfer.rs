@@ -0,0 +1,136 @@
+use core::time::Duration;
+use std::{path::PathBuf, sync::Arc};
+
+use ipiis_api::{
+    client::IpiisClient,
+    common::{handle_external_call, Ipiis, ServerResult},
+    server::IpiisServer,
+};
+use ipiis_modules_file::{recv_file, send_file, serve_by_hash, serve_range, MemoryBlobStore};
+use ipis::{
+    async_trait::async_trait,
+    core::{account::AccountRef, anyhow::Result},
+    env::Infer,
+    tokio::{self, io::AsyncRead},
+};
+
+::ipis::lazy_static::lazy_static! {
+    static ref STORE: MemoryBlobStore = Default::default();
+}
+
+fn dir_root() -> PathBuf {
+    ::std::env::temp_dir().join("ipiis-file-transfer")
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    // prepare the directories
+    let dir_src = dir_root().join("src");
+    let dir_dst = dir_root().join("dst");
+    tokio::fs::create_dir_all(&dir_src).await?;
+    tokio::fs::create_dir_all(&dir_dst).await?;
+
+    // create a multi-MB file
+    let path = dir_src.join("data.bin");
+    let data: Vec<u8> = (0..8 * 1024 * 1024).map(|i| (i % 251) as u8).collect();
+    tokio::fs::write(&path, &data).await?;
+
+    // init peers
+    let server = run_server(5003).await?;
+    let client = run_client(server, 5003).await?;
+
+    // send the file
+    let header = send_file(&client, None, &server, &path).await?;
+
+    // verify the received file
+    let received = tokio::fs::read(dir_dst.join(&header.name)).await?;
+    assert_eq!(received.len() as u64, header.size);
+    assert_eq!(received, data);
+    Ok(())
+}
+
+async fn run_client(server: AccountRef, port: u16) -> Result<IpiisClient> {
+    // init a client
+    let client = IpiisClient::genesis(None).await?;
+    client
+        .set_address(None, &server, &format!("127.0.0.1:{}", port).parse()?)
+        .await?;
+    Ok(client)
+}
+
+async fn run_server(port: u16) -> Result<AccountRef> {
+    // init a server
+    let server = FileServer::genesis(port).await?;
+    let public_key = *server.as_ref().account_ref();
+
+    // accept a single connection
+    tokio::spawn(async move { server.run().await });
+    tokio::time::sleep(Duration::from_secs(1)).await;
+
+    Ok(public_key)
+}
+
+pub struct FileServer {
+    client: Arc<IpiisServer>,
+}
+
+impl AsRef<IpiisClient> for FileServer {
+    fn as_ref(&self) -> &IpiisClient {
+        &self.client
+    }
+}
+
+#[async_trait]
+impl<'a> Infer<'a> for FileServer {
+    type GenesisArgs = <IpiisServer as Infer<'a>>::GenesisArgs;
+    type GenesisResult = Self;
+
+    async fn try_infer() -> Result<Self> {
+        Ok(Self {
+            client: IpiisServer::try_infer().await?.into(),
+        })
+    }
+
+    async fn genesis(
+        args: <Self as Infer<'a>>::GenesisArgs,
+    ) -> Result<<Self as Infer<'a>>::GenesisResult> {
+        Ok(Self {
+            client: IpiisServer::genesis(args).await?.into(),
+        })
+    }
+}
+
+handle_external_call!(
+    server: FileServer => IpiisServer,
+    name: run,
+    request: ::ipiis_modules_file::io => {
+        GetRange => handle_get_range,
+        GetByHash => handle_get_by_hash,
+    },
+    request_raw: ::ipiis_modules_file::io => {
+        SendFile => handle_send_file,
+    },
+);
+
+impl FileServer {
+    async fn handle_get_range(
+        client: &IpiisServer,
+        req: ::ipiis_modules_file::io::request::GetRange<'static>,
+    ) -> Result<::ipiis_modules_file::io::response::GetRange<'static>> {
+        serve_range(client, dir_root().join("dst"), req).await
+    }
+
+    async fn handle_get_by_hash(
+        client: &IpiisServer,
+        req: ::ipiis_modules_file::io::request::GetByHash<'static>,
+    ) -> Result<::ipiis_modules_file::io::response::GetByHash<'static>> {
+        serve_by_hash(client, &*STORE, req).await
+    }
+
+    async fn handle_send_file(
+        client: &IpiisServer,
+        recv: impl AsyncRead + Send + Unpin + 'static,
+    ) -> Result<::ipiis_modules_file::io::response::SendFile<'static>> {
+        recv_file(client, dir_root().join("dst"), recv).await
+    }
+}
@@ -0,0 +1,437 @@
+use std::{
+    collections::HashMap,
+    path::Path,
+    sync::{Arc, RwLock},
+};
+
+use bytecheck::CheckBytes;
+use ipiis_common::{define_io, external_call, recv_server_result, Ipiis, IpiisError, ServerResult};
+use ipis::{
+    async_trait::async_trait,
+    core::{
+        account::{AccountRef, GuaranteeSigned, GuarantorSigned},
+        anyhow::{anyhow, bail, Result},
+        data::Data,
+        signed::IsSigned,
+        value::hash::Hash,
+    },
+    stream::DynStream,
+    tokio::{
+        fs,
+        io::{AsyncRead, AsyncReadExt, AsyncSeekExt, AsyncWriteExt, SeekFrom},
+    },
+};
+use rkyv::{Archive, Deserialize, Serialize};
+
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// The maximum number of bytes served by a single range request.
+pub const MAX_RANGE_SIZE: u64 = 4 * 1024 * 1024;
+
+#[derive(Clone, Debug, PartialEq, Eq, Archive, Serialize, Deserialize)]
+#[archive(compare(PartialEq))]
+#[archive_attr(derive(CheckBytes, Debug, PartialEq, Eq))]
+pub struct FileHeader {
+    /// Name of the file, without any directory
+    pub name: String,
+
+    /// Size of the file in bytes
+    pub size: u64,
+
+    /// BLAKE3 checksum of the file
+    pub checksum: [u8; 32],
+}
+
+impl IsSigned for FileHeader {}
+
+#[derive(Clone, Debug, PartialEq, Eq, Archive, Serialize, Deserialize)]
+#[archive(compare(PartialEq))]
+#[archive_attr(derive(CheckBytes, Debug, PartialEq, Eq))]
+pub struct FileRange {
+    /// Name of the file, without any directory
+    pub name: String,
+
+    /// Offset of the range in bytes
+    pub offset: u64,
+
+    /// Length of the range in bytes, truncated to [`MAX_RANGE_SIZE`]
+    pub len: u64,
+}
+
+impl IsSigned for FileRange {}
+
+/// Streams a file to the target without buffering it in memory.
+///
+/// The header is signed, so the server can verify both the sender and the file integrity.
+pub async fn send_file<IpiisClient>(
+    client: &IpiisClient,
+    kind: Option<&Hash>,
+    target: &AccountRef,
+    path: impl AsRef<Path>,
+) -> Result<FileHeader>
+where
+    IpiisClient: Ipiis,
+{
+    let path = path.as_ref();
+
+    // make a header
+    let header = FileHeader {
+        name: path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .ok_or_else(|| anyhow!("failed to get the file name: {path:?}"))?
+            .to_string(),
+        size: fs::metadata(path).await?.len(),
+        checksum: checksum(fs::File::open(path).await?).await?,
+    };
+
+    // make a opcode
+    let opcode = crate::io::OpCode::SendFile.to_bytes();
+
+    // sign data
+    let mut sign: DynStream<Data<GuaranteeSigned, FileHeader>> =
+        DynStream::OwnedAlignedVec(client.sign_owned(*target, header.clone())?.to_bytes()?);
+
+    // pack data
+    sign.serialize_inner().await?;
+
+    // make a connection
+    let (mut send, mut recv) = client.call_raw(kind, target).await?;
+
+    // send opcode
+    send.write_all(&opcode).await?;
+
+    // send sign
+    sign.copy_to(&mut send).await?;
+
+    // send data
+    let mut file = fs::File::open(path).await?.take(header.size);
+    ::ipis::tokio::io::copy(&mut file, &mut send).await?;
+    send.flush().await?;
+
+    // recv flag
+    recv_server_result(&mut recv).await?;
+
+    // recv response
+    crate::io::response::SendFile::recv(target, recv).await?;
+    Ok(header)
+}
+
+/// Receives a file sent by [`send_file`] into the given directory.
+///
+/// The file is removed if its size or checksum does not match the signed header.
+pub async fn recv_file<IpiisClient, R>(
+    client: &IpiisClient,
+    dir: impl AsRef<Path>,
+    mut recv: R,
+) -> Result<crate::io::response::SendFile<'static>>
+where
+    IpiisClient: Ipiis,
+    R: AsyncRead + Send + Unpin + 'static,
+{
+    // recv request
+    let req = crate::io::request::SendFile::recv(client, &mut recv).await?;
+
+    // unpack sign
+    let sign_as_guarantee = req.__sign.into_owned().await?;
+
+    // unpack data
+    let header = sign_as_guarantee.data.clone();
+    let name = Path::new(&header.name)
+        .file_name()
+        .ok_or_else(|| anyhow!("invalid file name: {:?}", &header.name))?;
+    let path = dir.as_ref().join(name);
+
+    // recv data
+    let mut hasher = ::blake3::Hasher::new();
+    let mut size = 0u64;
+    {
+        let mut file = fs::File::create(&path).await?;
+        let mut recv = recv.take(header.size);
+        let mut buf = vec![0; CHUNK_SIZE];
+        loop {
+            let len = recv.read(&mut buf).await?;
+            if len == 0 {
+                break;
+            }
+
+            hasher.update(&buf[..len]);
+            file.write_all(&buf[..len]).await?;
+            size += len as u64;
+        }
+        file.flush().await?;
+    }
+
+    // verify data
+    if size != header.size || hasher.finalize().as_bytes() != &header.checksum {
+        fs::remove_file(&path).await?;
+        bail!("corrupted file: {:?}", &header.name)
+    }
+
+    // sign data
+    let sign = client.sign_as_guarantor(sign_as_guarantee)?;
+
+    // pack data
+    Ok(crate::io::response::SendFile {
+        __lifetime: Default::default(),
+        __sign: DynStream::Owned(sign),
+    })
+}
+
+/// Requests a range of a file served by [`serve_range`].
+///
+/// Returns the total size of the file and the bytes of the range,
+/// which may be shorter than requested at the end of the file.
+pub async fn get_range<IpiisClient>(
+    client: &IpiisClient,
+    kind: Option<&Hash>,
+    target: &AccountRef,
+    range: FileRange,
+) -> Result<(u64, Vec<u8>)>
+where
+    IpiisClient: Ipiis,
+{
+    // external call
+    let (size, data) = external_call!(
+        client: client,
+        target: kind => target,
+        request: crate::io => GetRange,
+        sign: client.sign_owned(*target, range)?,
+        inputs: { },
+        outputs: { size, data, },
+    );
+
+    // unpack response
+    Ok((size, data))
+}
+
+/// Downloads a file served by [`serve_range`] into the given path.
+///
+/// If the path already holds a part of the file, e.g. after a dropped connection,
+/// the download is resumed from its end.
+pub async fn download_file<IpiisClient>(
+    client: &IpiisClient,
+    kind: Option<&Hash>,
+    target: &AccountRef,
+    name: &str,
+    path: impl AsRef<Path>,
+) -> Result<u64>
+where
+    IpiisClient: Ipiis,
+{
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .await?;
+    let mut offset = file.metadata().await?.len();
+
+    loop {
+        let range = FileRange {
+            name: name.to_string(),
+            offset,
+            len: MAX_RANGE_SIZE,
+        };
+        let (size, data) = get_range(client, kind, target, range).await?;
+
+        if offset > size {
+            bail!("the local file is larger than the remote one: {name:?}")
+        }
+        if data.is_empty() {
+            if offset < size {
+                bail!("unexpected end of file: {name:?}")
+            }
+            file.flush().await?;
+            break Ok(size);
+        }
+
+        file.write_all(&data).await?;
+        offset += data.len() as u64;
+    }
+}
+
+/// Serves a range of a file in the given directory, requested by [`get_range`].
+pub async fn serve_range<IpiisClient>(
+    client: &IpiisClient,
+    dir: impl AsRef<Path>,
+    req: crate::io::request::GetRange<'static>,
+) -> Result<crate::io::response::GetRange<'static>>
+where
+    IpiisClient: Ipiis,
+{
+    // unpack sign
+    let sign_as_guarantee = req.__sign.into_owned().await?;
+
+    // unpack data
+    let range = &sign_as_guarantee.data;
+    let name = Path::new(&range.name)
+        .file_name()
+        .ok_or_else(|| anyhow!("invalid file name: {:?}", &range.name))?;
+    let path = dir.as_ref().join(name);
+
+    // handle data
+    let mut file = fs::File::open(&path).await?;
+    let size = file.metadata().await?.len();
+    let offset = range.offset.min(size);
+    let len = range.len.min(MAX_RANGE_SIZE).min(size - offset);
+
+    let mut data = vec![0; len as usize];
+    file.seek(SeekFrom::Start(offset)).await?;
+    file.read_exact(&mut data).await?;
+
+    // sign data
+    let sign = client.sign_as_guarantor(sign_as_guarantee)?;
+
+    // pack data
+    Ok(crate::io::response::GetRange {
+        __lifetime: Default::default(),
+        __sign: DynStream::Owned(sign),
+        size: DynStream::Owned(size),
+        data: DynStream::Owned(data),
+    })
+}
+
+/// A storage of the blobs addressed by their hashes, served by [`serve_by_hash`].
+#[async_trait]
+pub trait BlobStore: Send + Sync {
+    /// Returns the bytes stored under the hash, which are not verified yet.
+    async fn get(&self, hash: &Hash) -> Result<Option<Vec<u8>>>;
+
+    /// Stores the bytes under their hash, returning it.
+    async fn put(&self, data: Vec<u8>) -> Result<Hash>;
+}
+
+/// A [`BlobStore`] kept in memory.
+#[derive(Clone, Debug, Default)]
+pub struct MemoryBlobStore {
+    blobs: Arc<RwLock<HashMap<Hash, Vec<u8>>>>,
+}
+
+#[async_trait]
+impl BlobStore for MemoryBlobStore {
+    async fn get(&self, hash: &Hash) -> Result<Option<Vec<u8>>> {
+        Ok(self.blobs.read().unwrap().get(hash).cloned())
+    }
+
+    async fn put(&self, data: Vec<u8>) -> Result<Hash> {
+        let hash = Hash::with_bytes(&data);
+        self.blobs.write().unwrap().insert(hash, data);
+        Ok(hash)
+    }
+}
+
+/// Requests a blob by its hash, served by [`serve_by_hash`].
+///
+/// The bytes are rejected unless they hash to the requested one,
+/// so the target does not need to be trusted.
+pub async fn get_by_hash<IpiisClient>(
+    client: &IpiisClient,
+    kind: Option<&Hash>,
+    target: &AccountRef,
+    hash: Hash,
+) -> Result<Vec<u8>>
+where
+    IpiisClient: Ipiis,
+{
+    // external call
+    let (data,) = external_call!(
+        client: client,
+        target: kind => target,
+        request: crate::io => GetByHash,
+        sign: client.sign_owned(*target, hash)?,
+        inputs: { },
+        outputs: { data, },
+    );
+
+    // verify data
+    if Hash::with_bytes(&data) != hash {
+        bail!("corrupted blob: {hash}")
+    }
+
+    // unpack response
+    Ok(data)
+}
+
+/// Serves a blob in the given store, requested by [`get_by_hash`].
+///
+/// The blob is not sent unless its bytes hash to the requested one,
+/// e.g. if the store is corrupted.
+pub async fn serve_by_hash<IpiisClient, Store>(
+    client: &IpiisClient,
+    store: &Store,
+    req: crate::io::request::GetByHash<'static>,
+) -> Result<crate::io::response::GetByHash<'static>>
+where
+    IpiisClient: Ipiis,
+    Store: BlobStore + ?Sized,
+{
+    // unpack sign
+    let sign_as_guarantee = req.__sign.into_owned().await?;
+
+    // unpack data
+    let hash = sign_as_guarantee.data;
+
+    // handle data
+    let data = store
+        .get(&hash)
+        .await?
+        .ok_or_else(|| IpiisError::NotFound(format!("blob {hash}")))?;
+
+    // verify data
+    if Hash::with_bytes(&data) != hash {
+        bail!("corrupted blob in the store: {hash}")
+    }
+
+    // sign data
+    let sign = client.sign_as_guarantor(sign_as_guarantee)?;
+
+    // pack data
+    Ok(crate::io::response::GetByHash {
+        __lifetime: Default::default(),
+        __sign: DynStream::Owned(sign),
+        data: DynStream::Owned(data),
+    })
+}
+
+async fn checksum(mut reader: impl AsyncRead + Unpin) -> Result<[u8; 32]> {
+    let mut hasher = ::blake3::Hasher::new();
+    let mut buf = vec![0; CHUNK_SIZE];
+    loop {
+        let len = reader.read(&mut buf).await?;
+        if len == 0 {
+            break Ok(hasher.finalize().into());
+        }
+        hasher.update(&buf[..len]);
+    }
+}
+
+define_io! {
+    SendFile = 0 {
+        inputs: { },
+        input_sign: Data<GuaranteeSigned, FileHeader>,
+        outputs: { },
+        output_sign: Data<GuarantorSigned, FileHeader>,
+        generics: { },
+    },
+    GetRange = 1 {
+        idempotent: true,
+        inputs: { },
+        input_sign: Data<GuaranteeSigned, FileRange>,
+        outputs: {
+            size: u64,
+            data: Vec<u8>,
+        },
+        output_sign: Data<GuarantorSigned, FileRange>,
+        generics: { },
+    },
+    GetByHash = 2 {
+        idempotent: true,
+        inputs: { },
+        input_sign: Data<GuaranteeSigned, Hash>,
+        outputs: {
+            data: Vec<u8>,
+        },
+        output_sign: Data<GuarantorSigned, Hash>,
+        generics: { },
+    },
+}
@@ -0,0 +1,162 @@
+#[path = "../../../api/tests/common/mod.rs"]
+mod common;
+
+use std::{
+    collections::HashSet,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+};
+
+use ipiis_api::{
+    client::IpiisClient,
+    common::{handle_external_call, Ipiis, ServerResult},
+    server::IpiisServer,
+};
+use ipiis_modules_file::{
+    get_by_hash, recv_file, serve_by_hash, serve_range, BlobStore, MemoryBlobStore,
+};
+use ipis::{
+    async_trait::async_trait,
+    core::{anyhow::Result, value::hash::Hash},
+    env::Infer,
+    tokio::{self, io::AsyncRead},
+};
+
+use self::common::{introduce, test_client, test_server};
+
+::ipis::lazy_static::lazy_static! {
+    static ref STORE: TamperableStore = Default::default();
+}
+
+fn dir_root() -> PathBuf {
+    ::std::env::temp_dir().join("ipiis-test-file-blob")
+}
+
+#[tokio::test]
+async fn test_get_by_hash() {
+    // deploy a server
+    let (server, address) = test_server("file-blob").await.unwrap();
+    let server = BlobServer {
+        client: server.into(),
+    };
+    let server_ref = *server.as_ref().account_ref();
+    tokio::spawn(server.run());
+
+    // create a client
+    let client = test_client("file-blob").await.unwrap();
+    introduce(&client, &server_ref, &address).unwrap();
+
+    // store a blob
+    let data: Vec<u8> = (0..100_000).map(|i| (i % 251) as u8).collect();
+    let hash = STORE.put(data.clone()).await.unwrap();
+    assert_eq!(hash, Hash::with_bytes(&data));
+
+    // fetch the blob by its hash
+    let received = get_by_hash(&client, None, &server_ref, hash).await.unwrap();
+    assert_eq!(received, data);
+
+    // the unknown blobs are not found
+    let unknown = Hash::with_str("__ipiis__test__file__blob__");
+    assert!(get_by_hash(&client, None, &server_ref, unknown)
+        .await
+        .is_err());
+
+    // the tampered blobs are not served
+    STORE.tamper(hash);
+    assert!(get_by_hash(&client, None, &server_ref, hash).await.is_err());
+}
+
+/// A blob store flipping the bytes of the tampered blobs.
+#[derive(Default)]
+struct TamperableStore {
+    inner: MemoryBlobStore,
+    tampered: Mutex<HashSet<Hash>>,
+}
+
+impl TamperableStore {
+    fn tamper(&self, hash: Hash) {
+        self.tampered.lock().unwrap().insert(hash);
+    }
+}
+
+#[async_trait]
+impl BlobStore for TamperableStore {
+    async fn get(&self, hash: &Hash) -> Result<Option<Vec<u8>>> {
+        let mut data = self.inner.get(hash).await?;
+        if self.tampered.lock().unwrap().contains(hash) {
+            if let Some(byte) = data.as_mut().and_then(|data| data.first_mut()) {
+                *byte ^= 0xff;
+            }
+        }
+        Ok(data)
+    }
+
+    async fn put(&self, data: Vec<u8>) -> Result<Hash> {
+        self.inner.put(data).await
+    }
+}
+
+pub struct BlobServer {
+    client: Arc<IpiisServer>,
+}
+
+impl AsRef<IpiisClient> for BlobServer {
+    fn as_ref(&self) -> &IpiisClient {
+        &self.client
+    }
+}
+
+#[async_trait]
+impl<'a> Infer<'a> for BlobServer {
+    type GenesisArgs = <IpiisServer as Infer<'a>>::GenesisArgs;
+    type GenesisResult = Self;
+
+    async fn try_infer() -> Result<Self> {
+        Ok(Self {
+            client: IpiisServer::try_infer().await?.into(),
+        })
+    }
+
+    async fn genesis(
+        args: <Self as Infer<'a>>::GenesisArgs,
+    ) -> Result<<Self as Infer<'a>>::GenesisResult> {
+        Ok(Self {
+            client: IpiisServer::genesis(args).await?.into(),
+        })
+    }
+}
+
+handle_external_call!(
+    server: BlobServer => IpiisServer,
+    name: run,
+    request: ::ipiis_modules_file::io => {
+        GetRange => handle_get_range,
+        GetByHash => handle_get_by_hash,
+    },
+    request_raw: ::ipiis_modules_file::io => {
+        SendFile => handle_send_file,
+    },
+);
+
+impl BlobServer {
+    async fn handle_get_range(
+        client: &IpiisServer,
+        req: ::ipiis_modules_file::io::request::GetRange<'static>,
+    ) -> Result<::ipiis_modules_file::io::response::GetRange<'static>> {
+        serve_range(client, dir_root(), req).await
+    }
+
+    async fn handle_get_by_hash(
+        client: &IpiisServer,
+        req: ::ipiis_modules_file::io::request::GetByHash<'static>,
+    ) -> Result<::ipiis_modules_file::io::response::GetByHash<'static>> {
+        serve_by_hash(client, &*STORE, req).await
+    }
+
+    async fn handle_send_file(
+        client: &IpiisServer,
+        recv: impl AsyncRead + Send + Unpin + 'static,
+    ) -> Result<::ipiis_modules_file::io::response::SendFile<'static>> {
+        recv_file(client, dir_root(), recv).await
+    }
+}
@@ -0,0 +1,147 @@
+#[path = "../../../api/tests/common/mod.rs"]
+mod common;
+
+use std::{path::PathBuf, sync::Arc};
+
+use ipiis_api::{
+    client::IpiisClient,
+    common::{handle_external_call, Ipiis, ServerResult},
+    server::IpiisServer,
+};
+use ipiis_modules_file::{
+    download_file, get_range, recv_file, serve_by_hash, serve_range, FileRange, MemoryBlobStore,
+};
+use ipis::{
+    async_trait::async_trait,
+    core::anyhow::Result,
+    env::Infer,
+    tokio::{self, io::AsyncRead},
+};
+
+use self::common::{introduce, test_client, test_server};
+
+const FILE_NAME: &str = "data.bin";
+const FILE_SIZE: usize = 6 * 1024 * 1024 + 123;
+
+::ipis::lazy_static::lazy_static! {
+    static ref STORE: MemoryBlobStore = Default::default();
+}
+
+fn dir_root() -> PathBuf {
+    ::std::env::temp_dir().join("ipiis-test-file-range")
+}
+
+#[tokio::test]
+async fn test_resume_download() {
+    // prepare the directories
+    let dir_src = dir_root().join("src");
+    let dir_dst = dir_root().join("dst");
+    tokio::fs::create_dir_all(&dir_src).await.unwrap();
+    tokio::fs::create_dir_all(&dir_dst).await.unwrap();
+
+    // create a file to be served
+    let data: Vec<u8> = (0..FILE_SIZE).map(|i| (i % 251) as u8).collect();
+    tokio::fs::write(dir_src.join(FILE_NAME), &data)
+        .await
+        .unwrap();
+
+    // deploy a server
+    let (server, address) = test_server("file-range").await.unwrap();
+    let server = FileServer {
+        client: server.into(),
+    };
+    let server_ref = *server.as_ref().account_ref();
+    tokio::spawn(server.run());
+
+    // create a client
+    let client = test_client("file-range").await.unwrap();
+    introduce(&client, &server_ref, &address).unwrap();
+
+    // download the first range, as if the connection is dropped after it
+    let range = FileRange {
+        name: FILE_NAME.to_string(),
+        offset: 0,
+        len: 1_000_000,
+    };
+    let (size, head) = get_range(&client, None, &server_ref, range).await.unwrap();
+    assert_eq!(size, FILE_SIZE as u64);
+    assert_eq!(head.len(), 1_000_000);
+
+    let path = dir_dst.join(FILE_NAME);
+    tokio::fs::write(&path, &head).await.unwrap();
+
+    // resume the download
+    let size = download_file(&client, None, &server_ref, FILE_NAME, &path)
+        .await
+        .unwrap();
+    assert_eq!(size, FILE_SIZE as u64);
+
+    // verify the reassembled file
+    let received = tokio::fs::read(&path).await.unwrap();
+    assert_eq!(received, data);
+}
+
+pub struct FileServer {
+    client: Arc<IpiisServer>,
+}
+
+impl AsRef<IpiisClient> for FileServer {
+    fn as_ref(&self) -> &IpiisClient {
+        &self.client
+    }
+}
+
+#[async_trait]
+impl<'a> Infer<'a> for FileServer {
+    type GenesisArgs = <IpiisServer as Infer<'a>>::GenesisArgs;
+    type GenesisResult = Self;
+
+    async fn try_infer() -> Result<Self> {
+        Ok(Self {
+            client: IpiisServer::try_infer().await?.into(),
+        })
+    }
+
+    async fn genesis(
+        args: <Self as Infer<'a>>::GenesisArgs,
+    ) -> Result<<Self as Infer<'a>>::GenesisResult> {
+        Ok(Self {
+            client: IpiisServer::genesis(args).await?.into(),
+        })
+    }
+}
+
+handle_external_call!(
+    server: FileServer => IpiisServer,
+    name: run,
+    request: ::ipiis_modules_file::io => {
+        GetRange => handle_get_range,
+        GetByHash => handle_get_by_hash,
+    },
+    request_raw: ::ipiis_modules_file::io => {
+        SendFile => handle_send_file,
+    },
+);
+
+impl FileServer {
+    async fn handle_get_range(
+        client: &IpiisServer,
+        req: ::ipiis_modules_file::io::request::GetRange<'static>,
+    ) -> Result<::ipiis_modules_file::io::response::GetRange<'static>> {
+        serve_range(client, dir_root().join("src"), req).await
+    }
+
+    async fn handle_get_by_hash(
+        client: &IpiisServer,
+        req: ::ipiis_modules_file::io::request::GetByHash<'static>,
+    ) -> Result<::ipiis_modules_file::io::response::GetByHash<'static>> {
+        serve_by_hash(client, &*STORE, req).await
+    }
+
+    async fn handle_send_file(
+        client: &IpiisServer,
+        recv: impl AsyncRead + Send + Unpin + 'static,
+    ) -> Result<::ipiis_modules_file::io::response::SendFile<'static>> {
+        recv_file(client, dir_root().join("src"), recv).await
+    }
+}
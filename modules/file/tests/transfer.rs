@@ -0,0 +1,131 @@
+#[path = "../../../api/tests/common/mod.rs"]
+mod common;
+
+use std::{path::PathBuf, sync::Arc};
+
+use ipiis_api::{
+    client::IpiisClient,
+    common::{handle_external_call, Ipiis, ServerResult},
+    server::IpiisServer,
+};
+use ipiis_modules_file::{recv_file, send_file, serve_by_hash, serve_range, MemoryBlobStore};
+use ipis::{
+    async_trait::async_trait,
+    core::anyhow::Result,
+    env::Infer,
+    tokio::{self, io::AsyncRead},
+};
+
+use self::common::{introduce, test_client, test_server};
+
+const FILE_NAME: &str = "data.bin";
+const FILE_SIZE: usize = 8 * 1024 * 1024 + 123;
+
+::ipis::lazy_static::lazy_static! {
+    static ref STORE: MemoryBlobStore = Default::default();
+}
+
+fn dir_root() -> PathBuf {
+    ::std::env::temp_dir().join("ipiis-test-file-transfer")
+}
+
+#[tokio::test]
+async fn test_send_file() {
+    // prepare the directories
+    let dir_src = dir_root().join("src");
+    let dir_dst = dir_root().join("dst");
+    tokio::fs::create_dir_all(&dir_src).await.unwrap();
+    tokio::fs::create_dir_all(&dir_dst).await.unwrap();
+
+    // create a multi-MB file
+    let data: Vec<u8> = (0..FILE_SIZE).map(|i| (i % 251) as u8).collect();
+    let path = dir_src.join(FILE_NAME);
+    tokio::fs::write(&path, &data).await.unwrap();
+
+    // deploy a server
+    let (server, address) = test_server("file-transfer").await.unwrap();
+    let server = FileServer {
+        client: server.into(),
+    };
+    let server_ref = *server.as_ref().account_ref();
+    tokio::spawn(server.run());
+
+    // create a client
+    let client = test_client("file-transfer").await.unwrap();
+    introduce(&client, &server_ref, &address).unwrap();
+
+    // send the file
+    let header = send_file(&client, None, &server_ref, &path).await.unwrap();
+    assert_eq!(header.size, FILE_SIZE as u64);
+    assert_eq!(header.checksum, *::blake3::hash(&data).as_bytes());
+
+    // verify the received file
+    let received = tokio::fs::read(dir_dst.join(&header.name)).await.unwrap();
+    assert_eq!(received.len(), FILE_SIZE);
+    assert_eq!(*::blake3::hash(&received).as_bytes(), header.checksum);
+}
+
+pub struct FileServer {
+    client: Arc<IpiisServer>,
+}
+
+impl AsRef<IpiisClient> for FileServer {
+    fn as_ref(&self) -> &IpiisClient {
+        &self.client
+    }
+}
+
+#[async_trait]
+impl<'a> Infer<'a> for FileServer {
+    type GenesisArgs = <IpiisServer as Infer<'a>>::GenesisArgs;
+    type GenesisResult = Self;
+
+    async fn try_infer() -> Result<Self> {
+        Ok(Self {
+            client: IpiisServer::try_infer().await?.into(),
+        })
+    }
+
+    async fn genesis(
+        args: <Self as Infer<'a>>::GenesisArgs,
+    ) -> Result<<Self as Infer<'a>>::GenesisResult> {
+        Ok(Self {
+            client: IpiisServer::genesis(args).await?.into(),
+        })
+    }
+}
+
+handle_external_call!(
+    server: FileServer => IpiisServer,
+    name: run,
+    request: ::ipiis_modules_file::io => {
+        GetRange => handle_get_range,
+        GetByHash => handle_get_by_hash,
+    },
+    request_raw: ::ipiis_modules_file::io => {
+        SendFile => handle_send_file,
+    },
+);
+
+impl FileServer {
+    async fn handle_get_range(
+        client: &IpiisServer,
+        req: ::ipiis_modules_file::io::request::GetRange<'static>,
+    ) -> Result<::ipiis_modules_file::io::response::GetRange<'static>> {
+        serve_range(client, dir_root().join("dst"), req).await
+    }
+
+    async fn handle_get_by_hash(
+        client: &IpiisServer,
+        req: ::ipiis_modules_file::io::request::GetByHash<'static>,
+    ) -> Result<::ipiis_modules_file::io::response::GetByHash<'static>> {
+        serve_by_hash(client, &*STORE, req).await
+    }
+
+    async fn handle_send_file(
+        client: &IpiisServer,
+        recv: impl AsyncRead + Send + Unpin + 'static,
+    ) -> Result<::ipiis_modules_file::io::response::SendFile<'static>> {
+        recv_file(client, dir_root().join("dst"), recv).await
+    }
+}
@@ -1,33 +1,262 @@
+pub extern crate sled;
+
 use core::{marker::PhantomData, str::FromStr};
-use std::{net::ToSocketAddrs, path::PathBuf, sync::Arc};
+use std::{
+    collections::BTreeMap,
+    net::{SocketAddr, ToSocketAddrs},
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
 
+use bytecheck::CheckBytes;
 use ipis::{
     core::{
-        account::{Account, AccountRef},
+        account::{Account, AccountRef, GuaranteeSigned, Verifier},
         anyhow::{anyhow, bail, Result},
+        data::Data,
+        signed::IsSigned,
         value::hash::Hash,
     },
     env::infer,
+    futures::{stream, Stream, StreamExt, TryStreamExt},
+    log::warn,
+    tokio::{self, sync::broadcast},
 };
+use rkyv::{Archive, Deserialize, Serialize};
+use sled::{transaction::TransactionError, Transactional};
+
+/// Prefix of the auxiliary trees opened by [`RouterClient::open_tree`]
+const TREE_PREFIX: &[u8] = b"__ipiis__ext__";
+
+/// Name of the tree storing the metadata of the routing table
+const TREE_META: &[u8] = b"__ipiis__meta__";
+
+/// Name of the tree storing the tags of the address entries, by the same keys
+const TREE_TAGS: &[u8] = b"__ipiis__tags__";
+
+/// Version of the key encoding of the routing table
+///
+/// - `0`: `[flag] + kind + account`, concatenated
+/// - `1`: `[flag] + len(kind) + kind + len(account) + account`,
+///   with big-endian `u16` lengths, omitting the absent components
+pub const SCHEMA_VERSION: u8 = 1;
+
+const KEY_SCHEMA_VERSION: &[u8] = b"schema_version";
+
+/// Byte length of the account in the schema `0`, as an Ed25519 public key
+const SCHEMA_0_ACCOUNT_LEN: usize = 32;
+
+/// Number of the changes kept for the slow watchers, which skip the older ones
+const CHANGES_CAPACITY: usize = 256;
+
+/// Number of the addresses resolved concurrently by [`RouterClient::set_bulk`]
+const BULK_RESOLVE_CONCURRENCY: usize = 64;
+
+/// Number of the entries read at once by [`RouterClient::stream`]
+const STREAM_CHUNK_SIZE: usize = 256;
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct MaintenanceReport {
+    /// Number of bytes flushed to the disk
+    pub flushed_bytes: usize,
+
+    /// Size of the database on the disk
+    pub size_on_disk: u64,
+
+    /// Number of the expired entries evicted from the table
+    pub evicted_entries: usize,
+
+    /// Number of the closed connections pruned from the pool
+    pub pruned_connections: usize,
+}
+
+/// A mutation of the routing table made by this process.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BookChange {
+    SetAddress {
+        kind: Option<Hash>,
+        account: AccountRef,
+        address: String,
+    },
+    DeleteAddress {
+        kind: Option<Hash>,
+        account: AccountRef,
+    },
+    SetPrimary {
+        kind: Option<Hash>,
+        account: AccountRef,
+    },
+    DeletePrimary {
+        kind: Option<Hash>,
+    },
+}
+
+/// The metadata annotating an address entry, e.g. its region, owner or environment.
+pub type Tags = BTreeMap<String, String>;
+
+/// An entry of the routing table.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BookEntry {
+    Primary {
+        kind: Option<Hash>,
+        account: AccountRef,
+    },
+    Address {
+        kind: Option<Hash>,
+        account: AccountRef,
+        address: String,
+    },
+}
+
+/// A portable copy of the routing table, e.g. to seed the fresh nodes.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Archive, Serialize, Deserialize)]
+#[archive(compare(PartialEq))]
+#[archive_attr(derive(CheckBytes, Debug, PartialEq, Eq))]
+pub struct RouteBook {
+    /// The primary accounts, with their kinds
+    pub primaries: Vec<(Option<Hash>, AccountRef)>,
+
+    /// The addresses of the accounts, with their kinds
+    pub addresses: Vec<(Option<Hash>, AccountRef, String)>,
+}
+
+impl IsSigned for RouteBook {}
+
+/// A [`RouteBook`] signed by the exporting node, so that any tampering is evident.
+pub type SignedBook = Data<GuaranteeSigned, RouteBook>;
 
 #[derive(Clone, Debug)]
 pub struct RouterClient<Address> {
     pub account_me: Arc<Account>,
     pub account_ref: Arc<AccountRef>,
     table: sled::Db,
+    /// The path of the routing table, if opened by this client
+    path: Option<Arc<PathBuf>>,
+    flusher: Option<Arc<Flusher>>,
+    /// The local mutations, shared by the clones
+    changes: broadcast::Sender<BookChange>,
     _address: PhantomData<Address>,
 }
 
+/// A background task flushing the routing table, cancelled on drop.
+#[derive(Debug)]
+struct Flusher(tokio::task::JoinHandle<()>);
+
+impl Drop for Flusher {
+    fn drop(&mut self) {
+        self.0.abort()
+    }
+}
+
+/// Options to open the routing table.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct RouterOptions {
+    /// Compresses the routing table on the disk with zstd, trading CPU for disk.
+    ///
+    /// It requires the `compress-db` feature.
+    pub compress: bool,
+    /// Caps the memory caching the routing table, e.g. for the constrained devices.
+    ///
+    /// The default is the one of sled (1 GiB).
+    pub cache_capacity_bytes: Option<u64>,
+    /// Flushes the routing table by sled itself every given milliseconds,
+    /// where `0` disables it, trading the durability for the write throughput.
+    ///
+    /// The default is the one of sled (500 ms).
+    pub flush_every_ms: Option<u64>,
+}
+
+impl RouterOptions {
+    fn infer() -> Self {
+        Self {
+            compress: infer("ipiis_router_compress").unwrap_or_default(),
+            cache_capacity_bytes: infer("ipiis_router_cache_capacity_bytes").ok(),
+            flush_every_ms: infer("ipiis_router_flush_every_ms").ok(),
+        }
+    }
+}
+
 impl<Address> RouterClient<Address> {
     pub fn new(account_me: Account) -> Result<Self> {
+        Self::with_options(account_me, RouterOptions::infer())
+    }
+
+    pub fn with_options(account_me: Account, options: RouterOptions) -> Result<Self> {
+        let path = Self::infer_db_path()?;
+        let table = open_db(path.clone(), options)?;
+        let client = Self {
+            path: Some(path.into()),
+            ..Self::with_db(account_me, table)?
+        };
+
+        // spawn a background flush task if requested
+        let flush_interval_ms: Result<u64> = infer("ipiis_router_flush_interval_ms");
+        match flush_interval_ms {
+            Ok(interval) => client.with_flush_interval(Duration::from_millis(interval)),
+            Err(_) => Ok(client),
+        }
+    }
+
+    /// Creates a client on an already-opened routing table.
+    ///
+    /// To share the table with the other clients of this process,
+    /// prefer cloning a client and rebinding its account with [`Self::with_account`],
+    /// so that its watchers observe the changes of all of them.
+    pub fn with_db(account_me: Account, table: sled::Db) -> Result<Self> {
+        migrate(&table)?;
+
         Ok(Self {
             account_ref: account_me.account_ref().into(),
             account_me: account_me.into(),
-            table: sled::open(Self::infer_db_path()?)?,
+            table,
+            path: None,
+            flusher: None,
+            changes: broadcast::channel(CHANGES_CAPACITY).0,
             _address: Default::default(),
         })
     }
 
+    /// Rebinds the account, sharing the same routing table.
+    pub fn with_account(self, account_me: Account) -> Self {
+        Self {
+            account_ref: account_me.account_ref().into(),
+            account_me: account_me.into(),
+            ..self
+        }
+    }
+
+    /// Flushes the routing table periodically in background,
+    /// bounding the data loss on unexpected exit.
+    ///
+    /// The task is cancelled when the last clone of this client is dropped.
+    pub fn with_flush_interval(self, interval: Duration) -> Result<Self> {
+        let runtime = tokio::runtime::Handle::try_current()
+            .map_err(|e| anyhow!("failed to spawn the flush task: {e}"))?;
+
+        let table = self.table.clone();
+        let task = runtime.spawn(async move {
+            let mut interval = tokio::time::interval(interval);
+            loop {
+                interval.tick().await;
+                if let Err(e) = table.flush_async().await {
+                    warn!("failed to flush the routing table: {e}");
+                }
+            }
+        });
+
+        Ok(Self {
+            flusher: Some(Arc::new(Flusher(task))),
+            ..self
+        })
+    }
+
+    /// Returns the path of the routing table,
+    /// unless it has been opened by the caller with [`Self::with_db`].
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref().map(PathBuf::as_path)
+    }
+
     fn infer_db_path() -> Result<PathBuf> {
         infer("ipiis_router_db").or_else(|e| {
             let mut dir = ::dirs::home_dir().ok_or(e)?;
@@ -62,54 +291,709 @@ impl<Address> RouterClient<Address> {
     where
         Address: ::std::fmt::Debug + ToSocketAddrs + ToString,
     {
-        // verify address
-        match address
-            .to_socket_addrs()
-            .map_err(|e| anyhow!("failed to parse the socket address: {address:?}: {e}"))?
-            .next()
-        {
-            Some(address) => {
-                let key = self.to_key_canonical(kind, Some(target));
-
-                self.table
-                    .insert(key, address.to_string().into_bytes())
-                    .map(|_| ())
-                    .map_err(Into::into)
-            }
-            None => bail!("failed to parse the socket address: {address:?}"),
-        }
+        let key = self.to_key_canonical(kind, Some(target));
+        let value = Self::to_value_address(address)?;
+
+        self.table.insert(key, value)?;
+        self.notify(BookChange::SetAddress {
+            kind: kind.copied(),
+            account: *target,
+            address: address.to_string(),
+        });
+        Ok(())
+    }
+
+    /// Sets the address along with its tags, replacing the previous tags.
+    ///
+    /// The tags are kept by [`Self::set`], e.g. refreshing the address from the primary,
+    /// and removed with the address by [`Self::delete`].
+    pub fn set_tagged(
+        &self,
+        kind: Option<&Hash>,
+        target: &AccountRef,
+        address: &Address,
+        tags: &Tags,
+    ) -> Result<()>
+    where
+        Address: ::std::fmt::Debug + ToSocketAddrs + ToString,
+    {
+        let key = self.to_key_canonical(kind, Some(target));
+        let value = Self::to_value_address(address)?;
+        let value_tags = encode_tags(tags);
+
+        let tags = self.table.open_tree(TREE_TAGS)?;
+        (&*self.table, &tags)
+            .transaction(|(table, tags)| {
+                table.insert(key.as_slice(), value.as_slice())?;
+                if value_tags.is_empty() {
+                    tags.remove(key.as_slice())?;
+                } else {
+                    tags.insert(key.as_slice(), value_tags.as_slice())?;
+                }
+                Ok(())
+            })
+            .map_err(|e: TransactionError| anyhow!("failed to set the address: {e}"))?;
+        self.notify(BookChange::SetAddress {
+            kind: kind.copied(),
+            account: *target,
+            address: address.to_string(),
+        });
+        Ok(())
+    }
+
+    /// Gets the address along with its tags, which are empty if not tagged.
+    pub fn get_with_tags(
+        &self,
+        kind: Option<&Hash>,
+        target: &AccountRef,
+    ) -> Result<Option<(Address, Tags)>>
+    where
+        Address: FromStr + ToSocketAddrs,
+        <Address as FromStr>::Err: ::std::error::Error + Send + Sync + 'static,
+    {
+        let address = match self.get(kind, target)? {
+            Some(address) => address,
+            None => return Ok(None),
+        };
+
+        let key = self.to_key_canonical(kind, Some(target));
+        let tags = match self.table.open_tree(TREE_TAGS)?.get(key)? {
+            Some(tags) => decode_tags(&tags)?,
+            None => Default::default(),
+        };
+        Ok(Some((address, tags)))
     }
 
     pub fn set_primary(&self, kind: Option<&Hash>, account: &AccountRef) -> Result<()> {
         let key = self.to_key_canonical(kind, None);
 
-        self.table
-            .insert(key, account.to_string().into_bytes())
-            .map(|_| ())
-            .map_err(Into::into)
+        self.table.insert(key, account.to_string().into_bytes())?;
+        self.notify(BookChange::SetPrimary {
+            kind: kind.copied(),
+            account: *account,
+        });
+        Ok(())
+    }
+
+    /// Sets the primary account of the kind and its address at once.
+    ///
+    /// Both entries are written atomically, so a reader never sees
+    /// a primary account without its address.
+    pub fn set_primary_with_address(
+        &self,
+        kind: Option<&Hash>,
+        account: &AccountRef,
+        address: &Address,
+    ) -> Result<()>
+    where
+        Address: ::std::fmt::Debug + ToSocketAddrs + ToString,
+    {
+        let mut batch = sled::Batch::default();
+        batch.insert(
+            self.to_key_canonical(kind, None),
+            account.to_string().into_bytes(),
+        );
+        batch.insert(
+            self.to_key_canonical(kind, Some(account)),
+            Self::to_value_address(address)?,
+        );
+
+        self.table.apply_batch(batch)?;
+        self.notify(BookChange::SetPrimary {
+            kind: kind.copied(),
+            account: *account,
+        });
+        self.notify(BookChange::SetAddress {
+            kind: kind.copied(),
+            account: *account,
+            address: address.to_string(),
+        });
+        Ok(())
+    }
+
+    /// Imports the addresses at once in a single batch, returning the number of them.
+    ///
+    /// If `validate` is `false`, the addresses are trusted as they are, e.g. pre-validated literals,
+    /// and otherwise they are resolved concurrently with the async resolver.
+    /// Either way, nothing is written if any of them fails.
+    pub async fn set_bulk<I>(&self, entries: I, validate: bool) -> Result<usize>
+    where
+        I: IntoIterator<Item = (Option<Hash>, AccountRef, Address)>,
+        Address: ToString,
+    {
+        let entries: Vec<_> = entries
+            .into_iter()
+            .map(|(kind, account, address)| (kind, account, address.to_string()))
+            .collect();
+
+        // verify addresses
+        let values: Vec<String> = if validate {
+            stream::iter(&entries)
+                .map(|(_, _, address)| async move {
+                    resolve_address_async(address)
+                        .await
+                        .map(|address| address.to_string())
+                })
+                .buffered(BULK_RESOLVE_CONCURRENCY)
+                .try_collect()
+                .await?
+        } else {
+            entries
+                .iter()
+                .map(|(_, _, address)| address.clone())
+                .collect()
+        };
+
+        let mut batch = sled::Batch::default();
+        for ((kind, account, _), value) in entries.iter().zip(&values) {
+            batch.insert(
+                self.to_key_canonical(kind.as_ref(), Some(account)),
+                value.as_bytes(),
+            );
+        }
+
+        self.table.apply_batch(batch)?;
+        for ((kind, account, _), address) in entries.iter().zip(values) {
+            self.notify(BookChange::SetAddress {
+                kind: *kind,
+                account: *account,
+                address,
+            });
+        }
+        Ok(entries.len())
     }
 
     pub fn delete(&self, kind: Option<&Hash>, target: &AccountRef) -> Result<()> {
         let key = self.to_key_canonical(kind, Some(target));
 
-        self.table.remove(key).map(|_| ()).map_err(Into::into)
+        let tags = self.table.open_tree(TREE_TAGS)?;
+        (&*self.table, &tags)
+            .transaction(|(table, tags)| {
+                table.remove(key.as_slice())?;
+                tags.remove(key.as_slice())?;
+                Ok(())
+            })
+            .map_err(|e: TransactionError| anyhow!("failed to delete the address: {e}"))?;
+        self.notify(BookChange::DeleteAddress {
+            kind: kind.copied(),
+            account: *target,
+        });
+        Ok(())
     }
 
     pub fn delete_primary(&self, kind: Option<&Hash>) -> Result<()> {
         let key = self.to_key_canonical(kind, None);
 
-        self.table.remove(key).map(|_| ()).map_err(Into::into)
+        self.table.remove(key)?;
+        self.notify(BookChange::DeletePrimary {
+            kind: kind.copied(),
+        });
+        Ok(())
+    }
+
+    /// Watches the mutations of the routing table made by this client and its clones,
+    /// from now on.
+    ///
+    /// A watcher falling behind skips the oldest changes.
+    pub fn watch(&self) -> impl Stream<Item = BookChange> {
+        stream::unfold(self.changes.subscribe(), |mut changes| async move {
+            loop {
+                match changes.recv().await {
+                    Ok(change) => break Some((change, changes)),
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        warn!("skipped the changes of the routing table: {skipped}");
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break None,
+                }
+            }
+        })
+    }
+
+    fn notify(&self, change: BookChange) {
+        // no one may be watching
+        let _ = self.changes.send(change);
+    }
+
+    /// Lists the accounts having an address of the given kind.
+    pub fn list(&self, kind: Option<&Hash>) -> Result<Vec<AccountRef>> {
+        let mut prefix = self.to_key_canonical(kind, None);
+        // mark as an address entry
+        prefix[0] |= 1;
+
+        self.table
+            .scan_prefix(&prefix)
+            .keys()
+            .map(|key| {
+                let key = key?;
+                match decode_key(&key)? {
+                    (_, Some(account)) => AccountRef::from_bytes(account).map_err(Into::into),
+                    (_, None) => bail!("corrupted key: {key:?}"),
+                }
+            })
+            .collect()
+    }
+
+    /// Lists the accounts having an address of the given kind,
+    /// tagged with all the given tags, e.g. the ones in a region.
+    pub fn list_with_tags(&self, kind: Option<&Hash>, filter: &Tags) -> Result<Vec<AccountRef>> {
+        let tags = self.table.open_tree(TREE_TAGS)?;
+
+        let mut accounts = vec![];
+        for account in self.list(kind)? {
+            let key = self.to_key_canonical(kind, Some(&account));
+            let matched = match tags.get(key)? {
+                Some(tags) => {
+                    let tags = decode_tags(&tags)?;
+                    filter
+                        .iter()
+                        .all(|(name, value)| tags.get(name) == Some(value))
+                }
+                None => filter.is_empty(),
+            };
+            if matched {
+                accounts.push(account);
+            }
+        }
+        Ok(accounts)
+    }
+
+    /// Streams all the entries of the routing table, in the order of their keys.
+    ///
+    /// The entries are read in chunks on the blocking threads,
+    /// so that scanning a huge table does not stall the other tasks.
+    pub fn stream(&self) -> impl Stream<Item = Result<BookEntry>> {
+        // the primary and address entries, not the auxiliary trees
+        let entries = self.table.range(..[0b100u8]);
+
+        stream::unfold(Some(entries), |entries| async move {
+            let mut entries = entries?;
+            let chunk = tokio::task::spawn_blocking(move || {
+                let chunk: Vec<_> = entries
+                    .by_ref()
+                    .take(STREAM_CHUNK_SIZE)
+                    .map(|entry| {
+                        let (key, value) = entry?;
+                        decode_entry(&key, &value)
+                    })
+                    .collect();
+                (chunk, entries)
+            })
+            .await;
+
+            match chunk {
+                Ok((chunk, _)) if chunk.is_empty() => None,
+                Ok((chunk, entries)) => Some((stream::iter(chunk), Some(entries))),
+                Err(e) => Some((stream::iter(vec![Err(e.into())]), None)),
+            }
+        })
+        .flatten()
+    }
+
+    /// Counts all the entries of the routing table, both the primary and address ones.
+    pub fn count(&self) -> Result<usize> {
+        self.table
+            .range(..[0b100u8])
+            .keys()
+            .try_fold(0, |count, key| {
+                key?;
+                Ok(count + 1)
+            })
+    }
+
+    /// Lists the kinds which the account is primary for.
+    ///
+    /// Note that it scans all primary entries, as there is no reverse index: O(n).
+    pub fn kinds_for_primary(&self, account: &AccountRef) -> Result<Vec<Option<Hash>>> {
+        let account = account.to_string().into_bytes();
+
+        // the primary entries without and with a kind
+        let mut kinds = vec![];
+        for flag in [0b00, 0b10] {
+            for entry in self.table.scan_prefix([flag]) {
+                let (key, value) = entry?;
+                if value.as_ref() == account.as_slice() {
+                    let (kind, _) = decode_key(&key)?;
+                    kinds.push(kind.map(Self::from_key_kind).transpose()?);
+                }
+            }
+        }
+        Ok(kinds)
+    }
+
+    /// Lists all primary accounts, with their kinds.
+    pub fn list_primaries(&self) -> Result<Vec<(Option<Hash>, AccountRef)>> {
+        // the primary entries without and with a kind
+        let mut primaries = vec![];
+        for flag in [0b00, 0b10] {
+            for entry in self.table.scan_prefix([flag]) {
+                let (key, value) = entry?;
+                let (kind, _) = decode_key(&key)?;
+                primaries.push((
+                    kind.map(Self::from_key_kind).transpose()?,
+                    String::from_utf8(value.to_vec())?.parse()?,
+                ));
+            }
+        }
+        Ok(primaries)
+    }
+
+    /// Exports the whole routing table, signed by `account_me`.
+    pub fn export_signed(&self) -> Result<SignedBook> {
+        let mut addresses = vec![];
+        for flag in [0b01, 0b11] {
+            for entry in self.table.scan_prefix([flag]) {
+                let (key, value) = entry?;
+                match decode_key(&key)? {
+                    (kind, Some(account)) => addresses.push((
+                        kind.map(Self::from_key_kind).transpose()?,
+                        AccountRef::from_bytes(account)?,
+                        String::from_utf8(value.to_vec())?,
+                    )),
+                    (_, None) => bail!("corrupted key: {key:?}"),
+                }
+            }
+        }
+
+        let book = RouteBook {
+            primaries: self.list_primaries()?,
+            addresses,
+        };
+        Data::builder().build_owned(&self.account_me, *self.account_ref, book)
+    }
+
+    /// Imports the routing table exported by [`Self::export_signed`] at once in a single batch,
+    /// returning the number of the entries.
+    ///
+    /// Nothing is written unless the bundle is signed by the expected account.
+    pub fn import_signed(
+        &self,
+        bundle: &SignedBook,
+        expected_signer: &AccountRef,
+    ) -> Result<usize> {
+        // verify the bundle
+        let signer = &bundle.metadata.guarantee;
+        if signer != expected_signer {
+            bail!(
+                "unexpected signer of the routing table: expected {expected_signer}, got {signer}"
+            );
+        }
+        bundle.verify(Some(expected_signer))?;
+
+        self.import(&bundle.data)
+    }
+
+    /// Imports the routing table at once in a single batch, returning the number of the entries.
+    ///
+    /// The table is trusted as it is, e.g. a fixed topology known in advance.
+    pub fn import(&self, book: &RouteBook) -> Result<usize> {
+        let RouteBook {
+            primaries,
+            addresses,
+        } = book;
+
+        let mut batch = sled::Batch::default();
+        for (kind, account) in primaries {
+            batch.insert(
+                self.to_key_canonical(kind.as_ref(), None),
+                account.to_string().into_bytes(),
+            );
+        }
+        for (kind, account, address) in addresses {
+            batch.insert(
+                self.to_key_canonical(kind.as_ref(), Some(account)),
+                address.as_bytes(),
+            );
+        }
+
+        self.table.apply_batch(batch)?;
+        for (kind, account) in primaries {
+            self.notify(BookChange::SetPrimary {
+                kind: *kind,
+                account: *account,
+            });
+        }
+        for (kind, account, address) in addresses {
+            self.notify(BookChange::SetAddress {
+                kind: *kind,
+                account: *account,
+                address: address.clone(),
+            });
+        }
+        Ok(primaries.len() + addresses.len())
+    }
+
+    /// Opens an auxiliary tree sharing the database of the routing table.
+    ///
+    /// The tree is namespaced, so it never collides with the routing entries.
+    /// Note that the callers own the schema of their tree.
+    pub fn open_tree(&self, name: impl AsRef<[u8]>) -> Result<sled::Tree> {
+        let name = [TREE_PREFIX, name.as_ref()].concat();
+
+        self.table.open_tree(name).map_err(Into::into)
+    }
+
+    /// Flushes the pending writes, and reports the size of the database.
+    ///
+    /// The table keeps no deadlines, so the expired entries are evicted
+    /// by the owners of their deadlines, e.g. the maintenance of the clients.
+    pub async fn maintenance(&self) -> Result<MaintenanceReport> {
+        Ok(MaintenanceReport {
+            flushed_bytes: self.table.flush_async().await?,
+            size_on_disk: self.table.size_on_disk()?,
+            ..Default::default()
+        })
+    }
+
+    fn to_value_address(address: &Address) -> Result<Vec<u8>>
+    where
+        Address: ToString,
+    {
+        // verify address
+        resolve_address(&address.to_string()).map(|address| address.to_string().into_bytes())
+    }
+
+    fn from_key_kind(kind: &[u8]) -> Result<Hash> {
+        decode_key_kind(kind)
     }
 
     fn to_key_canonical(&self, kind: Option<&Hash>, account: Option<&AccountRef>) -> Vec<u8> {
-        #[allow(clippy::identity_op)]
-        let flag = ((kind.is_some() as u8) << 1) + ((account.is_some() as u8) << 0);
+        let kind: Option<Vec<u8>> = kind.cloned().map(Into::into);
+        let account = account.map(|e| e.as_bytes().as_ref());
+
+        encode_key(kind.as_deref(), account)
+    }
+}
+
+fn decode_key_kind(kind: &[u8]) -> Result<Hash> {
+    Hash::try_from(kind).map_err(|_| anyhow!("failed to parse the kind: {kind:?}"))
+}
 
-        let kind: Vec<u8> = kind.cloned().map(Into::into).unwrap_or_default();
-        let account = account
-            .map(|e| e.as_bytes().as_ref())
-            .unwrap_or_else(|| &[]);
+fn decode_entry(key: &[u8], value: &[u8]) -> Result<BookEntry> {
+    let (kind, account) = decode_key(key)?;
+    let kind = kind.map(decode_key_kind).transpose()?;
 
-        [&[flag], kind.as_slice(), account].concat()
+    // the address entries are marked
+    match (key[0] & 1, account) {
+        (0, _) => Ok(BookEntry::Primary {
+            kind,
+            account: String::from_utf8(value.to_vec())?.parse()?,
+        }),
+        (_, Some(account)) => Ok(BookEntry::Address {
+            kind,
+            account: AccountRef::from_bytes(account)?,
+            address: String::from_utf8(value.to_vec())?,
+        }),
+        (_, None) => bail!("corrupted key: {key:?}"),
     }
 }
+
+/// Encodes the tags as the length-prefixed names and values, as the keys.
+fn encode_tags(tags: &Tags) -> Vec<u8> {
+    let mut value = vec![];
+    for (name, tag) in tags {
+        for component in [name, tag] {
+            value.extend_from_slice(&(component.len() as u16).to_be_bytes());
+            value.extend_from_slice(component.as_bytes());
+        }
+    }
+    value
+}
+
+fn decode_tags(mut value: &[u8]) -> Result<Tags> {
+    fn next(value: &mut &[u8]) -> Result<String> {
+        if value.len() < 2 {
+            bail!("corrupted tags: missing the length");
+        }
+
+        let len = u16::from_be_bytes([value[0], value[1]]) as usize;
+        if value.len() < 2 + len {
+            bail!("corrupted tags: too short");
+        }
+
+        let component = String::from_utf8(value[2..2 + len].to_vec())?;
+        *value = &value[2 + len..];
+        Ok(component)
+    }
+
+    let mut tags = Tags::default();
+    while !value.is_empty() {
+        let name = next(&mut value)?;
+        let tag = next(&mut value)?;
+        tags.insert(name, tag);
+    }
+    Ok(tags)
+}
+
+fn open_db(path: PathBuf, options: RouterOptions) -> Result<sled::Db> {
+    if options.compress && !cfg!(feature = "compress-db") {
+        bail!("compressing the routing table requires the `compress-db` feature");
+    }
+
+    let mut config = sled::Config::new().path(&path);
+    if let Some(capacity) = options.cache_capacity_bytes {
+        config = config.cache_capacity(capacity);
+    }
+    if let Some(interval) = options.flush_every_ms {
+        config = config.flush_every_ms(Some(interval).filter(|interval| *interval > 0));
+    }
+
+    match config.clone().use_compression(options.compress).open() {
+        Ok(table) => Ok(table),
+        // keep the compression of the existing table
+        Err(e) if path.exists() => {
+            warn!("reopening the routing table with its own compression: {e}");
+            config
+                .use_compression(!options.compress)
+                .open()
+                .map_err(Into::into)
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Validates the address formatted as `host:port`, and resolves it.
+///
+/// The literal IPs are parsed as-is, so only the hostnames are resolved.
+pub fn resolve_address(address: &str) -> Result<SocketAddr> {
+    // literal IP
+    if let Ok(address) = address.parse() {
+        return Ok(address);
+    }
+
+    // hostname
+    let (host, port) = parse_hostname(address)?;
+    (host, port)
+        .to_socket_addrs()
+        .map_err(|e| anyhow!("failed to resolve the address: {address:?}: {e}"))?
+        .next()
+        .ok_or_else(|| anyhow!("failed to resolve the address: {address:?}: no records"))
+}
+
+/// Same as [`resolve_address`], but resolves the hostnames without blocking the runtime.
+pub async fn resolve_address_async(address: &str) -> Result<SocketAddr> {
+    // literal IP
+    if let Ok(address) = address.parse() {
+        return Ok(address);
+    }
+
+    // hostname
+    let (host, port) = parse_hostname(address)?;
+    tokio::net::lookup_host((host, port))
+        .await
+        .map_err(|e| anyhow!("failed to resolve the address: {address:?}: {e}"))?
+        .next()
+        .ok_or_else(|| anyhow!("failed to resolve the address: {address:?}: no records"))
+}
+
+fn parse_hostname(address: &str) -> Result<(&str, u16)> {
+    let (host, port) = address
+        .rsplit_once(':')
+        .ok_or_else(|| anyhow!("malformed address: {address:?}: expected `host:port`"))?;
+    if host.is_empty() || host.contains(|c: char| c.is_whitespace() || c == '/') {
+        bail!("malformed address: {address:?}: invalid host: {host:?}");
+    }
+    let port: u16 = port
+        .parse()
+        .map_err(|_| anyhow!("malformed address: {address:?}: invalid port: {port:?}"))?;
+    Ok((host, port))
+}
+
+/// Encodes a key of the routing table, length-prefixing each component.
+pub fn encode_key(kind: Option<&[u8]>, account: Option<&[u8]>) -> Vec<u8> {
+    #[allow(clippy::identity_op)]
+    let flag = ((kind.is_some() as u8) << 1) + ((account.is_some() as u8) << 0);
+
+    let mut key = vec![flag];
+    for component in [kind, account].into_iter().flatten() {
+        key.extend_from_slice(&(component.len() as u16).to_be_bytes());
+        key.extend_from_slice(component);
+    }
+    key
+}
+
+/// Decodes a key of the routing table into `(kind, account)`.
+pub fn decode_key(key: &[u8]) -> Result<(Option<&[u8]>, Option<&[u8]>)> {
+    fn next<'a>(key: &mut &'a [u8], present: bool) -> Result<Option<&'a [u8]>> {
+        if !present {
+            return Ok(None);
+        }
+        if key.len() < 2 {
+            bail!("corrupted key: missing the length");
+        }
+
+        let len = u16::from_be_bytes([key[0], key[1]]) as usize;
+        if key.len() < 2 + len {
+            bail!("corrupted key: too short");
+        }
+
+        let component = &key[2..2 + len];
+        *key = &key[2 + len..];
+        Ok(Some(component))
+    }
+
+    match key.split_first() {
+        Some((&flag, mut rest)) if flag <= 0b11 => {
+            let kind = next(&mut rest, flag & 0b10 != 0)?;
+            let account = next(&mut rest, flag & 0b01 != 0)?;
+            if rest.is_empty() {
+                Ok((kind, account))
+            } else {
+                bail!("corrupted key: trailing bytes")
+            }
+        }
+        _ => bail!("corrupted key: unknown flag"),
+    }
+}
+
+/// Migrates the routing table to the latest [`SCHEMA_VERSION`].
+fn migrate(table: &sled::Db) -> Result<()> {
+    let meta = table.open_tree(TREE_META)?;
+
+    match meta.get(KEY_SCHEMA_VERSION)?.as_deref() {
+        Some([SCHEMA_VERSION]) => return Ok(()),
+        Some([0]) | None => {
+            let mut batch = sled::Batch::default();
+            for entry in table.iter() {
+                let (key, value) = entry?;
+
+                // decode as the schema `0`
+                let (flag, rest) = match key.split_first() {
+                    Some((&flag, rest)) if flag <= 0b11 => (flag, rest),
+                    _ => bail!("corrupted key: unknown flag: {key:?}"),
+                };
+                let (kind, account) = match flag {
+                    0b00 => (None, None),
+                    0b01 => (None, Some(rest)),
+                    0b10 => (Some(rest), None),
+                    _ => {
+                        let len = rest
+                            .len()
+                            .checked_sub(SCHEMA_0_ACCOUNT_LEN)
+                            .ok_or_else(|| anyhow!("corrupted key: too short: {key:?}"))?;
+                        let (kind, account) = rest.split_at(len);
+                        (Some(kind), Some(account))
+                    }
+                };
+
+                batch.remove(key.clone());
+                batch.insert(encode_key(kind, account), value);
+            }
+
+            // rewrite the keys along with the version at once,
+            // so that an interrupted migration is never applied twice
+            (&**table, &meta)
+                .transaction(|(table, meta)| {
+                    table.apply_batch(&batch)?;
+                    meta.insert(KEY_SCHEMA_VERSION, vec![SCHEMA_VERSION])?;
+                    Ok(())
+                })
+                .map_err(|e: TransactionError| {
+                    anyhow!("failed to migrate the routing table: {e}")
+                })?;
+        }
+        Some(version) => bail!("unsupported schema version of the routing table: {version:?}"),
+    }
+
+    table.flush()?;
+    Ok(())
+}
@@ -0,0 +1,56 @@
+use std::time::Instant;
+
+use ipiis_modules_router::RouterClient;
+use ipis::{
+    core::{account::Account, value::hash::Hash},
+    tokio,
+};
+
+const NUM_ENTRIES: u16 = 10_000;
+
+#[tokio::test]
+async fn test_set_bulk() {
+    let _ = ::std::fs::remove_dir_all(db_path());
+    ::std::env::set_var("ipiis_router_db", db_path());
+    let router = RouterClient::<String>::new(Account::generate()).unwrap();
+
+    // import the literal addresses without validation
+    let kind = Hash::with_str("__ipiis__test__router__bulk__");
+    let entries: Vec<_> = (0..NUM_ENTRIES)
+        .map(|port| {
+            let account = Account::generate().account_ref();
+            (Some(kind), account, format!("127.0.0.1:{port}"))
+        })
+        .collect();
+
+    let instant = Instant::now();
+    let count = router.set_bulk(entries.clone(), false).await.unwrap();
+    assert_eq!(count, entries.len());
+    assert!(instant.elapsed().as_secs() < 10);
+
+    // the imported addresses should be queryable
+    assert_eq!(router.list(Some(&kind)).unwrap().len(), entries.len());
+    for (kind, account, address) in entries.iter().step_by(100) {
+        assert_eq!(
+            router.get(kind.as_ref(), account).unwrap().as_ref(),
+            Some(address),
+        );
+    }
+
+    // the malformed addresses should be rejected as a whole on validation
+    let account = Account::generate().account_ref();
+    let entries = vec![
+        (None, account, "127.0.0.1:1".to_string()),
+        (
+            None,
+            Account::generate().account_ref(),
+            "bad address".to_string(),
+        ),
+    ];
+    assert!(router.set_bulk(entries, true).await.is_err());
+    assert_eq!(router.get(None, &account).unwrap(), None);
+}
+
+fn db_path() -> ::std::path::PathBuf {
+    ::std::env::temp_dir().join("ipiis-test-router-bulk")
+}
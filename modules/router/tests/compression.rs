@@ -0,0 +1,62 @@
+#![cfg(feature = "compress-db")]
+
+use ipiis_modules_router::{RouterClient, RouterOptions};
+use ipis::{core::account::Account, tokio};
+
+const NUM_ENTRIES: u16 = 50_000;
+
+async fn size_on_disk(name: &str, options: RouterOptions) -> u64 {
+    let _ = ::std::fs::remove_dir_all(db_path(name));
+    set_router_db(name);
+
+    // write a synthetic large table
+    let router = RouterClient::<String>::with_options(Account::generate(), options).unwrap();
+    for port in 0..NUM_ENTRIES {
+        let account = Account::generate().account_ref();
+        router
+            .set(None, &account, &format!("127.0.0.1:{port}"))
+            .unwrap();
+    }
+    router.maintenance().await.unwrap().size_on_disk
+}
+
+#[tokio::test]
+async fn test_compression() {
+    let plain = size_on_disk(
+        "plain",
+        RouterOptions {
+            compress: false,
+            ..Default::default()
+        },
+    )
+    .await;
+    let compressed = size_on_disk(
+        "compressed",
+        RouterOptions {
+            compress: true,
+            ..Default::default()
+        },
+    )
+    .await;
+    assert!(compressed < plain, "compressed={compressed}, plain={plain}");
+
+    // the existing uncompressed table should be still opened
+    set_router_db("plain");
+    let router = RouterClient::<String>::with_options(
+        Account::generate(),
+        RouterOptions {
+            compress: true,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    assert_eq!(router.list(None).unwrap().len(), NUM_ENTRIES as usize);
+}
+
+fn db_path(name: &str) -> ::std::path::PathBuf {
+    ::std::env::temp_dir().join(format!("ipiis-test-router-compression-{name}"))
+}
+
+fn set_router_db(name: &str) {
+    ::std::env::set_var("ipiis_router_db", db_path(name));
+}
@@ -0,0 +1,19 @@
+use ipiis_modules_router::{decode_key, encode_key};
+
+#[test]
+fn test_key_components_do_not_collide() {
+    // these pairs collide under the plain concatenation: "ab" + "c" == "a" + "bc"
+    let key_a = encode_key(Some(&b"ab"[..]), Some(&b"c"[..]));
+    let key_b = encode_key(Some(&b"a"[..]), Some(&b"bc"[..]));
+    assert_ne!(key_a, key_b);
+
+    // and each key is decoded back into its own components
+    assert_eq!(
+        decode_key(&key_a).unwrap(),
+        (Some(&b"ab"[..]), Some(&b"c"[..])),
+    );
+    assert_eq!(
+        decode_key(&key_b).unwrap(),
+        (Some(&b"a"[..]), Some(&b"bc"[..])),
+    );
+}
@@ -0,0 +1,39 @@
+use ipiis_modules_router::{RouterClient, RouterOptions};
+use ipis::{core::account::Account, tokio};
+
+const NUM_ENTRIES: u16 = 1_000;
+
+#[tokio::test]
+async fn test_small_cache() {
+    let _ = ::std::fs::remove_dir_all(db_path());
+    ::std::env::set_var("ipiis_router_db", db_path());
+
+    // cap the cache far below the table, flushing it manually
+    let options = RouterOptions {
+        cache_capacity_bytes: Some(16 * 1024),
+        flush_every_ms: Some(0),
+        ..Default::default()
+    };
+    let router = RouterClient::<String>::with_options(Account::generate(), options).unwrap();
+
+    let entries: Vec<_> = (0..NUM_ENTRIES)
+        .map(|port| {
+            let account = Account::generate().account_ref();
+            (account, format!("127.0.0.1:{port}"))
+        })
+        .collect();
+    for (account, address) in &entries {
+        router.set(None, account, address).unwrap();
+    }
+
+    // the entries evicted from the cache should be still readable
+    assert_eq!(router.list(None).unwrap().len(), entries.len());
+    for (account, address) in &entries {
+        assert_eq!(router.get(None, account).unwrap().as_ref(), Some(address));
+    }
+    router.maintenance().await.unwrap();
+}
+
+fn db_path() -> ::std::path::PathBuf {
+    ::std::env::temp_dir().join("ipiis-test-router-options")
+}
@@ -0,0 +1,55 @@
+use ipiis_modules_router::RouterClient;
+use ipis::core::{account::Account, value::hash::Hash};
+
+#[test]
+fn test_signed_book() {
+    // seed a routing table
+    let _ = ::std::fs::remove_dir_all(db_path("src"));
+    ::std::env::set_var("ipiis_router_db", db_path("src"));
+    let account = Account::generate();
+    let signer = account.account_ref();
+    let src = RouterClient::<String>::new(account).unwrap();
+
+    let kind = Hash::with_str("__ipiis__test__router__signed__");
+    let primary = Account::generate().account_ref();
+    let peer = Account::generate().account_ref();
+    src.set_primary(Some(&kind), &primary).unwrap();
+    src.set(Some(&kind), &primary, &"127.0.0.1:5001".to_string())
+        .unwrap();
+    src.set(None, &peer, &"127.0.0.1:5002".to_string()).unwrap();
+
+    let bundle = src.export_signed().unwrap();
+    assert_eq!(bundle.data.primaries.len(), 1);
+    assert_eq!(bundle.data.addresses.len(), 2);
+
+    // import the bundle into a fresh node
+    let _ = ::std::fs::remove_dir_all(db_path("dst"));
+    ::std::env::set_var("ipiis_router_db", db_path("dst"));
+    let dst = RouterClient::<String>::new(Account::generate()).unwrap();
+
+    // the bundle of the other signer should be rejected
+    let stranger = Account::generate().account_ref();
+    assert!(dst.import_signed(&bundle, &stranger).is_err());
+
+    // the tampered bundle should be rejected
+    let mut tampered = bundle.clone();
+    tampered.data.addresses[0].2 = "10.0.0.1:5001".to_string();
+    assert!(dst.import_signed(&tampered, &signer).is_err());
+    assert_eq!(dst.get(None, &peer).unwrap(), None);
+
+    // the genuine bundle should be imported as it is
+    assert_eq!(dst.import_signed(&bundle, &signer).unwrap(), 3);
+    assert_eq!(dst.get_primary(Some(&kind)).unwrap(), Some(primary));
+    assert_eq!(
+        dst.get(Some(&kind), &primary).unwrap().as_deref(),
+        Some("127.0.0.1:5001"),
+    );
+    assert_eq!(
+        dst.get(None, &peer).unwrap().as_deref(),
+        Some("127.0.0.1:5002"),
+    );
+}
+
+fn db_path(name: &str) -> ::std::path::PathBuf {
+    ::std::env::temp_dir().join(format!("ipiis-test-router-signed-{name}"))
+}
@@ -0,0 +1,70 @@
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc,
+};
+
+use ipiis_modules_router::{BookEntry, RouterClient};
+use ipis::{
+    core::{account::Account, value::hash::Hash},
+    futures::TryStreamExt,
+    tokio,
+};
+
+const NUM_ENTRIES: u16 = 5_000;
+
+#[tokio::test]
+async fn test_stream() {
+    let _ = ::std::fs::remove_dir_all(db_path());
+    ::std::env::set_var("ipiis_router_db", db_path());
+    let router = RouterClient::<String>::new(Account::generate()).unwrap();
+
+    // fill the table
+    let kind = Hash::with_str("__ipiis__test__router__stream__");
+    let entries: Vec<_> = (0..NUM_ENTRIES)
+        .map(|port| {
+            let account = Account::generate().account_ref();
+            (Some(kind), account, format!("127.0.0.1:{port}"))
+        })
+        .collect();
+    router.set_bulk(entries.clone(), false).await.unwrap();
+
+    let primary = Account::generate().account_ref();
+    router.set_primary(None, &primary).unwrap();
+
+    // run a task along with the stream, on the same thread
+    let ticks = Arc::new(AtomicU64::default());
+    let ticker = {
+        let ticks = ticks.clone();
+        tokio::spawn(async move {
+            loop {
+                ticks.fetch_add(1, Ordering::Relaxed);
+                tokio::task::yield_now().await;
+            }
+        })
+    };
+
+    // stream the whole table
+    let streamed: Vec<_> = router.stream().try_collect().await.unwrap();
+    ticker.abort();
+
+    // the task should not be stalled while scanning
+    assert!(ticks.load(Ordering::Relaxed) > 0);
+
+    // every entry should be streamed
+    assert_eq!(streamed.len(), entries.len() + 1);
+    assert!(streamed.contains(&BookEntry::Primary {
+        kind: None,
+        account: primary,
+    }));
+    for (kind, account, address) in entries.into_iter().step_by(100) {
+        assert!(streamed.contains(&BookEntry::Address {
+            kind,
+            account,
+            address,
+        }));
+    }
+}
+
+fn db_path() -> ::std::path::PathBuf {
+    ::std::env::temp_dir().join("ipiis-test-router-stream")
+}
@@ -0,0 +1,91 @@
+use ipiis_modules_router::{RouterClient, Tags};
+use ipis::core::{account::Account, value::hash::Hash};
+
+#[test]
+fn test_tags() {
+    let _ = ::std::fs::remove_dir_all(db_path());
+    ::std::env::set_var("ipiis_router_db", db_path());
+    let router = RouterClient::<String>::new(Account::generate()).unwrap();
+
+    let kind = Hash::with_str("__ipiis__test__router__tags__");
+    let tagged = |region: &str| -> Tags {
+        [
+            ("region".to_string(), region.to_string()),
+            ("owner".to_string(), "ops".to_string()),
+        ]
+        .into_iter()
+        .collect()
+    };
+
+    // tag two entries by region, leaving the other untagged
+    let seoul = Account::generate().account_ref();
+    let tokyo = Account::generate().account_ref();
+    let untagged = Account::generate().account_ref();
+    router
+        .set_tagged(
+            Some(&kind),
+            &seoul,
+            &"127.0.0.1:9201".to_string(),
+            &tagged("seoul"),
+        )
+        .unwrap();
+    router
+        .set_tagged(
+            Some(&kind),
+            &tokyo,
+            &"127.0.0.1:9202".to_string(),
+            &tagged("tokyo"),
+        )
+        .unwrap();
+    router
+        .set(Some(&kind), &untagged, &"127.0.0.1:9203".to_string())
+        .unwrap();
+
+    // only the matching entries should be listed
+    let filter: Tags = [("region".to_string(), "seoul".to_string())]
+        .into_iter()
+        .collect();
+    assert_eq!(
+        router.list_with_tags(Some(&kind), &filter).unwrap(),
+        [seoul]
+    );
+    assert_eq!(router.list(Some(&kind)).unwrap().len(), 3);
+
+    // the tags should be stored alongside the address
+    assert_eq!(
+        router.get_with_tags(Some(&kind), &tokyo).unwrap(),
+        Some(("127.0.0.1:9202".to_string(), tagged("tokyo"))),
+    );
+
+    // the untagged entries should keep working
+    assert_eq!(
+        router.get(Some(&kind), &untagged).unwrap(),
+        Some("127.0.0.1:9203".to_string()),
+    );
+    assert_eq!(
+        router.get_with_tags(Some(&kind), &untagged).unwrap(),
+        Some(("127.0.0.1:9203".to_string(), Tags::default())),
+    );
+
+    // the tags should be kept by refreshing the address, and removed with it
+    router
+        .set(Some(&kind), &seoul, &"127.0.0.1:9204".to_string())
+        .unwrap();
+    assert_eq!(
+        router.list_with_tags(Some(&kind), &filter).unwrap(),
+        [seoul]
+    );
+
+    router.delete(Some(&kind), &seoul).unwrap();
+    router
+        .set(Some(&kind), &seoul, &"127.0.0.1:9201".to_string())
+        .unwrap();
+    assert!(router
+        .list_with_tags(Some(&kind), &filter)
+        .unwrap()
+        .is_empty());
+}
+
+fn db_path() -> ::std::path::PathBuf {
+    ::std::env::temp_dir().join("ipiis-test-router-tags")
+}
@@ -1,9 +1,83 @@
 use std::sync::Arc;
 
-use ipiis_api::server::IpiisServer;
-use ipis::{env::Infer, tokio};
+use ipiis_api::{health::HealthServer, server::IpiisServer};
+use ipis::{
+    core::anyhow::{bail, Result},
+    env::{infer, Infer},
+    log::info,
+    tokio::{
+        self,
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpStream,
+    },
+};
 
 #[tokio::main]
 async fn main() {
-    Arc::new(IpiisServer::infer().await).run_ipiis().await
+    // dump the state of the running node
+    if ::std::env::args().nth(1).as_deref() == Some("diag") {
+        let dump = diag().await.expect("failed to collect the diagnostics");
+        print!("{dump}");
+        return;
+    }
+
+    let server = Arc::new(IpiisServer::infer().await);
+
+    // probe the node if requested
+    if let Some(health) = HealthServer::try_infer(server.clone())
+        .await
+        .expect("failed to bind the health server")
+    {
+        tokio::spawn(health.run());
+    }
+
+    let report = server
+        .run_ipiis_with_shutdown(shutdown_signal())
+        .await
+        .expect("failed to shut down gracefully");
+    info!("shut down: {report:?}");
+}
+
+/// Fetches the diagnostics from the health server of the running node,
+/// on the port of `ipiis_health_port`.
+async fn diag() -> Result<String> {
+    let port: u16 = infer("ipiis_health_port")?;
+
+    let mut stream = TcpStream::connect(("127.0.0.1", port)).await?;
+    stream
+        .write_all(b"GET /diagz HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n")
+        .await?;
+
+    let mut response = String::new();
+    stream.read_to_string(&mut response).await?;
+
+    // strip the header
+    match response.split_once("\r\n\r\n") {
+        Some((header, body)) if header.starts_with("HTTP/1.1 200") => Ok(body.to_string()),
+        Some((header, _)) => bail!(
+            "failed to fetch the diagnostics: {}",
+            header.lines().next().unwrap_or_default(),
+        ),
+        None => bail!("malformed response of the diagnostics"),
+    }
+}
+
+/// Waits for `SIGINT` (Ctrl-C), or `SIGTERM` sent by the orchestrators on the unix-like systems.
+async fn shutdown_signal() {
+    #[cfg(unix)]
+    {
+        let mut terminate =
+            tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+                .expect("failed to install the SIGTERM handler");
+
+        tokio::select! {
+            _ = tokio::signal::ctrl_c() => {}
+            _ = terminate.recv() => {}
+        }
+    }
+
+    #[cfg(not(unix))]
+    {
+        let _ = tokio::signal::ctrl_c().await;
+    }
 }